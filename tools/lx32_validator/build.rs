@@ -3,6 +3,12 @@ use std::fs;
 use std::path::PathBuf;
 
 fn main() {
+    // 0. Without the `rtl` feature only the golden model is built
+    //    (lx32-sim, lx32-as), so Verilator is not needed at all
+    if env::var_os("CARGO_FEATURE_RTL").is_none() {
+        return;
+    }
+
     // 1. Detect Verilator root smartly:
     //    - Use VERILATOR_ROOT if set
     //    - Otherwise auto-detect by OS
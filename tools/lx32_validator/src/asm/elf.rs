@@ -0,0 +1,271 @@
+// ============================================================
+// LX32 Assembler – ELF Object Writer
+// ============================================================
+// Writes an `Object` as an ELF32 little-endian relocatable
+// file that ld.lld links with tools/lx32_backend's link.ld.
+//
+// Fixups still pending after assembly become RELA entries of
+// the standard RISC-V types, so the object mixes freely with
+// ones from the LLVM backend (which also uses EM_RISCV).
+// References to labels go through section symbols; the
+// PCREL_LO12_I half of `la` points at a local `.Lpcrel_hiN`
+// label on its AUIPC, as GNU as does.
+// ============================================================
+
+use super::{AsmError, Base, FixupKind, Object, SymbolDef, SymbolKind, Value};
+
+const EM_RISCV: u16 = 243;
+const ET_REL: u16 = 1;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+
+const SHF_WRITE: u32 = 0x1;
+const SHF_ALLOC: u32 = 0x2;
+const SHF_EXECINSTR: u32 = 0x4;
+const SHF_INFO_LINK: u32 = 0x40;
+
+const SHN_ABS: u16 = 0xFFF1;
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STT_NOTYPE: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_SECTION: u8 = 3;
+
+pub const R_RISCV_32: u32 = 1;
+pub const R_RISCV_BRANCH: u32 = 16;
+pub const R_RISCV_JAL: u32 = 17;
+pub const R_RISCV_CALL: u32 = 18;
+pub const R_RISCV_PCREL_HI20: u32 = 23;
+pub const R_RISCV_PCREL_LO12_I: u32 = 24;
+pub const R_RISCV_HI20: u32 = 26;
+pub const R_RISCV_LO12_I: u32 = 27;
+pub const R_RISCV_LO12_S: u32 = 28;
+
+const EHDR_SIZE: usize = 52;
+const SHDR_SIZE: usize = 40;
+const SYM_SIZE: usize = 16;
+const RELA_SIZE: usize = 12;
+
+/// Symbol a relocation refers to.
+enum Target {
+    Section(usize),
+    Named(String),
+    /// n-th `.Lpcrel_hi` label
+    PcrelLabel(usize),
+}
+
+struct Reloc {
+    offset: u32,
+    kind: u32,
+    target: Target,
+    addend: i64,
+}
+
+struct ElfSymbol {
+    name: String,
+    value: u32,
+    size: u32,
+    info: u8,
+    shndx: u16,
+}
+
+/// Appends `name` to a string table and returns its offset.
+fn add_string(table: &mut Vec<u8>, name: &str) -> u32 {
+    if name.is_empty() {
+        return 0;
+    }
+    let offset = table.len() as u32;
+    table.extend_from_slice(name.as_bytes());
+    table.push(0);
+    offset
+}
+
+fn put16(buf: &mut Vec<u8>, value: u16) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+fn pad_to(buf: &mut Vec<u8>, align: usize) {
+    buf.resize(buf.len().next_multiple_of(align), 0);
+}
+
+pub fn write(obj: &Object) -> Result<Vec<u8>, AsmError> {
+    // Relocations, and the AUIPC of every `la` that needs a label
+    let mut relocs: Vec<Vec<Reloc>> = Vec::new();
+    let mut pcrel_labels: Vec<(usize, u32)> = Vec::new();
+    for (index, section) in obj.sections.iter().enumerate() {
+        let mut list = Vec::new();
+        for fixup in &section.fixups {
+            let error = |message: String| AsmError { line: fixup.line, message };
+            let (target, addend) = match obj.eval(&fixup.expr).map_err(error)? {
+                Value::Rel(Base::Section(section), offset) => (Target::Section(section), offset),
+                Value::Rel(Base::Extern(name), offset) => (Target::Named(name), offset),
+                Value::Abs(_) => return Err(error("constant fixup left for the ELF writer".to_string())),
+            };
+            let kind = match fixup.kind {
+                FixupKind::Branch => R_RISCV_BRANCH,
+                FixupKind::Jal => R_RISCV_JAL,
+                FixupKind::Call => R_RISCV_CALL,
+                FixupKind::Hi20 => R_RISCV_HI20,
+                FixupKind::Lo12I => R_RISCV_LO12_I,
+                FixupKind::Lo12S => R_RISCV_LO12_S,
+                FixupKind::Data32 => R_RISCV_32,
+                FixupKind::PcrelPair => {
+                    list.push(Reloc { offset: fixup.offset, kind: R_RISCV_PCREL_HI20, target, addend });
+                    list.push(Reloc { offset: fixup.offset + 4, kind: R_RISCV_PCREL_LO12_I, target: Target::PcrelLabel(pcrel_labels.len()), addend: 0 });
+                    pcrel_labels.push((index, fixup.offset));
+                    continue;
+                }
+                FixupKind::Data16 | FixupKind::Data8 | FixupKind::Imm12I | FixupKind::Imm12S | FixupKind::Imm20U => {
+                    return Err(error("value must be a constant in an ELF object".to_string()));
+                }
+            };
+            list.push(Reloc { offset: fixup.offset, kind, target, addend });
+        }
+        relocs.push(list);
+    }
+
+    // Symbol table: null, section symbols, locals, then globals
+    let mut symbols = vec![ElfSymbol { name: String::new(), value: 0, size: 0, info: 0, shndx: 0 }];
+    for index in 0..obj.sections.len() {
+        symbols.push(ElfSymbol { name: String::new(), value: 0, size: 0, info: STT_SECTION, shndx: index as u16 + 1 });
+    }
+    let elf_symbol = |name: &str, def: SymbolDef, kind: SymbolKind, size: u32, bind: u8| {
+        let (value, shndx) = match def {
+            SymbolDef::Undefined => (0, 0),
+            SymbolDef::Absolute(value) => (value as u32, SHN_ABS),
+            SymbolDef::Label { section, offset } => (offset, section as u16 + 1),
+        };
+        let kind = match kind {
+            SymbolKind::NoType => STT_NOTYPE,
+            SymbolKind::Func => STT_FUNC,
+            SymbolKind::Object => STT_OBJECT,
+        };
+        ElfSymbol { name: name.to_string(), value, size, info: bind << 4 | kind, shndx }
+    };
+    // `.L` labels are assembler-local and stay out of the table
+    for symbol in &obj.symbols {
+        if !symbol.global && symbol.def != SymbolDef::Undefined && !symbol.name.starts_with(".L") {
+            symbols.push(elf_symbol(&symbol.name, symbol.def, symbol.kind, symbol.size, STB_LOCAL));
+        }
+    }
+    let first_label = symbols.len();
+    for (n, (section, offset)) in pcrel_labels.iter().enumerate() {
+        let def = SymbolDef::Label { section: *section, offset: *offset };
+        symbols.push(elf_symbol(&format!(".Lpcrel_hi{}", n), def, SymbolKind::NoType, 0, STB_LOCAL));
+    }
+    let first_global = symbols.len();
+    for symbol in &obj.symbols {
+        if symbol.global || symbol.def == SymbolDef::Undefined {
+            symbols.push(elf_symbol(&symbol.name, symbol.def, symbol.kind, symbol.size, STB_GLOBAL));
+        }
+    }
+    // Undefined names only ever used in expressions
+    for reloc in relocs.iter().flatten() {
+        if let Target::Named(name) = &reloc.target
+            && !symbols[first_global..].iter().any(|symbol| symbol.name == *name)
+        {
+            symbols.push(elf_symbol(name, SymbolDef::Undefined, SymbolKind::NoType, 0, STB_GLOBAL));
+        }
+    }
+    let symbol_index = |target: &Target| match target {
+        Target::Section(section) => section + 1,
+        Target::PcrelLabel(n) => first_label + n,
+        Target::Named(name) => first_global + symbols[first_global..].iter().position(|symbol| symbol.name == *name).expect("added above"),
+    };
+
+    // Section contents, each at its alignment
+    let mut out = vec![0; EHDR_SIZE];
+    let mut section_offsets = Vec::new();
+    for section in &obj.sections {
+        pad_to(&mut out, section.align as usize);
+        section_offsets.push(out.len());
+        if !section.nobits {
+            out.extend_from_slice(&section.data);
+        }
+    }
+    let mut rela_offsets = Vec::new();
+    for list in &relocs {
+        pad_to(&mut out, 4);
+        rela_offsets.push(out.len());
+        for reloc in list {
+            put32(&mut out, reloc.offset);
+            put32(&mut out, (symbol_index(&reloc.target) as u32) << 8 | reloc.kind);
+            put32(&mut out, reloc.addend as u32);
+        }
+    }
+
+    let mut strtab = vec![0];
+    pad_to(&mut out, 4);
+    let symtab_offset = out.len();
+    for symbol in &symbols {
+        put32(&mut out, add_string(&mut strtab, &symbol.name));
+        put32(&mut out, symbol.value);
+        put32(&mut out, symbol.size);
+        out.push(symbol.info);
+        out.push(0);
+        put16(&mut out, symbol.shndx);
+    }
+    let strtab_offset = out.len();
+    out.extend_from_slice(&strtab);
+
+    // Section headers: null, sections, non-empty .rela, .symtab, .strtab, .shstrtab
+    let rela_count = relocs.iter().filter(|list| !list.is_empty()).count();
+    let symtab_index = 1 + obj.sections.len() + rela_count;
+    let mut shstrtab = vec![0];
+    let mut headers = vec![0; SHDR_SIZE];
+    let mut header = |name: u32, kind: u32, flags: u32, offset: usize, size: usize, link: usize, info: usize, align: u32, entsize: usize| {
+        for value in [name, kind, flags, 0, offset as u32, size as u32, link as u32, info as u32, align, entsize as u32] {
+            put32(&mut headers, value);
+        }
+    };
+    for (section, offset) in obj.sections.iter().zip(&section_offsets) {
+        let kind = if section.nobits { SHT_NOBITS } else { SHT_PROGBITS };
+        let flags = SHF_ALLOC | if section.write { SHF_WRITE } else { 0 } | if section.exec { SHF_EXECINSTR } else { 0 };
+        header(add_string(&mut shstrtab, &section.name), kind, flags, *offset, section.data.len(), 0, 0, section.align, 0);
+    }
+    for (index, list) in relocs.iter().enumerate().filter(|(_, list)| !list.is_empty()) {
+        let name = add_string(&mut shstrtab, &format!(".rela{}", obj.sections[index].name));
+        header(name, SHT_RELA, SHF_INFO_LINK, rela_offsets[index], list.len() * RELA_SIZE, symtab_index, index + 1, 4, RELA_SIZE);
+    }
+    let name = add_string(&mut shstrtab, ".symtab");
+    header(name, SHT_SYMTAB, 0, symtab_offset, symbols.len() * SYM_SIZE, symtab_index + 1, first_global, 4, SYM_SIZE);
+    let name = add_string(&mut shstrtab, ".strtab");
+    header(name, SHT_STRTAB, 0, strtab_offset, strtab.len(), 0, 0, 1, 0);
+    let name = add_string(&mut shstrtab, ".shstrtab");
+    let shstrtab_offset = out.len();
+    header(name, SHT_STRTAB, 0, shstrtab_offset, shstrtab.len(), 0, 0, 1, 0);
+    out.extend_from_slice(&shstrtab);
+
+    pad_to(&mut out, 4);
+    let shoff = out.len();
+    out.extend_from_slice(&headers);
+
+    // ELF header
+    let mut ehdr = vec![0x7F, b'E', b'L', b'F', 1 /* ELFCLASS32 */, 1 /* ELFDATA2LSB */, 1 /* EV_CURRENT */];
+    ehdr.resize(16, 0);
+    put16(&mut ehdr, ET_REL);
+    put16(&mut ehdr, EM_RISCV);
+    put32(&mut ehdr, 1); // e_version
+    put32(&mut ehdr, 0); // e_entry
+    put32(&mut ehdr, 0); // e_phoff
+    put32(&mut ehdr, shoff as u32);
+    put32(&mut ehdr, 0); // e_flags: soft-float ABI, no RVC
+    put16(&mut ehdr, EHDR_SIZE as u16);
+    put16(&mut ehdr, 0); // e_phentsize
+    put16(&mut ehdr, 0); // e_phnum
+    put16(&mut ehdr, SHDR_SIZE as u16);
+    put16(&mut ehdr, (symtab_index + 3) as u16);
+    put16(&mut ehdr, (symtab_index + 2) as u16);
+    out[..EHDR_SIZE].copy_from_slice(&ehdr);
+    Ok(out)
+}
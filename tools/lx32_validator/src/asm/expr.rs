@@ -0,0 +1,266 @@
+// ============================================================
+// LX32 Assembler – Expressions
+// ============================================================
+// Operand and directive expressions: numbers, symbols, `.`
+// and the C operators GNU as accepts.
+//
+// Expressions are parsed where they are written but evaluated
+// later, once every label is known. A value is either an
+// absolute number or a base (a section or an undefined
+// symbol) plus an addend; only the latter needs relocating.
+// ============================================================
+
+/// Parsed expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(i64),
+    Sym(String),
+    /// `.`: the location (section, offset) where it was written
+    Here(usize, u32),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Bin(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Shl,
+    Shr,
+    And,
+    Or,
+    Xor,
+}
+
+/// What a relocatable value is relative to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base {
+    /// Start of a section of this object
+    Section(usize),
+    /// Symbol defined elsewhere
+    Extern(String),
+}
+
+/// Evaluated expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Abs(i64),
+    Rel(Base, i64),
+}
+
+/// Binary operators from lowest to highest precedence.
+const LEVELS: [&[(&str, BinOp)]; 6] = [
+    &[("|", BinOp::Or)],
+    &[("^", BinOp::Xor)],
+    &[("&", BinOp::And)],
+    &[("<<", BinOp::Shl), (">>", BinOp::Shr)],
+    &[("+", BinOp::Add), ("-", BinOp::Sub)],
+    &[("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Rem)],
+];
+
+/// True for characters that can start a symbol name.
+pub fn is_symbol_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == '.' || c == '$'
+}
+
+/// True for characters that can continue a symbol name.
+pub fn is_symbol_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '$'
+}
+
+/// Parses `text` as one expression. `here` is the location `.` stands for,
+/// or None outside any section.
+pub fn parse_expr(text: &str, here: Option<(usize, u32)>) -> Result<Expr, String> {
+    let mut parser = Parser { text, pos: 0, here };
+    let expr = parser.binary(0)?;
+    parser.skip_space();
+    if parser.pos != text.len() {
+        return Err(format!("unexpected `{}` in expression `{}`", &text[parser.pos..], text.trim()));
+    }
+    Ok(expr)
+}
+
+/// Parses a number literal (decimal, 0x, 0b, 0o or a 'c' character).
+pub fn parse_number(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (digits, radix) = match text.get(..2).map(|p| p.to_ascii_lowercase()) {
+        Some(p) if p == "0x" => (&text[2..], 16),
+        Some(p) if p == "0b" => (&text[2..], 2),
+        Some(p) if p == "0o" => (&text[2..], 8),
+        _ => (text, 10),
+    };
+    if let Some(c) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+        let mut chars = c.chars();
+        return match (chars.next(), chars.next(), chars.next()) {
+            (Some('\\'), Some(e), None) => unescape(e).map(|b| b as i64),
+            (Some(c), None, None) if c.is_ascii() => Some(c as i64),
+            _ => None,
+        };
+    }
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix) || c == '_') {
+        return None;
+    }
+    i64::from_str_radix(&digits.replace('_', ""), radix).ok()
+}
+
+/// Byte for a backslash escape (`\n`, `\t`, `\0`...).
+pub fn unescape(c: char) -> Option<u8> {
+    match c {
+        'n' => Some(b'\n'),
+        't' => Some(b'\t'),
+        'r' => Some(b'\r'),
+        '0' => Some(0),
+        '\\' => Some(b'\\'),
+        '"' => Some(b'"'),
+        '\'' => Some(b'\''),
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    here: Option<(usize, u32)>,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn skip_space(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start().len();
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expr, String> {
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        'next: loop {
+            self.skip_space();
+            for (token, op) in LEVELS[level] {
+                if self.rest().starts_with(token) {
+                    self.pos += token.len();
+                    let rhs = self.binary(level + 1)?;
+                    lhs = Expr::Bin(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'next;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        self.skip_space();
+        let Some(c) = self.rest().chars().next() else {
+            return Err(format!("missing operand in expression `{}`", self.text.trim()));
+        };
+        match c {
+            '-' => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            '~' => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            '+' => {
+                self.pos += 1;
+                self.unary()
+            }
+            '(' => {
+                self.pos += 1;
+                let inner = self.binary(0)?;
+                self.skip_space();
+                if !self.rest().starts_with(')') {
+                    return Err(format!("missing `)` in expression `{}`", self.text.trim()));
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            '\'' => {
+                let len = self.rest()[1..].find('\'').map(|end| end + 2).unwrap_or(self.rest().len());
+                let literal = &self.rest()[..len];
+                let value = parse_number(literal).ok_or_else(|| format!("bad character literal {}", literal))?;
+                self.pos += len;
+                Ok(Expr::Num(value))
+            }
+            _ if c.is_ascii_digit() => {
+                let len = self.rest().find(|c: char| !c.is_ascii_alphanumeric() && c != '_').unwrap_or(self.rest().len());
+                let literal = &self.rest()[..len];
+                let value = parse_number(literal).ok_or_else(|| format!("bad number `{}`", literal))?;
+                self.pos += len;
+                Ok(Expr::Num(value))
+            }
+            _ if is_symbol_start(c) => {
+                let len = self.rest().find(|c: char| !is_symbol_char(c)).unwrap_or(self.rest().len());
+                let name = &self.rest()[..len];
+                self.pos += len;
+                if name != "." {
+                    return Ok(Expr::Sym(name.to_string()));
+                }
+                let (section, offset) = self.here.ok_or("`.` used outside a section")?;
+                Ok(Expr::Here(section, offset))
+            }
+            _ => Err(format!("unexpected `{}` in expression `{}`", c, self.text.trim())),
+        }
+    }
+}
+
+impl Expr {
+    /// Evaluates the expression, looking symbols up with `lookup`.
+    pub fn eval(&self, lookup: &dyn Fn(&str) -> Value) -> Result<Value, String> {
+        match self {
+            Expr::Num(value) => Ok(Value::Abs(*value)),
+            Expr::Sym(name) => Ok(lookup(name)),
+            Expr::Here(section, offset) => Ok(Value::Rel(Base::Section(*section), *offset as i64)),
+            Expr::Neg(inner) => Ok(Value::Abs(inner.eval(lookup)?.absolute("-")?.wrapping_neg())),
+            Expr::Not(inner) => Ok(Value::Abs(!inner.eval(lookup)?.absolute("~")?)),
+            Expr::Bin(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(lookup)?, rhs.eval(lookup)?);
+                match (op, lhs, rhs) {
+                    (BinOp::Add, Value::Abs(a), Value::Abs(b)) => Ok(Value::Abs(a.wrapping_add(b))),
+                    (BinOp::Add, Value::Rel(base, a), Value::Abs(b)) | (BinOp::Add, Value::Abs(b), Value::Rel(base, a)) => {
+                        Ok(Value::Rel(base, a.wrapping_add(b)))
+                    }
+                    (BinOp::Sub, Value::Abs(a), Value::Abs(b)) => Ok(Value::Abs(a.wrapping_sub(b))),
+                    (BinOp::Sub, Value::Rel(base, a), Value::Abs(b)) => Ok(Value::Rel(base, a.wrapping_sub(b))),
+                    // Distance between two locations of one section
+                    (BinOp::Sub, Value::Rel(a_base, a), Value::Rel(b_base, b)) if a_base == b_base => Ok(Value::Abs(a.wrapping_sub(b))),
+                    (op, lhs, rhs) => {
+                        let (a, b) = (lhs.absolute("operator")?, rhs.absolute("operator")?);
+                        let value = match op {
+                            BinOp::Mul => a.wrapping_mul(b),
+                            BinOp::Div | BinOp::Rem if b == 0 => return Err("division by zero".to_string()),
+                            BinOp::Div => a.wrapping_div(b),
+                            BinOp::Rem => a.wrapping_rem(b),
+                            BinOp::Shl => a.wrapping_shl(b as u32),
+                            BinOp::Shr => a.wrapping_shr(b as u32),
+                            BinOp::And => a & b,
+                            BinOp::Or => a | b,
+                            BinOp::Xor => a ^ b,
+                            BinOp::Add | BinOp::Sub => return Err("symbols from different sections cannot be combined".to_string()),
+                        };
+                        Ok(Value::Abs(value))
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Value {
+    /// The number, or an error naming `what` needed one.
+    pub fn absolute(self, what: &str) -> Result<i64, String> {
+        match self {
+            Value::Abs(value) => Ok(value),
+            Value::Rel(Base::Extern(name), _) => Err(format!("{} needs a constant, but `{}` is not defined", what, name)),
+            Value::Rel(Base::Section(_), _) => Err(format!("{} needs a constant, not an address", what)),
+        }
+    }
+}
@@ -0,0 +1,560 @@
+// ============================================================
+// LX32 Assembler – Instructions
+// ============================================================
+// Mnemonic table, operand parsing and pseudo-instructions.
+//
+// Every mnemonic the disassembler prints is accepted with the
+// operand syntax it prints, so disassembly assembles back to
+// the same word. Pseudo-instructions expand to the sequences
+// GNU as uses; `li` picks its length from the value when the
+// value is already known, and otherwise always uses LUI+ADDI.
+// ============================================================
+
+use super::expr::{Expr, Value};
+use super::{Assembler, FixupKind, hi_lo, i32_of};
+use crate::models::arch::lx32_csr_pkg::csr_addr_t;
+use crate::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL, INSTR_MRET, INSTR_SFENCE_VMA};
+use crate::models::disasm::{F_ABI_NAMES, X_ABI_NAMES, csr_name};
+
+/// `addi x0, x0, 0`
+pub const NOP: u32 = 0x0000_0013;
+
+/// Register file of an FP-instruction operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reg {
+    X,
+    F,
+}
+
+/// Operand syntax of a mnemonic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// rd, rs1, rs2
+    R,
+    /// rd, rs1, imm
+    I,
+    /// rd, rs1, shamt
+    Shift,
+    /// rd, rs1
+    Unary,
+    /// rd, imm(rs1)
+    Load,
+    /// rs2, imm(rs1)
+    Store,
+    /// rs1, rs2, target
+    Branch,
+    /// rd, imm20
+    U,
+    /// [rd,] target
+    Jal,
+    /// rd, imm(rs1) | rs1 | rd, rs1, imm
+    Jalr,
+    /// rd, csr, rs1
+    Csr,
+    /// rd, csr, uimm5
+    CsrImm,
+    /// rd, rs2, (rs1), with an optional .aq/.rl/.aqrl suffix
+    Amo,
+    /// rd, (rs1), same suffixes
+    Lr,
+    /// no operands
+    Fixed,
+    /// [pred, succ]
+    Fence,
+    /// [rs1[, rs2]]
+    SfenceVma,
+    /// fd, imm(rs1)
+    FLoad,
+    /// fs2, imm(rs1)
+    FStore,
+    /// rd, rs1[, fs2][, rm]
+    Fp { rd: Reg, rs1: Reg, rs2: bool, rm: bool },
+    /// fd, fs1, fs2, fs3[, rm]
+    Fused,
+}
+
+use Format::*;
+
+const FP_FF: Format = Fp { rd: Reg::F, rs1: Reg::F, rs2: true, rm: false };
+const FP_FF_RM: Format = Fp { rd: Reg::F, rs1: Reg::F, rs2: true, rm: true };
+const FP_XFF: Format = Fp { rd: Reg::X, rs1: Reg::F, rs2: true, rm: false };
+
+/// Mnemonic, syntax and encoding with every operand field zero.
+const INSTRUCTIONS: &[(&str, Format, u32)] = &[
+    // RV32I
+    ("lui", U, 0x0000_0037),
+    ("auipc", U, 0x0000_0017),
+    ("jal", Jal, 0x0000_006F),
+    ("jalr", Jalr, 0x0000_0067),
+    ("beq", Branch, 0x0000_0063),
+    ("bne", Branch, 0x0000_1063),
+    ("blt", Branch, 0x0000_4063),
+    ("bge", Branch, 0x0000_5063),
+    ("bltu", Branch, 0x0000_6063),
+    ("bgeu", Branch, 0x0000_7063),
+    ("lb", Load, 0x0000_0003),
+    ("lh", Load, 0x0000_1003),
+    ("lw", Load, 0x0000_2003),
+    ("lbu", Load, 0x0000_4003),
+    ("lhu", Load, 0x0000_5003),
+    ("sb", Store, 0x0000_0023),
+    ("sh", Store, 0x0000_1023),
+    ("sw", Store, 0x0000_2023),
+    ("addi", I, 0x0000_0013),
+    ("slti", I, 0x0000_2013),
+    ("sltiu", I, 0x0000_3013),
+    ("xori", I, 0x0000_4013),
+    ("ori", I, 0x0000_6013),
+    ("andi", I, 0x0000_7013),
+    ("slli", Shift, 0x0000_1013),
+    ("srli", Shift, 0x0000_5013),
+    ("srai", Shift, 0x4000_5013),
+    ("add", R, 0x0000_0033),
+    ("sub", R, 0x4000_0033),
+    ("sll", R, 0x0000_1033),
+    ("slt", R, 0x0000_2033),
+    ("sltu", R, 0x0000_3033),
+    ("xor", R, 0x0000_4033),
+    ("srl", R, 0x0000_5033),
+    ("sra", R, 0x4000_5033),
+    ("or", R, 0x0000_6033),
+    ("and", R, 0x0000_7033),
+    ("fence", Fence, 0x0000_000F),
+    ("ecall", Fixed, INSTR_ECALL),
+    ("ebreak", Fixed, INSTR_EBREAK),
+    ("mret", Fixed, INSTR_MRET),
+    ("sfence.vma", SfenceVma, INSTR_SFENCE_VMA),
+    // Zicsr
+    ("csrrw", Csr, 0x0000_1073),
+    ("csrrs", Csr, 0x0000_2073),
+    ("csrrc", Csr, 0x0000_3073),
+    ("csrrwi", CsrImm, 0x0000_5073),
+    ("csrrsi", CsrImm, 0x0000_6073),
+    ("csrrci", CsrImm, 0x0000_7073),
+    // M
+    ("mul", R, 0x0200_0033),
+    ("mulh", R, 0x0200_1033),
+    ("mulhsu", R, 0x0200_2033),
+    ("mulhu", R, 0x0200_3033),
+    ("div", R, 0x0200_4033),
+    ("divu", R, 0x0200_5033),
+    ("rem", R, 0x0200_6033),
+    ("remu", R, 0x0200_7033),
+    // A
+    ("lr.w", Lr, 0x1000_202F),
+    ("sc.w", Amo, 0x1800_202F),
+    ("amoswap.w", Amo, 0x0800_202F),
+    ("amoadd.w", Amo, 0x0000_202F),
+    ("amoxor.w", Amo, 0x2000_202F),
+    ("amoand.w", Amo, 0x6000_202F),
+    ("amoor.w", Amo, 0x4000_202F),
+    ("amomin.w", Amo, 0x8000_202F),
+    ("amomax.w", Amo, 0xA000_202F),
+    ("amominu.w", Amo, 0xC000_202F),
+    ("amomaxu.w", Amo, 0xE000_202F),
+    // Zba
+    ("sh1add", R, 0x2000_2033),
+    ("sh2add", R, 0x2000_4033),
+    ("sh3add", R, 0x2000_6033),
+    // Zbb
+    ("andn", R, 0x4000_7033),
+    ("orn", R, 0x4000_6033),
+    ("xnor", R, 0x4000_4033),
+    ("min", R, 0x0A00_4033),
+    ("minu", R, 0x0A00_5033),
+    ("max", R, 0x0A00_6033),
+    ("maxu", R, 0x0A00_7033),
+    ("rol", R, 0x6000_1033),
+    ("ror", R, 0x6000_5033),
+    ("rori", Shift, 0x6000_5013),
+    ("clz", Unary, 0x6000_1013),
+    ("ctz", Unary, 0x6010_1013),
+    ("cpop", Unary, 0x6020_1013),
+    ("sext.b", Unary, 0x6040_1013),
+    ("sext.h", Unary, 0x6050_1013),
+    ("zext.h", Unary, 0x0800_4033),
+    ("rev8", Unary, 0x6980_5013),
+    ("orc.b", Unary, 0x2870_5013),
+    // Zbs
+    ("bclr", R, 0x4800_1033),
+    ("bext", R, 0x4800_5033),
+    ("binv", R, 0x6800_1033),
+    ("bset", R, 0x2800_1033),
+    ("bclri", Shift, 0x4800_1013),
+    ("bexti", Shift, 0x4800_5013),
+    ("binvi", Shift, 0x6800_1013),
+    ("bseti", Shift, 0x2800_1013),
+    // F
+    ("flw", FLoad, 0x0000_2007),
+    ("fsw", FStore, 0x0000_2027),
+    ("fadd.s", FP_FF_RM, 0x0000_0053),
+    ("fsub.s", FP_FF_RM, 0x0800_0053),
+    ("fmul.s", FP_FF_RM, 0x1000_0053),
+    ("fdiv.s", FP_FF_RM, 0x1800_0053),
+    ("fsqrt.s", Fp { rd: Reg::F, rs1: Reg::F, rs2: false, rm: true }, 0x5800_0053),
+    ("fsgnj.s", FP_FF, 0x2000_0053),
+    ("fsgnjn.s", FP_FF, 0x2000_1053),
+    ("fsgnjx.s", FP_FF, 0x2000_2053),
+    ("fmin.s", FP_FF, 0x2800_0053),
+    ("fmax.s", FP_FF, 0x2800_1053),
+    ("feq.s", FP_XFF, 0xA000_2053),
+    ("flt.s", FP_XFF, 0xA000_1053),
+    ("fle.s", FP_XFF, 0xA000_0053),
+    ("fclass.s", Fp { rd: Reg::X, rs1: Reg::F, rs2: false, rm: false }, 0xE000_1053),
+    ("fcvt.w.s", Fp { rd: Reg::X, rs1: Reg::F, rs2: false, rm: true }, 0xC000_0053),
+    ("fcvt.wu.s", Fp { rd: Reg::X, rs1: Reg::F, rs2: false, rm: true }, 0xC010_0053),
+    ("fcvt.s.w", Fp { rd: Reg::F, rs1: Reg::X, rs2: false, rm: true }, 0xD000_0053),
+    ("fcvt.s.wu", Fp { rd: Reg::F, rs1: Reg::X, rs2: false, rm: true }, 0xD010_0053),
+    ("fmv.x.w", Fp { rd: Reg::X, rs1: Reg::F, rs2: false, rm: false }, 0xE000_0053),
+    ("fmv.w.x", Fp { rd: Reg::F, rs1: Reg::X, rs2: false, rm: false }, 0xF000_0053),
+    ("fmadd.s", Fused, 0x0000_0043),
+    ("fmsub.s", Fused, 0x0000_0047),
+    ("fnmsub.s", Fused, 0x0000_004B),
+    ("fnmadd.s", Fused, 0x0000_004F),
+];
+
+/// Rounding mode operand names, by rm field value.
+const RM_NAMES: [&str; 8] = ["rne", "rtz", "rdn", "rup", "rmm", "", "", "dyn"];
+
+/// Dynamic rounding mode, used when none is written.
+const RM_DYN: u32 = 0b111;
+
+/// Parses an integer register name (x5, t0, fp).
+pub fn xreg(text: &str) -> Result<u32, String> {
+    let text = text.trim();
+    if text == "fp" {
+        return Ok(8);
+    }
+    let index = match text.strip_prefix('x') {
+        Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => digits.parse().ok(),
+        _ => X_ABI_NAMES.iter().position(|name| *name == text).map(|index| index as u32),
+    };
+    index.filter(|index| *index < 32).ok_or_else(|| format!("expected an integer register, found `{}`", text))
+}
+
+/// Parses a floating-point register name (f5, ft5, fa0).
+pub fn freg(text: &str) -> Result<u32, String> {
+    let text = text.trim();
+    let index = match F_ABI_NAMES.iter().position(|name| *name == text) {
+        Some(index) => Some(index as u32),
+        None => text.strip_prefix('f').filter(|d| !d.is_empty() && d.chars().all(|c| c.is_ascii_digit())).and_then(|d| d.parse().ok()),
+    };
+    index.filter(|index| *index < 32).ok_or_else(|| format!("expected a floating-point register, found `{}`", text))
+}
+
+/// Splits `imm(reg)` into the offset text (possibly empty) and the base register.
+fn mem(text: &str) -> Result<(&str, u32), String> {
+    let error = || format!("expected `offset(register)`, found `{}`", text);
+    let inner = text.strip_suffix(')').ok_or_else(error)?;
+    let open = inner.rfind('(').ok_or_else(error)?;
+    let base = xreg(&inner[open + 1..]).map_err(|_| error())?;
+    Ok((inner[..open].trim(), base))
+}
+
+/// Relocation operator in front of an operand: `%hi(x)` gives ("hi", "(x)").
+fn modifier(text: &str) -> Option<(&str, &str)> {
+    let rest = text.strip_prefix('%')?;
+    let len = rest.find('(')?;
+    Some((&rest[..len], &rest[len..]))
+}
+
+/// FENCE predecessor/successor set from "iorw" letters.
+fn fence_set(text: &str) -> Result<u32, String> {
+    if text == "0" {
+        return Ok(0);
+    }
+    let mut bits = 0;
+    for c in text.chars() {
+        let bit = match c {
+            'i' => 0b1000,
+            'o' => 0b0100,
+            'r' => 0b0010,
+            'w' => 0b0001,
+            _ => return Err(format!("bad fence set `{}`", text)),
+        };
+        bits |= bit;
+    }
+    Ok(bits)
+}
+
+fn operands<'a, const N: usize>(mnemonic: &str, ops: &[&'a str]) -> Result<[&'a str; N], String> {
+    <[&str; N]>::try_from(ops).map_err(|_| format!("{} takes {} operand(s), found {}", mnemonic, N, ops.len()))
+}
+
+fn fields(base: u32, rd: u32, rs1: u32, rs2: u32) -> u32 {
+    base | rd << 7 | rs1 << 15 | rs2 << 20
+}
+
+impl Assembler {
+    /// Encodes one instruction or pseudo-instruction.
+    pub(super) fn instruction(&mut self, mnemonic: &str, ops: &[&str]) -> Result<(), String> {
+        if self.pseudo(mnemonic, ops)? {
+            return Ok(());
+        }
+
+        // AMO ordering bits
+        let (name, ordering) = match [(".aqrl", 0b11), (".aq", 0b10), (".rl", 0b01)].iter().find_map(|(suffix, bits)| Some((mnemonic.strip_suffix(suffix)?, *bits))) {
+            Some((name, bits)) if name.starts_with("amo") || name == "lr.w" || name == "sc.w" => (name, bits << 25),
+            _ => (mnemonic, 0),
+        };
+        let Some(&(_, format, base)) = INSTRUCTIONS.iter().find(|(known, ..)| *known == name) else {
+            return Err(format!("unknown instruction `{}`", mnemonic));
+        };
+
+        match format {
+            R => {
+                let [rd, rs1, rs2] = operands(mnemonic, ops)?;
+                self.emit_word(fields(base, xreg(rd)?, xreg(rs1)?, xreg(rs2)?), None)
+            }
+            I => {
+                let [rd, rs1, imm] = operands(mnemonic, ops)?;
+                let fixup = self.i_imm(imm)?;
+                self.emit_word(fields(base, xreg(rd)?, xreg(rs1)?, 0), Some(fixup))
+            }
+            Shift => {
+                let [rd, rs1, shamt] = operands(mnemonic, ops)?;
+                let shamt = self.uimm5(shamt, "shift amount")?;
+                self.emit_word(fields(base, xreg(rd)?, xreg(rs1)?, shamt), None)
+            }
+            Unary => {
+                let [rd, rs1] = operands(mnemonic, ops)?;
+                self.emit_word(fields(base, xreg(rd)?, xreg(rs1)?, 0), None)
+            }
+            Load | FLoad => {
+                let [rd, addr] = operands(mnemonic, ops)?;
+                let rd = if format == Load { xreg(rd)? } else { freg(rd)? };
+                let (offset, rs1) = mem(addr)?;
+                let fixup = self.i_imm(offset)?;
+                self.emit_word(fields(base, rd, rs1, 0), Some(fixup))
+            }
+            Store | FStore => {
+                let [rs2, addr] = operands(mnemonic, ops)?;
+                let rs2 = if format == Store { xreg(rs2)? } else { freg(rs2)? };
+                let (offset, rs1) = mem(addr)?;
+                let fixup = self.s_imm(offset)?;
+                self.emit_word(fields(base, 0, rs1, rs2), Some(fixup))
+            }
+            Branch => {
+                let [rs1, rs2, target] = operands(mnemonic, ops)?;
+                let fixup = (FixupKind::Branch, self.expr(target)?);
+                self.emit_word(fields(base, 0, xreg(rs1)?, xreg(rs2)?), Some(fixup))
+            }
+            U => {
+                let [rd, imm] = operands(mnemonic, ops)?;
+                let fixup = match modifier(imm) {
+                    Some(("hi", inner)) if name == "lui" => (FixupKind::Hi20, self.expr(inner)?),
+                    Some((op, _)) => return Err(format!("%{} is not allowed in {}", op, mnemonic)),
+                    None => (FixupKind::Imm20U, self.expr(imm)?),
+                };
+                self.emit_word(fields(base, xreg(rd)?, 0, 0), Some(fixup))
+            }
+            Jal => {
+                let (rd, target) = match ops {
+                    [target] => (1, target),
+                    [rd, target] => (xreg(rd)?, target),
+                    _ => return Err(format!("jal takes 1 or 2 operands, found {}", ops.len())),
+                };
+                let fixup = (FixupKind::Jal, self.expr(target)?);
+                self.emit_word(fields(base, rd, 0, 0), Some(fixup))
+            }
+            Jalr => {
+                let (rd, rs1, offset) = match ops {
+                    [rs1] => (1, xreg(rs1)?, "0"),
+                    [rd, rs1] if xreg(rs1).is_ok() => (xreg(rd)?, xreg(rs1)?, "0"),
+                    [rd, addr] => {
+                        let (offset, rs1) = mem(addr)?;
+                        (xreg(rd)?, rs1, offset)
+                    }
+                    [rd, rs1, offset] => (xreg(rd)?, xreg(rs1)?, *offset),
+                    _ => return Err(format!("jalr takes 1 to 3 operands, found {}", ops.len())),
+                };
+                let fixup = self.i_imm(offset)?;
+                self.emit_word(fields(base, rd, rs1, 0), Some(fixup))
+            }
+            Csr | CsrImm => {
+                let [rd, csr, rs1] = operands(mnemonic, ops)?;
+                let rs1 = if format == Csr { xreg(rs1)? } else { self.uimm5(rs1, "CSR immediate")? };
+                self.emit_word(fields(base, xreg(rd)?, rs1, 0) | self.csr(csr)? << 20, None)
+            }
+            Amo => {
+                let [rd, rs2, addr] = operands(mnemonic, ops)?;
+                let rs1 = self.amo_addr(addr)?;
+                self.emit_word(fields(base | ordering, xreg(rd)?, rs1, xreg(rs2)?), None)
+            }
+            Lr => {
+                let [rd, addr] = operands(mnemonic, ops)?;
+                let rs1 = self.amo_addr(addr)?;
+                self.emit_word(fields(base | ordering, xreg(rd)?, rs1, 0), None)
+            }
+            Fixed => {
+                operands::<0>(mnemonic, ops)?;
+                self.emit_word(base, None)
+            }
+            Fence => {
+                let (pred, succ) = match ops {
+                    [] => (0b1111, 0b1111),
+                    [pred, succ] => (fence_set(pred)?, fence_set(succ)?),
+                    _ => return Err(format!("fence takes 0 or 2 operands, found {}", ops.len())),
+                };
+                self.emit_word(base | pred << 24 | succ << 20, None)
+            }
+            SfenceVma => {
+                let (rs1, rs2) = match ops {
+                    [] => (0, 0),
+                    [rs1] => (xreg(rs1)?, 0),
+                    [rs1, rs2] => (xreg(rs1)?, xreg(rs2)?),
+                    _ => return Err(format!("sfence.vma takes at most 2 operands, found {}", ops.len())),
+                };
+                self.emit_word(fields(base, 0, rs1, rs2), None)
+            }
+            Fp { rd, rs1, rs2, rm } => {
+                let count = 2 + rs2 as usize;
+                if ops.len() != count && !(rm && ops.len() == count + 1) {
+                    return Err(format!("{} takes {} operands, found {}", mnemonic, count, ops.len()));
+                }
+                let reg = |file: Reg, text: &str| if file == Reg::X { xreg(text) } else { freg(text) };
+                let rs2 = if rs2 { freg(ops[2])? } else { 0 };
+                let rm = if rm { rounding_mode(ops.get(count))? << 12 } else { 0 };
+                self.emit_word(fields(base | rm, reg(rd, ops[0])?, reg(rs1, ops[1])?, rs2), None)
+            }
+            Fused => {
+                if ops.len() != 4 && ops.len() != 5 {
+                    return Err(format!("{} takes 4 operands, found {}", mnemonic, ops.len()));
+                }
+                let rm = rounding_mode(ops.get(4))? << 12;
+                self.emit_word(fields(base | rm, freg(ops[0])?, freg(ops[1])?, freg(ops[2])?) | freg(ops[3])? << 27, None)
+            }
+        }
+    }
+
+    /// Expands a pseudo-instruction. False when `mnemonic` is not one.
+    fn pseudo(&mut self, mnemonic: &str, ops: &[&str]) -> Result<bool, String> {
+        match (mnemonic, ops) {
+            ("nop", &[]) => self.emit_word(NOP, None)?,
+            ("li", &[rd, value]) => self.li(xreg(rd)?, value)?,
+            ("la" | "lla", &[rd, target]) => {
+                let rd = xreg(rd)?;
+                let fixup = (FixupKind::PcrelPair, self.expr(target)?);
+                self.emit_word(fields(0x17, rd, 0, 0), Some(fixup))?; // auipc rd
+                self.emit_word(fields(0x13, rd, rd, 0), None)?; // addi rd, rd
+            }
+            ("call" | "tail", &[target]) => {
+                // call: auipc ra + jalr ra, ra; tail: auipc t1 + jalr x0, t1
+                let (rd, tmp) = if mnemonic == "call" { (1, 1) } else { (0, 6) };
+                let fixup = (FixupKind::Call, self.expr(target)?);
+                self.emit_word(fields(0x17, tmp, 0, 0), Some(fixup))?;
+                self.emit_word(fields(0x67, rd, tmp, 0), None)?;
+            }
+            ("mv", &[rd, rs]) => self.instruction("addi", &[rd, rs, "0"])?,
+            ("not", &[rd, rs]) => self.instruction("xori", &[rd, rs, "-1"])?,
+            ("neg", &[rd, rs]) => self.instruction("sub", &[rd, "x0", rs])?,
+            ("seqz", &[rd, rs]) => self.instruction("sltiu", &[rd, rs, "1"])?,
+            ("snez", &[rd, rs]) => self.instruction("sltu", &[rd, "x0", rs])?,
+            ("j", &[target]) => self.instruction("jal", &["x0", target])?,
+            ("jr", &[rs]) => self.instruction("jalr", &["x0", rs])?,
+            ("ret", &[]) => self.instruction("jalr", &["x0", "x1"])?,
+            ("beqz", &[rs, target]) => self.instruction("beq", &[rs, "x0", target])?,
+            ("bnez", &[rs, target]) => self.instruction("bne", &[rs, "x0", target])?,
+            ("bltz", &[rs, target]) => self.instruction("blt", &[rs, "x0", target])?,
+            ("bgez", &[rs, target]) => self.instruction("bge", &[rs, "x0", target])?,
+            ("blez", &[rs, target]) => self.instruction("bge", &["x0", rs, target])?,
+            ("bgtz", &[rs, target]) => self.instruction("blt", &["x0", rs, target])?,
+            ("bgt", &[rs, rt, target]) => self.instruction("blt", &[rt, rs, target])?,
+            ("ble", &[rs, rt, target]) => self.instruction("bge", &[rt, rs, target])?,
+            ("bgtu", &[rs, rt, target]) => self.instruction("bltu", &[rt, rs, target])?,
+            ("bleu", &[rs, rt, target]) => self.instruction("bgeu", &[rt, rs, target])?,
+            ("csrr", &[rd, csr]) => self.instruction("csrrs", &[rd, csr, "x0"])?,
+            ("csrw", &[csr, rs]) => self.instruction("csrrw", &["x0", csr, rs])?,
+            ("csrs", &[csr, rs]) => self.instruction("csrrs", &["x0", csr, rs])?,
+            ("csrc", &[csr, rs]) => self.instruction("csrrc", &["x0", csr, rs])?,
+            ("csrwi", &[csr, imm]) => self.instruction("csrrwi", &["x0", csr, imm])?,
+            ("csrsi", &[csr, imm]) => self.instruction("csrrsi", &["x0", csr, imm])?,
+            ("csrci", &[csr, imm]) => self.instruction("csrrci", &["x0", csr, imm])?,
+            ("fmv.s", &[rd, rs]) => self.instruction("fsgnj.s", &[rd, rs, rs])?,
+            ("fneg.s", &[rd, rs]) => self.instruction("fsgnjn.s", &[rd, rs, rs])?,
+            ("fabs.s", &[rd, rs]) => self.instruction("fsgnjx.s", &[rd, rs, rs])?,
+            ("fmv.x.s", _) => self.instruction("fmv.x.w", ops)?,
+            ("fmv.s.x", _) => self.instruction("fmv.w.x", ops)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// `li rd, value`: ADDI, LUI or LUI+ADDI for a known value, LUI+ADDI
+    /// with %hi/%lo otherwise.
+    fn li(&mut self, rd: u32, text: &str) -> Result<(), String> {
+        let expr = self.expr(text)?;
+        let Ok(Value::Abs(value)) = self.obj.eval(&expr) else {
+            self.emit_word(fields(0x37, rd, 0, 0), Some((FixupKind::Hi20, expr.clone())))?;
+            return self.emit_word(fields(0x13, rd, rd, 0), Some((FixupKind::Lo12I, expr)));
+        };
+        let value = i32_of(value).ok_or_else(|| format!("li value {} does not fit in 32 bits", value))?;
+        if (-2048..2048).contains(&value) {
+            return self.emit_word(fields(0x13, rd, 0, 0) | (value as u32) << 20, None);
+        }
+        let (hi, lo) = hi_lo(value);
+        self.emit_word(fields(0x37, rd, 0, 0) | hi << 12, None)?;
+        if lo != 0 {
+            self.emit_word(fields(0x13, rd, rd, 0) | (lo as u32) << 20, None)?;
+        }
+        Ok(())
+    }
+
+    /// I-type immediate: a constant or `%lo(symbol)`.
+    fn i_imm(&self, text: &str) -> Result<(FixupKind, Expr), String> {
+        match modifier(text) {
+            Some(("lo", inner)) => Ok((FixupKind::Lo12I, self.expr(inner)?)),
+            Some((op, _)) => Err(format!("%{} is not allowed in a 12-bit immediate", op)),
+            None if text.is_empty() => Ok((FixupKind::Imm12I, Expr::Num(0))),
+            None => Ok((FixupKind::Imm12I, self.expr(text)?)),
+        }
+    }
+
+    /// S-type immediate: a constant or `%lo(symbol)`.
+    fn s_imm(&self, text: &str) -> Result<(FixupKind, Expr), String> {
+        match self.i_imm(text)? {
+            (FixupKind::Lo12I, expr) => Ok((FixupKind::Lo12S, expr)),
+            (_, expr) => Ok((FixupKind::Imm12S, expr)),
+        }
+    }
+
+    fn uimm5(&self, text: &str, what: &str) -> Result<u32, String> {
+        let value = self.constant(text, what)?;
+        if !(0..32).contains(&value) {
+            return Err(format!("{} {} out of range [0, 31]", what, value));
+        }
+        Ok(value as u32)
+    }
+
+    /// CSR operand: a name the disassembler prints or a 12-bit number.
+    fn csr(&self, text: &str) -> Result<u32, String> {
+        if let Some(addr) = (0..0x1000 as csr_addr_t).find(|addr| csr_name(*addr) == text) {
+            return Ok(addr as u32);
+        }
+        let value = self.constant(text, "CSR")?;
+        if !(0..0x1000).contains(&value) {
+            return Err(format!("CSR address {} out of range", value));
+        }
+        Ok(value as u32)
+    }
+
+    /// `(rs1)` of an AMO; an explicit offset must be zero.
+    fn amo_addr(&self, text: &str) -> Result<u32, String> {
+        let (offset, rs1) = mem(text)?;
+        if !offset.is_empty() && self.constant(offset, "AMO offset")? != 0 {
+            return Err("AMO address offset must be 0".to_string());
+        }
+        Ok(rs1)
+    }
+}
+
+/// rm field of an optional rounding mode operand (DYN when absent).
+fn rounding_mode(text: Option<&&str>) -> Result<u32, String> {
+    let Some(text) = text else {
+        return Ok(RM_DYN);
+    };
+    match RM_NAMES.iter().position(|name| !name.is_empty() && name == text) {
+        Some(rm) => Ok(rm as u32),
+        None => Err(format!("unknown rounding mode `{}`", text)),
+    }
+}
@@ -0,0 +1,686 @@
+// ============================================================
+// LX32 Assembler
+// ============================================================
+// Turns LX32 assembly into flat binaries and ELF relocatable
+// objects, so test programs and crt0.S can be built without
+// the LLVM backend in tools/lx32_backend.
+//
+// A single pass over the source fills each section with
+// encoded words. Fields that depend on a symbol are left zero
+// and recorded as fixups. Once the whole file is read, every
+// fixup that does not depend on where sections end up (numbers,
+// branches inside one section, label differences) is patched.
+// The remaining ones are resolved against final addresses for
+// a flat binary, or become ELF relocations.
+//
+// Design Principles:
+//   - GNU as syntax for the subset the repo uses: labels,
+//     section and data directives, %hi/%lo, ABI register names
+//     and the usual pseudo-instructions.
+//   - Instruction sizes never depend on symbol values, so one
+//     pass is enough.
+//   - A numeric branch or jump operand is a PC-relative offset,
+//     like `jal x1, 16` in crt0.S.
+//   - Disassembler output assembles back to the same word.
+// ============================================================
+
+mod elf;
+mod expr;
+mod instr;
+
+pub use expr::{Base, Expr, Value};
+
+use expr::{is_symbol_char, is_symbol_start, parse_expr, parse_number, unescape};
+use std::fmt;
+
+/// Assembly error, with the 1-based source line it comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for AsmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for AsmError {}
+
+/// Field of the code or data a fixup patches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixupKind {
+    /// I-type immediate (constant)
+    Imm12I,
+    /// S-type immediate (constant)
+    Imm12S,
+    /// LUI/AUIPC immediate (constant)
+    Imm20U,
+    /// B-type PC-relative offset
+    Branch,
+    /// J-type PC-relative offset
+    Jal,
+    /// `%hi(expr)` in a LUI
+    Hi20,
+    /// `%lo(expr)` in an I-type instruction
+    Lo12I,
+    /// `%lo(expr)` in an S-type instruction
+    Lo12S,
+    /// AUIPC + ADDI pair of `la`
+    PcrelPair,
+    /// AUIPC + JALR pair of `call` and `tail`
+    Call,
+    /// `.word`
+    Data32,
+    /// `.half`
+    Data16,
+    /// `.byte`
+    Data8,
+}
+
+impl FixupKind {
+    /// True when the encoded value is an offset from the fixup itself.
+    pub fn is_pc_relative(self) -> bool {
+        matches!(self, Self::Branch | Self::Jal | Self::PcrelPair | Self::Call)
+    }
+}
+
+/// Field left for later: `expr` written at `offset` of its section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixup {
+    pub offset: u32,
+    pub kind: FixupKind,
+    pub expr: Expr,
+    pub line: usize,
+}
+
+/// One output section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    pub data: Vec<u8>,
+    /// Alignment in bytes (a power of two)
+    pub align: u32,
+    pub write: bool,
+    pub exec: bool,
+    /// Occupies no file space (.bss): its data must be all zeros
+    pub nobits: bool,
+    /// Fields still waiting for final addresses
+    pub fixups: Vec<Fixup>,
+}
+
+impl Section {
+    /// Empty section with the flags its name implies.
+    fn new(name: &str) -> Self {
+        let exec = name == ".text" || name.starts_with(".text.");
+        let nobits = [".bss", ".sbss"].iter().any(|bss| name == *bss || name.starts_with(&format!("{}.", bss)));
+        let write = nobits || [".data", ".sdata"].iter().any(|data| name == *data || name.starts_with(&format!("{}.", data)));
+        Self { name: name.to_string(), data: Vec::new(), align: if exec { 4 } else { 1 }, write, exec, nobits, fixups: Vec::new() }
+    }
+}
+
+/// Where a symbol is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolDef {
+    Undefined,
+    /// `.equ`/`.set` constant
+    Absolute(i64),
+    Label { section: usize, offset: u32 },
+}
+
+/// `.type` of a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    NoType,
+    Func,
+    Object,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub def: SymbolDef,
+    pub global: bool,
+    pub kind: SymbolKind,
+    /// `.size`, or 0
+    pub size: u32,
+}
+
+/// Assembled translation unit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Object {
+    pub sections: Vec<Section>,
+    pub symbols: Vec<Symbol>,
+}
+
+/// Assembles `source` into an object.
+pub fn assemble(source: &str) -> Result<Object, AsmError> {
+    let mut asm = Assembler::default();
+    for (index, line) in source.lines().enumerate() {
+        asm.line = index + 1;
+        asm.statement(line).map_err(|message| AsmError { line: asm.line, message })?;
+    }
+    asm.finish()
+}
+
+impl Object {
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    /// Value of a symbol for expression evaluation.
+    fn lookup(&self, name: &str) -> Value {
+        match self.symbol(name).map(|symbol| symbol.def) {
+            Some(SymbolDef::Absolute(value)) => Value::Abs(value),
+            Some(SymbolDef::Label { section, offset }) => Value::Rel(Base::Section(section), offset as i64),
+            _ => Value::Rel(Base::Extern(name.to_string()), 0),
+        }
+    }
+
+    fn eval(&self, expr: &Expr) -> Result<Value, String> {
+        expr.eval(&|name| self.lookup(name))
+    }
+
+    /// Load address of every section when the flat image starts at `base`:
+    /// sections follow each other in source order, each at its alignment.
+    pub fn layout(&self, base: u32) -> Vec<u32> {
+        let mut addr = base;
+        let mut addrs = Vec::new();
+        for section in &self.sections {
+            addr = addr.next_multiple_of(section.align);
+            addrs.push(addr);
+            addr += section.data.len() as u32;
+        }
+        addrs
+    }
+
+    /// Address of a label in the flat image starting at `base`.
+    pub fn symbol_address(&self, name: &str, base: u32) -> Option<u32> {
+        match self.symbol(name)?.def {
+            SymbolDef::Label { section, offset } => Some(self.layout(base)[section] + offset),
+            SymbolDef::Absolute(value) => Some(value as u32),
+            SymbolDef::Undefined => None,
+        }
+    }
+
+    /// Flat binary image loaded at `base`. Every symbol must be defined.
+    pub fn to_flat(&self, base: u32) -> Result<Vec<u8>, AsmError> {
+        let addrs = self.layout(base);
+        let mut image = Vec::new();
+        for (section, addr) in self.sections.iter().zip(&addrs) {
+            image.resize((addr - base) as usize, 0);
+            let mut data = section.data.clone();
+            for fixup in &section.fixups {
+                let error = |message| AsmError { line: fixup.line, message };
+                let target = match self.eval(&fixup.expr).map_err(error)? {
+                    Value::Abs(value) => value,
+                    Value::Rel(Base::Section(index), offset) => addrs[index] as i64 + offset,
+                    Value::Rel(Base::Extern(name), _) => return Err(error(format!("undefined symbol `{}`", name))),
+                };
+                let value = if fixup.kind.is_pc_relative() { target - (addr + fixup.offset) as i64 } else { target };
+                apply(&mut data, fixup, value).map_err(error)?;
+            }
+            image.extend(data);
+        }
+        Ok(image)
+    }
+
+    /// ELF32 relocatable object (EM_RISCV, like the LLVM backend emits).
+    pub fn to_elf(&self) -> Result<Vec<u8>, AsmError> {
+        elf::write(self)
+    }
+}
+
+/// Patches `value` into the field `fixup` names, checking its range.
+pub fn apply(data: &mut [u8], fixup: &Fixup, value: i64) -> Result<(), String> {
+    let at = fixup.offset as usize;
+    let value = i32_of(value).ok_or_else(|| format!("value {} does not fit in 32 bits", value))?;
+    let word = |data: &[u8], at: usize| u32::from_le_bytes([data[at], data[at + 1], data[at + 2], data[at + 3]]);
+    let mut put = |at: usize, bits: u32| {
+        let patched = word(data, at) | bits;
+        data[at..at + 4].copy_from_slice(&patched.to_le_bytes());
+    };
+    let (hi, lo) = hi_lo(value);
+
+    match fixup.kind {
+        FixupKind::Imm12I => put(at, (check_signed(value, 12, "immediate")? as u32 & 0xFFF) << 20),
+        FixupKind::Imm12S => put(at, s_field(check_signed(value, 12, "immediate")?)),
+        FixupKind::Imm20U => {
+            if !(0..=0xFFFFF).contains(&(value as u32)) {
+                return Err(format!("immediate 0x{:x} does not fit in 20 bits", value as u32));
+            }
+            put(at, (value as u32) << 12)
+        }
+        FixupKind::Branch => {
+            let offset = check_signed(value, 13, "branch offset")?;
+            check_even(offset)?;
+            let imm = offset as u32;
+            put(at, ((imm >> 12) & 1) << 31 | ((imm >> 5) & 0x3F) << 25 | ((imm >> 1) & 0xF) << 8 | ((imm >> 11) & 1) << 7)
+        }
+        FixupKind::Jal => {
+            let offset = check_signed(value, 21, "jump offset")?;
+            check_even(offset)?;
+            let imm = offset as u32;
+            put(at, ((imm >> 20) & 1) << 31 | ((imm >> 1) & 0x3FF) << 21 | ((imm >> 11) & 1) << 20 | ((imm >> 12) & 0xFF) << 12)
+        }
+        FixupKind::Hi20 => put(at, hi << 12),
+        FixupKind::Lo12I => put(at, (lo as u32 & 0xFFF) << 20),
+        FixupKind::Lo12S => put(at, s_field(lo)),
+        FixupKind::PcrelPair | FixupKind::Call => {
+            put(at, hi << 12);
+            put(at + 4, (lo as u32 & 0xFFF) << 20);
+        }
+        FixupKind::Data32 => data[at..at + 4].copy_from_slice(&value.to_le_bytes()),
+        FixupKind::Data16 => {
+            if !(-0x8000..=0xFFFF).contains(&value) {
+                return Err(format!("value {} does not fit in 16 bits", value));
+            }
+            data[at..at + 2].copy_from_slice(&(value as u16).to_le_bytes());
+        }
+        FixupKind::Data8 => {
+            if !(-0x80..=0xFF).contains(&value) {
+                return Err(format!("value {} does not fit in 8 bits", value));
+            }
+            data[at] = value as u8;
+        }
+    }
+    Ok(())
+}
+
+/// `value` as a 32-bit word: signed values and unsigned ones up to
+/// 0xFFFF_FFFF are both accepted (0xFFFF_FFFF is -1).
+fn i32_of(value: i64) -> Option<i32> {
+    (-(1i64 << 31)..(1i64 << 32)).contains(&value).then_some(value as u32 as i32)
+}
+
+/// %hi and %lo of `value`: hi << 12 plus the sign-extended lo gives it back.
+pub fn hi_lo(value: i32) -> (u32, i32) {
+    let hi = (value as u32).wrapping_add(0x800) >> 12;
+    (hi, value.wrapping_sub((hi << 12) as i32))
+}
+
+fn check_signed(value: i32, bits: u32, what: &str) -> Result<i32, String> {
+    let limit = 1i32 << (bits - 1);
+    if (-limit..limit).contains(&value) { Ok(value) } else { Err(format!("{} {} out of range [{}, {}]", what, value, -limit, limit - 1)) }
+}
+
+fn check_even(offset: i32) -> Result<(), String> {
+    if offset % 2 == 0 { Ok(()) } else { Err(format!("offset {} is not a multiple of 2", offset)) }
+}
+
+fn s_field(imm: i32) -> u32 {
+    let imm = imm as u32;
+    ((imm >> 5) & 0x7F) << 25 | (imm & 0x1F) << 7
+}
+
+/// Source-to-object state.
+#[derive(Default)]
+struct Assembler {
+    obj: Object,
+    current: Option<usize>,
+    /// `.size` directives, evaluated once every label is known
+    sizes: Vec<(String, Expr, usize)>,
+    line: usize,
+}
+
+impl Assembler {
+    fn statement(&mut self, line: &str) -> Result<(), String> {
+        let mut rest = strip_comment(line).trim();
+
+        // Any number of `label:` prefixes
+        while let Some(len) = label_len(rest) {
+            self.define_label(&rest[..len])?;
+            rest = rest[len + 1..].trim_start();
+        }
+        if rest.is_empty() {
+            return Ok(());
+        }
+
+        let (head, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let operands = split_operands(tail.trim());
+        if head.starts_with('.') {
+            self.directive(head, &operands, tail.trim())
+        } else {
+            // Open .text first, so `.` in an operand has a location
+            self.section();
+            self.instruction(&head.to_ascii_lowercase(), &operands)
+        }
+    }
+
+    /// Index of the current section, opening `.text` when there is none.
+    fn section(&mut self) -> usize {
+        match self.current {
+            Some(index) => index,
+            None => self.switch_section(".text"),
+        }
+    }
+
+    fn switch_section(&mut self, name: &str) -> usize {
+        let index = match self.obj.sections.iter().position(|section| section.name == name) {
+            Some(index) => index,
+            None => {
+                self.obj.sections.push(Section::new(name));
+                self.obj.sections.len() - 1
+            }
+        };
+        self.current = Some(index);
+        index
+    }
+
+    /// Current location, if a section is open.
+    fn here(&self) -> Option<(usize, u32)> {
+        self.current.map(|index| (index, self.obj.sections[index].data.len() as u32))
+    }
+
+    fn expr(&self, text: &str) -> Result<Expr, String> {
+        parse_expr(text, self.here())
+    }
+
+    /// Value of `text` using the symbols defined so far.
+    fn constant(&self, text: &str, what: &str) -> Result<i64, String> {
+        self.obj.eval(&self.expr(text)?)?.absolute(what)
+    }
+
+    fn symbol_mut(&mut self, name: &str) -> Result<&mut Symbol, String> {
+        if !name.starts_with(is_symbol_start) || !name.chars().all(is_symbol_char) || name == "." {
+            return Err(format!("bad symbol name `{}`", name));
+        }
+        let index = match self.obj.symbols.iter().position(|symbol| symbol.name == name) {
+            Some(index) => index,
+            None => {
+                self.obj.symbols.push(Symbol { name: name.to_string(), def: SymbolDef::Undefined, global: false, kind: SymbolKind::NoType, size: 0 });
+                self.obj.symbols.len() - 1
+            }
+        };
+        Ok(&mut self.obj.symbols[index])
+    }
+
+    fn define(&mut self, name: &str, def: SymbolDef) -> Result<(), String> {
+        let symbol = self.symbol_mut(name)?;
+        if symbol.def != SymbolDef::Undefined {
+            return Err(format!("symbol `{}` is already defined", name));
+        }
+        symbol.def = def;
+        Ok(())
+    }
+
+    fn define_label(&mut self, name: &str) -> Result<(), String> {
+        let section = self.section();
+        let offset = self.obj.sections[section].data.len() as u32;
+        self.define(name, SymbolDef::Label { section, offset })
+    }
+
+    fn emit(&mut self, bytes: &[u8]) -> Result<(), String> {
+        let index = self.section();
+        let section = &mut self.obj.sections[index];
+        if section.nobits && bytes.iter().any(|byte| *byte != 0) {
+            return Err(format!("{} can only hold zeros", section.name));
+        }
+        section.data.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Appends `word`, with `fixup` patched into it later.
+    fn emit_word(&mut self, word: u32, fixup: Option<(FixupKind, Expr)>) -> Result<(), String> {
+        self.emit_fixup(fixup);
+        self.emit(&word.to_le_bytes())
+    }
+
+    fn emit_fixup(&mut self, fixup: Option<(FixupKind, Expr)>) {
+        let index = self.section();
+        let section = &mut self.obj.sections[index];
+        if let Some((kind, expr)) = fixup {
+            section.fixups.push(Fixup { offset: section.data.len() as u32, kind, expr, line: self.line });
+        }
+    }
+
+    /// Pads to a multiple of `align` bytes, with NOPs in code.
+    fn align(&mut self, align: u32) -> Result<(), String> {
+        if !align.is_power_of_two() {
+            return Err(format!("alignment {} is not a power of two", align));
+        }
+        let index = self.section();
+        let section = &mut self.obj.sections[index];
+        section.align = section.align.max(align);
+        while !section.data.len().is_multiple_of(align as usize) {
+            if section.exec && section.data.len().is_multiple_of(4) {
+                section.data.extend_from_slice(&instr::NOP.to_le_bytes());
+            } else {
+                section.data.push(0);
+            }
+        }
+        Ok(())
+    }
+
+    fn directive(&mut self, name: &str, operands: &[&str], raw: &str) -> Result<(), String> {
+        let count = |n: usize| if operands.len() == n { Ok(()) } else { Err(format!("{} takes {} operand(s)", name, n)) };
+        match name {
+            ".text" | ".data" | ".bss" | ".rodata" => {
+                count(0)?;
+                self.switch_section(name);
+            }
+            ".section" => {
+                let Some(section_name) = operands.first() else {
+                    return Err(".section needs a name".to_string());
+                };
+                let index = self.switch_section(section_name);
+                // Optional "flags" and @type override what the name implies
+                let section = &mut self.obj.sections[index];
+                if let Some(flags) = operands.get(1) {
+                    let flags = flags.trim_matches('"');
+                    section.write = flags.contains('w');
+                    section.exec = flags.contains('x');
+                    section.align = if section.exec { section.align.max(4) } else { section.align };
+                }
+                if let Some(kind) = operands.get(2) {
+                    section.nobits = kind.trim_start_matches(['@', '%']) == "nobits";
+                }
+            }
+            ".globl" | ".global" => {
+                for operand in operands {
+                    self.symbol_mut(operand)?.global = true;
+                }
+            }
+            ".local" | ".extern" => {
+                for operand in operands {
+                    self.symbol_mut(operand)?;
+                }
+            }
+            ".type" => {
+                count(2)?;
+                let kind = match operands[1].trim_start_matches(['@', '%']) {
+                    "function" => SymbolKind::Func,
+                    "object" => SymbolKind::Object,
+                    "notype" => SymbolKind::NoType,
+                    other => return Err(format!("unknown symbol type `{}`", other)),
+                };
+                self.symbol_mut(operands[0])?.kind = kind;
+            }
+            ".size" => {
+                count(2)?;
+                self.symbol_mut(operands[0])?;
+                let expr = self.expr(operands[1])?;
+                self.sizes.push((operands[0].to_string(), expr, self.line));
+            }
+            ".equ" | ".set" => {
+                count(2)?;
+                let def = match self.obj.eval(&self.expr(operands[1])?)? {
+                    Value::Abs(value) => SymbolDef::Absolute(value),
+                    Value::Rel(Base::Section(section), offset) => SymbolDef::Label { section, offset: offset as u32 },
+                    Value::Rel(Base::Extern(other), _) => return Err(format!("`{}` is not defined yet", other)),
+                };
+                self.define(operands[0], def)?;
+            }
+            ".word" | ".long" | ".4byte" => self.data(operands, FixupKind::Data32, 4)?,
+            ".half" | ".short" | ".2byte" => self.data(operands, FixupKind::Data16, 2)?,
+            ".byte" => self.data(operands, FixupKind::Data8, 1)?,
+            ".ascii" | ".asciz" | ".string" => {
+                let mut bytes = parse_string(raw)?;
+                if name != ".ascii" {
+                    bytes.push(0);
+                }
+                self.emit(&bytes)?;
+            }
+            ".zero" | ".space" | ".skip" => {
+                if operands.is_empty() || operands.len() > 2 {
+                    return Err(format!("{} takes a size and an optional fill byte", name));
+                }
+                let size = self.constant(operands[0], name)?;
+                let fill = match operands.get(1) {
+                    Some(fill) => self.constant(fill, name)? as u8,
+                    None => 0,
+                };
+                let size = usize::try_from(size).map_err(|_| format!("negative size {}", size))?;
+                self.emit(&vec![fill; size])?;
+            }
+            // GNU as for RISC-V: `.align n` means 2^n bytes, like `.p2align`
+            ".align" | ".p2align" => {
+                count(1)?;
+                let log2 = self.constant(operands[0], name)?;
+                if !(0..=12).contains(&log2) {
+                    return Err(format!("alignment 2^{} out of range", log2));
+                }
+                self.align(1 << log2)?;
+            }
+            ".balign" => {
+                count(1)?;
+                let align = self.constant(operands[0], name)?;
+                self.align(u32::try_from(align).map_err(|_| format!("bad alignment {}", align))?)?;
+            }
+            // No effect on a flat, non-relaxing assembler
+            ".option" | ".file" | ".ident" | ".attribute" | ".addrsig" | ".cfi_startproc" | ".cfi_endproc" => {}
+            _ => return Err(format!("unknown directive {}", name)),
+        }
+        Ok(())
+    }
+
+    fn data(&mut self, operands: &[&str], kind: FixupKind, size: usize) -> Result<(), String> {
+        if operands.is_empty() {
+            return Err("missing value".to_string());
+        }
+        let index = self.section();
+        for operand in operands {
+            let expr = self.expr(operand)?;
+            if self.obj.sections[index].nobits {
+                // A fixup would be patched after emit() checks for zeros
+                if self.obj.eval(&expr)? != Value::Abs(0) {
+                    return Err(format!("{} can only hold zeros", self.obj.sections[index].name));
+                }
+            } else {
+                self.emit_fixup(Some((kind, expr)));
+            }
+            self.emit(&vec![0; size])?;
+        }
+        Ok(())
+    }
+
+    /// Applies `.size`, patches every fixup that is already known and
+    /// keeps the rest for `to_flat`/`to_elf`.
+    fn finish(mut self) -> Result<Object, AsmError> {
+        for (name, expr, line) in std::mem::take(&mut self.sizes) {
+            let error = |message| AsmError { line, message };
+            let size = self.obj.eval(&expr).and_then(|value| value.absolute(".size")).map_err(error)?;
+            self.obj.symbols.iter_mut().find(|symbol| symbol.name == name).expect("declared by .size").size = size as u32;
+        }
+
+        let mut obj = self.obj;
+        for index in 0..obj.sections.len() {
+            let mut pending = Vec::new();
+            for fixup in std::mem::take(&mut obj.sections[index].fixups) {
+                let error = |message| AsmError { line: fixup.line, message };
+                let value = match obj.eval(&fixup.expr).map_err(error)? {
+                    Value::Abs(value) => Some(value),
+                    Value::Rel(Base::Section(section), offset) if section == index && fixup.kind.is_pc_relative() => Some(offset - fixup.offset as i64),
+                    Value::Rel(..) if matches!(fixup.kind, FixupKind::Imm12I | FixupKind::Imm12S | FixupKind::Imm20U) => {
+                        return Err(error("immediate needs a constant; use %hi/%lo for addresses".to_string()));
+                    }
+                    Value::Rel(..) => None,
+                };
+                match value {
+                    Some(value) => apply(&mut obj.sections[index].data, &fixup, value).map_err(error)?,
+                    None => pending.push(fixup),
+                }
+            }
+            obj.sections[index].fixups = pending;
+        }
+        Ok(obj)
+    }
+}
+
+/// `line` without its `#` comment.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = None;
+    let mut escaped = false;
+    for (pos, c) in line.char_indices() {
+        match (quoted, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), _) if c == q => quoted = None,
+            (None, '"' | '\'') => quoted = Some(c),
+            (None, '#') => return &line[..pos],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Length of the label name when `text` starts with `name:`.
+fn label_len(text: &str) -> Option<usize> {
+    let len = text.find(|c: char| !is_symbol_char(c))?;
+    (len > 0 && text.starts_with(is_symbol_start) && text[len..].starts_with(':')).then_some(len)
+}
+
+/// Splits operands on commas outside parentheses and quotes.
+fn split_operands(text: &str) -> Vec<&str> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let mut operands = Vec::new();
+    let (mut depth, mut quoted, mut start) = (0, false, 0);
+    for (pos, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                operands.push(text[start..pos].trim());
+                start = pos + 1;
+            }
+            _ => {}
+        }
+    }
+    operands.push(text[start..].trim());
+    operands
+}
+
+/// Bytes of one or more comma-separated string literals.
+fn parse_string(text: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    for literal in split_operands(text) {
+        let inner = literal.strip_prefix('"').and_then(|l| l.strip_suffix('"')).ok_or_else(|| format!("expected a string, found `{}`", literal))?;
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                let mut buf = [0; 4];
+                bytes.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                continue;
+            }
+            let escape = chars.next().ok_or("string ends in `\\`")?;
+            bytes.push(unescape(escape).ok_or_else(|| format!("unknown escape `\\{}`", escape))?);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Parses a number literal, for command-line options.
+pub fn parse_int(text: &str) -> Option<i64> {
+    match text.strip_prefix('-') {
+        Some(digits) => parse_number(digits).map(|value| -value),
+        None => parse_number(text),
+    }
+}
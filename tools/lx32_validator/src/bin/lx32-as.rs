@@ -0,0 +1,86 @@
+use clap::{Parser, ValueEnum};
+use lx32_validator::asm::{assemble, parse_int};
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::disasm::Disassembler;
+use std::fs;
+use std::process::ExitCode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Raw image, sections back to back from --base
+    Bin,
+    /// ELF32 relocatable object for ld.lld
+    Elf,
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "lx32-as")]
+#[command(about = "Assembles LX32 assembly into a flat binary or an ELF object", long_about = None)]
+struct Args {
+    /// Assembly source file
+    input: String,
+
+    /// Output file
+    #[arg(short, long)]
+    output: String,
+
+    /// Output format (default: elf for .o/.elf outputs, bin otherwise)
+    #[arg(short, long, value_enum)]
+    format: Option<Format>,
+
+    /// Load address of a flat binary
+    #[arg(short, long, default_value = "0", value_parser = parse_address)]
+    base: u32,
+
+    /// Print a listing of the flat image (address, word, disassembly)
+    #[arg(short, long)]
+    listing: bool,
+}
+
+fn parse_address(text: &str) -> Result<u32, String> {
+    parse_int(text).and_then(|value| u32::try_from(value).ok()).ok_or_else(|| format!("bad address `{}`", text))
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let source = match fs::read_to_string(&args.input) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{}: {}", args.input, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let format = args.format.unwrap_or(if args.output.ends_with(".o") || args.output.ends_with(".elf") { Format::Elf } else { Format::Bin });
+    let result = assemble(&source).and_then(|obj| {
+        if args.listing {
+            let image = obj.to_flat(args.base)?;
+            // Nothing here is compressed: show every word whole
+            let disassembler = Disassembler::new(IsaConfig { c_ext: false, ..IsaConfig::full() });
+            for (index, chunk) in image.chunks(4).enumerate() {
+                let addr = args.base + 4 * index as u32;
+                let word = chunk.iter().rev().fold(0u32, |word, byte| word << 8 | *byte as u32);
+                println!("{:08x}: {:08x}  {}", addr, word, disassembler.disasm(word, addr));
+            }
+        }
+        match format {
+            Format::Bin => obj.to_flat(args.base),
+            Format::Elf => obj.to_elf(),
+        }
+    });
+
+    let bytes = match result {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            eprintln!("{}:{}: {}", args.input, err.line, err.message);
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(err) = fs::write(&args.output, &bytes) {
+        eprintln!("{}: {}", args.output, err);
+        return ExitCode::FAILURE;
+    }
+    println!("Wrote {} bytes to {}", bytes.len(), args.output);
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,134 @@
+use clap::{Parser, ValueEnum};
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{MemOp, StepStatus};
+use lx32_validator::models::disasm::Disassembler;
+use lx32_validator::sim::{EXIT_PORTS, Image, SIM_MEMORY_SIZE, Simulator, Stop, exit_status};
+use std::fs;
+use std::process::ExitCode;
+
+/// Exit status when the program never reached an exit port (as `timeout` does).
+const NO_EXIT_STATUS: u8 = 124;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Isa {
+    /// Profile implemented by the RTL core (no CSRs or traps)
+    Base,
+    Rv32i,
+    Rv32im,
+    Rv32imc,
+    Rv32imac,
+    /// Every extension the golden model implements
+    Full,
+}
+
+impl Isa {
+    fn config(self) -> IsaConfig {
+        match self {
+            Isa::Base => IsaConfig::base(),
+            Isa::Rv32i => IsaConfig::rv32i(),
+            Isa::Rv32im => IsaConfig::rv32im(),
+            Isa::Rv32imc => IsaConfig::rv32imc(),
+            Isa::Rv32imac => IsaConfig::rv32imac(),
+            Isa::Full => IsaConfig::full(),
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "lx32-sim")]
+#[command(about = "Runs a flat binary or ELF executable on the LX32 golden model (no RTL needed)", long_about = None)]
+struct Args {
+    /// Flat binary (loaded and entered at 0) or linked ELF executable
+    binary: String,
+
+    /// Maximum clock cycles to simulate
+    #[arg(short = 'm', long, default_value_t = 1000000)]
+    max_cycles: u64,
+
+    /// Verbose cycle-by-cycle logging
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Use ABI register names (ra, sp, a0...) in the verbose disassembly
+    #[arg(long)]
+    abi_names: bool,
+
+    /// ISA profile of the golden model
+    #[arg(long, value_enum, default_value = "full")]
+    isa: Isa,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let image = match fs::read(&args.binary).map_err(|err| err.to_string()).and_then(|bytes| Image::parse(&bytes)) {
+        Ok(image) => image,
+        Err(err) => {
+            eprintln!("{}: {}", args.binary, err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = args.isa.config();
+    let mut sim = match Simulator::new(config, &image, SIM_MEMORY_SIZE) {
+        Ok(sim) => sim,
+        Err(err) => {
+            eprintln!("{}: {}", args.binary, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let loaded: usize = image.segments.iter().map(|segment| segment.data.len()).sum();
+    println!("Loaded {} bytes from {} (entry 0x{:08X})", loaded, args.binary, image.entry);
+    println!("Starting simulation (Max cycles: {})...", args.max_cycles);
+
+    let disassembler = Disassembler::new(config);
+    let disassembler = if args.abi_names { disassembler.with_abi_names() } else { disassembler };
+    let ram_size = sim.bus.ram.len();
+
+    let stop = sim.run(args.max_cycles, |cycle, res| {
+        if let Some(access) = res.mem_access.filter(|access| matches!(access.op, MemOp::Store | MemOp::Amo)) {
+            if args.verbose {
+                println!("Cycle {}: Memory Write 0x{:08X} -> [0x{:08X}]", cycle, access.data, access.addr);
+            }
+            if (access.addr as usize) >= ram_size && !EXIT_PORTS.contains(&access.addr) {
+                println!("Warning: Out of bounds write at 0x{:08X}", access.addr);
+            }
+        }
+        if args.verbose {
+            let instr = res.compressed.map_or(res.instr, |parcel| parcel as u32);
+            println!("Cycle {:05}: PC=0x{:08X}, Instr=0x{:08X}  {}", cycle, res.pc, instr, disassembler.disasm(instr, res.pc));
+            match res.status {
+                StepStatus::Trap(trap) => println!("             Trap: {:?} (tval 0x{:08X}) -> PC=0x{:08X}", trap.cause, trap.tval, res.next_pc),
+                StepStatus::Interrupt(irq) => println!("             Interrupt: {:?} -> PC=0x{:08X}", irq.cause, res.next_pc),
+                StepStatus::Illegal => println!("             Illegal instruction (executed with the fallback decode)"),
+                _ => {}
+            }
+        }
+    });
+
+    let status = match stop {
+        Stop::Exit(code) => {
+            println!("Simulation exited via MMIO (code: {}) at cycle {}", code, sim.cycles - 1);
+            exit_status(code)
+        }
+        Stop::MaxCycles => {
+            println!("Simulation stopped: Reached max cycles ({})", args.max_cycles);
+            NO_EXIT_STATUS
+        }
+        Stop::PcOutOfBounds(pc) => {
+            println!("Execution halted: PC out of bounds (0x{:08X})", pc);
+            NO_EXIT_STATUS
+        }
+    };
+
+    // Dump final registers for verification
+    println!("\nFinal Register State (PC=0x{:08X}):", sim.core.pc);
+    for i in 0..sim.core.reg_file.count() {
+        print!("x{:<2}: 0x{:08X}    ", i, sim.core.reg_file.get_reg(i));
+        if (i + 1) % 4 == 0 {
+            println!();
+        }
+    }
+
+    ExitCode::from(status)
+}
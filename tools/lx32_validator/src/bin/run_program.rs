@@ -1,7 +1,16 @@
 use clap::Parser;
 use std::fs;
 use lx32_validator::*;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lsu::MemInterface;
+use lx32_validator::models::core::lx32_system::StepResult;
+use lx32_validator::models::disasm::{Disassembler, X_ABI_NAMES};
+use lx32_validator::sim::{Image, Simulator};
 use std::ffi::c_void;
+use std::process::ExitCode;
+
+/// Instructions shown on each side of the diverging PC.
+const WINDOW_RADIUS: u32 = 4;
 
 #[derive(Parser, Debug)]
 #[command(name = "lx32_runner")]
@@ -18,9 +27,84 @@ struct Args {
     /// Verbose cycle-by-cycle logging
     #[arg(short, long)]
     verbose: bool,
+
+    /// Use ABI register names (ra, sp, a0...) in the verbose disassembly
+    #[arg(long)]
+    abi_names: bool,
+
+    /// Run the golden model on the same memory image and stop at the first divergence
+    #[arg(long)]
+    lockstep: bool,
+}
+
+/// RTL and golden state after one lockstep cycle.
+struct LockstepCycle<'a> {
+    cycle: u64,
+    rtl_pc: u32,
+    gold_pc: u32,
+    rtl_port: MemInterface,
+    rtl_regs: [u32; 32],
+    gold: &'a Simulator,
+    step: &'a StepResult,
 }
 
-fn main() {
+impl LockstepCycle<'_> {
+    /// Fetch PC and memory write port fields that differ, as (name, RTL, golden).
+    fn diffs(&self) -> Vec<(&'static str, u32, u32)> {
+        let (rtl, gold) = (&self.rtl_port, &self.step.lsu);
+        let mut fields = vec![("pc", self.rtl_pc, self.gold_pc), ("mem_we", rtl.mem_we as u32, gold.mem_we as u32)];
+        // Address and data only matter for a write
+        if rtl.mem_we || gold.mem_we {
+            fields.extend([("mem_addr", rtl.mem_addr, gold.mem_addr), ("mem_wdata", rtl.mem_wdata, gold.mem_wdata), ("mem_be", rtl.mem_be as u32, gold.mem_be as u32)]);
+        }
+        fields.retain(|(_, rtl, gold)| rtl != gold);
+        fields
+    }
+
+    /// Registers that differ after the cycle, as (index, RTL, golden).
+    fn reg_diffs(&self) -> Vec<(usize, u32, u32)> {
+        (0..32).map(|i| (i, self.rtl_regs[i], self.gold.core.reg_file.get_reg(i))).filter(|(_, rtl, gold)| rtl != gold).collect()
+    }
+
+    fn matches(&self) -> bool {
+        self.diffs().is_empty() && self.reg_diffs().is_empty()
+    }
+
+    /// Prints the diverging fields, the code around both PCs and the register diff.
+    fn report(&self, memory: &[u8], disassembler: &Disassembler) {
+        println!("\n{:!^100}", " LOCKSTEP DIVERGENCE ");
+        println!("Cycle {}: RTL PC=0x{:08X}, Golden PC=0x{:08X}", self.cycle, self.rtl_pc, self.gold_pc);
+        for (field, rtl, gold) in self.diffs() {
+            println!("  {:<9} RTL=0x{:08X} GOLD=0x{:08X}", field, rtl, gold);
+        }
+        println!("  Golden step: instr=0x{:08X} next_pc=0x{:08X} status={:?}", self.step.instr, self.step.next_pc, self.step.status);
+
+        println!("\nInstruction window (R> RTL PC, G> golden PC, -> both):");
+        let first = self.rtl_pc.saturating_sub(4 * WINDOW_RADIUS);
+        for addr in (first..=self.rtl_pc.saturating_add(4 * WINDOW_RADIUS)).step_by(4) {
+            let Some(bytes) = memory.get(addr as usize..addr as usize + 4) else { break };
+            let instr = u32::from_le_bytes(bytes.try_into().unwrap());
+            let marker = match (addr == self.rtl_pc, addr == self.gold_pc) {
+                (true, true) => "->",
+                (true, false) => "R>",
+                (false, true) => "G>",
+                _ => "",
+            };
+            println!("  {:<2} 0x{:08X}: 0x{:08X}  {}", marker, addr, instr, disassembler.disasm(instr, addr));
+        }
+
+        println!("\nRegister diff:");
+        let reg_diffs = self.reg_diffs();
+        if reg_diffs.is_empty() {
+            println!("  (none)");
+        }
+        for (i, rtl, gold) in reg_diffs {
+            println!("  x{:<2} ({:<4}) RTL=0x{:08X} GOLD=0x{:08X}", i, X_ABI_NAMES[i], rtl, gold);
+        }
+    }
+}
+
+fn main() -> ExitCode {
     let args = Args::parse();
 
     // Load binary file
@@ -44,8 +128,16 @@ fn main() {
         unsafe { tick_core(core, 1 /* reset=true */, 0 /* instr=0 */, 0 /* mem_rdata=0 */) };
     }
 
+    // The RTL implements the base profile; the golden model gets its own copy of the image
+    let mut golden = args.lockstep.then(|| Simulator::new(IsaConfig::base(), &Image::flat(&memory, 0), memory.len()).expect("memory image fits its own size"));
+    let mut diverged = false;
+
     println!("Starting simulation (Max cycles: {})...", args.max_cycles);
 
+    // The RTL fetches whole words, so compressed parcels are shown as .word
+    let disassembler = Disassembler::new(IsaConfig { c_ext: false, ..IsaConfig::full() });
+    let disassembler = if args.abi_names { disassembler.with_abi_names() } else { disassembler };
+
     let mut cycles = 0;
 
     loop {
@@ -87,6 +179,21 @@ fn main() {
             }
         }
 
+        // Lockstep compares the write port as commanded in the combinational phase of the
+        // cycle: sample it with the load data applied, before the clock edge updates the
+        // register file
+        let rtl_port = golden.is_some().then(|| unsafe {
+            eval_core(core, 0, instr, mem_rdata);
+            let mem_we = get_mem_we(core) != 0;
+            MemInterface {
+                mem_addr: get_mem_addr(core),
+                mem_wdata: get_mem_wdata(core),
+                mem_we,
+                // The RTL data port only performs word writes
+                mem_be: if mem_we { 0xF } else { 0 },
+            }
+        });
+
         // Tick the core one clock cycle (applies rdata, evaluates, then pulses clock to commit to registers/PC)
         unsafe { tick_core(core, 0, instr, mem_rdata) };
 
@@ -95,6 +202,24 @@ fn main() {
         // The `mem_we`, `mem_wdata`, `mem_addr` signals were stable correctly BEFORE we pulsed the clock.
         // So we should capture them evaluating before `tick_core`!
 
+        if let (Some(gold), Some(rtl_port)) = (golden.as_mut(), rtl_port) {
+            let gold_pc = gold.core.pc;
+            let step = gold.step();
+            let lockstep = LockstepCycle {
+                cycle: cycles,
+                rtl_pc: pc,
+                gold_pc,
+                rtl_port,
+                rtl_regs: std::array::from_fn(|i| unsafe { get_reg(core, i as u8) }),
+                gold,
+                step: &step,
+            };
+            if !lockstep.matches() {
+                lockstep.report(&memory, &disassembler);
+                diverged = true;
+                break;
+            }
+        }
 
         // Handle memory write
         let mem_we = unsafe { get_mem_we(core) };
@@ -125,7 +250,7 @@ fn main() {
         }
 
         if args.verbose {
-            println!("Cycle {:05}: PC=0x{:08X}, Instr=0x{:08X}", cycles, pc, instr);
+            println!("Cycle {:05}: PC=0x{:08X}, Instr=0x{:08X}  {}", cycles, pc, instr, disassembler.disasm(instr, pc));
         }
 
         cycles += 1;
@@ -144,6 +269,14 @@ fn main() {
             println!();
         }
     }
+
+    if diverged {
+        return ExitCode::FAILURE;
+    }
+    if golden.is_some() {
+        println!("Lockstep: no divergence from the golden model");
+    }
+    ExitCode::SUCCESS
 }
 
 
@@ -1,9 +1,14 @@
+pub mod asm;
 pub mod models;
 pub mod program_generator;
+pub mod seeding;
 pub mod shrinking;
+pub mod sim;
 
+#[cfg(feature = "rtl")]
 use std::ffi::c_void;
 
+#[cfg(feature = "rtl")]
 #[link(name = "lx32_bridge", kind = "static")]
 unsafe extern "C" {
     pub fn create_core() -> *mut c_void;
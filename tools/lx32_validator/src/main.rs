@@ -10,6 +10,7 @@
 // Author: LX32 Validation Team
 
 use clap::Parser;
+use lx32_validator::seeding::suite_seed;
 
 #[path = "../tests/test_alu.rs"]
 mod test_alu;
@@ -29,6 +30,9 @@ mod test_imm_gen;
 #[path = "../tests/test_memory_sim.rs"]
 mod test_memory_sim;
 
+#[path = "../tests/test_bus.rs"]
+mod test_bus;
+
 #[path = "../tests/test_reg_generic.rs"]
 mod test_reg_generic;
 
@@ -38,6 +42,48 @@ mod test_register_file;
 #[path = "../tests/test_lx32_system.rs"]
 mod test_lx32_system;
 
+#[path = "../tests/test_csr.rs"]
+mod test_csr;
+
+#[path = "../tests/test_traps.rs"]
+mod test_traps;
+
+#[path = "../tests/test_interrupts.rs"]
+mod test_interrupts;
+
+#[path = "../tests/test_compressed.rs"]
+mod test_compressed;
+
+#[path = "../tests/test_atomics.rs"]
+mod test_atomics;
+
+#[path = "../tests/test_custom.rs"]
+mod test_custom;
+
+#[path = "../tests/test_privilege.rs"]
+mod test_privilege;
+
+#[path = "../tests/test_mmu.rs"]
+mod test_mmu;
+
+#[path = "../tests/test_fpu.rs"]
+mod test_fpu;
+
+#[path = "../tests/test_disasm.rs"]
+mod test_disasm;
+
+#[path = "../tests/test_asm.rs"]
+mod test_asm;
+
+#[path = "../tests/test_instr.rs"]
+mod test_instr;
+
+#[path = "../tests/test_sim.rs"]
+mod test_sim;
+
+#[path = "../tests/test_illegal.rs"]
+mod test_illegal;
+
 #[path = "../tests/test_long_programs.rs"]
 mod test_long_programs;
 
@@ -69,11 +115,29 @@ struct Args {
     /// Length of each long program (number of instructions)
     #[arg(long, default_value = "500")]
     program_length: usize,
+
+    /// Fetch long programs from a shared memory image at the current PC
+    #[arg(long)]
+    pc_driven: bool,
+
+    /// Also check that the RTL rejects every word the golden strict decoder calls illegal
+    #[arg(long)]
+    check_illegal: bool,
+
+    /// Run long programs over x0-x15 with an RV32E golden model (for a 16-register RTL build)
+    #[arg(long)]
+    rv32e: bool,
 }
 
 fn main() {
     let args = Args::parse();
 
+    let execution_mode = if args.pc_driven {
+        test_long_programs::ExecutionMode::PcDriven
+    } else {
+        test_long_programs::ExecutionMode::Streamed
+    };
+
     // Determine seed
     let seed = args.seed.unwrap_or_else(|| {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -85,6 +149,7 @@ fn main() {
 
     println!("{:=^100}", " LX32 FULL HARDWARE VALIDATION ");
     println!("Seed: {} (use --seed {} to reproduce this run)", seed, seed);
+    println!("Each suite derives its own seed from this one and reports it on failure");
     println!();
 
     if args.long_only {
@@ -95,85 +160,256 @@ fn main() {
                 program_length: args.program_length,
                 enable_shrinking: true,
                 enable_logging: args.verbose,
+                execution_mode,
+                rv32e: args.rv32e,
             },
-            seed,
+            suite_seed(seed, test_long_programs::SUITE),
         );
     } else {
         // Run standard unit tests
 
         // ALU validation
-        test_alu::run_alu_fuzzer(test_alu::AluTestParams {
-            iterations: 3000,
-            rd_range: (1, 32),
-            rs1_range: (0, 32),
-            imm_range: (0, 4096),
-            enable_logging: args.verbose,
-        });
+        test_alu::run_alu_fuzzer(
+            test_alu::AluTestParams {
+                iterations: 3000,
+                rd_range: (1, 32),
+                rs1_range: (0, 32),
+                imm_range: (0, 4096),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_alu::SUITE),
+        );
 
         // Branch validation
-        test_branch_unit::run_branch_fuzzer(test_branch_unit::BranchTestParams {
-            iterations: 10000,
-            reg_range: (0, 32),
-            offset_word_range: (-128, 128),
-            enable_logging: args.verbose,
-        });
+        test_branch_unit::run_branch_fuzzer(
+            test_branch_unit::BranchTestParams {
+                iterations: 10000,
+                reg_range: (0, 32),
+                offset_word_range: (-128, 128),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_branch_unit::SUITE),
+        );
 
         // Control Unit validation
-        test_control_unit::run_control_unit_fuzzer(test_control_unit::ControlUnitTestParams {
-            iterations: 500,
-            reg_range: (0, 32),
-            imm_range: (-2048, 2047),
-            enable_logging: args.verbose,
-        });
+        test_control_unit::run_control_unit_fuzzer(
+            test_control_unit::ControlUnitTestParams {
+                iterations: 500,
+                reg_range: (0, 32),
+                imm_range: (-2048, 2047),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_control_unit::SUITE),
+        );
 
         // LSU validation
-        test_lsu::run_lsu_fuzzer(test_lsu::LsuTestParams {
-            iterations: 2000,
-            reg_range: (0, 32),
-            imm_range: (-2048, 2047),
-            enable_logging: args.verbose,
-        });
+        test_lsu::run_lsu_fuzzer(
+            test_lsu::LsuTestParams {
+                iterations: 2000,
+                reg_range: (0, 32),
+                imm_range: (-2048, 2047),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_lsu::SUITE),
+        );
 
         // IMM_GEN validation
-        test_imm_gen::run_imm_gen_fuzzer(test_imm_gen::ImmGenTestParams {
-            iterations: 2000,
-            rd_range: (1, 32),
-            branch_offset_range: (-1024, 1024),
-            i_imm_range: (-2048, 2047),
-            s_imm_range: (-2048, 2047),
-            enable_logging: args.verbose,
-        });
+        test_imm_gen::run_imm_gen_fuzzer(
+            test_imm_gen::ImmGenTestParams {
+                iterations: 2000,
+                rd_range: (1, 32),
+                branch_offset_range: (-1024, 1024),
+                i_imm_range: (-2048, 2047),
+                s_imm_range: (-2048, 2047),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_imm_gen::SUITE),
+        );
 
         // Memory simulation validation
-        test_memory_sim::run_memory_sim_fuzzer(test_memory_sim::MemorySimTestParams {
-            iterations: 1000,
-            addr_range: (0, 4096),
-            data_range: (0, u32::MAX),
-            enable_logging: args.verbose,
-        });
+        test_memory_sim::run_memory_sim_fuzzer(
+            test_memory_sim::MemorySimTestParams {
+                iterations: 1000,
+                addr_range: (0, 4096),
+                data_range: (0, u32::MAX),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_memory_sim::SUITE),
+        );
+
+        // Memory bus validation
+        test_bus::run_bus_fuzzer(
+            test_bus::BusTestParams {
+                iterations: 1000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_bus::SUITE),
+        );
 
         // Register generic validation
-        test_reg_generic::run_reg_generic_fuzzer(test_reg_generic::RegGenericTestParams {
-            iterations: 2000,
-            data_range: (0, u32::MAX),
-            enable_logging: args.verbose,
-        });
+        test_reg_generic::run_reg_generic_fuzzer(
+            test_reg_generic::RegGenericTestParams {
+                iterations: 2000,
+                data_range: (0, u32::MAX),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_reg_generic::SUITE),
+        );
 
         // Register file validation
-        test_register_file::run_register_file_fuzzer(test_register_file::RegisterFileTestParams {
-            iterations: 2000,
-            reg_range: (0, 32),
-            data_range: (0, u32::MAX),
-            enable_logging: args.verbose,
-        });
+        test_register_file::run_register_file_fuzzer(
+            test_register_file::RegisterFileTestParams {
+                iterations: 2000,
+                reg_range: (0, 32),
+                data_range: (0, u32::MAX),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_register_file::SUITE),
+        );
 
         // LX32 System validation
-        test_lx32_system::run_lx32_system_fuzzer(test_lx32_system::LX32SystemTestParams {
-            iterations: 500,
-            reg_range: (0, 32),
-            imm_range: (-2048, 2047),
-            enable_logging: args.verbose,
-        });
+        test_lx32_system::run_lx32_system_fuzzer(
+            test_lx32_system::LX32SystemTestParams {
+                iterations: 500,
+                reg_range: (0, 32),
+                imm_range: (-2048, 2047),
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_lx32_system::SUITE),
+        );
+
+        // CSR file validation (golden only)
+        test_csr::run_csr_fuzzer(
+            test_csr::CsrTestParams {
+                iterations: 2000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_csr::SUITE),
+        );
+
+        // Machine-mode exception validation (golden only)
+        test_traps::run_trap_fuzzer(
+            test_traps::TrapTestParams {
+                iterations: 2000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_traps::SUITE),
+        );
+
+        // Interrupt injection (golden only)
+        test_interrupts::run_interrupt_fuzzer(
+            test_interrupts::InterruptTestParams {
+                num_programs: 20,
+                program_length: 300,
+                inject_period: 16,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_interrupts::SUITE),
+        );
+
+        // Compressed instruction expansion (golden only)
+        test_compressed::run_compressed_fuzzer(
+            test_compressed::CompressedTestParams {
+                iterations: 2000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_compressed::SUITE),
+        );
+
+        // Atomics on shared memory (golden only)
+        test_atomics::run_atomic_fuzzer(
+            test_atomics::AtomicTestParams {
+                iterations: 3000,
+                harts: 2,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_atomics::SUITE),
+        );
+
+        // Registered custom instructions (golden only)
+        test_custom::run_custom_fuzzer(
+            test_custom::CustomTestParams {
+                iterations: 2000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_custom::SUITE),
+        );
+
+        // U-mode and PMP (golden only)
+        test_privilege::run_privilege_fuzzer(
+            test_privilege::PrivilegeTestParams {
+                iterations: 3000,
+                max_entries: 4,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_privilege::SUITE),
+        );
+
+        // Sv32 virtual memory (golden only)
+        test_mmu::run_mmu_fuzzer(
+            test_mmu::MmuTestParams {
+                iterations: 3000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_mmu::SUITE),
+        );
+
+        // F extension soft-float (golden only)
+        test_fpu::run_fpu_fuzzer(
+            test_fpu::FpuTestParams {
+                iterations: 20000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_fpu::SUITE),
+        );
+
+        // Disassembler against the strict decoder (golden only)
+        test_disasm::run_disasm_fuzzer(
+            test_disasm::DisasmTestParams {
+                iterations: 20000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_disasm::SUITE),
+        );
+
+        // Assembler round trip through the disassembler (golden only)
+        test_asm::run_asm_fuzzer(
+            test_asm::AsmTestParams {
+                iterations: 20000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_asm::SUITE),
+        );
+
+        // Typed instructions against the strict decoder (golden only)
+        test_instr::run_instr_fuzzer(
+            test_instr::InstrTestParams {
+                iterations: 20000,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_instr::SUITE),
+        );
+
+        // Whole programs on the instruction-set simulator (golden only)
+        test_sim::run_sim_fuzzer(
+            test_sim::SimTestParams {
+                iterations: 500,
+                max_program_len: 64,
+                enable_logging: args.verbose,
+            },
+            suite_seed(seed, test_sim::SUITE),
+        );
+
+        // Illegal instruction agreement (opt-in: the RTL has no illegal detection yet)
+        if args.check_illegal {
+            test_illegal::run_illegal_fuzzer(
+                test_illegal::IllegalTestParams {
+                    iterations: 1000,
+                    enable_logging: args.verbose,
+                },
+                suite_seed(seed, test_illegal::SUITE),
+            );
+        }
 
         // Long program tests (if not skipped)
         if !args.skip_long {
@@ -183,8 +419,10 @@ fn main() {
                     program_length: args.program_length,
                     enable_shrinking: true,
                     enable_logging: args.verbose,
+                    execution_mode,
+                    rv32e: args.rv32e,
                 },
-                seed,
+                suite_seed(seed, test_long_programs::SUITE),
             );
         }
     }
@@ -45,4 +45,52 @@ pub enum alu_op_e {
     ALU_XOR = 7,
     ALU_OR = 8,
     ALU_AND = 9,
+
+    // -------------------------
+    // M extension (golden only)
+    // -------------------------
+    ALU_MUL = 10,    // Low 32 bits of A * B
+    ALU_MULH = 11,   // High 32 bits, signed x signed
+    ALU_MULHSU = 12, // High 32 bits, signed x unsigned
+    ALU_MULHU = 13,  // High 32 bits, unsigned x unsigned
+    ALU_DIV = 14,    // Signed quotient
+    ALU_DIVU = 15,   // Unsigned quotient
+    ALU_REM = 16,    // Signed remainder
+    ALU_REMU = 17,   // Unsigned remainder
+
+    // -------------------------
+    // Zba: address generation (golden only)
+    // -------------------------
+    ALU_SH1ADD = 18, // (A << 1) + B
+    ALU_SH2ADD = 19, // (A << 2) + B
+    ALU_SH3ADD = 20, // (A << 3) + B
+
+    // -------------------------
+    // Zbb: basic bit manipulation (golden only)
+    // -------------------------
+    ALU_ANDN = 21,   // A & ~B
+    ALU_ORN = 22,    // A | ~B
+    ALU_XNOR = 23,   // ~(A ^ B)
+    ALU_CLZ = 24,    // Count leading zeros of A
+    ALU_CTZ = 25,    // Count trailing zeros of A
+    ALU_CPOP = 26,   // Count set bits of A
+    ALU_MIN = 27,    // Signed minimum
+    ALU_MINU = 28,   // Unsigned minimum
+    ALU_MAX = 29,    // Signed maximum
+    ALU_MAXU = 30,   // Unsigned maximum
+    ALU_ROL = 31,    // Rotate A left by B[4:0]
+    ALU_ROR = 32,    // Rotate A right by B[4:0]
+    ALU_SEXT_B = 33, // Sign-extend A[7:0]
+    ALU_SEXT_H = 34, // Sign-extend A[15:0]
+    ALU_ZEXT_H = 35, // Zero-extend A[15:0]
+    ALU_REV8 = 36,   // Reverse the byte order of A
+    ALU_ORC_B = 37,  // Each byte of A: 0xFF if non-zero, else 0x00
+
+    // -------------------------
+    // Zbs: single-bit operations (golden only)
+    // -------------------------
+    ALU_BCLR = 38, // Clear bit B[4:0] of A
+    ALU_BEXT = 39, // Extract bit B[4:0] of A
+    ALU_BINV = 40, // Invert bit B[4:0] of A
+    ALU_BSET = 41, // Set bit B[4:0] of A
 }
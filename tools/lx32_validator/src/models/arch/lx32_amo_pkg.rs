@@ -0,0 +1,63 @@
+// ============================================================
+// LX32 Atomic Memory Operation Definitions
+// ============================================================
+// Operations of the A extension (AMO opcode, funct3 = 010).
+//
+// The encoding is funct5 = instr[31:27], so the decoder maps
+// the field directly. aq/rl (instr[26:25]) only order memory
+// between harts and do not change what a single-cycle core
+// executes.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+/// funct3 of the word-sized atomics (.W); every other width is reserved on RV32
+pub const AMO_FUNCT3_W: u8 = 0b010;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum amo_op_e {
+    // -------------------------
+    // Load-reserved / store-conditional
+    // -------------------------
+    AMO_LR = 0b00010, // rd = M[rs1]; reserve the word (rs2 must be x0)
+    AMO_SC = 0b00011, // M[rs1] = rs2 if still reserved; rd = 0 on success, 1 on failure
+
+    // -------------------------
+    // Read-modify-write (rd = old M[rs1])
+    // -------------------------
+    AMO_SWAP = 0b00001, // M = rs2
+    AMO_ADD = 0b00000,  // M = M + rs2
+    AMO_XOR = 0b00100,  // M = M ^ rs2
+    AMO_AND = 0b01100,  // M = M & rs2
+    AMO_OR = 0b01000,   // M = M | rs2
+    AMO_MIN = 0b10000,  // M = min(M, rs2), signed
+    AMO_MAX = 0b10100,  // M = max(M, rs2), signed
+    AMO_MINU = 0b11000, // M = min(M, rs2), unsigned
+    AMO_MAXU = 0b11100, // M = max(M, rs2), unsigned
+}
+
+impl amo_op_e {
+    /// Operation selected by funct5 = instr[31:27], or `None` for a reserved value.
+    pub fn from_funct5(funct5: u8) -> Option<Self> {
+        match funct5 {
+            0b00010 => Some(Self::AMO_LR),
+            0b00011 => Some(Self::AMO_SC),
+            0b00001 => Some(Self::AMO_SWAP),
+            0b00000 => Some(Self::AMO_ADD),
+            0b00100 => Some(Self::AMO_XOR),
+            0b01100 => Some(Self::AMO_AND),
+            0b01000 => Some(Self::AMO_OR),
+            0b10000 => Some(Self::AMO_MIN),
+            0b10100 => Some(Self::AMO_MAX),
+            0b11000 => Some(Self::AMO_MINU),
+            0b11100 => Some(Self::AMO_MAXU),
+            _ => None,
+        }
+    }
+
+    /// True for the AMO*.W read-modify-write operations (not LR/SC).
+    pub fn is_rmw(self) -> bool {
+        !matches!(self, Self::AMO_LR | Self::AMO_SC)
+    }
+}
@@ -22,6 +22,10 @@ pub const REG_COUNT: usize = 32;
 // 2^5 = 32, por lo tanto clog2(32) = 5.
 pub const REG_ADDR_WIDTH: usize = 5;
 
+// Reduced register file (RV32E): x0-x15, 4-bit addresses
+pub const REG_COUNT_E: usize = 16;
+pub const REG_ADDR_WIDTH_E: usize = 4;
+
 // Program counter width
 pub const PC_WIDTH: usize = 32;
 
@@ -0,0 +1,237 @@
+// ============================================================
+// LX32 Typed Compressed Instructions (RV32C)
+// ============================================================
+// One variant per RV32C integer instruction, holding its
+// operands as plain numbers, with `encode()` to the 16-bit
+// parcel and `expand()` to the `Instr` it stands for. The
+// program generator builds its parcels through this type
+// instead of packing immediate bits itself.
+//
+// Design Principles:
+//   - Operands as in `Instr`: registers by number (x8-x15 for
+//     the 3-bit fields), immediates as the value the expanded
+//     instruction uses (byte offsets, the shifted LUI value).
+//   - `expand` is written from the spec's expansion table,
+//     independently of `expand_compressed`, so each checks
+//     the other.
+//   - `encode` refuses operands outside their field and the
+//     reserved code points (zero immediates, x0/x2 where they
+//     select another instruction) instead of emitting a
+//     different parcel.
+//   - No floating-point forms: `Instr` has no F instructions.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, reg_idx_t};
+use crate::models::arch::lx32_instr_pkg::Instr;
+use crate::models::arch::lx32_rvc_pkg::cinstr_t;
+
+/// An RV32C integer instruction with its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CInstr {
+    // Quadrant 0
+    Addi4spn { rd: reg_idx_t, imm: i32 },
+    Lw { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Sw { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    // Quadrant 1
+    /// C.ADDI; rd = x0 with imm = 0 is C.NOP
+    Addi { rd: reg_idx_t, imm: i32 },
+    Jal { imm: i32 },
+    Li { rd: reg_idx_t, imm: i32 },
+    Addi16sp { imm: i32 },
+    Lui { rd: reg_idx_t, imm: i32 },
+    Srli { rd: reg_idx_t, shamt: u8 },
+    Srai { rd: reg_idx_t, shamt: u8 },
+    Andi { rd: reg_idx_t, imm: i32 },
+    Sub { rd: reg_idx_t, rs2: reg_idx_t },
+    Xor { rd: reg_idx_t, rs2: reg_idx_t },
+    Or { rd: reg_idx_t, rs2: reg_idx_t },
+    And { rd: reg_idx_t, rs2: reg_idx_t },
+    J { imm: i32 },
+    Beqz { rs1: reg_idx_t, imm: i32 },
+    Bnez { rs1: reg_idx_t, imm: i32 },
+    // Quadrant 2
+    Slli { rd: reg_idx_t, shamt: u8 },
+    Lwsp { rd: reg_idx_t, imm: i32 },
+    Swsp { rs2: reg_idx_t, imm: i32 },
+    Jr { rs1: reg_idx_t },
+    Mv { rd: reg_idx_t, rs2: reg_idx_t },
+    Ebreak,
+    Jalr { rs1: reg_idx_t },
+    Add { rd: reg_idx_t, rs2: reg_idx_t },
+}
+
+use CInstr::*;
+
+impl CInstr {
+    /// 16-bit parcel. Panics when an operand does not fit its field
+    /// (see `fits`).
+    pub fn encode(self) -> cinstr_t {
+        assert!(self.fits(), "{:?} does not fit its encoding", self);
+        let parcel = match self {
+            Addi4spn { rd, imm } => {
+                (bits(imm, 5, 4) << 11) | (bits(imm, 9, 6) << 7) | (bits(imm, 2, 2) << 6) | (bits(imm, 3, 3) << 5) | (prime(rd) << 2)
+            }
+            Lw { rd, rs1, imm } => cl_type(0b010, imm, rs1, rd),
+            Sw { rs1, rs2, imm } => cl_type(0b110, imm, rs1, rs2),
+            Addi { rd, imm } => ci_type(0b000, rd, imm, 0b01),
+            Jal { imm } => cj_type(0b001, imm),
+            Li { rd, imm } => ci_type(0b010, rd, imm, 0b01),
+            Addi16sp { imm } => {
+                (0b011 << 13)
+                    | (bits(imm, 9, 9) << 12)
+                    | (2 << 7)
+                    | (bits(imm, 4, 4) << 6)
+                    | (bits(imm, 6, 6) << 5)
+                    | (bits(imm, 8, 7) << 3)
+                    | (bits(imm, 5, 5) << 2)
+                    | 0b01
+            }
+            Lui { rd, imm } => ci_type(0b011, rd, imm >> 12, 0b01),
+            Srli { rd, shamt } => cb_alu(0b00, rd, shamt as i32),
+            Srai { rd, shamt } => cb_alu(0b01, rd, shamt as i32),
+            Andi { rd, imm } => cb_alu(0b10, rd, imm),
+            Sub { rd, rs2 } => ca_type(0b00, rd, rs2),
+            Xor { rd, rs2 } => ca_type(0b01, rd, rs2),
+            Or { rd, rs2 } => ca_type(0b10, rd, rs2),
+            And { rd, rs2 } => ca_type(0b11, rd, rs2),
+            J { imm } => cj_type(0b101, imm),
+            Beqz { rs1, imm } => cb_branch(0b110, rs1, imm),
+            Bnez { rs1, imm } => cb_branch(0b111, rs1, imm),
+            Slli { rd, shamt } => ci_type(0b000, rd, shamt as i32, 0b10),
+            Lwsp { rd, imm } => (0b010 << 13) | (bits(imm, 5, 5) << 12) | ((rd as u32) << 7) | (bits(imm, 4, 2) << 4) | (bits(imm, 7, 6) << 2) | 0b10,
+            Swsp { rs2, imm } => (0b110 << 13) | (bits(imm, 5, 2) << 9) | (bits(imm, 7, 6) << 7) | ((rs2 as u32) << 2) | 0b10,
+            Jr { rs1 } => cr_type(0, rs1, 0),
+            Mv { rd, rs2 } => cr_type(0, rd, rs2),
+            Ebreak => cr_type(1, 0, 0),
+            Jalr { rs1 } => cr_type(1, rs1, 0),
+            Add { rd, rs2 } => cr_type(1, rd, rs2),
+        };
+        parcel as cinstr_t
+    }
+
+    /// The 32-bit instruction this one stands for.
+    pub fn expand(self) -> Instr {
+        match self {
+            Addi4spn { rd, imm } => Instr::Addi { rd, rs1: 2, imm },
+            Lw { rd, rs1, imm } => Instr::Lw { rd, rs1, imm },
+            Sw { rs1, rs2, imm } => Instr::Sw { rs1, rs2, imm },
+            Addi { rd, imm } => Instr::Addi { rd, rs1: rd, imm },
+            Jal { imm } => Instr::Jal { rd: 1, imm },
+            Li { rd, imm } => Instr::Addi { rd, rs1: 0, imm },
+            Addi16sp { imm } => Instr::Addi { rd: 2, rs1: 2, imm },
+            Lui { rd, imm } => Instr::Lui { rd, imm },
+            Srli { rd, shamt } => Instr::Srli { rd, rs1: rd, shamt },
+            Srai { rd, shamt } => Instr::Srai { rd, rs1: rd, shamt },
+            Andi { rd, imm } => Instr::Andi { rd, rs1: rd, imm },
+            Sub { rd, rs2 } => Instr::Sub { rd, rs1: rd, rs2 },
+            Xor { rd, rs2 } => Instr::Xor { rd, rs1: rd, rs2 },
+            Or { rd, rs2 } => Instr::Or { rd, rs1: rd, rs2 },
+            And { rd, rs2 } => Instr::And { rd, rs1: rd, rs2 },
+            J { imm } => Instr::Jal { rd: 0, imm },
+            Beqz { rs1, imm } => Instr::Beq { rs1, rs2: 0, imm },
+            Bnez { rs1, imm } => Instr::Bne { rs1, rs2: 0, imm },
+            Slli { rd, shamt } => Instr::Slli { rd, rs1: rd, shamt },
+            Lwsp { rd, imm } => Instr::Lw { rd, rs1: 2, imm },
+            Swsp { rs2, imm } => Instr::Sw { rs1: 2, rs2, imm },
+            Jr { rs1 } => Instr::Jalr { rd: 0, rs1, imm: 0 },
+            Mv { rd, rs2 } => Instr::Add { rd, rs1: 0, rs2 },
+            Ebreak => Instr::Ebreak,
+            Jalr { rs1 } => Instr::Jalr { rd: 1, rs1, imm: 0 },
+            Add { rd, rs2 } => Instr::Add { rd, rs1: rd, rs2 },
+        }
+    }
+
+    /// True when every operand fits its field and the parcel is not a
+    /// reserved or differently-decoded code point.
+    pub fn fits(self) -> bool {
+        let full = |reg: reg_idx_t| (reg as usize) < REG_COUNT;
+        let nonzero = |reg: reg_idx_t| reg != 0 && full(reg);
+        let prime = |reg: reg_idx_t| (8..16).contains(&reg);
+        let words = |imm: i32, limit: i32| (0..limit).contains(&imm) && imm % 4 == 0;
+        match self {
+            Addi4spn { rd, imm } => prime(rd) && imm != 0 && words(imm, 1024),
+            Lw { rd: a, rs1: b, imm } | Sw { rs1: a, rs2: b, imm } => prime(a) && prime(b) && words(imm, 128),
+            Addi { rd, imm } | Li { rd, imm } => full(rd) && (-32..32).contains(&imm),
+            Jal { imm } | J { imm } => (-2048..2048).contains(&imm) && imm % 2 == 0,
+            Addi16sp { imm } => imm != 0 && (-512..512).contains(&imm) && imm % 16 == 0,
+            // rd = x2 is C.ADDI16SP
+            Lui { rd, imm } => full(rd) && rd != 2 && imm != 0 && imm & 0xFFF == 0 && (-(1 << 17)..(1 << 17)).contains(&imm),
+            Srli { rd, shamt } | Srai { rd, shamt } => prime(rd) && shamt < 32,
+            Andi { rd, imm } => prime(rd) && (-32..32).contains(&imm),
+            Sub { rd, rs2 } | Xor { rd, rs2 } | Or { rd, rs2 } | And { rd, rs2 } => prime(rd) && prime(rs2),
+            Beqz { rs1, imm } | Bnez { rs1, imm } => prime(rs1) && (-256..256).contains(&imm) && imm % 2 == 0,
+            Slli { rd, shamt } => full(rd) && shamt < 32,
+            Lwsp { rd, imm } => nonzero(rd) && words(imm, 256),
+            Swsp { rs2, imm } => full(rs2) && words(imm, 256),
+            Jr { rs1 } | Jalr { rs1 } => nonzero(rs1),
+            // rs2 = x0 is C.JR/C.JALR/C.EBREAK
+            Mv { rd, rs2 } | Add { rd, rs2 } => full(rd) && nonzero(rs2),
+            Ebreak => true,
+        }
+    }
+}
+
+// ------------------------------------------------------------
+// Format encoders
+// ------------------------------------------------------------
+
+/// value[hi:lo]
+fn bits(value: i32, hi: u32, lo: u32) -> u32 {
+    ((value as u32) >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+/// 3-bit field of an x8-x15 register.
+fn prime(reg: reg_idx_t) -> u32 {
+    (reg - 8) as u32
+}
+
+/// CI: funct3 | imm[5] | rd | imm[4:0] | op
+fn ci_type(funct3: u32, rd: reg_idx_t, imm: i32, op: u32) -> u32 {
+    (funct3 << 13) | (bits(imm, 5, 5) << 12) | ((rd as u32) << 7) | (bits(imm, 4, 0) << 2) | op
+}
+
+/// CL/CS word access: funct3 | uimm[5:3] | rs1' | uimm[2|6] | rd'/rs2' | 00
+fn cl_type(funct3: u32, imm: i32, rs1: reg_idx_t, reg: reg_idx_t) -> u32 {
+    (funct3 << 13) | (bits(imm, 5, 3) << 10) | (prime(rs1) << 7) | (bits(imm, 2, 2) << 6) | (bits(imm, 6, 6) << 5) | (prime(reg) << 2)
+}
+
+/// CJ: funct3 | offset[11|4|9:8|10|6|7|3:1|5] | 01
+fn cj_type(funct3: u32, imm: i32) -> u32 {
+    (funct3 << 13)
+        | (bits(imm, 11, 11) << 12)
+        | (bits(imm, 4, 4) << 11)
+        | (bits(imm, 9, 8) << 9)
+        | (bits(imm, 10, 10) << 8)
+        | (bits(imm, 6, 6) << 7)
+        | (bits(imm, 7, 7) << 6)
+        | (bits(imm, 3, 1) << 3)
+        | (bits(imm, 5, 5) << 2)
+        | 0b01
+}
+
+/// CB shift/ANDI: 100 | imm[5] | funct2 | rd' | imm[4:0] | 01
+fn cb_alu(funct2: u32, rd: reg_idx_t, imm: i32) -> u32 {
+    (0b100 << 13) | (bits(imm, 5, 5) << 12) | (funct2 << 10) | (prime(rd) << 7) | (bits(imm, 4, 0) << 2) | 0b01
+}
+
+/// CB branch: funct3 | offset[8|4:3] | rs1' | offset[7:6|2:1|5] | 01
+fn cb_branch(funct3: u32, rs1: reg_idx_t, imm: i32) -> u32 {
+    (funct3 << 13)
+        | (bits(imm, 8, 8) << 12)
+        | (bits(imm, 4, 3) << 10)
+        | (prime(rs1) << 7)
+        | (bits(imm, 7, 6) << 5)
+        | (bits(imm, 2, 1) << 3)
+        | (bits(imm, 5, 5) << 2)
+        | 0b01
+}
+
+/// CA: 100011 | rd' | funct2 | rs2' | 01
+fn ca_type(funct2: u32, rd: reg_idx_t, rs2: reg_idx_t) -> u32 {
+    (0b100 << 13) | (0b11 << 10) | (prime(rd) << 7) | (funct2 << 5) | (prime(rs2) << 2) | 0b01
+}
+
+/// CR: 100 | bit 12 | rd/rs1 | rs2 | 10
+fn cr_type(bit12: u32, rd: reg_idx_t, rs2: reg_idx_t) -> u32 {
+    (0b100 << 13) | (bit12 << 12) | ((rd as u32) << 7) | ((rs2 as u32) << 2) | 0b10
+}
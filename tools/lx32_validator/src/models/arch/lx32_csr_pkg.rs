@@ -0,0 +1,193 @@
+// ============================================================
+// LX32 Control and Status Register Definitions
+// ============================================================
+// CSR addresses implemented by the golden model and the
+// Zicsr operation encoding (funct3 of the SYSTEM opcode).
+//
+// Addresses follow the RISC-V privileged specification:
+// csr[11:10] = 11 marks a read-only register.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+/// 12-bit CSR address
+pub type csr_addr_t = u16;
+
+// -------------------------
+// Machine information (read-only)
+// -------------------------
+pub const CSR_MVENDORID: csr_addr_t = 0xF11;
+pub const CSR_MARCHID: csr_addr_t = 0xF12;
+pub const CSR_MIMPID: csr_addr_t = 0xF13;
+pub const CSR_MHARTID: csr_addr_t = 0xF14;
+
+// -------------------------
+// Floating-point control and status (F extension)
+// -------------------------
+pub const CSR_FFLAGS: csr_addr_t = 0x001;
+pub const CSR_FRM: csr_addr_t = 0x002;
+pub const CSR_FCSR: csr_addr_t = 0x003;
+
+// -------------------------
+// Address translation (Sv32, see lx32_vm_pkg)
+// -------------------------
+pub const CSR_SATP: csr_addr_t = 0x180;
+
+// -------------------------
+// Machine trap setup
+// -------------------------
+pub const CSR_MSTATUS: csr_addr_t = 0x300;
+pub const CSR_MISA: csr_addr_t = 0x301;
+pub const CSR_MIE: csr_addr_t = 0x304;
+pub const CSR_MTVEC: csr_addr_t = 0x305;
+pub const CSR_MCOUNTEREN: csr_addr_t = 0x306;
+
+// -------------------------
+// Machine trap handling
+// -------------------------
+pub const CSR_MSCRATCH: csr_addr_t = 0x340;
+pub const CSR_MEPC: csr_addr_t = 0x341;
+pub const CSR_MCAUSE: csr_addr_t = 0x342;
+pub const CSR_MTVAL: csr_addr_t = 0x343;
+pub const CSR_MIP: csr_addr_t = 0x344;
+
+// -------------------------
+// Physical memory protection (16 entries)
+// -------------------------
+pub const CSR_PMPCFG0: csr_addr_t = 0x3A0; // pmpcfg0..pmpcfg3
+pub const CSR_PMPADDR0: csr_addr_t = 0x3B0; // pmpaddr0..pmpaddr15
+
+// -------------------------
+// Machine counters
+// -------------------------
+pub const CSR_MCYCLE: csr_addr_t = 0xB00;
+pub const CSR_MINSTRET: csr_addr_t = 0xB02;
+pub const CSR_MCYCLEH: csr_addr_t = 0xB80;
+pub const CSR_MINSTRETH: csr_addr_t = 0xB82;
+
+// -------------------------
+// Unprivileged counter shadows (read-only)
+// -------------------------
+pub const CSR_CYCLE: csr_addr_t = 0xC00;
+pub const CSR_INSTRET: csr_addr_t = 0xC02;
+pub const CSR_CYCLEH: csr_addr_t = 0xC80;
+pub const CSR_INSTRETH: csr_addr_t = 0xC82;
+
+// -------------------------
+// mstatus fields
+// -------------------------
+pub const MSTATUS_MIE: u32 = 1 << 3;
+pub const MSTATUS_MPIE: u32 = 1 << 7;
+pub const MSTATUS_MPP: u32 = 0b11 << 11;
+pub const MSTATUS_MPP_SHIFT: u32 = 11;
+pub const MSTATUS_FS: u32 = 0b11 << 13;
+pub const MSTATUS_FS_SHIFT: u32 = 13;
+pub const MSTATUS_MPRV: u32 = 1 << 17;
+/// Read-only summary: set when FS is Dirty
+pub const MSTATUS_SD: u32 = 1 << 31;
+
+// mstatus.FS states (floating-point unit context status)
+pub const FS_OFF: u32 = 0b00;
+pub const FS_INITIAL: u32 = 0b01;
+pub const FS_CLEAN: u32 = 0b10;
+pub const FS_DIRTY: u32 = 0b11;
+
+// -------------------------
+// mcounteren fields
+// -------------------------
+pub const MCOUNTEREN_CY: u32 = 1 << 0;
+pub const MCOUNTEREN_IR: u32 = 1 << 2;
+
+// -------------------------
+// pmpcfg entry fields (one byte per entry)
+// -------------------------
+pub const PMP_R: u8 = 1 << 0;
+pub const PMP_W: u8 = 1 << 1;
+pub const PMP_X: u8 = 1 << 2;
+pub const PMP_A_SHIFT: u8 = 3;
+pub const PMP_L: u8 = 1 << 7;
+
+/// pmpcfg address-matching mode (A field)
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum pmp_match_e {
+    PMP_OFF = 0,   // Entry disabled
+    PMP_TOR = 1,   // Top of range: pmpaddr[i-1] <= a < pmpaddr[i]
+    PMP_NA4 = 2,   // Naturally aligned 4-byte region
+    PMP_NAPOT = 3, // Naturally aligned power-of-two region, >= 8 bytes
+}
+
+impl pmp_match_e {
+    /// Mode encoded in the A field of a pmpcfg entry byte.
+    pub fn from_cfg(cfg: u8) -> Self {
+        match (cfg >> PMP_A_SHIFT) & 0b11 {
+            0 => Self::PMP_OFF,
+            1 => Self::PMP_TOR,
+            2 => Self::PMP_NA4,
+            _ => Self::PMP_NAPOT,
+        }
+    }
+}
+
+// -------------------------
+// Privilege modes (mstatus.MPP encoding)
+// -------------------------
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum priv_mode_e {
+    PRIV_U = 0b00,
+    #[default]
+    PRIV_M = 0b11,
+}
+
+impl priv_mode_e {
+    /// Mode held in mstatus.MPP; the reserved S/H encodings read as M.
+    pub fn from_mpp(mstatus: u32) -> Self {
+        if (mstatus & MSTATUS_MPP) >> MSTATUS_MPP_SHIFT == 0 { Self::PRIV_U } else { Self::PRIV_M }
+    }
+
+    /// This mode encoded in the mstatus.MPP field.
+    pub fn mpp(self) -> u32 {
+        (self as u32) << MSTATUS_MPP_SHIFT
+    }
+}
+
+// -------------------------
+// mie / mip fields
+// -------------------------
+pub const MIP_MSIP: u32 = 1 << 3;
+pub const MIP_MTIP: u32 = 1 << 7;
+pub const MIP_MEIP: u32 = 1 << 11;
+
+/// Zicsr operations, encoded as funct3 of the SYSTEM opcode.
+/// The immediate forms set funct3[2] and use the rs1 field as a 5-bit zimm.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum csr_op_e {
+    #[default]
+    CSR_RW = 0b001,
+    CSR_RS = 0b010,
+    CSR_RC = 0b011,
+}
+
+impl csr_op_e {
+    /// Operation selected by SYSTEM funct3 (immediate forms included); None for funct3 = x00.
+    pub fn from_funct3(funct3: u8) -> Option<Self> {
+        match funct3 & 0b011 {
+            0b01 => Some(Self::CSR_RW),
+            0b10 => Some(Self::CSR_RS),
+            0b11 => Some(Self::CSR_RC),
+            _ => None,
+        }
+    }
+}
+
+/// True for addresses in the read-only space (csr[11:10] = 11).
+pub fn csr_is_read_only(addr: csr_addr_t) -> bool {
+    addr >> 10 == 0b11
+}
+
+/// Lowest privilege level that may access `addr` (csr[9:8]).
+pub fn csr_min_privilege(addr: csr_addr_t) -> u8 {
+    ((addr >> 8) & 0b11) as u8
+}
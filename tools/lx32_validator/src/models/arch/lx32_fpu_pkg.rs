@@ -0,0 +1,201 @@
+// ============================================================
+// LX32 Floating-Point Definitions (F extension)
+// ============================================================
+// Single-precision operations, rounding modes and exception
+// flags of RV32F.
+//
+// OP-FP selects the operation with funct5 = instr[31:27] and
+// the format with fmt = instr[26:25] (only 00 = S exists
+// here). Some operations use funct3 or the rs2 field as a
+// sub-opcode; the rest take a rounding mode in funct3. The
+// fused multiply-add forms have their own major opcodes and
+// a third source register rs3 = instr[31:27].
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+use crate::models::arch::lx32_isa_pkg::opcode_t;
+
+/// funct3 of FLW/FSW
+pub const FP_FUNCT3_W: u8 = 0b010;
+
+/// fmt field of single-precision operations
+pub const FP_FMT_S: u8 = 0b00;
+
+/// Quiet NaN every NaN-producing operation returns
+pub const F32_CANONICAL_NAN: u32 = 0x7FC0_0000;
+
+// -------------------------
+// Accrued exception flags (fflags / fcsr[4:0])
+// -------------------------
+pub const FFLAG_NX: u8 = 1 << 0; // Inexact
+pub const FFLAG_UF: u8 = 1 << 1; // Underflow
+pub const FFLAG_OF: u8 = 1 << 2; // Overflow
+pub const FFLAG_DZ: u8 = 1 << 3; // Divide by zero
+pub const FFLAG_NV: u8 = 1 << 4; // Invalid operation
+pub const FFLAGS_MASK: u8 = 0x1F;
+
+/// rm field value selecting the dynamic rounding mode in frm
+pub const FRM_DYN: u8 = 0b111;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum round_mode_e {
+    #[default]
+    RM_RNE = 0b000, // Round to nearest, ties to even
+    RM_RTZ = 0b001, // Round towards zero
+    RM_RDN = 0b010, // Round down (towards -inf)
+    RM_RUP = 0b011, // Round up (towards +inf)
+    RM_RMM = 0b100, // Round to nearest, ties to max magnitude
+}
+
+impl round_mode_e {
+    /// Static rounding mode encoded in `bits`, or `None` for 101, 110 (reserved)
+    /// and 111 (dynamic).
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(Self::RM_RNE),
+            0b001 => Some(Self::RM_RTZ),
+            0b010 => Some(Self::RM_RDN),
+            0b011 => Some(Self::RM_RUP),
+            0b100 => Some(Self::RM_RMM),
+            _ => None,
+        }
+    }
+}
+
+// -------------------------
+// FCLASS.S result bits
+// -------------------------
+pub const FCLASS_NEG_INF: u32 = 1 << 0;
+pub const FCLASS_NEG_NORMAL: u32 = 1 << 1;
+pub const FCLASS_NEG_SUBNORMAL: u32 = 1 << 2;
+pub const FCLASS_NEG_ZERO: u32 = 1 << 3;
+pub const FCLASS_POS_ZERO: u32 = 1 << 4;
+pub const FCLASS_POS_SUBNORMAL: u32 = 1 << 5;
+pub const FCLASS_POS_NORMAL: u32 = 1 << 6;
+pub const FCLASS_POS_INF: u32 = 1 << 7;
+pub const FCLASS_SNAN: u32 = 1 << 8;
+pub const FCLASS_QNAN: u32 = 1 << 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum fp_op_e {
+    // -------------------------
+    // Arithmetic (rounded)
+    // -------------------------
+    FP_ADD,   // fd = fs1 + fs2
+    FP_SUB,   // fd = fs1 - fs2
+    FP_MUL,   // fd = fs1 * fs2
+    FP_DIV,   // fd = fs1 / fs2
+    FP_SQRT,  // fd = sqrt(fs1)
+    FP_MADD,  // fd = fs1 * fs2 + fs3
+    FP_MSUB,  // fd = fs1 * fs2 - fs3
+    FP_NMSUB, // fd = -(fs1 * fs2) + fs3
+    FP_NMADD, // fd = -(fs1 * fs2) - fs3
+
+    // -------------------------
+    // Sign injection and min/max (exact)
+    // -------------------------
+    FP_SGNJ,  // fd = |fs1| with the sign of fs2
+    FP_SGNJN, // fd = |fs1| with the opposite sign of fs2
+    FP_SGNJX, // fd = fs1 with sign(fs1) ^ sign(fs2)
+    FP_MIN,
+    FP_MAX,
+
+    // -------------------------
+    // Compare and classify (rd is an x register)
+    // -------------------------
+    FP_EQ,    // Quiet comparison
+    FP_LT,    // Signaling comparison
+    FP_LE,    // Signaling comparison
+    FP_CLASS, // One-hot FCLASS_* mask
+
+    // -------------------------
+    // Conversions and moves
+    // -------------------------
+    FP_CVT_W_S,  // rd = (i32) fs1
+    FP_CVT_WU_S, // rd = (u32) fs1
+    FP_CVT_S_W,  // fd = (f32) (i32) rs1
+    FP_CVT_S_WU, // fd = (f32) (u32) rs1
+    FP_MV_X_W,   // rd = bits of fs1
+    FP_MV_W_X,   // fd = bits of rs1
+}
+
+impl fp_op_e {
+    /// Operation of an OP-FP or fused multiply-add word, from the fields the
+    /// control unit sees, or `None` for a reserved encoding. The rounding mode
+    /// in funct3 is not checked here.
+    pub fn decode(opcode: opcode_t, funct3: u8, funct7: u8, rs2: u8) -> Option<Self> {
+        if funct7 & 0b11 != FP_FMT_S {
+            return None;
+        }
+
+        let op = match opcode {
+            opcode_t::OP_MADD => Self::FP_MADD,
+            opcode_t::OP_MSUB => Self::FP_MSUB,
+            opcode_t::OP_NMSUB => Self::FP_NMSUB,
+            opcode_t::OP_NMADD => Self::FP_NMADD,
+            opcode_t::OP_OP_FP => match (funct7 >> 2, funct3, rs2) {
+                (0b00000, _, _) => Self::FP_ADD,
+                (0b00001, _, _) => Self::FP_SUB,
+                (0b00010, _, _) => Self::FP_MUL,
+                (0b00011, _, _) => Self::FP_DIV,
+                (0b01011, _, 0) => Self::FP_SQRT,
+                (0b00100, 0b000, _) => Self::FP_SGNJ,
+                (0b00100, 0b001, _) => Self::FP_SGNJN,
+                (0b00100, 0b010, _) => Self::FP_SGNJX,
+                (0b00101, 0b000, _) => Self::FP_MIN,
+                (0b00101, 0b001, _) => Self::FP_MAX,
+                (0b10100, 0b010, _) => Self::FP_EQ,
+                (0b10100, 0b001, _) => Self::FP_LT,
+                (0b10100, 0b000, _) => Self::FP_LE,
+                (0b11000, _, 0) => Self::FP_CVT_W_S,
+                (0b11000, _, 1) => Self::FP_CVT_WU_S,
+                (0b11010, _, 0) => Self::FP_CVT_S_W,
+                (0b11010, _, 1) => Self::FP_CVT_S_WU,
+                (0b11100, 0b000, 0) => Self::FP_MV_X_W,
+                (0b11100, 0b001, 0) => Self::FP_CLASS,
+                (0b11110, 0b000, 0) => Self::FP_MV_W_X,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// True when funct3 holds a rounding mode.
+    pub fn uses_rm(self) -> bool {
+        !matches!(
+            self,
+            Self::FP_SGNJ
+                | Self::FP_SGNJN
+                | Self::FP_SGNJX
+                | Self::FP_MIN
+                | Self::FP_MAX
+                | Self::FP_EQ
+                | Self::FP_LT
+                | Self::FP_LE
+                | Self::FP_CLASS
+                | Self::FP_MV_X_W
+                | Self::FP_MV_W_X
+        )
+    }
+
+    /// True when rd is an x register.
+    pub fn writes_x(self) -> bool {
+        matches!(
+            self,
+            Self::FP_EQ | Self::FP_LT | Self::FP_LE | Self::FP_CLASS | Self::FP_CVT_W_S | Self::FP_CVT_WU_S | Self::FP_MV_X_W
+        )
+    }
+
+    /// True when rs1 is an x register.
+    pub fn reads_x(self) -> bool {
+        matches!(self, Self::FP_CVT_S_W | Self::FP_CVT_S_WU | Self::FP_MV_W_X)
+    }
+
+    /// True for the fused multiply-add forms, which read rs3.
+    pub fn is_fused(self) -> bool {
+        matches!(self, Self::FP_MADD | Self::FP_MSUB | Self::FP_NMSUB | Self::FP_NMADD)
+    }
+}
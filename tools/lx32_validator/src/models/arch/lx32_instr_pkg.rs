@@ -0,0 +1,425 @@
+// ============================================================
+// LX32 Typed Instructions (RV32IM)
+// ============================================================
+// One variant per RV32I/M instruction, holding its operands
+// as plain numbers, with the matching `encode()` and
+// `decode()`. Tools that build or rewrite programs (the
+// program generator, the shrinker) go through this type
+// instead of packing fields into bit positions themselves.
+//
+// Design Principles:
+//   - Immediates are the value the instruction uses: byte
+//     offsets for branches and jumps, the shifted value
+//     (low 12 bits zero) for LUI/AUIPC.
+//   - `decode` only accepts canonical words, so
+//     `decode(w).encode() == w` whenever it returns Some.
+//   - `encode` refuses operands outside their field instead
+//     of truncating them; `with_imm` checks first.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, instr_t, reg_idx_t};
+use crate::models::arch::lx32_decode_pkg::{get_b_imm, get_i_imm, get_j_imm, get_s_imm, get_u_imm};
+use crate::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL, opcode_t};
+
+/// An RV32IM instruction with its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    // U-type
+    Lui { rd: reg_idx_t, imm: i32 },
+    Auipc { rd: reg_idx_t, imm: i32 },
+    // Jumps
+    Jal { rd: reg_idx_t, imm: i32 },
+    Jalr { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    // Branches
+    Beq { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Bne { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Blt { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Bge { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Bltu { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Bgeu { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    // Loads
+    Lb { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Lh { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Lw { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Lbu { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Lhu { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    // Stores
+    Sb { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Sh { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    Sw { rs1: reg_idx_t, rs2: reg_idx_t, imm: i32 },
+    // Register-immediate ALU
+    Addi { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Slti { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Sltiu { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Xori { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Ori { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Andi { rd: reg_idx_t, rs1: reg_idx_t, imm: i32 },
+    Slli { rd: reg_idx_t, rs1: reg_idx_t, shamt: u8 },
+    Srli { rd: reg_idx_t, rs1: reg_idx_t, shamt: u8 },
+    Srai { rd: reg_idx_t, rs1: reg_idx_t, shamt: u8 },
+    // Register-register ALU
+    Add { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Sub { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Sll { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Slt { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Sltu { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Xor { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Srl { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Sra { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Or { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    And { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    // M extension
+    Mul { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Mulh { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Mulhsu { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Mulhu { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Div { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Divu { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Rem { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    Remu { rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t },
+    // Memory ordering and system
+    /// FENCE pred, succ (4-bit I/O/R/W sets; fm, rd and rs1 zero)
+    Fence { pred: u8, succ: u8 },
+    Ecall,
+    Ebreak,
+}
+
+use Instr::*;
+
+impl Instr {
+    /// Typed form of `instr`, or None for a word outside RV32IM or one
+    /// with a non-zero reserved field.
+    pub fn decode(instr: instr_t) -> Option<Instr> {
+        let rd = ((instr >> 7) & 0x1F) as reg_idx_t;
+        let rs1 = ((instr >> 15) & 0x1F) as reg_idx_t;
+        let rs2 = ((instr >> 20) & 0x1F) as reg_idx_t;
+        let funct3 = (instr >> 12) & 0x7;
+        let funct7 = instr >> 25;
+        let (i_imm, s_imm, b_imm) = (get_i_imm(instr) as i32, get_s_imm(instr) as i32, get_b_imm(instr) as i32);
+
+        let decoded = match opcode_t::from_bits((instr & 0x7F) as u8) {
+            opcode_t::OP_LUI => Lui { rd, imm: get_u_imm(instr) as i32 },
+            opcode_t::OP_AUIPC => Auipc { rd, imm: get_u_imm(instr) as i32 },
+            opcode_t::OP_JAL => Jal { rd, imm: get_j_imm(instr) as i32 },
+            opcode_t::OP_JALR if funct3 == 0b000 => Jalr { rd, rs1, imm: i_imm },
+            opcode_t::OP_BRANCH => match funct3 {
+                0b000 => Beq { rs1, rs2, imm: b_imm },
+                0b001 => Bne { rs1, rs2, imm: b_imm },
+                0b100 => Blt { rs1, rs2, imm: b_imm },
+                0b101 => Bge { rs1, rs2, imm: b_imm },
+                0b110 => Bltu { rs1, rs2, imm: b_imm },
+                0b111 => Bgeu { rs1, rs2, imm: b_imm },
+                _ => return None,
+            },
+            opcode_t::OP_LOAD => match funct3 {
+                0b000 => Lb { rd, rs1, imm: i_imm },
+                0b001 => Lh { rd, rs1, imm: i_imm },
+                0b010 => Lw { rd, rs1, imm: i_imm },
+                0b100 => Lbu { rd, rs1, imm: i_imm },
+                0b101 => Lhu { rd, rs1, imm: i_imm },
+                _ => return None,
+            },
+            opcode_t::OP_STORE => match funct3 {
+                0b000 => Sb { rs1, rs2, imm: s_imm },
+                0b001 => Sh { rs1, rs2, imm: s_imm },
+                0b010 => Sw { rs1, rs2, imm: s_imm },
+                _ => return None,
+            },
+            opcode_t::OP_OP_IMM => match (funct3, funct7) {
+                (0b000, _) => Addi { rd, rs1, imm: i_imm },
+                (0b010, _) => Slti { rd, rs1, imm: i_imm },
+                (0b011, _) => Sltiu { rd, rs1, imm: i_imm },
+                (0b100, _) => Xori { rd, rs1, imm: i_imm },
+                (0b110, _) => Ori { rd, rs1, imm: i_imm },
+                (0b111, _) => Andi { rd, rs1, imm: i_imm },
+                (0b001, 0b000_0000) => Slli { rd, rs1, shamt: rs2 },
+                (0b101, 0b000_0000) => Srli { rd, rs1, shamt: rs2 },
+                (0b101, 0b010_0000) => Srai { rd, rs1, shamt: rs2 },
+                _ => return None,
+            },
+            opcode_t::OP_OP => match (funct7, funct3) {
+                (0b000_0000, 0b000) => Add { rd, rs1, rs2 },
+                (0b010_0000, 0b000) => Sub { rd, rs1, rs2 },
+                (0b000_0000, 0b001) => Sll { rd, rs1, rs2 },
+                (0b000_0000, 0b010) => Slt { rd, rs1, rs2 },
+                (0b000_0000, 0b011) => Sltu { rd, rs1, rs2 },
+                (0b000_0000, 0b100) => Xor { rd, rs1, rs2 },
+                (0b000_0000, 0b101) => Srl { rd, rs1, rs2 },
+                (0b010_0000, 0b101) => Sra { rd, rs1, rs2 },
+                (0b000_0000, 0b110) => Or { rd, rs1, rs2 },
+                (0b000_0000, 0b111) => And { rd, rs1, rs2 },
+                (0b000_0001, 0b000) => Mul { rd, rs1, rs2 },
+                (0b000_0001, 0b001) => Mulh { rd, rs1, rs2 },
+                (0b000_0001, 0b010) => Mulhsu { rd, rs1, rs2 },
+                (0b000_0001, 0b011) => Mulhu { rd, rs1, rs2 },
+                (0b000_0001, 0b100) => Div { rd, rs1, rs2 },
+                (0b000_0001, 0b101) => Divu { rd, rs1, rs2 },
+                (0b000_0001, 0b110) => Rem { rd, rs1, rs2 },
+                (0b000_0001, 0b111) => Remu { rd, rs1, rs2 },
+                _ => return None,
+            },
+            // fm = 0000, rs1 = x0, funct3 = 000, rd = x0
+            opcode_t::OP_MISC_MEM if instr & 0xF00F_FFFF == 0x0000_000F => Fence {
+                pred: ((instr >> 24) & 0xF) as u8,
+                succ: ((instr >> 20) & 0xF) as u8,
+            },
+            opcode_t::OP_SYSTEM if instr == INSTR_ECALL => Ecall,
+            opcode_t::OP_SYSTEM if instr == INSTR_EBREAK => Ebreak,
+            _ => return None,
+        };
+        Some(decoded)
+    }
+
+    /// Instruction word. Panics when an operand does not fit its field
+    /// (see `fits`).
+    pub fn encode(self) -> instr_t {
+        assert!(self.fits(), "{:?} does not fit its encoding", self);
+        match self {
+            Lui { rd, imm } => u_type(imm, rd, opcode_t::OP_LUI),
+            Auipc { rd, imm } => u_type(imm, rd, opcode_t::OP_AUIPC),
+            Jal { rd, imm } => j_type(imm, rd),
+            Jalr { rd, rs1, imm } => i_type(imm, rs1, 0b000, rd, opcode_t::OP_JALR),
+            Beq { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b000),
+            Bne { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b001),
+            Blt { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b100),
+            Bge { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b101),
+            Bltu { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b110),
+            Bgeu { rs1, rs2, imm } => b_type(imm, rs2, rs1, 0b111),
+            Lb { rd, rs1, imm } => i_type(imm, rs1, 0b000, rd, opcode_t::OP_LOAD),
+            Lh { rd, rs1, imm } => i_type(imm, rs1, 0b001, rd, opcode_t::OP_LOAD),
+            Lw { rd, rs1, imm } => i_type(imm, rs1, 0b010, rd, opcode_t::OP_LOAD),
+            Lbu { rd, rs1, imm } => i_type(imm, rs1, 0b100, rd, opcode_t::OP_LOAD),
+            Lhu { rd, rs1, imm } => i_type(imm, rs1, 0b101, rd, opcode_t::OP_LOAD),
+            Sb { rs1, rs2, imm } => s_type(imm, rs2, rs1, 0b000),
+            Sh { rs1, rs2, imm } => s_type(imm, rs2, rs1, 0b001),
+            Sw { rs1, rs2, imm } => s_type(imm, rs2, rs1, 0b010),
+            Addi { rd, rs1, imm } => i_type(imm, rs1, 0b000, rd, opcode_t::OP_OP_IMM),
+            Slti { rd, rs1, imm } => i_type(imm, rs1, 0b010, rd, opcode_t::OP_OP_IMM),
+            Sltiu { rd, rs1, imm } => i_type(imm, rs1, 0b011, rd, opcode_t::OP_OP_IMM),
+            Xori { rd, rs1, imm } => i_type(imm, rs1, 0b100, rd, opcode_t::OP_OP_IMM),
+            Ori { rd, rs1, imm } => i_type(imm, rs1, 0b110, rd, opcode_t::OP_OP_IMM),
+            Andi { rd, rs1, imm } => i_type(imm, rs1, 0b111, rd, opcode_t::OP_OP_IMM),
+            Slli { rd, rs1, shamt } => r_type(0b000_0000, shamt, rs1, 0b001, rd, opcode_t::OP_OP_IMM),
+            Srli { rd, rs1, shamt } => r_type(0b000_0000, shamt, rs1, 0b101, rd, opcode_t::OP_OP_IMM),
+            Srai { rd, rs1, shamt } => r_type(0b010_0000, shamt, rs1, 0b101, rd, opcode_t::OP_OP_IMM),
+            Add { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b000, rd, opcode_t::OP_OP),
+            Sub { rd, rs1, rs2 } => r_type(0b010_0000, rs2, rs1, 0b000, rd, opcode_t::OP_OP),
+            Sll { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b001, rd, opcode_t::OP_OP),
+            Slt { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b010, rd, opcode_t::OP_OP),
+            Sltu { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b011, rd, opcode_t::OP_OP),
+            Xor { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b100, rd, opcode_t::OP_OP),
+            Srl { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b101, rd, opcode_t::OP_OP),
+            Sra { rd, rs1, rs2 } => r_type(0b010_0000, rs2, rs1, 0b101, rd, opcode_t::OP_OP),
+            Or { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b110, rd, opcode_t::OP_OP),
+            And { rd, rs1, rs2 } => r_type(0b000_0000, rs2, rs1, 0b111, rd, opcode_t::OP_OP),
+            Mul { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b000, rd, opcode_t::OP_OP),
+            Mulh { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b001, rd, opcode_t::OP_OP),
+            Mulhsu { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b010, rd, opcode_t::OP_OP),
+            Mulhu { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b011, rd, opcode_t::OP_OP),
+            Div { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b100, rd, opcode_t::OP_OP),
+            Divu { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b101, rd, opcode_t::OP_OP),
+            Rem { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b110, rd, opcode_t::OP_OP),
+            Remu { rd, rs1, rs2 } => r_type(0b000_0001, rs2, rs1, 0b111, rd, opcode_t::OP_OP),
+            Fence { pred, succ } => ((pred as u32) << 24) | ((succ as u32) << 20) | opcode_t::OP_MISC_MEM as u32,
+            Ecall => INSTR_ECALL,
+            Ebreak => INSTR_EBREAK,
+        }
+    }
+
+    /// True when every operand fits its field: registers x0-x31, the
+    /// immediate in range and aligned for its format.
+    pub fn fits(self) -> bool {
+        let regs = [self.rd(), self.rs1(), self.rs2()];
+        if regs.into_iter().flatten().any(|reg| reg as usize >= REG_COUNT) {
+            return false;
+        }
+        match self {
+            Lui { imm, .. } | Auipc { imm, .. } => imm & 0xFFF == 0,
+            Jal { imm, .. } => (-(1 << 20)..(1 << 20)).contains(&imm) && imm % 2 == 0,
+            Beq { imm, .. } | Bne { imm, .. } | Blt { imm, .. } | Bge { imm, .. } | Bltu { imm, .. } | Bgeu { imm, .. } => {
+                (-4096..4096).contains(&imm) && imm % 2 == 0
+            }
+            Slli { shamt, .. } | Srli { shamt, .. } | Srai { shamt, .. } => shamt < 32,
+            Fence { pred, succ } => pred < 16 && succ < 16,
+            _ => self.imm().is_none_or(|imm| (-2048..2048).contains(&imm)),
+        }
+    }
+
+    /// Destination register, if the instruction writes one.
+    pub fn rd(self) -> Option<reg_idx_t> {
+        match self {
+            Lui { rd, .. } | Auipc { rd, .. } | Jal { rd, .. } | Jalr { rd, .. } => Some(rd),
+            Lb { rd, .. } | Lh { rd, .. } | Lw { rd, .. } | Lbu { rd, .. } | Lhu { rd, .. } => Some(rd),
+            Addi { rd, .. } | Slti { rd, .. } | Sltiu { rd, .. } | Xori { rd, .. } | Ori { rd, .. } | Andi { rd, .. } => Some(rd),
+            Slli { rd, .. } | Srli { rd, .. } | Srai { rd, .. } => Some(rd),
+            Add { rd, .. } | Sub { rd, .. } | Sll { rd, .. } | Slt { rd, .. } | Sltu { rd, .. } => Some(rd),
+            Xor { rd, .. } | Srl { rd, .. } | Sra { rd, .. } | Or { rd, .. } | And { rd, .. } => Some(rd),
+            Mul { rd, .. } | Mulh { rd, .. } | Mulhsu { rd, .. } | Mulhu { rd, .. } => Some(rd),
+            Div { rd, .. } | Divu { rd, .. } | Rem { rd, .. } | Remu { rd, .. } => Some(rd),
+            _ => None,
+        }
+    }
+
+    /// First source register, if the instruction reads one.
+    pub fn rs1(self) -> Option<reg_idx_t> {
+        match self {
+            Lui { .. } | Auipc { .. } | Jal { .. } | Fence { .. } | Ecall | Ebreak => None,
+            Jalr { rs1, .. } | Beq { rs1, .. } | Bne { rs1, .. } | Blt { rs1, .. } | Bge { rs1, .. } | Bltu { rs1, .. } | Bgeu { rs1, .. } => Some(rs1),
+            Lb { rs1, .. } | Lh { rs1, .. } | Lw { rs1, .. } | Lbu { rs1, .. } | Lhu { rs1, .. } => Some(rs1),
+            Sb { rs1, .. } | Sh { rs1, .. } | Sw { rs1, .. } => Some(rs1),
+            Addi { rs1, .. } | Slti { rs1, .. } | Sltiu { rs1, .. } | Xori { rs1, .. } | Ori { rs1, .. } | Andi { rs1, .. } => Some(rs1),
+            Slli { rs1, .. } | Srli { rs1, .. } | Srai { rs1, .. } => Some(rs1),
+            Add { rs1, .. } | Sub { rs1, .. } | Sll { rs1, .. } | Slt { rs1, .. } | Sltu { rs1, .. } => Some(rs1),
+            Xor { rs1, .. } | Srl { rs1, .. } | Sra { rs1, .. } | Or { rs1, .. } | And { rs1, .. } => Some(rs1),
+            Mul { rs1, .. } | Mulh { rs1, .. } | Mulhsu { rs1, .. } | Mulhu { rs1, .. } => Some(rs1),
+            Div { rs1, .. } | Divu { rs1, .. } | Rem { rs1, .. } | Remu { rs1, .. } => Some(rs1),
+        }
+    }
+
+    /// Second source register, if the instruction reads one.
+    pub fn rs2(self) -> Option<reg_idx_t> {
+        match self {
+            Beq { rs2, .. } | Bne { rs2, .. } | Blt { rs2, .. } | Bge { rs2, .. } | Bltu { rs2, .. } | Bgeu { rs2, .. } => Some(rs2),
+            Sb { rs2, .. } | Sh { rs2, .. } | Sw { rs2, .. } => Some(rs2),
+            Add { rs2, .. } | Sub { rs2, .. } | Sll { rs2, .. } | Slt { rs2, .. } | Sltu { rs2, .. } => Some(rs2),
+            Xor { rs2, .. } | Srl { rs2, .. } | Sra { rs2, .. } | Or { rs2, .. } | And { rs2, .. } => Some(rs2),
+            Mul { rs2, .. } | Mulh { rs2, .. } | Mulhsu { rs2, .. } | Mulhu { rs2, .. } => Some(rs2),
+            Div { rs2, .. } | Divu { rs2, .. } | Rem { rs2, .. } | Remu { rs2, .. } => Some(rs2),
+            _ => None,
+        }
+    }
+
+    /// Immediate operand (the shift amount for SLLI/SRLI/SRAI), if any.
+    pub fn imm(self) -> Option<i32> {
+        match self {
+            Lui { imm, .. } | Auipc { imm, .. } | Jal { imm, .. } | Jalr { imm, .. } => Some(imm),
+            Beq { imm, .. } | Bne { imm, .. } | Blt { imm, .. } | Bge { imm, .. } | Bltu { imm, .. } | Bgeu { imm, .. } => Some(imm),
+            Lb { imm, .. } | Lh { imm, .. } | Lw { imm, .. } | Lbu { imm, .. } | Lhu { imm, .. } => Some(imm),
+            Sb { imm, .. } | Sh { imm, .. } | Sw { imm, .. } => Some(imm),
+            Addi { imm, .. } | Slti { imm, .. } | Sltiu { imm, .. } | Xori { imm, .. } | Ori { imm, .. } | Andi { imm, .. } => Some(imm),
+            Slli { shamt, .. } | Srli { shamt, .. } | Srai { shamt, .. } => Some(shamt as i32),
+            _ => None,
+        }
+    }
+
+    /// The same instruction with its immediate replaced by `new_imm`, or None
+    /// when it has no immediate or `new_imm` does not fit.
+    pub fn with_imm(self, new_imm: i32) -> Option<Instr> {
+        let mut instr = self;
+        match &mut instr {
+            Lui { imm, .. } | Auipc { imm, .. } | Jal { imm, .. } | Jalr { imm, .. } => *imm = new_imm,
+            Beq { imm, .. } | Bne { imm, .. } | Blt { imm, .. } | Bge { imm, .. } | Bltu { imm, .. } | Bgeu { imm, .. } => *imm = new_imm,
+            Lb { imm, .. } | Lh { imm, .. } | Lw { imm, .. } | Lbu { imm, .. } | Lhu { imm, .. } => *imm = new_imm,
+            Sb { imm, .. } | Sh { imm, .. } | Sw { imm, .. } => *imm = new_imm,
+            Addi { imm, .. } | Slti { imm, .. } | Sltiu { imm, .. } | Xori { imm, .. } | Ori { imm, .. } | Andi { imm, .. } => *imm = new_imm,
+            Slli { shamt, .. } | Srli { shamt, .. } | Srai { shamt, .. } => *shamt = u8::try_from(new_imm).ok()?,
+            _ => return None,
+        }
+        Some(instr).filter(|instr| instr.fits())
+    }
+
+    /// LB/LH/LW/LBU/LHU.
+    pub fn is_load(self) -> bool {
+        matches!(self, Lb { .. } | Lh { .. } | Lw { .. } | Lbu { .. } | Lhu { .. })
+    }
+
+    /// SB/SH/SW.
+    pub fn is_store(self) -> bool {
+        matches!(self, Sb { .. } | Sh { .. } | Sw { .. })
+    }
+
+    /// Upper-case name of the variant; the text of a whole instruction
+    /// comes from the disassembler.
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Lui { .. } => "LUI",
+            Auipc { .. } => "AUIPC",
+            Jal { .. } => "JAL",
+            Jalr { .. } => "JALR",
+            Beq { .. } => "BEQ",
+            Bne { .. } => "BNE",
+            Blt { .. } => "BLT",
+            Bge { .. } => "BGE",
+            Bltu { .. } => "BLTU",
+            Bgeu { .. } => "BGEU",
+            Lb { .. } => "LB",
+            Lh { .. } => "LH",
+            Lw { .. } => "LW",
+            Lbu { .. } => "LBU",
+            Lhu { .. } => "LHU",
+            Sb { .. } => "SB",
+            Sh { .. } => "SH",
+            Sw { .. } => "SW",
+            Addi { .. } => "ADDI",
+            Slti { .. } => "SLTI",
+            Sltiu { .. } => "SLTIU",
+            Xori { .. } => "XORI",
+            Ori { .. } => "ORI",
+            Andi { .. } => "ANDI",
+            Slli { .. } => "SLLI",
+            Srli { .. } => "SRLI",
+            Srai { .. } => "SRAI",
+            Add { .. } => "ADD",
+            Sub { .. } => "SUB",
+            Sll { .. } => "SLL",
+            Slt { .. } => "SLT",
+            Sltu { .. } => "SLTU",
+            Xor { .. } => "XOR",
+            Srl { .. } => "SRL",
+            Sra { .. } => "SRA",
+            Or { .. } => "OR",
+            And { .. } => "AND",
+            Mul { .. } => "MUL",
+            Mulh { .. } => "MULH",
+            Mulhsu { .. } => "MULHSU",
+            Mulhu { .. } => "MULHU",
+            Div { .. } => "DIV",
+            Divu { .. } => "DIVU",
+            Rem { .. } => "REM",
+            Remu { .. } => "REMU",
+            Fence { .. } => "FENCE",
+            Ecall => "ECALL",
+            Ebreak => "EBREAK",
+        }
+    }
+}
+
+// ------------------------------------------------------------
+// Format encoders
+// ------------------------------------------------------------
+
+/// value[hi:lo]
+fn bits(value: i32, hi: u32, lo: u32) -> u32 {
+    ((value as u32) >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+fn r_type(funct7: u32, rs2: reg_idx_t, rs1: reg_idx_t, funct3: u32, rd: reg_idx_t, opcode: opcode_t) -> instr_t {
+    (funct7 << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | opcode as u32
+}
+
+fn i_type(imm: i32, rs1: reg_idx_t, funct3: u32, rd: reg_idx_t, opcode: opcode_t) -> instr_t {
+    (bits(imm, 11, 0) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | opcode as u32
+}
+
+fn s_type(imm: i32, rs2: reg_idx_t, rs1: reg_idx_t, funct3: u32) -> instr_t {
+    (bits(imm, 11, 5) << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | (bits(imm, 4, 0) << 7) | opcode_t::OP_STORE as u32
+}
+
+fn b_type(imm: i32, rs2: reg_idx_t, rs1: reg_idx_t, funct3: u32) -> instr_t {
+    (bits(imm, 12, 12) << 31)
+        | (bits(imm, 10, 5) << 25)
+        | ((rs2 as u32) << 20)
+        | ((rs1 as u32) << 15)
+        | (funct3 << 12)
+        | (bits(imm, 4, 1) << 8)
+        | (bits(imm, 11, 11) << 7)
+        | opcode_t::OP_BRANCH as u32
+}
+
+fn u_type(imm: i32, rd: reg_idx_t, opcode: opcode_t) -> instr_t {
+    (imm as u32 & 0xFFFF_F000) | ((rd as u32) << 7) | opcode as u32
+}
+
+fn j_type(imm: i32, rd: reg_idx_t) -> instr_t {
+    (bits(imm, 20, 20) << 31) | (bits(imm, 10, 1) << 21) | (bits(imm, 11, 11) << 20) | (bits(imm, 19, 12) << 12) | ((rd as u32) << 7) | opcode_t::OP_JAL as u32
+}
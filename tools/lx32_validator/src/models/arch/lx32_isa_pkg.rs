@@ -39,11 +39,54 @@ pub enum opcode_t {
     OP_OP_IMM = 0b0010011, // I-type ALU
     OP_OP = 0b0110011,     // R-type ALU
 
+    // -------------------------
+    // Memory ordering
+    // -------------------------
+    OP_MISC_MEM = 0b0001111, // FENCE (golden only; executes as a NOP)
+
+    // -------------------------
+    // Atomics
+    // -------------------------
+    OP_AMO = 0b0101111, // LR/SC/AMO*.W (golden only)
+
+    // -------------------------
+    // Floating point (golden only, see lx32_fpu_pkg)
+    // -------------------------
+    OP_LOAD_FP = 0b0000111,  // FLW
+    OP_STORE_FP = 0b0100111, // FSW
+    OP_OP_FP = 0b1010011,    // Arithmetic, compare, convert and move
+    OP_MADD = 0b1000011,     // FMADD.S
+    OP_MSUB = 0b1000111,     // FMSUB.S
+    OP_NMSUB = 0b1001011,    // FNMSUB.S
+    OP_NMADD = 0b1001111,    // FNMADD.S
+
+    // -------------------------
+    // Custom (accelerators registered through IsaConfig::with_custom)
+    // -------------------------
+    OP_CUSTOM_0 = 0b0001011,
+    OP_CUSTOM_1 = 0b0101011,
+
+    // -------------------------
+    // System
+    // -------------------------
+    OP_SYSTEM = 0b1110011, // CSR access, ECALL/EBREAK/MRET/SFENCE.VMA (golden only)
+
     // -------------------------
     // Reserved / Fallback
     // -------------------------
     OP_INVALID = 0b0000000,
 }
+// -------------------------
+// SYSTEM instructions with funct3 = 000 (full words)
+// -------------------------
+pub const INSTR_ECALL: u32 = 0x0000_0073;
+pub const INSTR_EBREAK: u32 = 0x0010_0073;
+pub const INSTR_MRET: u32 = 0x3020_0073;
+
+/// SFENCE.VMA rs1, rs2: funct7 = 0001001, rd = 0; rs1/rs2 select what to flush
+pub const INSTR_SFENCE_VMA: u32 = 0x1200_0073;
+pub const SFENCE_VMA_MASK: u32 = 0xFE00_7FFF;
+
 impl opcode_t {
     pub fn from_bits(bits: u8) -> Self {
         match bits {
@@ -56,6 +99,18 @@ impl opcode_t {
             0b0100011 => Self::OP_STORE,
             0b0010011 => Self::OP_OP_IMM,
             0b0110011 => Self::OP_OP,
+            0b0001111 => Self::OP_MISC_MEM,
+            0b0101111 => Self::OP_AMO,
+            0b0000111 => Self::OP_LOAD_FP,
+            0b0100111 => Self::OP_STORE_FP,
+            0b1010011 => Self::OP_OP_FP,
+            0b1000011 => Self::OP_MADD,
+            0b1000111 => Self::OP_MSUB,
+            0b1001011 => Self::OP_NMSUB,
+            0b1001111 => Self::OP_NMADD,
+            0b0001011 => Self::OP_CUSTOM_0,
+            0b0101011 => Self::OP_CUSTOM_1,
+            0b1110011 => Self::OP_SYSTEM,
             _ => Self::OP_INVALID,
         }
     }
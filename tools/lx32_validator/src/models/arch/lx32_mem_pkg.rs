@@ -0,0 +1,65 @@
+// ============================================================
+// LX32 Memory Access Definitions
+// ============================================================
+// Defines the canonical data access widths used between
+// the execute stage and the memory system.
+//
+// The encoding matches funct3[1:0] of LOAD/STORE, so the
+// decoder can select the width without a translation table.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+use crate::models::arch::lx32_arch_pkg::addr_t;
+
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum mem_size_e {
+    MEM_BYTE = 0b00, // 8-bit access
+    MEM_HALF = 0b01, // 16-bit access
+    #[default]
+    MEM_WORD = 0b10, // 32-bit access
+}
+
+impl mem_size_e {
+    /// Number of bytes moved by an access of this width.
+    pub fn bytes(self) -> u32 {
+        match self {
+            Self::MEM_BYTE => 1,
+            Self::MEM_HALF => 2,
+            Self::MEM_WORD => 4,
+        }
+    }
+
+    /// Mask covering the low `bytes()` bytes of a data word.
+    pub fn mask(self) -> u32 {
+        match self {
+            Self::MEM_BYTE => 0x0000_00FF,
+            Self::MEM_HALF => 0x0000_FFFF,
+            Self::MEM_WORD => 0xFFFF_FFFF,
+        }
+    }
+
+    /// Byte-enable strobe of an access at lane 0.
+    pub fn strobe(self) -> u8 {
+        match self {
+            Self::MEM_BYTE => 0b0001,
+            Self::MEM_HALF => 0b0011,
+            Self::MEM_WORD => 0b1111,
+        }
+    }
+
+    /// Width selected by funct3[1:0] of a LOAD/STORE (0b11 falls back to word).
+    pub fn from_funct3(funct3: u8) -> Self {
+        match funct3 & 0b11 {
+            0b00 => Self::MEM_BYTE,
+            0b01 => Self::MEM_HALF,
+            _ => Self::MEM_WORD,
+        }
+    }
+
+    /// True when `addr` is naturally aligned for this width.
+    pub fn is_aligned(self, addr: addr_t) -> bool {
+        addr & (self.bytes() - 1) == 0
+    }
+}
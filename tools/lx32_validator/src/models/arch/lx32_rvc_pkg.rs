@@ -0,0 +1,233 @@
+// ============================================================
+// LX32 Compressed Instruction (RVC) Expansion
+// ============================================================
+// Maps every legal 16-bit RV32C instruction onto the 32-bit
+// instruction it stands for, so the rest of the core only
+// ever decodes full-size words.
+//
+// Design Principles:
+//   - One pure function per parcel, no core state involved.
+//   - Reserved encodings, RV64/RV128-only code points and the
+//     double-precision loads/stores expand to nothing (illegal).
+//     The single-precision ones (RV32FC) expand only when the
+//     F extension is enabled.
+//   - HINTs (rd = x0 forms) expand to their x0-writing
+//     equivalents, which execute as NOPs.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+use crate::models::arch::lx32_arch_pkg::instr_t;
+use crate::models::arch::lx32_isa_pkg::INSTR_EBREAK;
+
+// 16-bit instruction parcel
+pub type cinstr_t = u16;
+
+/// True when the low parcel of `instr` is a compressed instruction
+/// (instr[1:0] != 2'b11).
+pub fn is_compressed(instr: instr_t) -> bool {
+    instr & 0b11 != 0b11
+}
+
+/// Length in bytes of the instruction whose low parcel is `instr`.
+pub fn instr_len(instr: instr_t) -> u32 {
+    if is_compressed(instr) { 2 } else { 4 }
+}
+
+/// 32-bit equivalent of `c`, or `None` for a reserved or unsupported encoding.
+/// C.FLW/C.FSW/C.FLWSP/C.FSWSP expand only when `f_ext` is set.
+pub fn expand_compressed(c: cinstr_t, f_ext: bool) -> Option<instr_t> {
+    let c = c as u32;
+    let funct3 = bits(c, 15, 13);
+    // Full register fields (CR/CI/CSS) and the x8-x15 ones (CIW/CL/CS/CA/CB)
+    let rd = bits(c, 11, 7);
+    let rs2 = bits(c, 6, 2);
+    let rd_p = 8 + bits(c, 4, 2);
+    let rs1_p = 8 + bits(c, 9, 7);
+
+    match (c & 0b11, funct3) {
+        // ---------------- Quadrant 0 ----------------
+        // C.ADDI4SPN (nzuimm = 0 is reserved, which covers the all-zero parcel)
+        (0b00, 0b000) => {
+            let uimm = bits(c, 12, 11) << 4 | bits(c, 10, 7) << 6 | bit(c, 6) << 2 | bit(c, 5) << 3;
+            nonzero(uimm)?;
+            Some(i_type(uimm, 2, 0b000, rd_p, OPC_OP_IMM))
+        }
+        // C.LW
+        (0b00, 0b010) => Some(i_type(cl_uimm(c), rs1_p, 0b010, rd_p, OPC_LOAD)),
+        // C.FLW (RV32FC)
+        (0b00, 0b011) if f_ext => Some(i_type(cl_uimm(c), rs1_p, 0b010, rd_p, OPC_LOAD_FP)),
+        // C.SW
+        (0b00, 0b110) => Some(s_type(cl_uimm(c), rd_p, rs1_p, 0b010, OPC_STORE)),
+        // C.FSW (RV32FC)
+        (0b00, 0b111) if f_ext => Some(s_type(cl_uimm(c), rd_p, rs1_p, 0b010, OPC_STORE_FP)),
+
+        // ---------------- Quadrant 1 ----------------
+        // C.ADDI / C.NOP
+        (0b01, 0b000) => Some(i_type(ci_imm(c), rd, 0b000, rd, OPC_OP_IMM)),
+        // C.JAL (RV32 only)
+        (0b01, 0b001) => Some(j_type(cj_imm(c), 1)),
+        // C.LI
+        (0b01, 0b010) => Some(i_type(ci_imm(c), 0, 0b000, rd, OPC_OP_IMM)),
+        // C.ADDI16SP
+        (0b01, 0b011) if rd == 2 => {
+            let imm = sext(bit(c, 12) << 9 | bit(c, 6) << 4 | bit(c, 5) << 6 | bits(c, 4, 3) << 7 | bit(c, 2) << 5, 10);
+            nonzero(imm)?;
+            Some(i_type(imm, 2, 0b000, 2, OPC_OP_IMM))
+        }
+        // C.LUI
+        (0b01, 0b011) => {
+            let imm = sext(bit(c, 12) << 17 | rs2 << 12, 18);
+            nonzero(imm)?;
+            Some(imm & 0xFFFF_F000 | rd << 7 | OPC_LUI)
+        }
+        // MISC-ALU
+        (0b01, 0b100) => match bits(c, 11, 10) {
+            // C.SRLI / C.SRAI: shamt[5] = 1 is reserved on RV32
+            0b00 if bit(c, 12) == 0 => Some(i_type(rs2, rs1_p, 0b101, rs1_p, OPC_OP_IMM)),
+            0b01 if bit(c, 12) == 0 => Some(i_type(0x400 | rs2, rs1_p, 0b101, rs1_p, OPC_OP_IMM)),
+            // C.ANDI
+            0b10 => Some(i_type(ci_imm(c), rs1_p, 0b111, rs1_p, OPC_OP_IMM)),
+            // C.SUB / C.XOR / C.OR / C.AND (bit 12 set selects RV64 SUBW/ADDW)
+            0b11 if bit(c, 12) == 0 => {
+                let (funct7, funct3) = match bits(c, 6, 5) {
+                    0b00 => (0b010_0000, 0b000),
+                    0b01 => (0, 0b100),
+                    0b10 => (0, 0b110),
+                    _ => (0, 0b111),
+                };
+                Some(r_type(funct7, rd_p, rs1_p, funct3, rs1_p))
+            }
+            _ => None,
+        },
+        // C.J
+        (0b01, 0b101) => Some(j_type(cj_imm(c), 0)),
+        // C.BEQZ / C.BNEZ
+        (0b01, 0b110 | 0b111) => {
+            let imm = sext(bit(c, 12) << 8 | bits(c, 11, 10) << 3 | bits(c, 6, 5) << 6 | bits(c, 4, 3) << 1 | bit(c, 2) << 5, 9);
+            Some(b_type(imm, 0, rs1_p, funct3 & 1))
+        }
+
+        // ---------------- Quadrant 2 ----------------
+        // C.SLLI (shamt[5] = 1 is reserved on RV32)
+        (0b10, 0b000) if bit(c, 12) == 0 => Some(i_type(rs2, rd, 0b001, rd, OPC_OP_IMM)),
+        // C.LWSP (rd = x0 is reserved)
+        (0b10, 0b010) if rd != 0 => Some(i_type(lwsp_uimm(c), 2, 0b010, rd, OPC_LOAD)),
+        // C.FLWSP (RV32FC; any rd, f0 included)
+        (0b10, 0b011) if f_ext => Some(i_type(lwsp_uimm(c), 2, 0b010, rd, OPC_LOAD_FP)),
+        (0b10, 0b100) => match (bit(c, 12), rd, rs2) {
+            // C.JR (rs1 = x0 is reserved)
+            (0, 0, 0) => None,
+            (0, rs1, 0) => Some(i_type(0, rs1, 0b000, 0, OPC_JALR)),
+            // C.MV
+            (0, rd, rs2) => Some(r_type(0, rs2, 0, 0b000, rd)),
+            // C.EBREAK
+            (_, 0, 0) => Some(INSTR_EBREAK),
+            // C.JALR
+            (_, rs1, 0) => Some(i_type(0, rs1, 0b000, 1, OPC_JALR)),
+            // C.ADD
+            (_, rd, rs2) => Some(r_type(0, rs2, rd, 0b000, rd)),
+        },
+        // C.SWSP
+        (0b10, 0b110) => Some(s_type(swsp_uimm(c), rs2, 2, 0b010, OPC_STORE)),
+        // C.FSWSP (RV32FC)
+        (0b10, 0b111) if f_ext => Some(s_type(swsp_uimm(c), rs2, 2, 0b010, OPC_STORE_FP)),
+
+        // Reserved, RV64/RV128, double-precision and (without F) single-precision encodings
+        _ => None,
+    }
+}
+
+// ------------------------------------------------------------
+// Field helpers
+// ------------------------------------------------------------
+
+const OPC_LOAD: u32 = 0b0000011;
+const OPC_LOAD_FP: u32 = 0b0000111;
+const OPC_OP_IMM: u32 = 0b0010011;
+const OPC_STORE: u32 = 0b0100011;
+const OPC_STORE_FP: u32 = 0b0100111;
+const OPC_LUI: u32 = 0b0110111;
+const OPC_JALR: u32 = 0b1100111;
+
+/// c[hi:lo]
+fn bits(c: u32, hi: u32, lo: u32) -> u32 {
+    (c >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+fn bit(c: u32, pos: u32) -> u32 {
+    (c >> pos) & 1
+}
+
+/// Sign-extends the low `width` bits of `value`.
+fn sext(value: u32, width: u32) -> u32 {
+    (((value << (32 - width)) as i32) >> (32 - width)) as u32
+}
+
+fn nonzero(imm: u32) -> Option<()> {
+    (imm != 0).then_some(())
+}
+
+/// CI-format 6-bit signed immediate (C.ADDI, C.LI, C.ANDI).
+fn ci_imm(c: u32) -> u32 {
+    sext(bit(c, 12) << 5 | bits(c, 6, 2), 6)
+}
+
+/// CL/CS-format word offset (C.LW, C.SW, C.FLW, C.FSW).
+fn cl_uimm(c: u32) -> u32 {
+    bits(c, 12, 10) << 3 | bit(c, 6) << 2 | bit(c, 5) << 6
+}
+
+/// CI-format stack word offset (C.LWSP, C.FLWSP).
+fn lwsp_uimm(c: u32) -> u32 {
+    bit(c, 12) << 5 | bits(c, 6, 4) << 2 | bits(c, 3, 2) << 6
+}
+
+/// CSS-format stack word offset (C.SWSP, C.FSWSP).
+fn swsp_uimm(c: u32) -> u32 {
+    bits(c, 12, 9) << 2 | bits(c, 8, 7) << 6
+}
+
+/// CJ-format jump offset (C.J, C.JAL).
+fn cj_imm(c: u32) -> u32 {
+    let imm = bit(c, 12) << 11
+        | bit(c, 11) << 4
+        | bits(c, 10, 9) << 8
+        | bit(c, 8) << 10
+        | bit(c, 7) << 6
+        | bit(c, 6) << 7
+        | bits(c, 5, 3) << 1
+        | bit(c, 2) << 5;
+    sext(imm, 12)
+}
+
+// ------------------------------------------------------------
+// 32-bit encoders
+// ------------------------------------------------------------
+
+fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> instr_t {
+    funct7 << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | 0b0110011
+}
+
+fn i_type(imm: u32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> instr_t {
+    (imm & 0xFFF) << 20 | rs1 << 15 | funct3 << 12 | rd << 7 | opcode
+}
+
+fn s_type(imm: u32, rs2: u32, rs1: u32, funct3: u32, opcode: u32) -> instr_t {
+    bits(imm, 11, 5) << 25 | rs2 << 20 | rs1 << 15 | funct3 << 12 | bits(imm, 4, 0) << 7 | opcode
+}
+
+fn b_type(imm: u32, rs2: u32, rs1: u32, funct3: u32) -> instr_t {
+    bit(imm, 12) << 31
+        | bits(imm, 10, 5) << 25
+        | rs2 << 20
+        | rs1 << 15
+        | funct3 << 12
+        | bits(imm, 4, 1) << 8
+        | bit(imm, 11) << 7
+        | 0b1100011
+}
+
+fn j_type(imm: u32, rd: u32) -> instr_t {
+    bit(imm, 20) << 31 | bits(imm, 10, 1) << 21 | bit(imm, 11) << 20 | bits(imm, 19, 12) << 12 | rd << 7 | 0b1101111
+}
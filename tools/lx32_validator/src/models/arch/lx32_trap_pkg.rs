@@ -0,0 +1,80 @@
+// ============================================================
+// LX32 Trap Cause Definitions
+// ============================================================
+// Synchronous exception and interrupt codes, using the RISC-V
+// privileged mcause encoding (interrupt bit clear for
+// exceptions, set for interrupts).
+//
+// These are the values the golden model reports for a trap
+// and writes into mcause.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum trap_cause_e {
+    // -------------------------
+    // Fetch / Decode
+    // -------------------------
+    INSTR_ADDR_MISALIGNED = 0,
+    INSTR_ACCESS_FAULT = 1,
+    ILLEGAL_INSTRUCTION = 2,
+    BREAKPOINT = 3,
+
+    // -------------------------
+    // Memory
+    // -------------------------
+    LOAD_ADDR_MISALIGNED = 4,
+    LOAD_ACCESS_FAULT = 5,
+    STORE_ADDR_MISALIGNED = 6,
+    STORE_ACCESS_FAULT = 7,
+
+    // -------------------------
+    // Environment call
+    // -------------------------
+    ECALL_U = 8,
+    ECALL_M = 11,
+
+    // -------------------------
+    // Virtual memory (Sv32)
+    // -------------------------
+    INSTR_PAGE_FAULT = 12,
+    LOAD_PAGE_FAULT = 13,
+    STORE_PAGE_FAULT = 15,
+}
+
+impl trap_cause_e {
+    /// mcause value of this exception
+    pub fn code(self) -> u32 {
+        self as u32
+    }
+}
+
+/// mcause[31]: set for interrupts
+pub const MCAUSE_INTERRUPT: u32 = 1 << 31;
+
+/// Machine-level interrupt sources; the value is both the mcause
+/// exception code and the bit position in mie/mip.
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum irq_cause_e {
+    M_SOFTWARE = 3,
+    M_TIMER = 7,
+    M_EXTERNAL = 11,
+}
+
+impl irq_cause_e {
+    /// Delivery order when several interrupts are pending and enabled
+    pub const PRIORITY: [Self; 3] = [Self::M_EXTERNAL, Self::M_SOFTWARE, Self::M_TIMER];
+
+    /// mcause value of this interrupt (interrupt bit set)
+    pub fn code(self) -> u32 {
+        MCAUSE_INTERRUPT | self as u32
+    }
+
+    /// mie/mip bit of this interrupt
+    pub fn mask(self) -> u32 {
+        1 << self as u32
+    }
+}
@@ -0,0 +1,82 @@
+// ============================================================
+// LX32 Virtual Memory Definitions (Sv32)
+// ============================================================
+// satp fields, page table entry bits and access types of the
+// Sv32 scheme: 4 KB pages, 4 MB megapages, two-level tables
+// of 1024 four-byte entries and 34-bit physical addresses.
+// ============================================================
+
+#![allow(non_camel_case_types)]
+
+use crate::models::arch::lx32_arch_pkg::addr_t;
+use crate::models::arch::lx32_trap_pkg::trap_cause_e;
+
+// -------------------------
+// Pages
+// -------------------------
+pub const PAGE_SHIFT: u32 = 12;
+pub const PAGE_SIZE: u32 = 1 << PAGE_SHIFT;
+/// Bits of a virtual page number field (VPN[1], VPN[0])
+pub const VPN_BITS: u32 = 10;
+
+// -------------------------
+// satp
+// -------------------------
+pub const SATP_MODE_SV32: u32 = 1 << 31;
+pub const SATP_ASID_SHIFT: u32 = 22;
+pub const SATP_ASID_MASK: u32 = 0x1FF;
+pub const SATP_PPN_MASK: u32 = 0x003F_FFFF;
+
+// -------------------------
+// Page table entry
+// -------------------------
+pub const PTE_V: u32 = 1 << 0;
+pub const PTE_R: u32 = 1 << 1;
+pub const PTE_W: u32 = 1 << 2;
+pub const PTE_X: u32 = 1 << 3;
+pub const PTE_U: u32 = 1 << 4;
+pub const PTE_G: u32 = 1 << 5;
+pub const PTE_A: u32 = 1 << 6;
+pub const PTE_D: u32 = 1 << 7;
+pub const PTE_PPN_SHIFT: u32 = 10;
+
+/// VPN[`level`] of `vaddr` (level 1 indexes the root table).
+pub fn vpn(vaddr: addr_t, level: u32) -> u32 {
+    (vaddr >> (PAGE_SHIFT + VPN_BITS * level)) & ((1 << VPN_BITS) - 1)
+}
+
+/// Kind of access being translated; selects the permission bit and the fault causes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum access_type_e {
+    ACCESS_FETCH,
+    ACCESS_LOAD,
+    /// Stores, SC.W and AMOs
+    ACCESS_STORE,
+}
+
+impl access_type_e {
+    /// PTE bit a leaf must grant for this access
+    pub fn pte_perm(self) -> u32 {
+        match self {
+            Self::ACCESS_FETCH => PTE_X,
+            Self::ACCESS_LOAD => PTE_R,
+            Self::ACCESS_STORE => PTE_W,
+        }
+    }
+
+    pub fn page_fault(self) -> trap_cause_e {
+        match self {
+            Self::ACCESS_FETCH => trap_cause_e::INSTR_PAGE_FAULT,
+            Self::ACCESS_LOAD => trap_cause_e::LOAD_PAGE_FAULT,
+            Self::ACCESS_STORE => trap_cause_e::STORE_PAGE_FAULT,
+        }
+    }
+
+    pub fn access_fault(self) -> trap_cause_e {
+        match self {
+            Self::ACCESS_FETCH => trap_cause_e::INSTR_ACCESS_FAULT,
+            Self::ACCESS_LOAD => trap_cause_e::LOAD_ACCESS_FAULT,
+            Self::ACCESS_STORE => trap_cause_e::STORE_ACCESS_FAULT,
+        }
+    }
+}
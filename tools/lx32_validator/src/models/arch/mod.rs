@@ -1,6 +1,15 @@
 // src/models/arch/mods.rs
 pub mod lx32_alu_pkg;
+pub mod lx32_amo_pkg;
 pub mod lx32_arch_pkg;
 pub mod lx32_branch_pkg;
+pub mod lx32_cinstr_pkg;
+pub mod lx32_csr_pkg;
 pub mod lx32_decode_pkg;
+pub mod lx32_fpu_pkg;
+pub mod lx32_instr_pkg;
 pub mod lx32_isa_pkg;
+pub mod lx32_mem_pkg;
+pub mod lx32_rvc_pkg;
+pub mod lx32_trap_pkg;
+pub mod lx32_vm_pkg;
@@ -1,7 +1,8 @@
 // ============================================================
 // LX32 Arithmetic Logic Unit
 // ============================================================
-// Supports LX32 base base ALU operations.
+// Supports LX32 base base ALU operations, the M extension and
+// the Zba/Zbb/Zbs bit-manipulation extensions.
 //
 // Design Goals:
 //   - WIDTH parametrizable
@@ -57,5 +58,70 @@ pub fn alu_golden_model(src_a: u32, src_b: u32, alu_control: alu_op_e) -> u32 {
         alu_op_e::ALU_XOR => src_a ^ src_b,
         alu_op_e::ALU_OR => src_a | src_b,
         alu_op_e::ALU_AND => src_a & src_b,
+
+        // -------------------------
+        // M extension
+        // -------------------------
+        alu_op_e::ALU_MUL => src_a.wrapping_mul(src_b),
+        alu_op_e::ALU_MULH => ((src_a as i32 as i64 * src_b as i32 as i64) >> 32) as u32,
+        alu_op_e::ALU_MULHSU => ((src_a as i32 as i64 * src_b as i64) >> 32) as u32,
+        alu_op_e::ALU_MULHU => ((src_a as u64 * src_b as u64) >> 32) as u32,
+
+        // Division never traps:
+        //   x / 0          -> quotient all ones, remainder x
+        //   i32::MIN / -1  -> quotient i32::MIN, remainder 0
+        alu_op_e::ALU_DIV => {
+            if src_b == 0 {
+                u32::MAX
+            } else {
+                (src_a as i32).wrapping_div(src_b as i32) as u32
+            }
+        }
+        alu_op_e::ALU_DIVU => src_a.checked_div(src_b).unwrap_or(u32::MAX),
+        alu_op_e::ALU_REM => {
+            if src_b == 0 {
+                src_a
+            } else {
+                (src_a as i32).wrapping_rem(src_b as i32) as u32
+            }
+        }
+        alu_op_e::ALU_REMU => src_a.checked_rem(src_b).unwrap_or(src_a),
+
+        // -------------------------
+        // Zba
+        // -------------------------
+        alu_op_e::ALU_SH1ADD => (src_a << 1).wrapping_add(src_b),
+        alu_op_e::ALU_SH2ADD => (src_a << 2).wrapping_add(src_b),
+        alu_op_e::ALU_SH3ADD => (src_a << 3).wrapping_add(src_b),
+
+        // -------------------------
+        // Zbb
+        // -------------------------
+        alu_op_e::ALU_ANDN => src_a & !src_b,
+        alu_op_e::ALU_ORN => src_a | !src_b,
+        alu_op_e::ALU_XNOR => !(src_a ^ src_b),
+        // Unary operations ignore src_b
+        alu_op_e::ALU_CLZ => src_a.leading_zeros(),
+        alu_op_e::ALU_CTZ => src_a.trailing_zeros(),
+        alu_op_e::ALU_CPOP => src_a.count_ones(),
+        alu_op_e::ALU_MIN => (src_a as i32).min(src_b as i32) as u32,
+        alu_op_e::ALU_MINU => src_a.min(src_b),
+        alu_op_e::ALU_MAX => (src_a as i32).max(src_b as i32) as u32,
+        alu_op_e::ALU_MAXU => src_a.max(src_b),
+        alu_op_e::ALU_ROL => src_a.rotate_left(shamt),
+        alu_op_e::ALU_ROR => src_a.rotate_right(shamt),
+        alu_op_e::ALU_SEXT_B => src_a as i8 as i32 as u32,
+        alu_op_e::ALU_SEXT_H => src_a as i16 as i32 as u32,
+        alu_op_e::ALU_ZEXT_H => src_a & 0xFFFF,
+        alu_op_e::ALU_REV8 => src_a.swap_bytes(),
+        alu_op_e::ALU_ORC_B => u32::from_le_bytes(src_a.to_le_bytes().map(|b| if b != 0 { 0xFF } else { 0x00 })),
+
+        // -------------------------
+        // Zbs
+        // -------------------------
+        alu_op_e::ALU_BCLR => src_a & !(1 << shamt),
+        alu_op_e::ALU_BEXT => (src_a >> shamt) & 1,
+        alu_op_e::ALU_BINV => src_a ^ (1 << shamt),
+        alu_op_e::ALU_BSET => src_a | (1 << shamt),
     }
 }
@@ -0,0 +1,31 @@
+// ============================================================
+// LX32 Atomic Memory Operation Unit
+// ============================================================
+// Computes the value an AMO*.W writes back to memory from the
+// old memory word and rs2.
+//
+// Design Principles:
+//   - Pure combinational logic, like the ALU.
+//   - The read, the write and the reservation set live in the
+//     system model; this unit only combines the operands.
+// ============================================================
+
+use crate::models::arch::lx32_amo_pkg::amo_op_e;
+use crate::models::arch::lx32_arch_pkg::data_t;
+
+/// New memory value of `op` applied to the old value `mem` and `src` (rs2).
+/// LR leaves memory unchanged; SC stores `src`.
+pub fn amo_golden(op: amo_op_e, mem: data_t, src: data_t) -> data_t {
+    match op {
+        amo_op_e::AMO_LR => mem,
+        amo_op_e::AMO_SC | amo_op_e::AMO_SWAP => src,
+        amo_op_e::AMO_ADD => mem.wrapping_add(src),
+        amo_op_e::AMO_XOR => mem ^ src,
+        amo_op_e::AMO_AND => mem & src,
+        amo_op_e::AMO_OR => mem | src,
+        amo_op_e::AMO_MIN => (mem as i32).min(src as i32) as data_t,
+        amo_op_e::AMO_MAX => (mem as i32).max(src as i32) as data_t,
+        amo_op_e::AMO_MINU => mem.min(src),
+        amo_op_e::AMO_MAXU => mem.max(src),
+    }
+}
@@ -0,0 +1,83 @@
+// ============================================================
+// LX32 Memory Bus Abstraction
+// ============================================================
+// Lets the golden model drive its own instruction fetches,
+// loads and stores instead of receiving them from a harness.
+//
+// Design Principles:
+//   - One trait for every memory the model can run against
+//   - Access width travels with each data access
+//   - Little-endian byte order, as on the RTL data port
+//   - Infallible: unmapped reads return 0, unmapped writes drop
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::core::lsu::lsu_read_lane;
+use crate::models::core::memory_sim::MemorySim;
+
+/// Memory system seen by `Lx32System::step_with_bus`.
+///
+/// Loads return the value zero-extended to 32 bits; sign extension is
+/// the core's job. Stores only use the low `size.bytes()` bytes of `data`.
+pub trait Bus {
+    /// Instruction fetch at `addr`.
+    fn fetch(&mut self, addr: addr_t) -> instr_t;
+
+    /// Data read of `size` bytes at `addr`.
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t;
+
+    /// Data write of the low `size` bytes of `data` at `addr`.
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e);
+}
+
+/// Flat byte-addressed RAM starting at address 0.
+impl Bus for [u8] {
+    fn fetch(&mut self, addr: addr_t) -> instr_t {
+        self.load(addr, mem_size_e::MEM_WORD)
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        let a = addr as usize;
+        let n = size.bytes() as usize;
+        match self.get(a..a.saturating_add(n)) {
+            Some(bytes) => bytes
+                .iter()
+                .rev()
+                .fold(0, |acc, &b| (acc << 8) | b as data_t),
+            None => 0, // Out-of-bounds reads return 0
+        }
+    }
+
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e) {
+        let a = addr as usize;
+        let n = size.bytes() as usize;
+        if let Some(bytes) = self.get_mut(a..a.saturating_add(n)) {
+            bytes.copy_from_slice(&data.to_le_bytes()[..n]);
+        }
+    }
+}
+
+/// Word-organised 4KB simulation memory; sub-word accesses select byte lanes,
+/// word accesses ignore addr[1:0].
+impl Bus for MemorySim {
+    fn fetch(&mut self, addr: addr_t) -> instr_t {
+        self.read_instr(addr)
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        lsu_read_lane(self.read_data(addr), addr, size)
+    }
+
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e) {
+        if size == mem_size_e::MEM_WORD {
+            // Whole-word writes ignore addr[1:0], like the data port
+            self.write_data(addr, data, true);
+            return;
+        }
+        let shift = 8 * (addr & 0x3);
+        let lanes = size.mask() << shift;
+        let merged = (self.read_data(addr) & !lanes) | ((data << shift) & lanes);
+        self.write_data(addr, merged, true);
+    }
+}
@@ -0,0 +1,123 @@
+// ============================================================
+// LX32 Core-Local Interruptor (CLINT)
+// ============================================================
+// Memory-mapped machine timer and software interrupt, using
+// the SiFive CLINT register layout:
+//   - msip      at BASE + 0x0000 (bit 0 drives mip.MSIP)
+//   - mtimecmp  at BASE + 0x4000 (64-bit)
+//   - mtime     at BASE + 0xBFF8 (64-bit, +1 every cycle)
+//
+// Design Principles:
+//   - Registers are plain state; the system ticks mtime and
+//     samples the interrupt lines between instructions.
+//   - Accessed through the same `Bus` trait as memory, so
+//     sub-word accesses select byte lanes of each register.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use crate::models::arch::lx32_csr_pkg::{MIP_MSIP, MIP_MTIP};
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::core::bus::Bus;
+use crate::models::core::lsu::lsu_read_lane;
+
+/// Base address of the CLINT register block
+pub const CLINT_BASE: addr_t = 0x0200_0000;
+/// Size of the CLINT register block in bytes
+pub const CLINT_SIZE: addr_t = 0x1_0000;
+
+pub const CLINT_MSIP: addr_t = CLINT_BASE;
+pub const CLINT_MTIMECMP: addr_t = CLINT_BASE + 0x4000;
+pub const CLINT_MTIME: addr_t = CLINT_BASE + 0xBFF8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clint {
+    /// Machine software interrupt request
+    pub msip: bool,
+    /// Timer compare value; MTIP is pending while mtime >= mtimecmp
+    pub mtimecmp: u64,
+    /// Free-running timer
+    pub mtime: u64,
+}
+
+/// Reset state: timer at 0 and mtimecmp at its maximum, so no timer
+/// interrupt is pending until software programs one.
+impl Default for Clint {
+    fn default() -> Self {
+        Self {
+            msip: false,
+            mtimecmp: u64::MAX,
+            mtime: 0,
+        }
+    }
+}
+
+impl Clint {
+    /// Reset state (see `Default`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True when `addr` falls in the CLINT register block.
+    pub fn contains(addr: addr_t) -> bool {
+        addr.wrapping_sub(CLINT_BASE) < CLINT_SIZE
+    }
+
+    /// Advances mtime by one cycle.
+    pub fn tick(&mut self) {
+        self.mtime = self.mtime.wrapping_add(1);
+    }
+
+    /// mip bits driven by the CLINT (MSIP, MTIP).
+    pub fn pending(&self) -> u32 {
+        let msip = if self.msip { MIP_MSIP } else { 0 };
+        let mtip = if self.mtime >= self.mtimecmp { MIP_MTIP } else { 0 };
+        msip | mtip
+    }
+
+    /// Register word at `addr` (word aligned); unmapped offsets read as 0.
+    fn read_word(&self, addr: addr_t) -> data_t {
+        match addr & !0b11 {
+            CLINT_MSIP => self.msip as data_t,
+            CLINT_MTIMECMP => self.mtimecmp as data_t,
+            a if a == CLINT_MTIMECMP + 4 => (self.mtimecmp >> 32) as data_t,
+            CLINT_MTIME => self.mtime as data_t,
+            a if a == CLINT_MTIME + 4 => (self.mtime >> 32) as data_t,
+            _ => 0,
+        }
+    }
+
+    fn write_word(&mut self, addr: addr_t, word: data_t) {
+        let lo = |old: u64| (old & !0xFFFF_FFFF) | word as u64;
+        let hi = |old: u64| (old & 0xFFFF_FFFF) | (word as u64) << 32;
+        match addr & !0b11 {
+            CLINT_MSIP => self.msip = word & 1 != 0,
+            CLINT_MTIMECMP => self.mtimecmp = lo(self.mtimecmp),
+            a if a == CLINT_MTIMECMP + 4 => self.mtimecmp = hi(self.mtimecmp),
+            CLINT_MTIME => self.mtime = lo(self.mtime),
+            a if a == CLINT_MTIME + 4 => self.mtime = hi(self.mtime),
+            _ => {}
+        }
+    }
+}
+
+impl Bus for Clint {
+    /// The CLINT holds no code
+    fn fetch(&mut self, _addr: addr_t) -> instr_t {
+        0
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        lsu_read_lane(self.read_word(addr), addr, size)
+    }
+
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e) {
+        if size == mem_size_e::MEM_WORD {
+            self.write_word(addr, data);
+            return;
+        }
+        let shift = 8 * (addr & 0x3);
+        let lanes = size.mask() << shift;
+        let merged = (self.read_word(addr) & !lanes) | ((data << shift) & lanes);
+        self.write_word(addr, merged);
+    }
+}
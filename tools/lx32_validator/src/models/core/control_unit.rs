@@ -12,12 +12,16 @@
 // ============================================================
 
 use crate::models::arch::lx32_alu_pkg::alu_op_e;
+use crate::models::arch::lx32_amo_pkg::{AMO_FUNCT3_W, amo_op_e};
 use crate::models::arch::lx32_branch_pkg::branch_op_e;
+use crate::models::arch::lx32_fpu_pkg::{FP_FUNCT3_W, fp_op_e};
 use crate::models::arch::lx32_isa_pkg::opcode_t;
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::core::isa_config::IsaConfig;
 
 /// ControlSignals groups all control lines generated by the decode logic.
 /// This acts as the output port of the Control Unit.
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct ControlSignals {
     pub reg_write: bool,
     pub alu_src: bool,
@@ -29,6 +33,14 @@ pub struct ControlSignals {
     pub src_a_pc: bool,
     pub branch_op: branch_op_e,
     pub alu_control: alu_op_e,
+    pub mem_size: mem_size_e,  // Access width of LOAD/STORE (word unless sub-word decode is on)
+    pub mem_unsigned: bool,    // Zero-extend loaded value (LBU/LHU)
+    pub csr: bool,             // Zicsr access; write-back takes the old CSR value
+    pub amo: Option<amo_op_e>, // A extension: address is rs1, SC writes back its status
+    pub custom: bool,          // Registered custom instruction; write-back takes its result
+    pub fp: Option<fp_op_e>,   // F extension: write-back takes the FPU result
+    pub fp_reg_write: bool,    // rd is an f register (FLW and FPU results)
+    pub fp_store: bool,        // FSW: store data comes from f[rs2]
 }
 
 /// Internal enumeration for the two-level ALU decoding scheme.
@@ -41,8 +53,12 @@ enum AluMain {
 
 /// LX32 Control Unit - Golden Model
 /// Performs instruction decoding to generate control signals.
-pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7_5: bool) -> ControlSignals {
+/// `cfg` selects decode beyond the RTL base profile (e.g. sub-word LOAD/STORE).
+/// `rs2` is instr[24:20], which selects the unary Zbb operations.
+pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7: u8, rs2: u8, cfg: &IsaConfig) -> ControlSignals {
     let mut sigs = ControlSignals::default();
+    // The RTL decoder only looks at funct7[5]
+    let funct7_5 = funct7 & 0x20 != 0;
     let mut alu_op_main = AluMain::Add;
 
     // ============================================================
@@ -54,11 +70,20 @@ pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7_5: bool) -> Cont
             sigs.reg_write = true;
             sigs.alu_src = true;
             sigs.result_src = 0b01;
+            // funct3: 000=LB 001=LH 010=LW 100=LBU 101=LHU
+            if cfg.sub_word_mem {
+                sigs.mem_size = mem_size_e::from_funct3(funct3);
+                sigs.mem_unsigned = funct3 & 0b100 != 0;
+            }
         }
         // Store Instructions
         opcode_t::OP_STORE => {
             sigs.alu_src = true;
             sigs.mem_write = true;
+            // funct3: 000=SB 001=SH 010=SW
+            if cfg.sub_word_mem {
+                sigs.mem_size = mem_size_e::from_funct3(funct3);
+            }
         }
         // R-Type ALU Instructions
         opcode_t::OP_OP => {
@@ -113,6 +138,46 @@ pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7_5: bool) -> Cont
             sigs.jalr = true;
             sigs.alu_src = true;
         }
+        // CSRRW/CSRRS/CSRRC and immediate forms (golden only)
+        opcode_t::OP_SYSTEM if cfg.zicsr && funct3 & 0b011 != 0 => {
+            sigs.reg_write = true;
+            sigs.csr = true;
+        }
+        // LR.W/SC.W/AMO*.W (golden only): the ALU adds the zero immediate to rs1
+        opcode_t::OP_AMO if cfg.a_ext && funct3 == AMO_FUNCT3_W => {
+            if let Some(op) = amo_op_e::from_funct5(funct7 >> 2) {
+                sigs.reg_write = true;
+                sigs.alu_src = true;
+                sigs.result_src = if op == amo_op_e::AMO_SC { 0b00 } else { 0b01 };
+                sigs.mem_write = op != amo_op_e::AMO_LR;
+                sigs.amo = Some(op);
+            }
+        }
+        // FLW/FSW (golden only): word accesses addressed like LW/SW
+        opcode_t::OP_LOAD_FP if cfg.f_ext && funct3 == FP_FUNCT3_W => {
+            sigs.fp_reg_write = true;
+            sigs.alu_src = true;
+            sigs.result_src = 0b01;
+        }
+        opcode_t::OP_STORE_FP if cfg.f_ext && funct3 == FP_FUNCT3_W => {
+            sigs.alu_src = true;
+            sigs.mem_write = true;
+            sigs.fp_store = true;
+        }
+        // OP-FP and fused multiply-add (golden only): compares, FCLASS and
+        // moves/conversions to an integer write x[rd], the rest f[rd]
+        opcode_t::OP_OP_FP | opcode_t::OP_MADD | opcode_t::OP_MSUB | opcode_t::OP_NMSUB | opcode_t::OP_NMADD if cfg.f_ext => {
+            if let Some(op) = fp_op_e::decode(opcode, funct3, funct7, rs2) {
+                sigs.reg_write = op.writes_x();
+                sigs.fp_reg_write = !op.writes_x();
+                sigs.fp = Some(op);
+            }
+        }
+        // custom-0/custom-1 (golden only): the registered instruction computes rd
+        opcode_t::OP_CUSTOM_0 | opcode_t::OP_CUSTOM_1 if cfg.custom.claims(opcode) => {
+            sigs.reg_write = true;
+            sigs.custom = true;
+        }
         // Default: No-op or Invalid
         _ => {}
     }
@@ -123,6 +188,17 @@ pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7_5: bool) -> Cont
     sigs.alu_control = match alu_op_main {
         AluMain::Add => alu_op_e::ALU_ADD,
         AluMain::Sub => alu_op_e::ALU_SUB,
+        // M extension: OP_OP with funct7 = 0000001
+        AluMain::Func if cfg.m_ext && opcode == opcode_t::OP_OP && funct7 == 0b000_0001 => match funct3 {
+            0b000 => alu_op_e::ALU_MUL,
+            0b001 => alu_op_e::ALU_MULH,
+            0b010 => alu_op_e::ALU_MULHSU,
+            0b011 => alu_op_e::ALU_MULHU,
+            0b100 => alu_op_e::ALU_DIV,
+            0b101 => alu_op_e::ALU_DIVU,
+            0b110 => alu_op_e::ALU_REM,
+            _ => alu_op_e::ALU_REMU,
+        },
         AluMain::Func => {
             match funct3 {
                 // ADD or SUB
@@ -153,5 +229,60 @@ pub fn control_unit_golden(opcode: opcode_t, funct3: u8, funct7_5: bool) -> Cont
         }
     };
 
+    // Zba/Zbb/Zbs need the full funct7, which the base decode above ignores
+    if alu_op_main == AluMain::Func
+        && let Some(op) = bitmanip_alu_op(opcode, funct3, funct7, rs2, cfg)
+    {
+        sigs.alu_control = op;
+    }
+
     sigs
 }
+
+/// ALU operation of a Zba/Zbb/Zbs instruction, or `None` when the encoding is
+/// not one of them or its extension is disabled in `cfg`.
+pub fn bitmanip_alu_op(opcode: opcode_t, funct3: u8, funct7: u8, rs2: u8, cfg: &IsaConfig) -> Option<alu_op_e> {
+    use opcode_t::{OP_OP, OP_OP_IMM};
+
+    let (enabled, op) = match (opcode, funct7, funct3) {
+        // Zba
+        (OP_OP, 0b001_0000, 0b010) => (cfg.zba, alu_op_e::ALU_SH1ADD),
+        (OP_OP, 0b001_0000, 0b100) => (cfg.zba, alu_op_e::ALU_SH2ADD),
+        (OP_OP, 0b001_0000, 0b110) => (cfg.zba, alu_op_e::ALU_SH3ADD),
+
+        // Zbb: logic with negate
+        (OP_OP, 0b010_0000, 0b111) => (cfg.zbb, alu_op_e::ALU_ANDN),
+        (OP_OP, 0b010_0000, 0b110) => (cfg.zbb, alu_op_e::ALU_ORN),
+        (OP_OP, 0b010_0000, 0b100) => (cfg.zbb, alu_op_e::ALU_XNOR),
+        // Zbb: min/max
+        (OP_OP, 0b000_0101, 0b100) => (cfg.zbb, alu_op_e::ALU_MIN),
+        (OP_OP, 0b000_0101, 0b101) => (cfg.zbb, alu_op_e::ALU_MINU),
+        (OP_OP, 0b000_0101, 0b110) => (cfg.zbb, alu_op_e::ALU_MAX),
+        (OP_OP, 0b000_0101, 0b111) => (cfg.zbb, alu_op_e::ALU_MAXU),
+        // Zbb: rotates (RORI takes the shift amount from the immediate)
+        (OP_OP, 0b011_0000, 0b001) => (cfg.zbb, alu_op_e::ALU_ROL),
+        (OP_OP | OP_OP_IMM, 0b011_0000, 0b101) => (cfg.zbb, alu_op_e::ALU_ROR),
+        // Zbb: unary operations, selected by the rs2 field
+        (OP_OP_IMM, 0b011_0000, 0b001) => match rs2 {
+            0b00000 => (cfg.zbb, alu_op_e::ALU_CLZ),
+            0b00001 => (cfg.zbb, alu_op_e::ALU_CTZ),
+            0b00010 => (cfg.zbb, alu_op_e::ALU_CPOP),
+            0b00100 => (cfg.zbb, alu_op_e::ALU_SEXT_B),
+            0b00101 => (cfg.zbb, alu_op_e::ALU_SEXT_H),
+            _ => return None,
+        },
+        (OP_OP, 0b000_0100, 0b100) if rs2 == 0 => (cfg.zbb, alu_op_e::ALU_ZEXT_H),
+        (OP_OP_IMM, 0b011_0100, 0b101) if rs2 == 0b11000 => (cfg.zbb, alu_op_e::ALU_REV8),
+        (OP_OP_IMM, 0b001_0100, 0b101) if rs2 == 0b00111 => (cfg.zbb, alu_op_e::ALU_ORC_B),
+
+        // Zbs (register and immediate forms)
+        (OP_OP | OP_OP_IMM, 0b010_0100, 0b001) => (cfg.zbs, alu_op_e::ALU_BCLR),
+        (OP_OP | OP_OP_IMM, 0b010_0100, 0b101) => (cfg.zbs, alu_op_e::ALU_BEXT),
+        (OP_OP | OP_OP_IMM, 0b011_0100, 0b001) => (cfg.zbs, alu_op_e::ALU_BINV),
+        (OP_OP | OP_OP_IMM, 0b001_0100, 0b001) => (cfg.zbs, alu_op_e::ALU_BSET),
+
+        _ => return None,
+    };
+
+    enabled.then_some(op)
+}
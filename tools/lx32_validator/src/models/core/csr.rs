@@ -0,0 +1,419 @@
+// ============================================================
+// LX32 Control and Status Register File (Zicsr)
+// ============================================================
+// Machine-mode CSRs of the golden model:
+//   - mstatus, misa, mie, mtvec, mscratch, mepc, mcause, mtval
+//   - mip, whose bits mirror the interrupt lines driven by
+//     the system (read-only to software)
+//   - mcycle/minstret (64-bit, with high halves) and their
+//     read-only unprivileged shadows cycle/instret
+//   - mvendorid/marchid/mimpid/mhartid (read-only zero)
+//   - with U-mode: the current privilege level, mstatus.MPP
+//     and MPRV, and mcounteren
+//   - with PMP: pmpcfg0-3 and pmpaddr0-15
+//   - with Sv32: satp
+//   - with F: fflags, frm and fcsr, which views both, and
+//     mstatus.FS/SD, which tell a trap handler whether f0-f31
+//     and fcsr were written since FS was last set
+//
+// Design Principles:
+//   - WARL fields are legalised on write, so a read always
+//     returns a legal value.
+//   - Legality of an access (unknown address, write to a
+//     read-only register) is a decode-time property and is
+//     checked by the strict decoder, not here.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t};
+use crate::models::arch::lx32_csr_pkg::*;
+use crate::models::arch::lx32_fpu_pkg::FFLAGS_MASK;
+use crate::models::arch::lx32_trap_pkg::MCAUSE_INTERRUPT;
+use crate::models::core::isa_config::IsaConfig;
+use crate::models::core::pmp::{PMP_ENTRIES, Pmp};
+
+/// mtvec MODE field: 0 = direct, 1 = vectored; 2 and 3 are reserved.
+const MTVEC_MODE_MASK: u32 = 0b11;
+
+/// mstatus bits software can change (MPP is fixed to M-mode without U-mode).
+const MSTATUS_WRITABLE: u32 = MSTATUS_MIE | MSTATUS_MPIE;
+
+/// frm field width (fcsr[7:5]); reserved modes are stored and trap on use.
+const FRM_MASK: u32 = 0b111;
+
+/// mcounteren bits backed by a counter (CY, IR).
+const MCOUNTEREN_IMPLEMENTED: u32 = MCOUNTEREN_CY | MCOUNTEREN_IR;
+
+/// Interrupt bits implemented in mie and mip.
+const MIP_IMPLEMENTED: u32 = MIP_MSIP | MIP_MTIP | MIP_MEIP;
+
+/// True when `addr` names a CSR implemented by the golden model for `cfg`.
+pub fn csr_exists(addr: csr_addr_t, cfg: &IsaConfig) -> bool {
+    let pmpcfg = CSR_PMPCFG0..CSR_PMPCFG0 + PMP_ENTRIES as csr_addr_t / 4;
+    let pmpaddr = CSR_PMPADDR0..CSR_PMPADDR0 + PMP_ENTRIES as csr_addr_t;
+    match addr {
+        CSR_MCOUNTEREN => return cfg.user_mode,
+        CSR_SATP => return cfg.sv32,
+        CSR_FFLAGS | CSR_FRM | CSR_FCSR => return cfg.f_ext,
+        _ if pmpcfg.contains(&addr) || pmpaddr.contains(&addr) => return cfg.pmp,
+        _ => {}
+    }
+
+    matches!(
+        addr,
+        CSR_MVENDORID
+            | CSR_MARCHID
+            | CSR_MIMPID
+            | CSR_MHARTID
+            | CSR_MSTATUS
+            | CSR_MISA
+            | CSR_MIE
+            | CSR_MTVEC
+            | CSR_MSCRATCH
+            | CSR_MEPC
+            | CSR_MCAUSE
+            | CSR_MTVAL
+            | CSR_MIP
+            | CSR_MCYCLE
+            | CSR_MINSTRET
+            | CSR_MCYCLEH
+            | CSR_MINSTRETH
+            | CSR_CYCLE
+            | CSR_INSTRET
+            | CSR_CYCLEH
+            | CSR_INSTRETH
+    )
+}
+
+/// New CSR value for a Zicsr operation on `old` with source operand `src`.
+pub fn csr_apply(op: csr_op_e, old: data_t, src: data_t) -> data_t {
+    match op {
+        csr_op_e::CSR_RW => src,
+        csr_op_e::CSR_RS => old | src,
+        csr_op_e::CSR_RC => old & !src,
+    }
+}
+
+/// Machine-mode CSR state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrFile {
+    mstatus: u32,
+    misa: u32,
+    mie: u32,
+    mip: u32,
+    mtvec: u32,
+    mscratch: u32,
+    mepc: u32,
+    mcause: u32,
+    mtval: u32,
+    mcycle: u64,
+    minstret: u64,
+    mcounteren: u32,
+    satp: u32,
+    /// Accrued floating-point exception flags (fcsr[4:0])
+    fflags: u32,
+    /// Dynamic rounding mode (fcsr[7:5])
+    frm: u32,
+    /// Current privilege level (always M without U-mode)
+    privilege: priv_mode_e,
+    user_mode: bool,
+    f_ext: bool,
+    pmp: Option<Pmp>,
+}
+
+impl CsrFile {
+    /// Reset state; misa reports the extensions enabled in `cfg`.
+    pub fn new(cfg: &IsaConfig) -> Self {
+        // E replaces I with the 16-register file
+        let base = if cfg.rv32e { b'E' } else { b'I' };
+        let mut misa = (1 << 30) | (1 << (base - b'A'));
+        if cfg.m_ext {
+            misa |= 1 << (b'M' - b'A');
+        }
+        if cfg.a_ext {
+            // Bit 0: A
+            misa |= 1;
+        }
+        if cfg.c_ext {
+            misa |= 1 << (b'C' - b'A');
+        }
+        // B is Zba + Zbb + Zbs
+        if cfg.zba && cfg.zbb && cfg.zbs {
+            misa |= 1 << (b'B' - b'A');
+        }
+        if cfg.f_ext {
+            misa |= 1 << (b'F' - b'A');
+        }
+        if cfg.user_mode {
+            misa |= 1 << (b'U' - b'A');
+        }
+
+        // FS resets to Initial rather than Off, so FP code runs without a
+        // csrs first (crt0.S never touches mstatus)
+        let fs = if cfg.f_ext { FS_INITIAL << MSTATUS_FS_SHIFT } else { 0 };
+
+        Self {
+            mstatus: MSTATUS_MPP | fs,
+            misa,
+            mie: 0,
+            mip: 0,
+            mtvec: 0,
+            mscratch: 0,
+            mepc: 0,
+            mcause: 0,
+            mtval: 0,
+            mcycle: 0,
+            minstret: 0,
+            mcounteren: 0,
+            satp: 0,
+            fflags: 0,
+            frm: 0,
+            privilege: priv_mode_e::PRIV_M,
+            user_mode: cfg.user_mode,
+            f_ext: cfg.f_ext,
+            pmp: cfg.pmp.then(Pmp::new),
+        }
+    }
+
+    /// Current value of `addr` (0 for unimplemented addresses).
+    pub fn read(&self, addr: csr_addr_t) -> data_t {
+        match addr {
+            CSR_MSTATUS if self.fs() == FS_DIRTY => self.mstatus | MSTATUS_SD,
+            CSR_MSTATUS => self.mstatus,
+            CSR_MISA => self.misa,
+            CSR_MIE => self.mie,
+            CSR_MIP => self.mip,
+            CSR_MTVEC => self.mtvec,
+            CSR_MSCRATCH => self.mscratch,
+            CSR_MEPC => self.mepc,
+            CSR_MCAUSE => self.mcause,
+            CSR_MTVAL => self.mtval,
+            CSR_MCYCLE | CSR_CYCLE => self.mcycle as u32,
+            CSR_MCYCLEH | CSR_CYCLEH => (self.mcycle >> 32) as u32,
+            CSR_MINSTRET | CSR_INSTRET => self.minstret as u32,
+            CSR_MINSTRETH | CSR_INSTRETH => (self.minstret >> 32) as u32,
+            CSR_MCOUNTEREN => self.mcounteren,
+            CSR_SATP => self.satp,
+            CSR_FFLAGS => self.fflags,
+            CSR_FRM => self.frm,
+            CSR_FCSR => self.frm << 5 | self.fflags,
+            _ => match (&self.pmp, pmp_index(addr)) {
+                (Some(pmp), Some(PmpCsr::Cfg(i))) => pmp.read_cfg(i),
+                (Some(pmp), Some(PmpCsr::Addr(i))) => pmp.read_addr(i),
+                _ => 0,
+            },
+        }
+    }
+
+    /// Writes `value` to `addr`, legalising WARL fields.
+    /// Read-only and unimplemented addresses, misa and mip ignore the write.
+    pub fn write(&mut self, addr: csr_addr_t, value: data_t) {
+        match addr {
+            CSR_MSTATUS if self.user_mode => {
+                // MPP holds M or U; the reserved encodings keep the old mode
+                let mpp = match (value & MSTATUS_MPP) >> MSTATUS_MPP_SHIFT {
+                    0b00 | 0b11 => value & MSTATUS_MPP,
+                    _ => self.mstatus & MSTATUS_MPP,
+                };
+                self.mstatus = mpp | (value & (self.mstatus_writable() | MSTATUS_MPRV));
+            }
+            CSR_MSTATUS => self.mstatus = MSTATUS_MPP | (value & self.mstatus_writable()),
+            CSR_MIE => self.mie = value & MIP_IMPLEMENTED,
+            CSR_MTVEC => {
+                // Reserved modes fall back to direct
+                let mode = if value & MTVEC_MODE_MASK <= 1 { value & MTVEC_MODE_MASK } else { 0 };
+                self.mtvec = (value & !MTVEC_MODE_MASK) | mode;
+            }
+            CSR_MSCRATCH => self.mscratch = value,
+            CSR_MEPC => self.mepc = value & self.mepc_mask(),
+            CSR_MCAUSE => self.mcause = value,
+            CSR_MTVAL => self.mtval = value,
+            CSR_MCYCLE => self.mcycle = (self.mcycle & !0xFFFF_FFFF) | value as u64,
+            CSR_MCYCLEH => self.mcycle = (self.mcycle & 0xFFFF_FFFF) | (value as u64) << 32,
+            CSR_MINSTRET => self.minstret = (self.minstret & !0xFFFF_FFFF) | value as u64,
+            CSR_MINSTRETH => self.minstret = (self.minstret & 0xFFFF_FFFF) | (value as u64) << 32,
+            CSR_MCOUNTEREN => self.mcounteren = value & MCOUNTEREN_IMPLEMENTED,
+            CSR_SATP => self.satp = value,
+            CSR_FFLAGS => {
+                self.fflags = value & FFLAGS_MASK as u32;
+                self.set_fs_dirty();
+            }
+            CSR_FRM => {
+                self.frm = value & FRM_MASK;
+                self.set_fs_dirty();
+            }
+            CSR_FCSR => {
+                self.fflags = value & FFLAGS_MASK as u32;
+                self.frm = (value >> 5) & FRM_MASK;
+                self.set_fs_dirty();
+            }
+            _ => match (&mut self.pmp, pmp_index(addr)) {
+                (Some(pmp), Some(PmpCsr::Cfg(i))) => pmp.write_cfg(i, value),
+                (Some(pmp), Some(PmpCsr::Addr(i))) => pmp.write_addr(i, value),
+                _ => {}
+            },
+        }
+    }
+
+    /// Advances the counters by one clock cycle; `retired` also bumps minstret.
+    pub fn tick(&mut self, retired: bool) {
+        self.mcycle = self.mcycle.wrapping_add(1);
+        if retired {
+            self.minstret = self.minstret.wrapping_add(1);
+        }
+    }
+
+    /// Drives the mip bits from the interrupt lines (MSIP/MTIP/MEIP).
+    pub fn set_pending(&mut self, mip: u32) {
+        self.mip = mip & MIP_IMPLEMENTED;
+    }
+
+    /// Interrupts that are pending, enabled in mie and globally enabled:
+    /// by mstatus.MIE in M-mode, always while running in U-mode.
+    pub fn enabled_interrupts(&self) -> u32 {
+        let global = self.mstatus & MSTATUS_MIE != 0 || self.privilege < priv_mode_e::PRIV_M;
+        if global { self.mip & self.mie } else { 0 }
+    }
+
+    /// Records trap entry: mepc/mcause/mtval, MIE saved into MPIE then cleared,
+    /// and the interrupted privilege saved into MPP before switching to M-mode.
+    /// MPRV and FS are kept.
+    /// Returns the handler address (synchronous exceptions always use the mtvec base).
+    pub fn enter_trap(&mut self, pc: addr_t, cause: u32, tval: data_t) -> addr_t {
+        self.mepc = pc & self.mepc_mask();
+        self.mcause = cause;
+        self.mtval = tval;
+
+        let mpie = if self.mstatus & MSTATUS_MIE != 0 { MSTATUS_MPIE } else { 0 };
+        self.mstatus = (self.mstatus & (MSTATUS_MPRV | MSTATUS_FS)) | self.privilege.mpp() | mpie;
+        self.privilege = priv_mode_e::PRIV_M;
+
+        self.trap_vector()
+    }
+
+    /// Records interrupt entry like `enter_trap` (mtval = 0). In vectored
+    /// mode the handler address is BASE + 4 * exception code.
+    pub fn enter_interrupt(&mut self, pc: addr_t, cause: u32) -> addr_t {
+        let base = self.enter_trap(pc, cause, 0);
+        if self.mtvec & MTVEC_MODE_MASK == 1 {
+            base.wrapping_add(4 * (cause & !MCAUSE_INTERRUPT))
+        } else {
+            base
+        }
+    }
+
+    /// MRET: restores MIE from MPIE, sets MPIE and returns mepc. With U-mode
+    /// it also drops to the mode in MPP, which becomes U; leaving M-mode clears MPRV.
+    /// FS is kept.
+    pub fn mret(&mut self) -> addr_t {
+        let mie = if self.mstatus & MSTATUS_MPIE != 0 { MSTATUS_MIE } else { 0 };
+        let fs = self.mstatus & MSTATUS_FS;
+        if !self.user_mode {
+            self.mstatus = MSTATUS_MPP | MSTATUS_MPIE | mie | fs;
+            return self.mepc;
+        }
+
+        self.privilege = priv_mode_e::from_mpp(self.mstatus);
+        let mprv = if self.privilege == priv_mode_e::PRIV_M { self.mstatus & MSTATUS_MPRV } else { 0 };
+        self.mstatus = mprv | priv_mode_e::PRIV_U.mpp() | MSTATUS_MPIE | mie | fs;
+        self.mepc
+    }
+
+    /// Current privilege level
+    pub fn privilege(&self) -> priv_mode_e {
+        self.privilege
+    }
+
+    /// Privilege loads and stores are checked at: MPP while mstatus.MPRV is set.
+    pub fn data_privilege(&self) -> priv_mode_e {
+        if self.mstatus & MSTATUS_MPRV != 0 { priv_mode_e::from_mpp(self.mstatus) } else { self.privilege }
+    }
+
+    /// True when the current mode may access `addr`: csr[9:8] must not exceed
+    /// it, and U-mode reads of cycle/instret need their mcounteren bit.
+    pub fn accessible(&self, addr: csr_addr_t) -> bool {
+        if self.privilege == priv_mode_e::PRIV_M {
+            return true;
+        }
+        let enable = match addr {
+            CSR_CYCLE | CSR_CYCLEH => MCOUNTEREN_CY,
+            CSR_INSTRET | CSR_INSTRETH => MCOUNTEREN_IR,
+            _ => 0,
+        };
+        csr_min_privilege(addr) <= self.privilege as u8 && self.mcounteren & enable == enable
+    }
+
+    /// PMP check of an access of `len` bytes at `addr` needing `perms`
+    /// (PMP_R/PMP_W/PMP_X) at privilege `mode`. Always allowed without PMP.
+    pub fn pmp_allows(&self, addr: addr_t, len: u32, perms: u8, mode: priv_mode_e) -> bool {
+        self.pmp.as_ref().is_none_or(|pmp| pmp.allows(addr, len, perms, mode))
+    }
+
+    /// ORs the exception flags raised by a floating-point instruction into fflags.
+    pub fn accrue_fflags(&mut self, flags: u8) {
+        if flags & FFLAGS_MASK != 0 {
+            self.fflags |= (flags & FFLAGS_MASK) as u32;
+            self.set_fs_dirty();
+        }
+    }
+
+    /// mstatus.FS (FS_OFF, FS_INITIAL, FS_CLEAN or FS_DIRTY); always Off without F.
+    pub fn fs(&self) -> u32 {
+        (self.mstatus & MSTATUS_FS) >> MSTATUS_FS_SHIFT
+    }
+
+    /// Records a write to f0-f31 or fcsr: FS becomes Dirty (unless Off).
+    pub fn set_fs_dirty(&mut self) {
+        if self.fs() != FS_OFF {
+            self.mstatus |= MSTATUS_FS;
+        }
+    }
+
+    /// Dynamic rounding mode field, reserved values included.
+    pub fn frm(&self) -> u8 {
+        self.frm as u8
+    }
+
+    /// mstatus bits software can change: MIE, MPIE, and FS with F
+    /// (MPP and MPRV are handled with U-mode).
+    fn mstatus_writable(&self) -> u32 {
+        if self.f_ext { MSTATUS_WRITABLE | MSTATUS_FS } else { MSTATUS_WRITABLE }
+    }
+
+    /// mtvec BASE: handler address of synchronous exceptions.
+    pub fn trap_vector(&self) -> addr_t {
+        self.mtvec & !MTVEC_MODE_MASK
+    }
+
+    /// mepc bits kept on write: IALIGN = 16 with C in misa, 32 otherwise.
+    fn mepc_mask(&self) -> u32 {
+        if self.misa & (1 << (b'C' - b'A')) != 0 { !0b01 } else { !0b11 }
+    }
+
+    /// 64-bit cycle counter
+    pub fn cycles(&self) -> u64 {
+        self.mcycle
+    }
+
+    /// 64-bit retired-instruction counter
+    pub fn instret(&self) -> u64 {
+        self.minstret
+    }
+}
+
+/// A PMP CSR: pmpcfg`n` or pmpaddr`n`.
+enum PmpCsr {
+    Cfg(usize),
+    Addr(usize),
+}
+
+fn pmp_index(addr: csr_addr_t) -> Option<PmpCsr> {
+    let cfg = addr.wrapping_sub(CSR_PMPCFG0) as usize;
+    let entry = addr.wrapping_sub(CSR_PMPADDR0) as usize;
+    if cfg < PMP_ENTRIES / 4 {
+        Some(PmpCsr::Cfg(cfg))
+    } else if entry < PMP_ENTRIES {
+        Some(PmpCsr::Addr(entry))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,129 @@
+// ============================================================
+// LX32 Custom Instruction Extension Point
+// ============================================================
+// Accelerator instructions in the custom-0 (0001011) and
+// custom-1 (0101011) opcode spaces, registered through
+// `IsaConfig::with_custom`.
+//
+// One `CustomInstruction` implementation supplies everything
+// the tool needs to know about an instruction: its decode,
+// its execute semantics, how the program generator builds a
+// random instance, its mnemonic and its encoder. The strict
+// decoder, the control unit, `Lx32System` and the program
+// generator consult the registered set, so nothing else has
+// to be edited to add one.
+//
+// Design Principles:
+//   - R-type datapath: read rs1/rs2, write rd. funct3, funct7
+//     and the rest of the word are the instruction's own to
+//     decode; an instruction that keeps an immediate where a
+//     register field would be says so in `register_fields`.
+//   - Registered sets are `'static`, so `IsaConfig` stays a
+//     plain `Copy` value.
+//   - First match wins when two instructions claim one word.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{data_t, instr_t, reg_idx_t};
+use crate::models::arch::lx32_isa_pkg::opcode_t;
+use rand::{Rng, RngExt};
+use std::fmt;
+
+/// One custom instruction (or a family sharing an implementation).
+pub trait CustomInstruction: Sync {
+    /// Major opcode: `OP_CUSTOM_0` or `OP_CUSTOM_1`.
+    fn opcode(&self) -> opcode_t;
+
+    /// Short name, used by the default mnemonic and in logs.
+    fn name(&self) -> &'static str;
+
+    /// True when `instr` (whose opcode is `opcode()`) is this instruction.
+    fn decode(&self, instr: instr_t) -> bool;
+
+    /// Value written to rd, from the instruction word and both source registers.
+    fn execute(&self, instr: instr_t, rs1: data_t, rs2: data_t) -> data_t;
+
+    /// Encoding with the given register fields.
+    fn encode(&self, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> instr_t;
+
+    /// Assembly text of `instr`; defaults to `name rd, rs1, rs2`.
+    fn mnemonic(&self, instr: instr_t) -> String {
+        format!("{} x{}, x{}, x{}", self.name(), rd_field(instr), rs1_field(instr), rs2_field(instr))
+    }
+
+    /// Register fields `instr` uses, as [rd, rs1, rs2]; defaults to all
+    /// three. Unused fields are not checked against RV32E's 16 registers,
+    /// and without rd nothing is written back.
+    fn register_fields(&self, _instr: instr_t) -> [bool; 3] {
+        [true; 3]
+    }
+
+    /// Generator strategy naming only x0..`reg_count`-1; defaults to random
+    /// registers (rd != x0).
+    fn generate(&self, rng: &mut dyn Rng, reg_count: usize) -> instr_t {
+        let count = reg_count as reg_idx_t;
+        self.encode(rng.random_range(1..count), rng.random_range(0..count), rng.random_range(0..count))
+    }
+}
+
+/// The custom instructions a golden model instance implements.
+#[derive(Clone, Copy, Default)]
+pub struct CustomSet(pub &'static [&'static dyn CustomInstruction]);
+
+impl CustomSet {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Registered instructions, in registration order.
+    pub fn iter(&self) -> impl Iterator<Item = &'static dyn CustomInstruction> {
+        self.0.iter().copied()
+    }
+
+    /// True when at least one registered instruction lives in `opcode`.
+    pub fn claims(&self, opcode: opcode_t) -> bool {
+        self.iter().any(|c| c.opcode() == opcode)
+    }
+
+    /// The registered instruction `instr` decodes to, if any.
+    pub fn find(&self, instr: instr_t) -> Option<&'static dyn CustomInstruction> {
+        let opcode = opcode_t::from_bits((instr & 0x7F) as u8);
+        self.iter().find(|c| c.opcode() == opcode && c.decode(instr))
+    }
+}
+
+/// Two sets are equal when they are the same registration.
+impl PartialEq for CustomSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.0.as_ptr() == other.0.as_ptr()
+    }
+}
+
+impl Eq for CustomSet {}
+
+impl fmt::Debug for CustomSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter().map(|c| c.name())).finish()
+    }
+}
+
+/// R-type word in a custom opcode space: funct7 | rs2 | rs1 | funct3 | rd | opcode.
+pub fn encode_custom(opcode: opcode_t, funct7: u8, funct3: u8, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> instr_t {
+    (funct7 as u32) << 25
+        | ((rs2 & 0x1F) as u32) << 20
+        | ((rs1 & 0x1F) as u32) << 15
+        | ((funct3 & 0x7) as u32) << 12
+        | ((rd & 0x1F) as u32) << 7
+        | opcode as u32
+}
+
+pub fn rd_field(instr: instr_t) -> reg_idx_t {
+    ((instr >> 7) & 0x1F) as reg_idx_t
+}
+
+pub fn rs1_field(instr: instr_t) -> reg_idx_t {
+    ((instr >> 15) & 0x1F) as reg_idx_t
+}
+
+pub fn rs2_field(instr: instr_t) -> reg_idx_t {
+    ((instr >> 20) & 0x1F) as reg_idx_t
+}
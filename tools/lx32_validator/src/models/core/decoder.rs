@@ -0,0 +1,199 @@
+// ============================================================
+// LX32 Strict Instruction Decoder
+// ============================================================
+// Classifies every 32-bit word as legal or illegal for a
+// given ISA profile. With the C extension, a compressed
+// parcel is classified through its 32-bit expansion.
+//
+// The control unit keeps the permissive RTL behaviour
+// (unknown opcodes act as NOPs, reserved branch funct3 values
+// fall back to BEQ). This module is the architectural view:
+// anything outside the enabled ISA is reported as illegal,
+// with the field that made it so.
+// ============================================================
+
+use crate::models::arch::lx32_amo_pkg::{AMO_FUNCT3_W, amo_op_e};
+use crate::models::arch::lx32_arch_pkg::instr_t;
+use crate::models::arch::lx32_csr_pkg::{csr_addr_t, csr_is_read_only, csr_op_e};
+use crate::models::arch::lx32_fpu_pkg::{FP_FUNCT3_W, fp_op_e};
+use crate::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL, INSTR_MRET, INSTR_SFENCE_VMA, SFENCE_VMA_MASK, opcode_t};
+use crate::models::arch::lx32_rvc_pkg::{expand_compressed, is_compressed};
+use crate::models::core::control_unit::bitmanip_alu_op;
+use crate::models::core::csr::csr_exists;
+use crate::models::core::isa_config::IsaConfig;
+
+/// Why a word is not a legal instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalReason {
+    /// instr[1:0] != 2'b11: not a 32-bit encoding (C extension disabled)
+    Length,
+    /// Reserved or unsupported 16-bit encoding (C extension enabled)
+    Compressed,
+    /// Opcode outside the enabled ISA
+    Opcode,
+    /// Reserved funct3 for this opcode (including the reserved rounding modes)
+    Funct3,
+    /// Reserved funct7 (or shift immediate upper bits, AMO funct5, LR.W rs2 != x0,
+    /// OP-FP funct5/fmt/sub-opcode)
+    Funct7,
+    /// Custom opcode whose registered instructions all reject the word
+    Custom,
+    /// Unimplemented CSR, or a write to a read-only CSR
+    Csr,
+    /// SYSTEM funct3 = 000 other than ECALL, EBREAK and MRET
+    System,
+    /// Register field naming x16-x31 (RV32E)
+    Register,
+}
+
+/// Strict decode: returns the opcode of a legal instruction or why it is illegal.
+pub fn classify_instr(instr: instr_t, cfg: &IsaConfig) -> Result<opcode_t, IllegalReason> {
+    if is_compressed(instr) {
+        if !cfg.c_ext {
+            return Err(IllegalReason::Length);
+        }
+        let expanded = expand_compressed(instr as u16, cfg.f_ext).ok_or(IllegalReason::Compressed)?;
+        return classify_instr(expanded, cfg);
+    }
+
+    let opcode = opcode_t::from_bits((instr & 0x7F) as u8);
+    let funct3 = (instr >> 12) & 0x7;
+    let funct7 = (instr >> 25) & 0x7F;
+
+    let rs2 = ((instr >> 20) & 0x1F) as u8;
+
+    let legal = match opcode {
+        // Zba/Zbb/Zbs reuse OP/OP-IMM funct7 values the base ISA reserves
+        opcode_t::OP_OP | opcode_t::OP_OP_IMM if bitmanip_alu_op(opcode, funct3 as u8, funct7 as u8, rs2, cfg).is_some() => Ok(()),
+        opcode_t::OP_LUI | opcode_t::OP_AUIPC | opcode_t::OP_JAL => Ok(()),
+        opcode_t::OP_JALR => check(funct3 == 0b000, IllegalReason::Funct3),
+        // 010 and 011 are reserved
+        opcode_t::OP_BRANCH => check(funct3 != 0b010 && funct3 != 0b011, IllegalReason::Funct3),
+        // LB, LH, LW, LBU, LHU
+        opcode_t::OP_LOAD => check(matches!(funct3, 0b000 | 0b001 | 0b010 | 0b100 | 0b101), IllegalReason::Funct3),
+        // SB, SH, SW
+        opcode_t::OP_STORE => check(funct3 <= 0b010, IllegalReason::Funct3),
+        opcode_t::OP_OP_IMM => match funct3 {
+            // SLLI
+            0b001 => check(funct7 == 0b000_0000, IllegalReason::Funct7),
+            // SRLI / SRAI
+            0b101 => check(funct7 == 0b000_0000 || funct7 == 0b010_0000, IllegalReason::Funct7),
+            _ => Ok(()),
+        },
+        opcode_t::OP_OP => match funct7 {
+            0b000_0000 => Ok(()),
+            // SUB / SRA
+            0b010_0000 => check(funct3 == 0b000 || funct3 == 0b101, IllegalReason::Funct7),
+            0b000_0001 => check(cfg.m_ext, IllegalReason::Funct7),
+            _ => Err(IllegalReason::Funct7),
+        },
+        // FENCE
+        opcode_t::OP_MISC_MEM => check(funct3 == 0b000, IllegalReason::Funct3),
+        opcode_t::OP_AMO if cfg.a_ext => match amo_op_e::from_funct5((funct7 >> 2) as u8) {
+            _ if funct3 != AMO_FUNCT3_W as u32 => Err(IllegalReason::Funct3),
+            Some(amo_op_e::AMO_LR) => check(rs2 == 0, IllegalReason::Funct7),
+            Some(_) => Ok(()),
+            None => Err(IllegalReason::Funct7),
+        },
+        // FLW, FSW
+        opcode_t::OP_LOAD_FP | opcode_t::OP_STORE_FP if cfg.f_ext => check(funct3 == FP_FUNCT3_W as u32, IllegalReason::Funct3),
+        opcode_t::OP_OP_FP | opcode_t::OP_MADD | opcode_t::OP_MSUB | opcode_t::OP_NMSUB | opcode_t::OP_NMADD if cfg.f_ext => {
+            match fp_op_e::decode(opcode, funct3 as u8, funct7 as u8, rs2) {
+                // Rounding modes 101 and 110 are reserved; 111 (dynamic) is checked against frm at execution
+                Some(op) => check(!op.uses_rm() || !matches!(funct3, 0b101 | 0b110), IllegalReason::Funct3),
+                None => Err(IllegalReason::Funct7),
+            }
+        }
+        opcode_t::OP_SYSTEM if cfg.zicsr || cfg.exceptions || cfg.interrupts => classify_system(instr, funct3, cfg),
+        opcode_t::OP_CUSTOM_0 | opcode_t::OP_CUSTOM_1 if cfg.custom.claims(opcode) => {
+            check(cfg.custom.find(instr).is_some(), IllegalReason::Custom)
+        }
+        opcode_t::OP_CUSTOM_0
+        | opcode_t::OP_CUSTOM_1
+        | opcode_t::OP_AMO
+        | opcode_t::OP_SYSTEM
+        | opcode_t::OP_LOAD_FP
+        | opcode_t::OP_STORE_FP
+        | opcode_t::OP_OP_FP
+        | opcode_t::OP_MADD
+        | opcode_t::OP_MSUB
+        | opcode_t::OP_NMSUB
+        | opcode_t::OP_NMADD
+        | opcode_t::OP_INVALID => Err(IllegalReason::Opcode),
+    };
+
+    legal?;
+    check(!cfg.rv32e || registers_fit(instr, opcode, funct3, cfg), IllegalReason::Register)?;
+    Ok(opcode)
+}
+
+/// True when `instr` is a legal instruction for `cfg`.
+pub fn is_legal_instr(instr: instr_t, cfg: &IsaConfig) -> bool {
+    classify_instr(instr, cfg).is_ok()
+}
+
+/// SYSTEM: ECALL/EBREAK with `exceptions`, MRET with `exceptions` or
+/// `interrupts`, SFENCE.VMA with `sv32`, CSR accesses with `zicsr`.
+fn classify_system(instr: instr_t, funct3: u32, cfg: &IsaConfig) -> Result<(), IllegalReason> {
+    match funct3 {
+        0b000 => check(
+            match instr {
+                INSTR_ECALL | INSTR_EBREAK => cfg.exceptions,
+                INSTR_MRET => cfg.exceptions || cfg.interrupts,
+                _ if instr & SFENCE_VMA_MASK == INSTR_SFENCE_VMA => cfg.sv32,
+                _ => false,
+            },
+            IllegalReason::System,
+        ),
+        _ if !cfg.zicsr => Err(IllegalReason::Opcode),
+        _ => classify_csr(instr, funct3, cfg),
+    }
+}
+
+/// Zicsr access: the CSR must exist, and CSRRW or a CSRRS/CSRRC with a
+/// non-zero rs1/zimm field writes it, so it must not be read-only.
+fn classify_csr(instr: instr_t, funct3: u32, cfg: &IsaConfig) -> Result<(), IllegalReason> {
+    let Some(op) = csr_op_e::from_funct3(funct3 as u8) else {
+        return Err(IllegalReason::Funct3);
+    };
+    let csr = (instr >> 20) as csr_addr_t;
+    let writes = op == csr_op_e::CSR_RW || (instr >> 15) & 0x1F != 0;
+
+    check(csr_exists(csr, cfg) && !(writes && csr_is_read_only(csr)), IllegalReason::Csr)
+}
+
+/// True when every register field `instr` uses names one of the
+/// `cfg.reg_count()` registers. Immediate fields sharing those bit
+/// positions (shift amounts, zimm, FENCE fields) are not registers.
+fn registers_fit(instr: instr_t, opcode: opcode_t, funct3: u32, cfg: &IsaConfig) -> bool {
+    // (rd, rs1, rs2)
+    let used = match opcode {
+        opcode_t::OP_OP | opcode_t::OP_AMO => [true, true, true],
+        // Checked after decode, so a claimed word has its instruction
+        opcode_t::OP_CUSTOM_0 | opcode_t::OP_CUSTOM_1 => cfg.custom.find(instr).map_or([false; 3], |c| c.register_fields(instr)),
+        opcode_t::OP_OP_IMM | opcode_t::OP_LOAD | opcode_t::OP_JALR => [true, true, false],
+        opcode_t::OP_STORE | opcode_t::OP_BRANCH => [false, true, true],
+        opcode_t::OP_LUI | opcode_t::OP_AUIPC | opcode_t::OP_JAL => [true, false, false],
+        // ECALL/EBREAK/MRET have zero fields; SFENCE.VMA reads rs1 and rs2
+        opcode_t::OP_SYSTEM if funct3 == 0b000 => [false, true, true],
+        // CSRRWI/CSRRSI/CSRRCI: rs1 is zimm
+        opcode_t::OP_SYSTEM if funct3 & 0b100 != 0 => [true, false, false],
+        opcode_t::OP_SYSTEM => [true, true, false],
+        // FLW/FSW address through x[rs1]; the data register is an f register
+        opcode_t::OP_LOAD_FP | opcode_t::OP_STORE_FP => [false, true, false],
+        opcode_t::OP_OP_FP => match fp_op_e::decode(opcode, funct3 as u8, (instr >> 25) as u8, ((instr >> 20) & 0x1F) as u8) {
+            Some(op) => [op.writes_x(), op.reads_x(), false],
+            None => [false; 3],
+        },
+        opcode_t::OP_MADD | opcode_t::OP_MSUB | opcode_t::OP_NMSUB | opcode_t::OP_NMADD => [false; 3],
+        opcode_t::OP_MISC_MEM | opcode_t::OP_INVALID => [false; 3],
+    };
+    [7, 15, 20]
+        .into_iter()
+        .zip(used)
+        .all(|(shift, used)| !used || ((instr >> shift) & 0x1F) < cfg.reg_count() as u32)
+}
+
+fn check(ok: bool, reason: IllegalReason) -> Result<(), IllegalReason> {
+    if ok { Ok(()) } else { Err(reason) }
+}
@@ -0,0 +1,77 @@
+// ============================================================
+// LX32 Floating-Point Unit (F extension)
+// ============================================================
+// The f0-f31 register file and the single-precision execute
+// stage of the golden model.
+//
+// Design Principles:
+//   - Pure combinational logic, like the ALU: the unit maps
+//     operands and a resolved rounding mode to a result and
+//     the exception flags it raised.
+//   - Arithmetic comes from the soft-float module, so results
+//     do not depend on the host FPU.
+//   - fcsr, the dynamic rounding mode and flag accrual live in
+//     the CSR file; FLW/FSW go through the LSU like LW/SW.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, data_t, reg_idx_t};
+use crate::models::arch::lx32_fpu_pkg::{fp_op_e, round_mode_e};
+use crate::models::core::softfloat::*;
+
+/// Sign bit of a binary32 value
+const SIGN: u32 = 0x8000_0000;
+
+/// f0-f31. Unlike x0, f0 is an ordinary register.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FpRegisterFile {
+    regs: [u32; REG_COUNT],
+}
+
+impl FpRegisterFile {
+    /// Reset state: every register holds +0.0.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&self, addr: reg_idx_t) -> u32 {
+        self.regs[addr as usize % REG_COUNT]
+    }
+
+    pub fn write(&mut self, addr: reg_idx_t, data: u32) {
+        self.regs[addr as usize % REG_COUNT] = data;
+    }
+}
+
+/// Result of `op` and the exception flags it raised. `x_rs1` is the integer
+/// source of FCVT.S.W(U)/FMV.W.X; `fs` holds fs1, fs2 and fs3; `rm` is the
+/// resolved (static or frm) rounding mode.
+pub fn fpu_golden(op: fp_op_e, x_rs1: data_t, fs: [u32; 3], rm: round_mode_e) -> (data_t, u8) {
+    let [a, b, c] = fs;
+    let compare = |(result, flags): (bool, u8)| (result as data_t, flags);
+    match op {
+        fp_op_e::FP_ADD => f32_add(a, b, rm),
+        fp_op_e::FP_SUB => f32_sub(a, b, rm),
+        fp_op_e::FP_MUL => f32_mul(a, b, rm),
+        fp_op_e::FP_DIV => f32_div(a, b, rm),
+        fp_op_e::FP_SQRT => f32_sqrt(a, rm),
+        fp_op_e::FP_MADD => f32_mul_add(a, b, c, false, false, rm),
+        fp_op_e::FP_MSUB => f32_mul_add(a, b, c, false, true, rm),
+        fp_op_e::FP_NMSUB => f32_mul_add(a, b, c, true, false, rm),
+        fp_op_e::FP_NMADD => f32_mul_add(a, b, c, true, true, rm),
+        fp_op_e::FP_SGNJ => ((a & !SIGN) | (b & SIGN), 0),
+        fp_op_e::FP_SGNJN => ((a & !SIGN) | (!b & SIGN), 0),
+        fp_op_e::FP_SGNJX => (a ^ (b & SIGN), 0),
+        fp_op_e::FP_MIN => f32_min(a, b),
+        fp_op_e::FP_MAX => f32_max(a, b),
+        fp_op_e::FP_EQ => compare(f32_eq(a, b)),
+        fp_op_e::FP_LT => compare(f32_lt(a, b)),
+        fp_op_e::FP_LE => compare(f32_le(a, b)),
+        fp_op_e::FP_CLASS => (f32_classify(a), 0),
+        fp_op_e::FP_CVT_W_S => f32_to_i32(a, rm),
+        fp_op_e::FP_CVT_WU_S => f32_to_u32(a, rm),
+        fp_op_e::FP_CVT_S_W => i32_to_f32(x_rs1, rm),
+        fp_op_e::FP_CVT_S_WU => u32_to_f32(x_rs1, rm),
+        fp_op_e::FP_MV_X_W => (a, 0),
+        fp_op_e::FP_MV_W_X => (x_rs1, 0),
+    }
+}
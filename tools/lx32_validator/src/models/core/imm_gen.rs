@@ -21,11 +21,11 @@ pub fn imm_gen_golden(instr: u32) -> u32 {
     // Safety check: In Rust, we compare the raw bits to the enum values
     // as Rust enums are stricter than SystemVerilog typedefs.
     match opcode_bits {
-        // I-Type: OP_OP_IMM (0x13), OP_LOAD (0x03), OP_JALR (0x67)
-        0x13 | 0x03 | 0x67 => get_i_imm(instr),
+        // I-Type: OP_OP_IMM (0x13), OP_LOAD (0x03), OP_JALR (0x67), OP_LOAD_FP (0x07)
+        0x13 | 0x03 | 0x67 | 0x07 => get_i_imm(instr),
 
-        // S-Type: OP_STORE (0x23)
-        0x23 => get_s_imm(instr),
+        // S-Type: OP_STORE (0x23), OP_STORE_FP (0x27)
+        0x23 | 0x27 => get_s_imm(instr),
 
         // B-Type: OP_BRANCH (0x63)
         0x63 => get_b_imm(instr),
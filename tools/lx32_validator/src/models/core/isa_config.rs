@@ -0,0 +1,198 @@
+// ============================================================
+// LX32 ISA Configuration
+// ============================================================
+// Selects which optional behaviour the golden model implements.
+//
+// The default profile matches the RTL core exactly, so every
+// lockstep fuzzer keeps comparing like with like. Features the
+// RTL does not implement yet are enabled per instance for
+// golden-only runs.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, REG_COUNT_E};
+use crate::models::core::custom::{CustomInstruction, CustomSet};
+
+/// Feature switches of one golden model instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IsaConfig {
+    /// Decode funct3 of LOAD/STORE into byte/halfword accesses.
+    /// Off: every load and store is a word access, like the RTL LSU.
+    pub sub_word_mem: bool,
+    /// M extension: MUL/MULH/MULHSU/MULHU/DIV/DIVU/REM/REMU (funct7 = 0000001).
+    /// Off: those encodings decode as their base-ISA funct3 operation, like the RTL.
+    pub m_ext: bool,
+    /// Trap on words the strict decoder rejects, redirecting to the trap vector.
+    /// Off: illegal words execute with the RTL fallback decode and are only flagged.
+    pub trap_illegal: bool,
+    /// Zicsr: CSRRW/CSRRS/CSRRC and their immediate forms on the machine CSR file.
+    /// Off: SYSTEM is an unknown opcode and executes as a NOP, like the RTL.
+    pub zicsr: bool,
+    /// Machine-mode exceptions: ECALL, EBREAK, MRET and instruction/load/store
+    /// address-misaligned traps, delivered through mtvec. Needs `zicsr`.
+    /// Off: misaligned targets and accesses behave like the RTL (no check).
+    pub exceptions: bool,
+    /// Interrupts: CLINT at 0x0200_0000 (mtime/mtimecmp/msip), the external
+    /// interrupt line, mie/mip and mstatus.MIE, taken between instructions.
+    /// Needs `zicsr`; also enables MRET. Off: no CLINT is mapped, like the RTL.
+    pub interrupts: bool,
+    /// User mode: MRET drops to the mode in mstatus.MPP (M or U), traps save
+    /// the interrupted mode there, ECALL from U-mode has its own cause, and
+    /// U-mode may only access CSRs with csr[9:8] = 00 (counters through
+    /// mcounteren). Adds mstatus.MPRV and mcounteren. Needs `exceptions`.
+    /// Off: the core always runs in M-mode, like the RTL.
+    pub user_mode: bool,
+    /// Physical memory protection: 16 entries (pmpcfg0-3, pmpaddr0-15) with
+    /// TOR/NA4/NAPOT matching, checked on fetch, load and store. Needs
+    /// `exceptions`. Off: every access is allowed.
+    pub pmp: bool,
+    /// Sv32 virtual memory: satp, a page-table walker with a TLB, page
+    /// faults, hardware A/D updates and SFENCE.VMA. Translates U-mode
+    /// accesses (and M-mode loads/stores under MPRV with MPP = U); M-mode
+    /// is the kernel and stays physical. Needs `user_mode`. Off: no
+    /// translation, like the RTL.
+    pub sv32: bool,
+    /// A extension: LR.W/SC.W with a single-word reservation set and the
+    /// AMO*.W read-modify-write operations. Off: the AMO opcode is unknown
+    /// and executes as a NOP, like the RTL.
+    pub a_ext: bool,
+    /// C extension: 16-bit instructions expanded to their 32-bit equivalents,
+    /// fetched at 2-byte alignment. Off: a parcel with instr[1:0] != 2'b11 is
+    /// an unknown opcode, like the RTL.
+    pub c_ext: bool,
+    /// Zba: SH1ADD/SH2ADD/SH3ADD.
+    /// Off: those encodings decode by funct7[5] only, like the RTL.
+    pub zba: bool,
+    /// Zbb: ANDN/ORN/XNOR, CLZ/CTZ/CPOP, MIN/MAX(U), ROL/ROR/RORI,
+    /// SEXT.B/SEXT.H/ZEXT.H, REV8 and ORC.B. Off: as for `zba`.
+    pub zbb: bool,
+    /// Zbs: BCLR/BEXT/BINV/BSET and their immediate forms. Off: as for `zba`.
+    pub zbs: bool,
+    /// F extension: f0-f31, fcsr and the RV32F single-precision instructions,
+    /// computed by a soft-float FPU. Needs `zicsr`. Off: the floating-point
+    /// opcodes are unknown and execute as NOPs, like the RTL.
+    pub f_ext: bool,
+    /// RV32E: 16 registers (x0-x15). Encodings naming x16-x31 are illegal,
+    /// and the register file keeps only the low 4 address bits, like an RTL
+    /// built with REG_COUNT = 16. Off: 32 registers.
+    pub rv32e: bool,
+    /// Custom instructions in the custom-0/custom-1 opcode spaces.
+    /// Empty: both opcodes are unknown and execute as NOPs, like the RTL.
+    pub custom: CustomSet,
+}
+
+impl IsaConfig {
+    /// Profile implemented by the RTL core.
+    pub fn base() -> Self {
+        Self::default()
+    }
+
+    /// Full RV32I: base profile plus sub-word loads and stores, the Zicsr CSR
+    /// file and machine-mode exceptions (illegal instruction, ECALL/EBREAK,
+    /// misaligned addresses).
+    pub fn rv32i() -> Self {
+        Self {
+            sub_word_mem: true,
+            trap_illegal: true,
+            zicsr: true,
+            exceptions: true,
+            ..Self::base()
+        }
+    }
+
+    /// RV32IM: full RV32I plus multiply and divide.
+    pub fn rv32im() -> Self {
+        Self {
+            m_ext: true,
+            ..Self::rv32i()
+        }
+    }
+
+    /// RV32IMC: RV32IM plus compressed instructions.
+    pub fn rv32imc() -> Self {
+        Self {
+            c_ext: true,
+            ..Self::rv32im()
+        }
+    }
+
+    /// RV32IMAC: RV32IMC plus atomics.
+    pub fn rv32imac() -> Self {
+        Self {
+            a_ext: true,
+            ..Self::rv32imc()
+        }
+    }
+
+    /// Every standard extension the golden model implements: RV32IMAFC with
+    /// Zicsr, Zba/Zbb/Zbs, interrupts, U-mode, PMP and Sv32 (32 registers,
+    /// no custom instructions).
+    pub fn full() -> Self {
+        Self {
+            f_ext: true,
+            interrupts: true,
+            ..Self::rv32imac().with_user_mode().with_sv32().with_bitmanip()
+        }
+    }
+
+    /// Adds U-mode and PMP to `self`: the machine/user split used to run
+    /// untrusted programs under a monitor.
+    pub fn with_user_mode(self) -> Self {
+        Self {
+            user_mode: true,
+            pmp: true,
+            ..self
+        }
+    }
+
+    /// Adds U-mode and Sv32 translation to `self` (PMP stays as it is).
+    pub fn with_sv32(self) -> Self {
+        Self {
+            user_mode: true,
+            sv32: true,
+            ..self
+        }
+    }
+
+    /// Adds the F extension to `self`, with the Zicsr it needs for fcsr.
+    pub fn with_f(self) -> Self {
+        Self {
+            f_ext: true,
+            zicsr: true,
+            ..self
+        }
+    }
+
+    /// Same profile with the 16-register file of RV32E.
+    pub fn with_rv32e(self) -> Self {
+        Self { rv32e: true, ..self }
+    }
+
+    /// Enables Zba, Zbb and Zbs (the B extension) on top of `self`.
+    pub fn with_bitmanip(self) -> Self {
+        Self {
+            zba: true,
+            zbb: true,
+            zbs: true,
+            ..self
+        }
+    }
+
+    /// Implements `custom` on top of `self`.
+    pub fn with_custom(self, custom: &'static [&'static dyn CustomInstruction]) -> Self {
+        Self {
+            custom: CustomSet(custom),
+            ..self
+        }
+    }
+
+    /// Number of general-purpose registers, x0 included.
+    pub fn reg_count(&self) -> usize {
+        if self.rv32e { REG_COUNT_E } else { REG_COUNT }
+    }
+
+    /// Bits of a PC or jump target that must be zero: IALIGN is 16 with
+    /// the C extension and 32 without.
+    pub fn ialign_mask(&self) -> u32 {
+        if self.c_ext { 0b01 } else { 0b11 }
+    }
+}
@@ -1,32 +1,70 @@
 // ============================================================
 // LX32 Load/Store Unit (LSU)
 // ============================================================
-// Minimal pass-through LSU for single-cycle memory interface.
+// Single-cycle memory interface with byte-lane steering.
 //
 // Design Principles:
 //   - Pure combinational datapath
 //   - No internal state
 //   - Clear separation between execute and memory stages
 //   - Tool-friendly (no qualifiers, no implicit latches)
+//
+// Word accesses pass through untouched, as in the RTL LSU.
+// Byte and halfword stores are shifted into their lane and
+// qualified by a byte-enable strobe; loads are sign or zero
+// extended after lane selection. Misaligned accesses are not
+// split: lanes past byte 3 are dropped.
 // ============================================================
 
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+
 /// Represents the Memory Interface ports
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct MemInterface {
     pub mem_addr: u32,
     pub mem_wdata: u32,
     pub mem_we: bool,
+    /// Write byte-enables, one bit per byte lane (0 when `mem_we` is low)
+    pub mem_be: u8,
 }
 
-pub fn lsu_golden(alu_result: u32, write_data: u32, mem_write: bool) -> MemInterface {
+pub fn lsu_golden(alu_result: u32, write_data: u32, mem_write: bool, size: mem_size_e) -> MemInterface {
     // In SV:
     // assign mem_addr  = alu_result;
     // assign mem_wdata = write_data;
     // assign mem_we    = mem_write;
+    let lane = alu_result & 0x3;
+
+    let (mem_wdata, mem_be) = match size {
+        // Word accesses are never steered: the RTL drives write_data as-is
+        mem_size_e::MEM_WORD => (write_data, size.strobe()),
+        _ => (
+            (write_data & size.mask()) << (8 * lane),
+            (size.strobe() << lane) & 0xF,
+        ),
+    };
 
     MemInterface {
         mem_addr: alu_result,
-        mem_wdata: write_data,
+        mem_wdata,
         mem_we: mem_write,
+        mem_be: if mem_write { mem_be } else { 0 },
+    }
+}
+
+/// Selects the byte lanes of `size` at `addr` from a full data-port word.
+pub fn lsu_read_lane(word: u32, addr: u32, size: mem_size_e) -> u32 {
+    match size {
+        mem_size_e::MEM_WORD => word,
+        _ => (word >> (8 * (addr & 0x3))) & size.mask(),
+    }
+}
+
+/// Sign- or zero-extends a loaded value of `size` to 32 bits.
+pub fn lsu_load_extend(value: u32, size: mem_size_e, unsigned: bool) -> u32 {
+    match (size, unsigned) {
+        (mem_size_e::MEM_BYTE, false) => value as u8 as i8 as i32 as u32,
+        (mem_size_e::MEM_HALF, false) => value as u16 as i16 as i32 as u32,
+        _ => value & size.mask(),
     }
 }
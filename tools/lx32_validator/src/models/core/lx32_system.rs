@@ -2,7 +2,8 @@
 // LX32 Processor System (Single Cycle) - Golden Model
 // ============================================================
 // Integration of all core sub-modules:
-// - Control Unit, ALU, Branch Unit, LSU, RF and ImmGen.
+// - Control Unit, ALU, Branch Unit, LSU, RF, ImmGen, AMO unit, CSRs,
+//   PMP, Sv32 MMU, CLINT, FPU and the registered custom instructions.
 //
 // Design Principles:
 //   - Clear signal naming and hierarchical structure.
@@ -10,26 +11,203 @@
 //   - Synchronous state updates matching RTL behavior.
 // ============================================================
 
-use crate::models::arch::lx32_isa_pkg::opcode_t;
+use crate::models::arch::lx32_amo_pkg::amo_op_e;
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t, pc_t, reg_idx_t};
+use crate::models::arch::lx32_csr_pkg::{CSR_FCSR, CSR_FFLAGS, CSR_FRM, CSR_SATP, FS_OFF, MIP_MEIP, PMP_R, PMP_W, PMP_X, csr_addr_t, csr_op_e, priv_mode_e};
+use crate::models::arch::lx32_fpu_pkg::{FRM_DYN, round_mode_e};
+use crate::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL, INSTR_MRET, INSTR_SFENCE_VMA, SFENCE_VMA_MASK, opcode_t};
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::arch::lx32_rvc_pkg::{cinstr_t, expand_compressed, is_compressed};
+use crate::models::arch::lx32_trap_pkg::{irq_cause_e, trap_cause_e};
+use crate::models::arch::lx32_vm_pkg::{PAGE_SIZE, SATP_ASID_MASK, SATP_MODE_SV32, access_type_e};
 use crate::models::core::alu::alu_golden_model;
+use crate::models::core::amo::amo_golden;
 use crate::models::core::branch_unit::branch_unit_golden;
-use crate::models::core::control_unit::control_unit_golden;
+use crate::models::core::bus::Bus;
+use crate::models::core::clint::Clint;
+use crate::models::core::control_unit::{ControlSignals, control_unit_golden};
+use crate::models::core::csr::{CsrFile, csr_apply};
+use crate::models::core::decoder::classify_instr;
+use crate::models::core::fpu::{FpRegisterFile, fpu_golden};
 use crate::models::core::imm_gen::imm_gen_golden;
+use crate::models::core::isa_config::IsaConfig;
+use crate::models::core::lsu::{MemInterface, lsu_golden, lsu_load_extend, lsu_read_lane};
+use crate::models::core::mmu::Mmu;
 use crate::models::core::register_file::RegisterFile;
 
 pub struct Lx32System {
     pub pc: u32,
     pub reg_file: RegisterFile,
     pub memory: Vec<u8>,
+    pub config: IsaConfig,
+    /// Machine CSRs; mtvec holds the handler address every trap redirects to
+    pub csr: CsrFile,
+    /// Memory-mapped timer and software interrupt (mapped with `IsaConfig::interrupts`)
+    pub clint: Clint,
+    /// External interrupt line, drives mip.MEIP (e.g. UART RX)
+    pub ext_irq: bool,
+    /// Word address reserved by the last LR.W; cleared by SC.W and by any
+    /// store to that word (see `snoop_store` for stores by other harts)
+    pub reservation: Option<addr_t>,
+    /// Sv32 page-table walker and TLB (used with `IsaConfig::sv32`)
+    pub mmu: Mmu,
+    /// f0-f31 (used with `IsaConfig::f_ext`)
+    pub f_regs: FpRegisterFile,
+}
+
+/// Outcome of an instruction fetch: the instruction bits and the physical
+/// address of the PC, or the fault to raise and its mtval.
+type Fetch = Result<(instr_t, addr_t), (trap_cause_e, data_t)>;
+
+/// Memory seen by `step`: the harness drives the read data, so a load gets
+/// `mem_rdata` steered to its lane and nothing else reaches memory.
+struct HarnessPort {
+    mem_rdata: data_t,
+}
+
+impl Bus for HarnessPort {
+    fn fetch(&mut self, _addr: addr_t) -> instr_t {
+        0
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        lsu_read_lane(self.mem_rdata, addr, size)
+    }
+
+    fn store(&mut self, _addr: addr_t, _data: data_t, _size: mem_size_e) {}
+}
+
+/// Register file write performed by one instruction (never x0). `rd` is the
+/// register written: with RV32E, the rd field truncated to 4 bits. Also used
+/// for f register writes, where f0 is an ordinary register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegWrite {
+    pub rd: reg_idx_t,
+    pub data: data_t,
+}
+
+/// CSR write performed by one Zicsr instruction.
+/// `data` is the value after WARL legalisation, as a later read returns it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsrWrite {
+    pub addr: csr_addr_t,
+    pub data: data_t,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemOp {
+    Load,
+    Store,
+    /// AMO*.W read-modify-write; the old value is the instruction's rd result
+    Amo,
+}
+
+/// Data memory access performed by one instruction, at its physical address.
+/// `data` holds the low `size` bytes: for loads the value returned by the
+/// bus before extension, for stores and AMOs the value written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemAccess {
+    pub op: MemOp,
+    pub addr: addr_t,
+    pub data: data_t,
+    pub size: mem_size_e,
+}
+
+/// A synchronous exception taken instead of executing an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trap {
+    pub cause: trap_cause_e,
+    /// PC of the faulting instruction
+    pub pc: pc_t,
+    /// Extra information (the instruction word for an illegal instruction)
+    pub tval: data_t,
+}
+
+/// An interrupt taken between two instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interrupt {
+    pub cause: irq_cause_e,
+    /// PC of the instruction that did not execute (saved in mepc)
+    pub pc: pc_t,
+}
+
+/// How a step ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StepStatus {
+    /// Instruction executed normally
+    #[default]
+    Retired,
+    /// Rejected by the strict decoder but executed with the RTL fallback
+    /// decode (traps disabled), e.g. an unknown opcode as a NOP
+    Illegal,
+    /// Trap taken; nothing executed and the PC moved to the trap vector
+    Trap(Trap),
+    /// Interrupt taken before the instruction; nothing executed and the
+    /// PC moved to the handler
+    Interrupt(Interrupt),
+    /// Reset was asserted; nothing executed
+    Reset,
+}
+
+/// Everything one executed instruction did to the architectural state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StepResult {
+    pub pc: pc_t,
+    /// 32-bit instruction word (the expansion of a compressed instruction)
+    pub instr: instr_t,
+    /// Original 16-bit parcel when `instr` came from a compressed instruction
+    pub compressed: Option<cinstr_t>,
+    /// Control lines decoded from `instr`
+    pub ctrl: ControlSignals,
+    pub next_pc: pc_t,
+    pub rd_write: Option<RegWrite>,
+    pub mem_access: Option<MemAccess>,
+    pub csr_write: Option<CsrWrite>,
+    /// f register written by FLW or an FPU operation
+    pub frd_write: Option<RegWrite>,
+    /// Exception flags raised by an FPU operation, accrued into fflags
+    pub fflags: u8,
+    /// True only for a conditional branch whose condition held
+    pub branch_taken: bool,
+    pub status: StepStatus,
+    /// Raw LSU port values, as driven on the RTL memory interface
+    pub lsu: MemInterface,
+}
+
+impl StepResult {
+    /// Size in bytes of the instruction at `pc`: 2 for a compressed one, 4 otherwise.
+    pub fn instr_len(&self) -> u32 {
+        if self.compressed.is_some() { 2 } else { 4 }
+    }
+}
+
+/// How a `run`/`run_with_bus` call ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSummary {
+    pub cycles: u64,
+    pub halted: bool,
+    pub last: Option<StepResult>,
 }
 
 impl Lx32System {
-    /// Initialize a new LX32 Golden Model with 4KB of memory
+    /// Initialize a new LX32 Golden Model with 4KB of memory (RTL base profile)
     pub fn new() -> Self {
+        Self::with_config(IsaConfig::base())
+    }
+
+    /// Initialize a golden model implementing the given ISA profile
+    pub fn with_config(config: IsaConfig) -> Self {
         Self {
             pc: 0,
-            reg_file: RegisterFile::new(),
+            reg_file: RegisterFile::with_count(config.reg_count()),
             memory: vec![0; 4096],
+            config,
+            csr: CsrFile::new(&config),
+            clint: Clint::new(),
+            ext_irq: false,
+            reservation: None,
+            mmu: Mmu::new(),
+            f_regs: FpRegisterFile::new(),
         }
     }
 
@@ -48,40 +226,257 @@ impl Lx32System {
         }
     }
 
+    /// Copy a raw image into the internal memory at `base` (truncated at the end of memory)
+    pub fn load_image(&mut self, base: addr_t, image: &[u8]) {
+        let start = (base as usize).min(self.memory.len());
+        let len = image.len().min(self.memory.len() - start);
+        self.memory[start..start + len].copy_from_slice(&image[..len]);
+    }
+
     /// Executes a single clock cycle of the processor
     ///
     /// Arguments:
     /// * `instr` - The 32-bit instruction fetched from memory
-    /// * `mem_rdata` - Data returned from memory (used for Load instructions)
+    /// * `mem_rdata` - Data port word returned from memory (used for Load instructions)
     /// * `rst` - Reset signal
     ///
     /// Returns:
-    /// * `(alu_res, rs2_data, mem_write)` - LSU signals to be compared with RTL
-    pub fn step(&mut self, instr: u32, mem_rdata: u32, rst: bool) -> (u32, u32, bool) {
+    /// * `StepResult` - Decode, write-back, memory and next-PC record of the cycle
+    pub fn step(&mut self, instr: u32, mem_rdata: u32, rst: bool) -> StepResult {
         // --- 1. Reset Logic ---
         if rst {
             self.pc = 0;
             self.reg_file.tick(true, 0, 0, false);
-            return (0, 0, false);
+            self.csr = CsrFile::new(&self.config);
+            self.clint = Clint::new();
+            self.reservation = None;
+            self.mmu = Mmu::new();
+            self.f_regs = FpRegisterFile::new();
+            return StepResult {
+                instr,
+                status: StepStatus::Reset,
+                ..Default::default()
+            };
+        }
+
+        // Addresses are physical here: without memory there are no page tables to walk
+        self.execute(Ok((instr, self.pc)), &mut HarnessPort { mem_rdata }, false)
+    }
+
+    /// Executes one instruction fetched from `bus` at the current PC.
+    ///
+    /// The model performs its own load and store through the bus, so no
+    /// external harness is needed to run a program.
+    pub fn step_with_bus<B: Bus + ?Sized>(&mut self, bus: &mut B) -> StepResult {
+        let fetch = self.fetch(bus);
+        let res = self.execute(fetch, bus, true);
+
+        if let Some(MemAccess {
+            op: MemOp::Store | MemOp::Amo,
+            addr,
+            data,
+            size,
+        }) = res.mem_access
+            && !self.maps_clint(addr)
+        {
+            bus.store(addr, data, size);
+        }
+
+        res
+    }
+
+    /// Runs from the current PC against `bus` until `halt` returns true for a
+    /// retired step or `max_cycles` have elapsed.
+    pub fn run_with_bus<B: Bus + ?Sized>(
+        &mut self,
+        bus: &mut B,
+        max_cycles: u64,
+        mut halt: impl FnMut(&StepResult) -> bool,
+    ) -> RunSummary {
+        let mut summary = RunSummary {
+            cycles: 0,
+            halted: false,
+            last: None,
+        };
+
+        while summary.cycles < max_cycles {
+            let res = self.step_with_bus(bus);
+            summary.cycles += 1;
+            summary.last = Some(res);
+            if halt(&res) {
+                summary.halted = true;
+                break;
+            }
+        }
+
+        summary
+    }
+
+    /// Same as `run_with_bus`, using the model's internal memory as the bus.
+    pub fn run(&mut self, max_cycles: u64, halt: impl FnMut(&StepResult) -> bool) -> RunSummary {
+        let mut memory = std::mem::take(&mut self.memory);
+        let summary = self.run_with_bus(memory.as_mut_slice(), max_cycles, halt);
+        self.memory = memory;
+        summary
+    }
+
+    /// Instruction bits at `pc`. With the C extension the PC may be
+    /// halfword-aligned: the word holding the low parcel is fetched first and
+    /// the next word only when the instruction turns out to be 32 bits wide.
+    /// That word may sit on the next page, which is translated on its own.
+    fn fetch<B: Bus + ?Sized>(&mut self, bus: &mut B) -> Fetch {
+        let pc = self.pc;
+        let pa = self.translate(bus, pc, access_type_e::ACCESS_FETCH).map_err(|cause| (cause, pc))?;
+        if !self.config.c_ext || pc & 0b10 == 0 {
+            return Ok((bus.fetch(pa), pa));
+        }
+
+        let low = bus.fetch(pa & !0b11) >> 16;
+        if is_compressed(low) {
+            return Ok((low, pa));
+        }
+
+        let next = pc.wrapping_add(2);
+        let high = if next.is_multiple_of(PAGE_SIZE) {
+            self.translate(bus, next, access_type_e::ACCESS_FETCH).map_err(|cause| (cause, next))?
+        } else {
+            pa.wrapping_add(2)
+        };
+        Ok((low | bus.fetch(high) << 16, pa))
+    }
+
+    /// Physical address of `access` to `vaddr`. Sv32 applies when satp enables
+    /// it and the access runs at U-mode privilege (MPRV included for data);
+    /// the walker's own accesses are PMP-checked as M-mode.
+    fn translate<B: Bus + ?Sized>(&mut self, bus: &mut B, vaddr: addr_t, access: access_type_e) -> Result<addr_t, trap_cause_e> {
+        let mode = if access == access_type_e::ACCESS_FETCH {
+            self.csr.privilege()
+        } else {
+            self.csr.data_privilege()
+        };
+        let satp = self.csr.read(CSR_SATP);
+        if !self.config.sv32 || mode == priv_mode_e::PRIV_M || satp & SATP_MODE_SV32 == 0 {
+            return Ok(vaddr);
+        }
+
+        let csr = &self.csr;
+        self.mmu.translate(bus, satp, vaddr, access, |addr, perms| csr.pmp_allows(addr, 4, perms, priv_mode_e::PRIV_M))
+    }
+
+    /// A store of `size` at `addr` by another agent sharing the memory (another
+    /// hart, DMA): drops the reservation when the store touches the reserved word.
+    pub fn snoop_store(&mut self, addr: addr_t, size: mem_size_e) {
+        let first = addr & !0b11;
+        let last = addr.wrapping_add(size.bytes() - 1) & !0b11;
+        if self.reservation.is_some_and(|word| word == first || word == last) {
+            self.reservation = None;
+        }
+    }
+
+    /// True when `addr` is served by the internal CLINT instead of the bus.
+    pub fn maps_clint(&self, addr: addr_t) -> bool {
+        self.config.interrupts && Clint::contains(addr)
+    }
+
+    /// Single-cycle datapath shared by `step` and `step_with_bus`.
+    ///
+    /// `bus` serves at most one data load, at the physical address and width of
+    /// a load (or of the read half of an AMO), plus the page-table walks of
+    /// data accesses when `translate` is set. Stores and AMO writes are only
+    /// reported in the result; committing them is the caller's job.
+    fn execute<B: Bus + ?Sized>(&mut self, fetch: Fetch, bus: &mut B, translate: bool) -> StepResult {
+        // --- 1.4 Interrupts ---
+        // Sampled between instructions: a taken interrupt replaces `instr`
+        if self.config.interrupts {
+            self.clint.tick();
+            let meip = if self.ext_irq { MIP_MEIP } else { 0 };
+            self.csr.set_pending(self.clint.pending() | meip);
+
+            let enabled = self.csr.enabled_interrupts();
+            if let Some(cause) = irq_cause_e::PRIORITY.into_iter().find(|c| enabled & c.mask() != 0) {
+                return self.take_interrupt(fetch.map_or(0, |(instr, _)| instr), cause);
+            }
+        }
+
+        // --- 1.45 Fetch Faults ---
+        let (instr, fetch_pa) = match fetch {
+            Ok(fetched) => fetched,
+            Err((cause, tval)) => return self.take_trap(0, cause, tval),
+        };
+
+        // --- 1.5 Compressed Expansion ---
+        // The datapath only sees 32-bit words; a parcel with no expansion
+        // keeps its raw bits and fails strict decode
+        let compressed = (self.config.c_ext && is_compressed(instr)).then_some(instr as cinstr_t);
+        let word = compressed.map_or(instr, |c| expand_compressed(c, self.config.f_ext).unwrap_or(c as instr_t));
+
+        StepResult {
+            compressed,
+            ..self.execute_word(word, compressed, fetch_pa, bus, translate)
+        }
+    }
+
+    /// Datapath of one 32-bit instruction word; `compressed` is the parcel it
+    /// was expanded from, if any (sets the instruction length and mtval), and
+    /// `fetch_pa` the physical address it was fetched from.
+    fn execute_word<B: Bus + ?Sized>(
+        &mut self,
+        instr: instr_t,
+        compressed: Option<cinstr_t>,
+        fetch_pa: addr_t,
+        bus: &mut B,
+        translate: bool,
+    ) -> StepResult {
+        let instr_len = if compressed.is_some() { 2 } else { 4 };
+
+        // --- 1.55 Fetch Protection ---
+        // PMP checks the instruction's physical bytes at the current privilege
+        if !self.csr.pmp_allows(fetch_pa, instr_len, PMP_X, self.csr.privilege()) {
+            return self.take_trap(instr, trap_cause_e::INSTR_ACCESS_FAULT, self.pc);
+        }
+
+        // --- 1.6 Strict Decode ---
+        // mtval holds the instruction as fetched, so a compressed one reports its parcel
+        let legal = classify_instr(instr, &self.config).is_ok() && self.privilege_allows(instr) && self.fs_allows(instr) && self.frm_allows(instr);
+        if !legal && self.config.trap_illegal {
+            let tval = compressed.map_or(instr, |c| c as instr_t);
+            return self.take_trap(instr, trap_cause_e::ILLEGAL_INSTRUCTION, tval);
+        }
+
+        // --- 1.7 ECALL / EBREAK / MRET ---
+        // Only legal when the profile implements them
+        if legal {
+            match instr {
+                INSTR_ECALL if self.csr.privilege() == priv_mode_e::PRIV_U => {
+                    return self.take_trap(instr, trap_cause_e::ECALL_U, 0);
+                }
+                INSTR_ECALL => return self.take_trap(instr, trap_cause_e::ECALL_M, 0),
+                INSTR_EBREAK => return self.take_trap(instr, trap_cause_e::BREAKPOINT, self.pc),
+                INSTR_MRET => return self.mret(instr),
+                _ if instr & SFENCE_VMA_MASK == INSTR_SFENCE_VMA => return self.sfence_vma(instr),
+                _ => {}
+            }
         }
 
         // --- 2. Decode Stage ---
         // Extracting fields from the instruction
         let opcode = opcode_t::from_bits((instr & 0x7F) as u8);
         let funct3 = ((instr >> 12) & 0x7) as u8;
-        let funct7_5 = ((instr >> 30) & 0x1) != 0;
+        let funct7 = ((instr >> 25) & 0x7F) as u8;
+        let rs1_addr = ((instr >> 15) & 0x1F) as u8;
+        let rs2_addr = ((instr >> 20) & 0x1F) as u8;
+        let rd_addr = ((instr >> 7) & 0x1F) as u8;
 
         // Generating control signals and extending immediate
-        let ctrl = control_unit_golden(opcode, funct3, funct7_5);
+        let mut ctrl = control_unit_golden(opcode, funct3, funct7, rs2_addr, &self.config);
         let imm_ext = imm_gen_golden(instr);
 
         // --- 3. Register File Read ---
-        let rs1_addr = ((instr >> 15) & 0x1F) as u8;
-        let rs2_addr = ((instr >> 20) & 0x1F) as u8;
-        let rd_addr = ((instr >> 7) & 0x1F) as u8;
 
         let rs1_data = self.reg_file.read_rs1(rs1_addr);
         let rs2_data = self.reg_file.read_rs2(rs2_addr);
+        // rs3 of the fused multiply-add forms is instr[31:27]
+        let fs = [rs1_addr, rs2_addr, (instr >> 27) as u8].map(|addr| self.f_regs.read(addr));
 
         // --- 4. Execution Stage ---
         let alu_a = if ctrl.src_a_pc { self.pc } else { rs1_data };
@@ -94,7 +489,22 @@ impl Lx32System {
         // Do not use alu_a/alu_b here to avoid confusion
         let branch_taken = branch_unit_golden(rs1_data, rs2_data, ctrl.branch, ctrl.branch_op);
 
-        // --- 6. State Update ---
+        // --- 4.1 Custom Instructions ---
+        // A word no registered instruction decodes falls back to a NOP;
+        // one without an rd field writes nothing back
+        let custom = if ctrl.custom { self.config.custom.find(instr) } else { None };
+        let custom_res = custom.map_or(0, |c| c.execute(instr, rs1_data, rs2_data));
+        if ctrl.custom && !custom.is_some_and(|c| c.register_fields(instr)[0]) {
+            ctrl.reg_write = false;
+        }
+
+        // --- 4.2 Floating Point ---
+        // funct3 holds the rounding mode; a reserved one (possible only when
+        // illegal words do not trap) falls back to RNE
+        let rm = self.rounding_mode(funct3).unwrap_or_default();
+        let (fpu_res, fflags) = ctrl.fp.map_or((0, 0), |op| fpu_golden(op, rs1_data, fs, rm));
+
+        // --- 4.5 Next PC ---
         let next_pc = if ctrl.jump {
             if ctrl.jalr {
                 rs1_data.wrapping_add(imm_ext) & 0xFFFF_FFFE
@@ -104,30 +514,317 @@ impl Lx32System {
         } else if ctrl.branch && branch_taken {
             self.pc.wrapping_add(imm_ext)
         } else {
-            self.pc.wrapping_add(4)
+            self.pc.wrapping_add(instr_len)
         };
 
+        // --- 5. Memory Stage ---
+        let size = ctrl.mem_size;
+        let is_sc = ctrl.amo == Some(amo_op_e::AMO_SC);
+        let is_load = ctrl.result_src == 0b01;
+
+        // --- 5.1 Address Exceptions ---
+        // Raised before any load, store or write-back takes place.
+        // AMOs and SC (even a failing one) report the store/AMO cause
+        if self.config.exceptions {
+            if next_pc & self.config.ialign_mask() != 0 {
+                return self.take_trap(instr, trap_cause_e::INSTR_ADDR_MISALIGNED, next_pc);
+            }
+            if ctrl.mem_write && !size.is_aligned(alu_res) {
+                return self.take_trap(instr, trap_cause_e::STORE_ADDR_MISALIGNED, alu_res);
+            }
+            if is_load && !size.is_aligned(alu_res) {
+                return self.take_trap(instr, trap_cause_e::LOAD_ADDR_MISALIGNED, alu_res);
+            }
+        }
+
+        // --- 5.12 Address Translation ---
+        // Page faults report the virtual address; everything below is physical.
+        // SC.W only writes while its (physical) word is still reserved
+        let access = if ctrl.mem_write {
+            access_type_e::ACCESS_STORE
+        } else {
+            access_type_e::ACCESS_LOAD
+        };
+        let mem_addr = if (is_load || ctrl.mem_write) && translate {
+            match self.translate(bus, alu_res, access) {
+                Ok(pa) => pa,
+                Err(cause) => return self.take_trap(instr, cause, alu_res),
+            }
+        } else {
+            alu_res
+        };
+        let sc_success = is_sc && self.reservation == Some(mem_addr);
+        // FSW stores f[rs2]
+        let rs2_store = if ctrl.fp_store { fs[1] } else { rs2_data };
+        let mut lsu = lsu_golden(mem_addr, rs2_store, ctrl.mem_write && (!is_sc || sc_success), size);
+
+        // --- 5.15 Access Protection ---
+        // PMP at the data privilege (MPP under mstatus.MPRV); AMOs need R and W
+        // and, like SC.W, report the store/AMO cause
+        let perms = if is_load { PMP_R } else { 0 } | if ctrl.mem_write { PMP_W } else { 0 };
+        if perms != 0 && !self.csr.pmp_allows(lsu.mem_addr, size.bytes(), perms, self.csr.data_privilege()) {
+            let cause = if ctrl.mem_write {
+                trap_cause_e::STORE_ACCESS_FAULT
+            } else {
+                trap_cause_e::LOAD_ACCESS_FAULT
+            };
+            return self.take_trap(instr, cause, lsu.mem_addr);
+        }
+
+        let load_raw = if !is_load {
+            0
+        } else if self.maps_clint(lsu.mem_addr) {
+            self.clint.load(lsu.mem_addr, size)
+        } else {
+            bus.load(lsu.mem_addr, size)
+        };
+        let mem_rdata = lsu_load_extend(load_raw, size, ctrl.mem_unsigned);
+
+        // --- 5.2 Atomic Read-Modify-Write ---
+        // The write port carries the combined value instead of rs2
+        let amo_rmw = ctrl.amo.filter(|op| op.is_rmw());
+        let store_data = amo_rmw.map_or(rs2_store, |op| amo_golden(op, load_raw, rs2_data));
+        if amo_rmw.is_some() {
+            lsu = lsu_golden(mem_addr, store_data, true, size);
+        }
+
+        let mem_access = if amo_rmw.is_some() {
+            Some(MemAccess {
+                op: MemOp::Amo,
+                addr: lsu.mem_addr,
+                data: store_data,
+                size,
+            })
+        } else if lsu.mem_we {
+            Some(MemAccess {
+                op: MemOp::Store,
+                addr: lsu.mem_addr,
+                data: rs2_store & size.mask(),
+                size,
+            })
+        } else if is_load {
+            Some(MemAccess {
+                op: MemOp::Load,
+                addr: lsu.mem_addr,
+                data: load_raw,
+                size,
+            })
+        } else {
+            None
+        };
+
+        // --- 5.5 CSR Stage ---
+        // Immediate forms (funct3[2]) use the rs1 field as a zero-extended operand.
+        // CSRRS/CSRRC with rs1/zimm = 0 only read.
+        let csr_addr = (instr >> 20) as csr_addr_t;
+        let csr_rdata = if ctrl.csr { self.csr.read(csr_addr) } else { 0 };
+        let csr_wdata = csr_op_e::from_funct3(funct3)
+            .filter(|&op| ctrl.csr && (op == csr_op_e::CSR_RW || rs1_addr != 0))
+            .map(|op| {
+                let src = if funct3 & 0b100 != 0 { rs1_addr as data_t } else { rs1_data };
+                csr_apply(op, csr_rdata, src)
+            });
+
         // --- 7. Result MUX (Write-back source) ---
-        // result_src: 00=ALU, 01=Mem, 10=PC+4, 11=IMM
+        // result_src: 00=ALU, 01=Mem, 10=PC+len, 11=IMM
+        // The link value skips the instruction's own length (2 for compressed)
+        // CSR accesses write back the old CSR value, SC.W 0 on success and 1 on failure
         let write_data = match ctrl.result_src {
+            _ if ctrl.csr => csr_rdata,
+            _ if ctrl.custom => custom_res,
+            _ if ctrl.fp.is_some() => fpu_res,
+            _ if is_sc => !sc_success as data_t,
             0b00 => alu_res,
             0b01 => mem_rdata,
-            0b10 => self.pc.wrapping_add(4),
+            0b10 => self.pc.wrapping_add(instr_len),
             0b11 => imm_ext,
             _ => alu_res,
         };
 
         // --- 8. Register File Write-back ---
         self.reg_file.tick(false, rd_addr, write_data, ctrl.reg_write);
+        let rd_index = self.reg_file.index(rd_addr);
+        let rd_write = (ctrl.reg_write && rd_index != 0).then_some(RegWrite {
+            rd: rd_index,
+            data: write_data,
+        });
+        let frd_write = ctrl.fp_reg_write.then(|| {
+            self.f_regs.write(rd_addr, write_data);
+            RegWrite {
+                rd: rd_addr,
+                data: write_data,
+            }
+        });
+        if frd_write.is_some() {
+            self.csr.set_fs_dirty();
+        }
 
         // --- 8.5 Commit next PC ---
+        let pc = self.pc;
         self.pc = next_pc;
 
-        // --- 9. Return LSU signals for validation ---
-        // (Address, Data to write, Write Enable)
-        (alu_res, rs2_data, ctrl.mem_write)
+        // CLINT stores commit here; every other store is the caller's
+        if lsu.mem_we && self.maps_clint(lsu.mem_addr) {
+            self.clint.store(lsu.mem_addr, store_data, size);
+        }
+
+        // --- 8.5.1 Reservation Set ---
+        // LR.W reserves its word; SC.W always gives the reservation up, and
+        // a store to the reserved word (including an AMO) invalidates it
+        match ctrl.amo {
+            Some(amo_op_e::AMO_LR) => self.reservation = Some(lsu.mem_addr & !0b11),
+            Some(amo_op_e::AMO_SC) => self.reservation = None,
+            _ if lsu.mem_we => self.snoop_store(lsu.mem_addr, size),
+            _ => {}
+        }
+
+        // --- 8.6 Counters and CSR write ---
+        // A write to a counter wins over this instruction's own increment
+        self.csr.tick(true);
+        self.csr.accrue_fflags(fflags);
+        let csr_write = csr_wdata.map(|data| {
+            self.csr.write(csr_addr, data);
+            CsrWrite {
+                addr: csr_addr,
+                data: self.csr.read(csr_addr),
+            }
+        });
+
+        // --- 9. Report the retired instruction ---
+        let status = if legal {
+            StepStatus::Retired
+        } else {
+            StepStatus::Illegal
+        };
+
+        StepResult {
+            pc,
+            instr,
+            compressed,
+            ctrl,
+            next_pc,
+            rd_write,
+            mem_access,
+            csr_write,
+            frd_write,
+            fflags,
+            branch_taken: ctrl.branch && branch_taken,
+            status,
+            lsu,
+        }
     }
-}
 
+    /// Privilege checks the static decoder cannot make: CSRs above the current
+    /// mode (or counters mcounteren hides), and MRET or SFENCE.VMA outside M-mode.
+    fn privilege_allows(&self, instr: instr_t) -> bool {
+        let is_csr = instr & 0x7F == opcode_t::OP_SYSTEM as u32 && (instr >> 12) & 0x7 != 0;
+        match instr {
+            INSTR_MRET => self.csr.privilege() == priv_mode_e::PRIV_M,
+            _ if instr & SFENCE_VMA_MASK == INSTR_SFENCE_VMA => self.csr.privilege() == priv_mode_e::PRIV_M,
+            _ if is_csr => self.csr.accessible((instr >> 20) as csr_addr_t),
+            _ => true,
+        }
+    }
+
+    /// Floating-point instructions and fflags/frm/fcsr accesses need
+    /// mstatus.FS != Off; this depends on CSR state, so the static decoder
+    /// cannot check it.
+    fn fs_allows(&self, instr: instr_t) -> bool {
+        let opcode = opcode_t::from_bits((instr & 0x7F) as u8);
+        let is_csr = opcode == opcode_t::OP_SYSTEM && (instr >> 12) & 0x7 != 0;
+        let fp_state = match opcode {
+            opcode_t::OP_LOAD_FP | opcode_t::OP_STORE_FP | opcode_t::OP_OP_FP | opcode_t::OP_MADD | opcode_t::OP_MSUB | opcode_t::OP_NMSUB | opcode_t::OP_NMADD => true,
+            _ => is_csr && matches!((instr >> 20) as csr_addr_t, CSR_FFLAGS | CSR_FRM | CSR_FCSR),
+        };
+        !fp_state || !self.config.f_ext || self.csr.fs() != FS_OFF
+    }
+
+    /// A floating-point instruction selecting the dynamic rounding mode needs
+    /// a valid mode in frm; this depends on CSR state, so the static decoder
+    /// cannot check it.
+    fn frm_allows(&self, instr: instr_t) -> bool {
+        let ctrl = control_unit_golden(
+            opcode_t::from_bits((instr & 0x7F) as u8),
+            ((instr >> 12) & 0x7) as u8,
+            ((instr >> 25) & 0x7F) as u8,
+            ((instr >> 20) & 0x1F) as u8,
+            &self.config,
+        );
+        let dynamic = ctrl.fp.is_some_and(|op| op.uses_rm()) && (instr >> 12) & 0x7 == FRM_DYN as u32;
+        !dynamic || self.rounding_mode(FRM_DYN).is_some()
+    }
+
+    /// Rounding mode selected by an rm field: static, or frm for DYN.
+    fn rounding_mode(&self, rm: u8) -> Option<round_mode_e> {
+        if rm == FRM_DYN {
+            round_mode_e::from_bits(self.csr.frm())
+        } else {
+            round_mode_e::from_bits(rm)
+        }
+    }
+
+    /// MRET: returns to mepc and restores the interrupt enable.
+    fn mret(&mut self, instr: u32) -> StepResult {
+        let pc = self.pc;
+        self.pc = self.csr.mret();
+        self.csr.tick(true);
+
+        StepResult {
+            pc,
+            instr,
+            next_pc: self.pc,
+            ..Default::default()
+        }
+    }
+
+    /// SFENCE.VMA: drops the TLB entries selected by rs1 (virtual address) and
+    /// rs2 (ASID); x0 selects every address or address space.
+    fn sfence_vma(&mut self, instr: u32) -> StepResult {
+        let rs1 = ((instr >> 15) & 0x1F) as u8;
+        let rs2 = ((instr >> 20) & 0x1F) as u8;
+        let vaddr = (rs1 != 0).then(|| self.reg_file.read_rs1(rs1));
+        let asid = (rs2 != 0).then(|| self.reg_file.read_rs2(rs2) & SATP_ASID_MASK);
+        self.mmu.flush(vaddr, asid);
+
+        let pc = self.pc;
+        self.pc = pc.wrapping_add(4);
+        self.csr.tick(true);
 
+        StepResult {
+            pc,
+            instr,
+            next_pc: self.pc,
+            ..Default::default()
+        }
+    }
+
+    /// Takes `cause` before `instr` executes and redirects to its handler.
+    fn take_interrupt(&mut self, instr: u32, cause: irq_cause_e) -> StepResult {
+        let pc = self.pc;
+        self.pc = self.csr.enter_interrupt(pc, cause.code());
+        self.csr.tick(false);
+
+        StepResult {
+            pc,
+            instr,
+            next_pc: self.pc,
+            status: StepStatus::Interrupt(Interrupt { cause, pc }),
+            ..Default::default()
+        }
+    }
+
+    /// Abandons `instr`, records the trap in the CSRs and redirects to mtvec.
+    fn take_trap(&mut self, instr: u32, cause: trap_cause_e, tval: data_t) -> StepResult {
+        let pc = self.pc;
+        self.pc = self.csr.enter_trap(pc, cause.code(), tval);
+        self.csr.tick(false);
 
+        StepResult {
+            pc,
+            instr,
+            next_pc: self.pc,
+            status: StepStatus::Trap(Trap { cause, pc, tval }),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// ============================================================
+// LX32 Sv32 MMU (Page-Table Walker + TLB)
+// ============================================================
+// Translates virtual addresses for U-mode fetches, loads and
+// stores when satp selects Sv32.
+//
+// Design Principles:
+//   - The walker reads and updates page tables through the
+//     same Bus as the core's own accesses.
+//   - A and D are set by hardware: the walker writes the PTE
+//     back before the access that needs them completes.
+//   - Small fully associative TLB, round-robin replacement,
+//     tagged by ASID; global pages match every ASID.
+//   - Stale entries stay until SFENCE.VMA or reset, as in
+//     hardware, so a missing fence is observable.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::addr_t;
+use crate::models::arch::lx32_csr_pkg::{PMP_R, PMP_W};
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::arch::lx32_trap_pkg::trap_cause_e;
+use crate::models::arch::lx32_vm_pkg::*;
+use crate::models::core::bus::Bus;
+
+/// Number of TLB entries
+pub const TLB_ENTRIES: usize = 8;
+
+/// A cached leaf PTE.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TlbEntry {
+    /// VPN[1]:VPN[0] of the page (VPN[0] unused for a megapage)
+    vpn: u32,
+    asid: u32,
+    /// Leaf PTE as last written to memory
+    pte: u32,
+    megapage: bool,
+}
+
+impl TlbEntry {
+    /// VPN bits compared for this entry's page size
+    fn vpn_mask(&self) -> u32 {
+        if self.megapage { !((1 << VPN_BITS) - 1) } else { !0 }
+    }
+
+    fn covers(&self, vaddr: addr_t) -> bool {
+        ((vaddr >> PAGE_SHIFT) ^ self.vpn) & self.vpn_mask() == 0
+    }
+
+    fn matches(&self, vaddr: addr_t, asid: u32) -> bool {
+        self.covers(vaddr) && (self.asid == asid || self.pte & PTE_G != 0)
+    }
+
+    /// 34-bit physical address of `vaddr`; a megapage keeps VPN[0] as well as the offset.
+    fn physical(&self, vaddr: addr_t) -> u64 {
+        let ppn = (self.pte >> PTE_PPN_SHIFT) as u64;
+        let offset_bits = if self.megapage { PAGE_SHIFT + VPN_BITS } else { PAGE_SHIFT };
+        let offset = vaddr as u64 & ((1 << offset_bits) - 1);
+        ((ppn << PAGE_SHIFT) & !((1 << offset_bits) - 1)) | offset
+    }
+}
+
+/// Leaf permission check: accesses come from U-mode, so U must be set.
+fn leaf_permits(pte: u32, access: access_type_e) -> bool {
+    pte & PTE_U != 0 && pte & access.pte_perm() != 0
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Mmu {
+    tlb: [Option<TlbEntry>; TLB_ENTRIES],
+    /// Next entry replaced when the TLB is full
+    next: usize,
+    /// Translations served by the TLB
+    pub hits: u64,
+    /// Translations that walked the page tables
+    pub misses: u64,
+}
+
+impl Mmu {
+    /// Reset state: empty TLB.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Physical address of a U-mode `access` to `vaddr` under `satp` (MODE = Sv32),
+    /// or the page/access fault to raise. `pmp(addr, perms)` checks the walker's
+    /// own PTE reads and A/D writes.
+    pub fn translate<B: Bus + ?Sized>(
+        &mut self,
+        bus: &mut B,
+        satp: u32,
+        vaddr: addr_t,
+        access: access_type_e,
+        pmp: impl Fn(addr_t, u8) -> bool,
+    ) -> Result<addr_t, trap_cause_e> {
+        let asid = (satp >> SATP_ASID_SHIFT) & SATP_ASID_MASK;
+        let hit = self.tlb.iter().flatten().find(|e| e.matches(vaddr, asid)).copied();
+
+        let entry = match hit {
+            // A store through a clean page walks again to set D
+            Some(entry) if access != access_type_e::ACCESS_STORE || entry.pte & PTE_D != 0 => {
+                self.hits += 1;
+                entry
+            }
+            _ => {
+                self.misses += 1;
+                let entry = self.walk(bus, satp, vaddr, access, &pmp)?;
+                self.insert(entry);
+                entry
+            }
+        };
+
+        if !leaf_permits(entry.pte, access) {
+            return Err(access.page_fault());
+        }
+        addr_t::try_from(entry.physical(vaddr)).map_err(|_| access.access_fault())
+    }
+
+    /// Drops the entries SFENCE.VMA selects: every page or the one holding
+    /// `vaddr`, in every address space or only the non-global pages of `asid`.
+    pub fn flush(&mut self, vaddr: Option<addr_t>, asid: Option<u32>) {
+        for slot in &mut self.tlb {
+            let selected = slot.is_some_and(|e| {
+                vaddr.is_none_or(|va| e.covers(va)) && asid.is_none_or(|id| e.asid == id && e.pte & PTE_G == 0)
+            });
+            if selected {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Number of valid TLB entries
+    pub fn tlb_len(&self) -> usize {
+        self.tlb.iter().flatten().count()
+    }
+
+    /// Two-level walk from the root table in satp. Faults leave memory and
+    /// the TLB untouched; a successful walk sets A (and D for a store).
+    fn walk<B: Bus + ?Sized>(
+        &self,
+        bus: &mut B,
+        satp: u32,
+        vaddr: addr_t,
+        access: access_type_e,
+        pmp: &impl Fn(addr_t, u8) -> bool,
+    ) -> Result<TlbEntry, trap_cause_e> {
+        let mut table = ((satp & SATP_PPN_MASK) as u64) << PAGE_SHIFT;
+
+        for level in (0..2).rev() {
+            let pte_addr = addr_t::try_from(table + 4 * vpn(vaddr, level) as u64)
+                .ok()
+                .filter(|&addr| pmp(addr, PMP_R))
+                .ok_or(access.access_fault())?;
+            let pte = bus.load(pte_addr, mem_size_e::MEM_WORD);
+
+            // Invalid, or the reserved W-without-R encoding
+            if pte & PTE_V == 0 || pte & (PTE_R | PTE_W) == PTE_W {
+                return Err(access.page_fault());
+            }
+
+            // R = X = 0: pointer to the next level
+            if pte & (PTE_R | PTE_X) == 0 {
+                table = ((pte >> PTE_PPN_SHIFT) as u64) << PAGE_SHIFT;
+                continue;
+            }
+
+            // A megapage must be 4 MB aligned (PPN[0] = 0)
+            let megapage = level == 1;
+            let misaligned = megapage && (pte >> PTE_PPN_SHIFT) & ((1 << VPN_BITS) - 1) != 0;
+            if misaligned || !leaf_permits(pte, access) {
+                return Err(access.page_fault());
+            }
+
+            let dirty = if access == access_type_e::ACCESS_STORE { PTE_D } else { 0 };
+            let updated = pte | PTE_A | dirty;
+            if updated != pte {
+                if !pmp(pte_addr, PMP_W) {
+                    return Err(access.access_fault());
+                }
+                bus.store(pte_addr, updated, mem_size_e::MEM_WORD);
+            }
+
+            return Ok(TlbEntry {
+                vpn: vaddr >> PAGE_SHIFT,
+                asid: (satp >> SATP_ASID_SHIFT) & SATP_ASID_MASK,
+                pte: updated,
+                megapage,
+            });
+        }
+
+        // Pointer at the last level
+        Err(access.page_fault())
+    }
+
+    /// Caches `entry`, replacing a stale copy of the same page if present.
+    fn insert(&mut self, entry: TlbEntry) {
+        let same_page = self.tlb.iter().position(|e| e.is_some_and(|e| e.matches(entry.vpn << PAGE_SHIFT, entry.asid)));
+        let slot = same_page.unwrap_or_else(|| {
+            let slot = self.next;
+            self.next = (self.next + 1) % TLB_ENTRIES;
+            slot
+        });
+        self.tlb[slot] = Some(entry);
+    }
+}
@@ -1,10 +1,21 @@
 // src/models/core/mods.rs
 pub mod alu;
+pub mod amo;
 pub mod branch_unit;
+pub mod bus;
+pub mod clint;
 pub mod control_unit;
+pub mod csr;
+pub mod custom;
+pub mod decoder;
+pub mod fpu;
 pub mod imm_gen;
+pub mod isa_config;
 pub mod lsu;
 pub mod lx32_system;
 pub mod memory_sim;
+pub mod mmu;
+pub mod pmp;
 pub mod reg_generic;
 pub mod register_file;
+pub mod softfloat;
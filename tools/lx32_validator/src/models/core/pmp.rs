@@ -0,0 +1,131 @@
+// ============================================================
+// LX32 Physical Memory Protection (PMP)
+// ============================================================
+// 16 PMP entries (pmpcfg0-3, pmpaddr0-15) with TOR, NA4 and
+// NAPOT address matching and 4-byte granularity.
+//
+// Design Principles:
+//   - Entries are checked in priority order (entry 0 first);
+//     the first entry matching any byte of the access decides.
+//   - An access matching only part of that entry fails.
+//   - M-mode is only restricted by locked entries; U-mode
+//     needs a matching entry that grants the access.
+//   - WARL fields are legalised on write, like the CSR file.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t};
+use crate::models::arch::lx32_csr_pkg::*;
+
+/// Number of implemented PMP entries
+pub const PMP_ENTRIES: usize = 16;
+
+/// Writable bits of an entry byte (bits 6:5 are reserved)
+const PMP_CFG_WRITABLE: u8 = PMP_L | (0b11 << PMP_A_SHIFT) | PMP_X | PMP_W | PMP_R;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pmp {
+    cfg: [u8; PMP_ENTRIES],
+    addr: [u32; PMP_ENTRIES],
+}
+
+impl Pmp {
+    /// Reset state: every entry OFF and unlocked.
+    pub fn new() -> Self {
+        Self {
+            cfg: [0; PMP_ENTRIES],
+            addr: [0; PMP_ENTRIES],
+        }
+    }
+
+    /// pmpcfg`index`: entries 4*index .. 4*index+3, one byte each.
+    pub fn read_cfg(&self, index: usize) -> data_t {
+        u32::from_le_bytes(self.cfg[4 * index..4 * index + 4].try_into().unwrap())
+    }
+
+    /// Writes pmpcfg`index`; locked entries keep their value and the
+    /// reserved R = 0, W = 1 combination drops W.
+    pub fn write_cfg(&mut self, index: usize, value: data_t) {
+        for (i, byte) in value.to_le_bytes().into_iter().enumerate() {
+            let entry = 4 * index + i;
+            if self.cfg[entry] & PMP_L != 0 {
+                continue;
+            }
+            let mut cfg = byte & PMP_CFG_WRITABLE;
+            if cfg & (PMP_R | PMP_W) == PMP_W {
+                cfg &= !PMP_W;
+            }
+            self.cfg[entry] = cfg;
+        }
+    }
+
+    /// pmpaddr`index`: address bits [33:2] of the region bound.
+    pub fn read_addr(&self, index: usize) -> data_t {
+        self.addr[index]
+    }
+
+    /// Writes pmpaddr`index` unless its entry is locked, or the next
+    /// entry is a locked TOR region using it as the bottom bound.
+    pub fn write_addr(&mut self, index: usize, value: data_t) {
+        let locked = self.cfg[index] & PMP_L != 0;
+        let next_tor_locked = self
+            .cfg
+            .get(index + 1)
+            .is_some_and(|&next| next & PMP_L != 0 && pmp_match_e::from_cfg(next) == pmp_match_e::PMP_TOR);
+        if !locked && !next_tor_locked {
+            self.addr[index] = value;
+        }
+    }
+
+    /// Byte range [lo, hi) covered by entry `index`, or `None` when it is OFF.
+    pub fn region(&self, index: usize) -> Option<(u64, u64)> {
+        let addr = self.addr[index] as u64;
+        match pmp_match_e::from_cfg(self.cfg[index]) {
+            pmp_match_e::PMP_OFF => None,
+            pmp_match_e::PMP_TOR => {
+                let lo = if index == 0 { 0 } else { (self.addr[index - 1] as u64) << 2 };
+                Some((lo, addr << 2))
+            }
+            pmp_match_e::PMP_NA4 => Some((addr << 2, (addr << 2) + 4)),
+            pmp_match_e::PMP_NAPOT => {
+                // pmpaddr = base[33:2] with (size / 8 - 1) set in its low bits
+                let ones = self.addr[index].trailing_ones() as u64;
+                let size = 1u64 << (ones + 3);
+                let base = (addr & !((1u64 << ones) - 1)) << 2;
+                Some((base, base + size))
+            }
+        }
+    }
+
+    /// True when an access of `len` bytes at `addr` needing the `perms` bits
+    /// (PMP_R/PMP_W/PMP_X) is allowed at privilege `mode`.
+    pub fn allows(&self, addr: addr_t, len: u32, perms: u8, mode: priv_mode_e) -> bool {
+        let first = addr as u64;
+        let last = first + len as u64 - 1;
+
+        for index in 0..PMP_ENTRIES {
+            let Some((lo, hi)) = self.region(index) else {
+                continue;
+            };
+            let first_in = lo <= first && first < hi;
+            let last_in = lo <= last && last < hi;
+            if !first_in && !last_in {
+                continue;
+            }
+            if !(first_in && last_in) {
+                return false;
+            }
+
+            let cfg = self.cfg[index];
+            return (mode == priv_mode_e::PRIV_M && cfg & PMP_L == 0) || cfg & perms == perms;
+        }
+
+        // No match: M-mode is unrestricted, U-mode is denied
+        mode == priv_mode_e::PRIV_M
+    }
+}
+
+impl Default for Pmp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,59 +1,79 @@
 // ============================================================
 // LX32 Register File (LX32 base)
 // ============================================================
-// - 32 registers (x0–x31), 32-bit wide.
+// - 32 registers (x0–x31), 32-bit wide; 16 (x0–x15) in the
+//   RV32E configuration.
 // - x0 is hardwired to zero (read-only).
 // - Dual asynchronous read ports for combinational decode.
 // - Single synchronous write port.
 // ============================================================
 
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, reg_idx_t};
+
 /// Replicates a 32-register file where x0 is hardwired to 0.
 /// Features dual-port asynchronous reads and single-port synchronous writes.
 pub struct RegisterFile {
-    // 32 registers (x0 to x31)
-    regs: [u32; 32],
+    // 32 registers (x0 to x31); only the first `count` are implemented
+    regs: [u32; REG_COUNT],
+    count: usize,
 }
 
 impl RegisterFile {
     pub fn new() -> Self {
-        Self { regs: [0; 32] }
+        Self::with_count(REG_COUNT)
+    }
+
+    /// Register file with `count` registers (`REG_COUNT` or `REG_COUNT_E`).
+    /// Addresses keep only their low log2(`count`) bits, like an RTL built
+    /// with a narrower `REG_ADDR_WIDTH`.
+    pub fn with_count(count: usize) -> Self {
+        assert!(count.is_power_of_two() && count <= REG_COUNT, "unsupported register count {}", count);
+        Self { regs: [0; REG_COUNT], count }
+    }
+
+    /// Number of implemented registers
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Register an address field selects (the address truncated to the file width)
+    pub fn index(&self, addr: reg_idx_t) -> reg_idx_t {
+        addr & (self.count - 1) as reg_idx_t
     }
 
     /// Read Port 1 (Asynchronous)
     pub fn read_rs1(&self, addr: u8) -> u32 {
-        if addr == 0 {
-            0
-        } else {
-            self.regs[addr as usize]
-        }
+        self.read(addr)
     }
 
     /// Read Port 2 (Asynchronous)
     pub fn read_rs2(&self, addr: u8) -> u32 {
-        if addr == 0 {
-            0
-        } else {
-            self.regs[addr as usize]
+        self.read(addr)
+    }
+
+    fn read(&self, addr: u8) -> u32 {
+        match self.index(addr) {
+            0 => 0,
+            index => self.regs[index as usize],
         }
     }
 
     /// Write Port (Synchronous / Clocked)
     /// This mirrors the generate block and the write_en logic in SV.
     pub fn tick(&mut self, rst: bool, addr_rd: u8, data_rd: u32, we: bool) {
+        let index = self.index(addr_rd);
         if rst {
             // Reset all registers (x0 stays 0 anyway)
-            self.regs = [0; 32];
-        } else if we && addr_rd != 0 {
+            self.regs = [0; REG_COUNT];
+        } else if we && index != 0 {
             // Replicates: assign write_en = (we && (addr_rd != 5'd0))
-            self.regs[addr_rd as usize] = data_rd;
+            self.regs[index as usize] = data_rd;
         }
     }
 
     /// Helper for debugging/trace: Get the current value of a register
+    /// (0 for x0 and for registers the file does not implement)
     pub fn get_reg(&self, index: usize) -> u32 {
-        if index == 0 { 0 } else { self.regs[index] }
+        if index == 0 || index >= self.count { 0 } else { self.regs[index] }
     }
 }
-
-
-
@@ -0,0 +1,445 @@
+// ============================================================
+// LX32 Soft-Float (IEEE-754 binary32)
+// ============================================================
+// Single-precision arithmetic in integer code, so results and
+// exception flags never depend on the host FPU.
+//
+// Design Principles:
+//   - Every operation forms its exact result, or enough of it
+//     plus a sticky bit, and rounds once in round_pack.
+//   - Tininess is detected after rounding, as RISC-V requires;
+//     underflow is only flagged when the result is inexact.
+//   - Every NaN result is the canonical quiet NaN; signaling
+//     NaN operands raise invalid.
+// ============================================================
+
+use crate::models::arch::lx32_fpu_pkg::*;
+
+/// Result bits and the exception flags the operation raised
+pub type FpResult = (u32, u8);
+
+const SIGN: u32 = 0x8000_0000;
+const EXP_MASK: u32 = 0x7F80_0000;
+const FRAC_MASK: u32 = 0x007F_FFFF;
+const HIDDEN: u32 = 0x0080_0000;
+const QUIET: u32 = 0x0040_0000;
+const INF: u32 = 0x7F80_0000;
+const MAX_FINITE: u32 = 0x7F7F_FFFF;
+
+/// Fraction bits of binary32
+const FRAC_BITS: i32 = 23;
+/// Exponent of the smallest normal number
+const EMIN: i32 = -126;
+/// Exponent of the least significant bit of a subnormal
+const MIN_LSB_EXP: i32 = EMIN - FRAC_BITS;
+
+fn sign_of(a: u32) -> bool {
+    a & SIGN != 0
+}
+
+fn sign_bit(sign: bool) -> u32 {
+    if sign { SIGN } else { 0 }
+}
+
+pub fn is_nan(a: u32) -> bool {
+    a & !SIGN > INF
+}
+
+pub fn is_snan(a: u32) -> bool {
+    is_nan(a) && a & QUIET == 0
+}
+
+fn is_inf(a: u32) -> bool {
+    a & !SIGN == INF
+}
+
+fn is_zero(a: u32) -> bool {
+    a & !SIGN == 0
+}
+
+/// Finite non-zero `a` as significand × 2^exponent (sign dropped).
+fn unpack(a: u32) -> (u64, i32) {
+    let exp = ((a & EXP_MASK) >> FRAC_BITS) as i32;
+    let frac = (a & FRAC_MASK) as u64;
+    if exp == 0 { (frac, MIN_LSB_EXP) } else { ((frac | HIDDEN as u64), exp - 127 - FRAC_BITS) }
+}
+
+/// Canonical NaN, with invalid raised if any operand is a signaling NaN.
+fn propagate_nan(operands: &[u32]) -> FpResult {
+    let nv = if operands.iter().any(|&x| is_snan(x)) { FFLAG_NV } else { 0 };
+    (F32_CANONICAL_NAN, nv)
+}
+
+fn invalid() -> FpResult {
+    (F32_CANONICAL_NAN, FFLAG_NV)
+}
+
+/// Sign of an exact zero sum: -0 only for (-0) + (-0), or under RDN when
+/// the operands cancel.
+fn zero_sum(sa: bool, sb: bool, rm: round_mode_e) -> FpResult {
+    let negative = if sa == sb { sa } else { rm == round_mode_e::RM_RDN };
+    (sign_bit(negative), 0)
+}
+
+/// `sig` shifted right by `shift` and rounded under `rm`, and whether any
+/// bit was lost. `sticky` stands for non-zero bits below `sig` (needs shift >= 1).
+fn shift_round(sign: bool, sig: u128, shift: i32, sticky: bool, rm: round_mode_e) -> (u128, bool) {
+    if shift <= 0 {
+        debug_assert!(!sticky);
+        return (sig << -shift, false);
+    }
+
+    let kept = sig >> shift;
+    let round = (sig >> (shift - 1)) & 1 != 0;
+    let rest = sticky || sig & ((1 << (shift - 1)) - 1) != 0;
+    let inexact = round || rest;
+    let up = match rm {
+        round_mode_e::RM_RNE => round && (rest || kept & 1 != 0),
+        round_mode_e::RM_RTZ => false,
+        round_mode_e::RM_RDN => sign && inexact,
+        round_mode_e::RM_RUP => !sign && inexact,
+        round_mode_e::RM_RMM => round,
+    };
+    (kept + up as u128, inexact)
+}
+
+/// Rounds (-1)^sign × (sig + sticky) × 2^exp to binary32. `sticky` means the
+/// exact value lies strictly above `sig` by less than one unit.
+fn round_pack(sign: bool, sig: u128, exp: i32, sticky: bool, rm: round_mode_e) -> FpResult {
+    if sig == 0 && !sticky {
+        return (sign_bit(sign), 0);
+    }
+
+    let msb = 127 - sig.leading_zeros() as i32;
+    debug_assert!(msb < 126);
+    // Exponent of the leading bit; the kept LSB is 23 below it, or the subnormal LSB
+    let top = msb + exp;
+    let lsb_exp = (top - FRAC_BITS).max(MIN_LSB_EXP);
+    // Shifting past the leading bit leaves only sticky bits, so the shift is clamped
+    let (kept, inexact) = shift_round(sign, sig, (lsb_exp - exp).min(msb + 2), sticky, rm);
+
+    // Tiny when the result rounded to 24 bits with unbounded exponent is below 2^EMIN
+    let tiny = top < EMIN && (top < EMIN - 1 || shift_round(sign, sig, msb - FRAC_BITS, sticky, rm).0 >> 24 == 0);
+
+    // A carry out of the significand lands in the exponent field
+    let bits = (((lsb_exp - MIN_LSB_EXP) as u128) << FRAC_BITS) + kept;
+    if bits >= INF as u128 {
+        let to_inf = match rm {
+            round_mode_e::RM_RNE | round_mode_e::RM_RMM => true,
+            round_mode_e::RM_RTZ => false,
+            round_mode_e::RM_RDN => sign,
+            round_mode_e::RM_RUP => !sign,
+        };
+        let magnitude = if to_inf { INF } else { MAX_FINITE };
+        return (sign_bit(sign) | magnitude, FFLAG_OF | FFLAG_NX);
+    }
+
+    let flags = match (inexact, tiny) {
+        (true, true) => FFLAG_NX | FFLAG_UF,
+        (true, false) => FFLAG_NX,
+        (false, _) => 0,
+    };
+    (sign_bit(sign) | bits as u32, flags)
+}
+
+/// Correctly rounded sum of two non-zero finite values given as
+/// (sign, significand of at most 61 bits, exponent).
+fn add_values(a: (bool, u64, i32), b: (bool, u64, i32), rm: round_mode_e) -> FpResult {
+    // Align both significands to bit 60 so exponents order magnitudes
+    let normalise = |(sign, sig, exp): (bool, u64, i32)| {
+        let shift = sig.leading_zeros() as i32 - 3;
+        (sign, sig << shift, exp - shift)
+    };
+    let (mut big, mut small) = (normalise(a), normalise(b));
+    if small.2 > big.2 {
+        std::mem::swap(&mut big, &mut small);
+    }
+
+    let diff = big.2 - small.2;
+    if diff > 64 {
+        // The small operand is below one unit of the big significand: it only
+        // decides the direction of rounding
+        let sig = if big.0 == small.0 { big.1 } else { big.1 - 1 };
+        return round_pack(big.0, sig as u128, big.2, true, rm);
+    }
+
+    let x = (big.1 as u128) << diff;
+    let y = small.1 as u128;
+    if big.0 == small.0 {
+        round_pack(big.0, x + y, small.2, false, rm)
+    } else if x > y {
+        round_pack(big.0, x - y, small.2, false, rm)
+    } else if y > x {
+        round_pack(small.0, y - x, small.2, false, rm)
+    } else {
+        zero_sum(big.0, small.0, rm)
+    }
+}
+
+/// a + b
+pub fn f32_add(a: u32, b: u32, rm: round_mode_e) -> FpResult {
+    if is_nan(a) || is_nan(b) {
+        return propagate_nan(&[a, b]);
+    }
+    match (is_inf(a), is_inf(b)) {
+        (true, true) if sign_of(a) != sign_of(b) => return invalid(),
+        (true, _) => return (a, 0),
+        (_, true) => return (b, 0),
+        _ => {}
+    }
+    match (is_zero(a), is_zero(b)) {
+        (true, true) => zero_sum(sign_of(a), sign_of(b), rm),
+        (true, false) => (b, 0),
+        (false, true) => (a, 0),
+        (false, false) => {
+            let (sa, ea) = unpack(a);
+            let (sb, eb) = unpack(b);
+            add_values((sign_of(a), sa, ea), (sign_of(b), sb, eb), rm)
+        }
+    }
+}
+
+/// a - b
+pub fn f32_sub(a: u32, b: u32, rm: round_mode_e) -> FpResult {
+    f32_add(a, b ^ SIGN, rm)
+}
+
+/// a × b
+pub fn f32_mul(a: u32, b: u32, rm: round_mode_e) -> FpResult {
+    if is_nan(a) || is_nan(b) {
+        return propagate_nan(&[a, b]);
+    }
+    let sign = sign_of(a) ^ sign_of(b);
+    if is_inf(a) || is_inf(b) {
+        return if is_zero(a) || is_zero(b) { invalid() } else { (sign_bit(sign) | INF, 0) };
+    }
+    if is_zero(a) || is_zero(b) {
+        return (sign_bit(sign), 0);
+    }
+
+    let (sa, ea) = unpack(a);
+    let (sb, eb) = unpack(b);
+    round_pack(sign, (sa * sb) as u128, ea + eb, false, rm)
+}
+
+/// a ÷ b
+pub fn f32_div(a: u32, b: u32, rm: round_mode_e) -> FpResult {
+    if is_nan(a) || is_nan(b) {
+        return propagate_nan(&[a, b]);
+    }
+    let sign = sign_of(a) ^ sign_of(b);
+    match (is_inf(a), is_inf(b), is_zero(a), is_zero(b)) {
+        (true, true, ..) | (.., true, true) => return invalid(),
+        (true, ..) => return (sign_bit(sign) | INF, 0),
+        (_, true, ..) | (_, _, true, _) => return (sign_bit(sign), 0),
+        (.., true) => return (sign_bit(sign) | INF, FFLAG_DZ),
+        _ => {}
+    }
+
+    // A 64-bit quotient extension leaves more than 24 significant bits
+    let (sa, ea) = unpack(a);
+    let (sb, eb) = unpack(b);
+    let num = (sa as u128) << 64;
+    let (q, r) = (num / sb as u128, num % sb as u128);
+    round_pack(sign, q, ea - eb - 64, r != 0, rm)
+}
+
+/// √a
+pub fn f32_sqrt(a: u32, rm: round_mode_e) -> FpResult {
+    if is_nan(a) {
+        return propagate_nan(&[a]);
+    }
+    if is_zero(a) {
+        return (a, 0);
+    }
+    if sign_of(a) {
+        return invalid();
+    }
+    if is_inf(a) {
+        return (a, 0);
+    }
+
+    // Even exponent, so the root's exponent is exact
+    let (sig, exp) = unpack(a);
+    let (sig, exp) = if exp & 1 != 0 { ((sig as u128) << 1, exp - 1) } else { (sig as u128, exp) };
+    let radicand = sig << 100;
+    let root = radicand.isqrt();
+    round_pack(false, root, (exp - 100) / 2, root * root != radicand, rm)
+}
+
+/// ±(a × b) ± c with a single rounding. `negate_product` and `negate_addend`
+/// select FNMSUB/FMSUB/FNMADD.
+pub fn f32_mul_add(a: u32, b: u32, c: u32, negate_product: bool, negate_addend: bool, rm: round_mode_e) -> FpResult {
+    let inf_times_zero = (is_inf(a) && is_zero(b)) || (is_zero(a) && is_inf(b));
+    if is_nan(a) || is_nan(b) || is_nan(c) {
+        // ∞ × 0 is invalid even when the addend is a quiet NaN
+        let (nan, flags) = propagate_nan(&[a, b, c]);
+        return (nan, if inf_times_zero { FFLAG_NV } else { flags });
+    }
+    if inf_times_zero {
+        return invalid();
+    }
+
+    let sign_p = sign_of(a) ^ sign_of(b) ^ negate_product;
+    let sign_c = sign_of(c) ^ negate_addend;
+    if is_inf(a) || is_inf(b) {
+        return if is_inf(c) && sign_c != sign_p { invalid() } else { (sign_bit(sign_p) | INF, 0) };
+    }
+    if is_inf(c) {
+        return (sign_bit(sign_c) | INF, 0);
+    }
+
+    let c = (c & !SIGN) | sign_bit(sign_c);
+    if is_zero(a) || is_zero(b) {
+        return if is_zero(c) { zero_sum(sign_p, sign_c, rm) } else { (c, 0) };
+    }
+
+    // The 48-bit product is exact
+    let (sa, ea) = unpack(a);
+    let (sb, eb) = unpack(b);
+    let product = (sign_p, sa * sb, ea + eb);
+    if is_zero(c) {
+        return round_pack(sign_p, product.1 as u128, product.2, false, rm);
+    }
+    let (sc, ec) = unpack(c);
+    add_values(product, (sign_c, sc, ec), rm)
+}
+
+/// `a` rounded to an integer under `rm`: (negative, magnitude, inexact), or
+/// `None` when it is infinite or too large for 64 bits. `a` must not be NaN.
+fn round_to_int(a: u32, rm: round_mode_e) -> Option<(bool, u64, bool)> {
+    let sign = sign_of(a);
+    if is_inf(a) {
+        return None;
+    }
+    if is_zero(a) {
+        return Some((sign, 0, false));
+    }
+
+    let (sig, exp) = unpack(a);
+    if exp >= 0 {
+        return (exp < 40).then(|| (sign, sig << exp, false));
+    }
+    let msb = 63 - sig.leading_zeros() as i32;
+    let (magnitude, inexact) = shift_round(sign, sig as u128, (-exp).min(msb + 2), false, rm);
+    Some((sign, magnitude as u64, inexact))
+}
+
+/// FCVT.W.S: out-of-range inputs saturate and raise invalid (NaN gives i32::MAX).
+pub fn f32_to_i32(a: u32, rm: round_mode_e) -> FpResult {
+    let rounded = if is_nan(a) { None } else { round_to_int(a, rm) };
+    match rounded {
+        Some((true, magnitude, inexact)) if magnitude <= 1 << 31 => {
+            ((magnitude as u32).wrapping_neg(), if inexact { FFLAG_NX } else { 0 })
+        }
+        Some((false, magnitude, inexact)) if magnitude < 1 << 31 => {
+            (magnitude as u32, if inexact { FFLAG_NX } else { 0 })
+        }
+        _ if sign_of(a) && !is_nan(a) => (i32::MIN as u32, FFLAG_NV),
+        _ => (i32::MAX as u32, FFLAG_NV),
+    }
+}
+
+/// FCVT.WU.S: out-of-range inputs saturate and raise invalid (NaN gives
+/// u32::MAX); negative values that round to zero only raise inexact.
+pub fn f32_to_u32(a: u32, rm: round_mode_e) -> FpResult {
+    let rounded = if is_nan(a) { None } else { round_to_int(a, rm) };
+    match rounded {
+        Some((true, 0, inexact)) => (0, if inexact { FFLAG_NX } else { 0 }),
+        Some((false, magnitude, inexact)) if magnitude <= u32::MAX as u64 => {
+            (magnitude as u32, if inexact { FFLAG_NX } else { 0 })
+        }
+        _ if sign_of(a) && !is_nan(a) => (0, FFLAG_NV),
+        _ => (u32::MAX, FFLAG_NV),
+    }
+}
+
+/// FCVT.S.W
+pub fn i32_to_f32(x: u32, rm: round_mode_e) -> FpResult {
+    let x = x as i32;
+    round_pack(x < 0, x.unsigned_abs() as u128, 0, false, rm)
+}
+
+/// FCVT.S.WU
+pub fn u32_to_f32(x: u32, rm: round_mode_e) -> FpResult {
+    round_pack(false, x as u128, 0, false, rm)
+}
+
+/// a < b for non-NaN operands, with -0 = +0.
+fn lt_ordered(a: u32, b: u32) -> bool {
+    if is_zero(a) && is_zero(b) {
+        return false;
+    }
+    match (sign_of(a), sign_of(b)) {
+        (true, false) => true,
+        (false, true) => false,
+        (false, false) => a < b,
+        (true, true) => a > b,
+    }
+}
+
+/// FEQ.S: quiet, so only signaling NaNs raise invalid.
+pub fn f32_eq(a: u32, b: u32) -> (bool, u8) {
+    if is_nan(a) || is_nan(b) {
+        let nv = if is_snan(a) || is_snan(b) { FFLAG_NV } else { 0 };
+        return (false, nv);
+    }
+    (a == b || (is_zero(a) && is_zero(b)), 0)
+}
+
+/// FLT.S: signaling, so any NaN raises invalid.
+pub fn f32_lt(a: u32, b: u32) -> (bool, u8) {
+    if is_nan(a) || is_nan(b) {
+        return (false, FFLAG_NV);
+    }
+    (lt_ordered(a, b), 0)
+}
+
+/// FLE.S: signaling, so any NaN raises invalid.
+pub fn f32_le(a: u32, b: u32) -> (bool, u8) {
+    if is_nan(a) || is_nan(b) {
+        return (false, FFLAG_NV);
+    }
+    (!lt_ordered(b, a), 0)
+}
+
+/// FMIN.S/FMAX.S: a single NaN operand yields the other one, two NaNs the
+/// canonical NaN; -0 is taken as less than +0.
+fn f32_min_max(a: u32, b: u32, max: bool) -> FpResult {
+    let nv = if is_snan(a) || is_snan(b) { FFLAG_NV } else { 0 };
+    match (is_nan(a), is_nan(b)) {
+        (true, true) => (F32_CANONICAL_NAN, nv),
+        (true, false) => (b, nv),
+        (false, true) => (a, nv),
+        (false, false) => {
+            let a_less = lt_ordered(a, b) || (is_zero(a) && is_zero(b) && sign_of(a));
+            (if a_less != max { a } else { b }, 0)
+        }
+    }
+}
+
+pub fn f32_min(a: u32, b: u32) -> FpResult {
+    f32_min_max(a, b, false)
+}
+
+pub fn f32_max(a: u32, b: u32) -> FpResult {
+    f32_min_max(a, b, true)
+}
+
+/// FCLASS.S: one-hot FCLASS_* mask.
+pub fn f32_classify(a: u32) -> u32 {
+    let signed = |neg, pos| if sign_of(a) { neg } else { pos };
+    if is_snan(a) {
+        FCLASS_SNAN
+    } else if is_nan(a) {
+        FCLASS_QNAN
+    } else if is_inf(a) {
+        signed(FCLASS_NEG_INF, FCLASS_POS_INF)
+    } else if is_zero(a) {
+        signed(FCLASS_NEG_ZERO, FCLASS_POS_ZERO)
+    } else if a & EXP_MASK == 0 {
+        signed(FCLASS_NEG_SUBNORMAL, FCLASS_POS_SUBNORMAL)
+    } else {
+        signed(FCLASS_NEG_NORMAL, FCLASS_POS_NORMAL)
+    }
+}
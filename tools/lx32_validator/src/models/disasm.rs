@@ -0,0 +1,338 @@
+// ============================================================
+// LX32 Disassembler
+// ============================================================
+// Turns instruction words back into assembly text for traces,
+// mismatch reports and program listings.
+//
+// Legality comes from the strict decoder, so a word is shown
+// as an instruction exactly when the profile accepts it and as
+// `.word 0x........` otherwise. Compressed parcels print as
+// their 32-bit expansion, like objdump does.
+//
+// Design Principles:
+//   - Canonical forms only (no pseudo-instructions), so the
+//     text maps back to one encoding.
+//   - Branch and jump targets are absolute addresses computed
+//     from the PC of the word.
+//   - Numeric register names by default; ABI names on request.
+// ============================================================
+
+use crate::models::arch::lx32_amo_pkg::amo_op_e;
+use crate::models::arch::lx32_arch_pkg::{addr_t, instr_t, reg_idx_t};
+use crate::models::arch::lx32_csr_pkg::*;
+use crate::models::arch::lx32_decode_pkg::{get_b_imm, get_i_imm, get_j_imm, get_s_imm};
+use crate::models::arch::lx32_fpu_pkg::{FRM_DYN, fp_op_e};
+use crate::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL, INSTR_MRET, opcode_t};
+use crate::models::arch::lx32_rvc_pkg::{expand_compressed, is_compressed};
+use crate::models::core::control_unit::bitmanip_alu_op;
+use crate::models::core::custom::{rd_field, rs1_field, rs2_field};
+use crate::models::core::decoder::is_legal_instr;
+use crate::models::core::isa_config::IsaConfig;
+use crate::models::core::pmp::PMP_ENTRIES;
+
+/// ABI names of x0-x31
+pub const X_ABI_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+    "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+];
+
+/// ABI names of f0-f31
+pub const F_ABI_NAMES: [&str; 32] = [
+    "ft0", "ft1", "ft2", "ft3", "ft4", "ft5", "ft6", "ft7", "fs0", "fs1", "fa0", "fa1", "fa2", "fa3", "fa4", "fa5", "fa6", "fa7", "fs2", "fs3", "fs4",
+    "fs5", "fs6", "fs7", "fs8", "fs9", "fs10", "fs11", "ft8", "ft9", "ft10", "ft11",
+];
+
+/// Rounding mode suffixes, indexed by the rm field (DYN is never printed)
+const RM_NAMES: [&str; 8] = ["rne", "rtz", "rdn", "rup", "rmm", "", "", "dyn"];
+
+/// Disassembly settings: the profile deciding which words are instructions
+/// (and which custom instructions exist) and the register naming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disassembler {
+    pub cfg: IsaConfig,
+    /// ra/sp/a0 and ft0/fa0 instead of x1/x2/x10 and f0/f10.
+    pub abi_names: bool,
+}
+
+/// Every standard extension, numeric register names.
+impl Default for Disassembler {
+    fn default() -> Self {
+        Self::new(IsaConfig::full())
+    }
+}
+
+impl Disassembler {
+    /// Disassembler for the words `cfg` accepts.
+    pub fn new(cfg: IsaConfig) -> Self {
+        Self { cfg, abi_names: false }
+    }
+
+    /// Same settings with ABI register names.
+    pub fn with_abi_names(self) -> Self {
+        Self { abi_names: true, ..self }
+    }
+
+    /// Assembly text of `instr` located at `pc`. Only the low parcel of a
+    /// compressed word is used.
+    pub fn disasm(&self, instr: instr_t, pc: addr_t) -> String {
+        if !is_legal_instr(instr, &self.cfg) {
+            return format!(".word 0x{:08x}", instr);
+        }
+        // Legal, so a compressed parcel has an expansion
+        let instr = if is_compressed(instr) { expand_compressed(instr as u16, self.cfg.f_ext).unwrap_or(instr) } else { instr };
+        self.text(instr, pc)
+    }
+
+    fn x(&self, reg: reg_idx_t) -> String {
+        if self.abi_names { X_ABI_NAMES[reg as usize & 0x1F].to_string() } else { format!("x{}", reg) }
+    }
+
+    fn f(&self, reg: reg_idx_t) -> String {
+        if self.abi_names { F_ABI_NAMES[reg as usize & 0x1F].to_string() } else { format!("f{}", reg) }
+    }
+
+    /// Text of a legal 32-bit word.
+    fn text(&self, instr: instr_t, pc: addr_t) -> String {
+        let opcode = opcode_t::from_bits((instr & 0x7F) as u8);
+        let funct3 = ((instr >> 12) & 0x7) as u8;
+        let funct7 = (instr >> 25) as u8;
+        let (rd, rs1, rs2) = (rd_field(instr), rs1_field(instr), rs2_field(instr));
+        let i_imm = get_i_imm(instr) as i32;
+
+        match opcode {
+            opcode_t::OP_LUI => format!("lui {}, 0x{:x}", self.x(rd), instr >> 12),
+            opcode_t::OP_AUIPC => format!("auipc {}, 0x{:x}", self.x(rd), instr >> 12),
+            opcode_t::OP_JAL => format!("jal {}, 0x{:x}", self.x(rd), pc.wrapping_add(get_j_imm(instr))),
+            opcode_t::OP_JALR => format!("jalr {}, {}({})", self.x(rd), i_imm, self.x(rs1)),
+            opcode_t::OP_BRANCH => {
+                let name = ["beq", "bne", "", "", "blt", "bge", "bltu", "bgeu"][funct3 as usize];
+                format!("{} {}, {}, 0x{:x}", name, self.x(rs1), self.x(rs2), pc.wrapping_add(get_b_imm(instr)))
+            }
+            opcode_t::OP_LOAD => {
+                let name = ["lb", "lh", "lw", "", "lbu", "lhu", "", ""][funct3 as usize];
+                format!("{} {}, {}({})", name, self.x(rd), i_imm, self.x(rs1))
+            }
+            opcode_t::OP_STORE => {
+                let name = ["sb", "sh", "sw", "", "", "", "", ""][funct3 as usize];
+                format!("{} {}, {}({})", name, self.x(rs2), get_s_imm(instr) as i32, self.x(rs1))
+            }
+            opcode_t::OP_OP_IMM => self.op_imm(funct3, funct7, rd, rs1, rs2, i_imm),
+            opcode_t::OP_OP => self.op(funct3, funct7, rd, rs1, rs2),
+            opcode_t::OP_MISC_MEM => format!("fence {}, {}", fence_set(instr >> 24), fence_set(instr >> 20)),
+            opcode_t::OP_AMO => self.amo(funct7, rd, rs1, rs2),
+            opcode_t::OP_LOAD_FP => format!("flw {}, {}({})", self.f(rd), i_imm, self.x(rs1)),
+            opcode_t::OP_STORE_FP => format!("fsw {}, {}({})", self.f(rs2), get_s_imm(instr) as i32, self.x(rs1)),
+            opcode_t::OP_OP_FP | opcode_t::OP_MADD | opcode_t::OP_MSUB | opcode_t::OP_NMSUB | opcode_t::OP_NMADD => self.fp(instr, opcode),
+            opcode_t::OP_CUSTOM_0 | opcode_t::OP_CUSTOM_1 => match self.cfg.custom.find(instr) {
+                Some(custom) => custom.mnemonic(instr),
+                None => format!(".word 0x{:08x}", instr),
+            },
+            opcode_t::OP_SYSTEM => self.system(instr, funct3, rd, rs1),
+            opcode_t::OP_INVALID => format!(".word 0x{:08x}", instr),
+        }
+    }
+
+    fn op_imm(&self, funct3: u8, funct7: u8, rd: reg_idx_t, rs1: reg_idx_t, shamt: u8, imm: i32) -> String {
+        use crate::models::arch::lx32_alu_pkg::alu_op_e::*;
+
+        let (rd, rs1) = (self.x(rd), self.x(rs1));
+        if let Some(op) = bitmanip_alu_op(opcode_t::OP_OP_IMM, funct3, funct7, shamt, &self.cfg) {
+            let name = match op {
+                ALU_CLZ => "clz",
+                ALU_CTZ => "ctz",
+                ALU_CPOP => "cpop",
+                ALU_SEXT_B => "sext.b",
+                ALU_SEXT_H => "sext.h",
+                ALU_REV8 => "rev8",
+                ALU_ORC_B => "orc.b",
+                ALU_ROR => return format!("rori {}, {}, {}", rd, rs1, shamt),
+                ALU_BCLR => return format!("bclri {}, {}, {}", rd, rs1, shamt),
+                ALU_BEXT => return format!("bexti {}, {}, {}", rd, rs1, shamt),
+                ALU_BINV => return format!("binvi {}, {}, {}", rd, rs1, shamt),
+                _ => return format!("bseti {}, {}, {}", rd, rs1, shamt),
+            };
+            return format!("{} {}, {}", name, rd, rs1);
+        }
+
+        match funct3 {
+            0b001 => format!("slli {}, {}, {}", rd, rs1, shamt),
+            0b101 if funct7 & 0b010_0000 != 0 => format!("srai {}, {}, {}", rd, rs1, shamt),
+            0b101 => format!("srli {}, {}, {}", rd, rs1, shamt),
+            _ => {
+                let name = ["addi", "", "slti", "sltiu", "xori", "", "ori", "andi"][funct3 as usize];
+                format!("{} {}, {}, {}", name, rd, rs1, imm)
+            }
+        }
+    }
+
+    fn op(&self, funct3: u8, funct7: u8, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> String {
+        use crate::models::arch::lx32_alu_pkg::alu_op_e::*;
+
+        let name = match bitmanip_alu_op(opcode_t::OP_OP, funct3, funct7, rs2, &self.cfg) {
+            Some(ALU_ZEXT_H) => return format!("zext.h {}, {}", self.x(rd), self.x(rs1)),
+            Some(op) => match op {
+                ALU_SH1ADD => "sh1add",
+                ALU_SH2ADD => "sh2add",
+                ALU_SH3ADD => "sh3add",
+                ALU_ANDN => "andn",
+                ALU_ORN => "orn",
+                ALU_XNOR => "xnor",
+                ALU_MIN => "min",
+                ALU_MINU => "minu",
+                ALU_MAX => "max",
+                ALU_MAXU => "maxu",
+                ALU_ROL => "rol",
+                ALU_ROR => "ror",
+                ALU_BCLR => "bclr",
+                ALU_BEXT => "bext",
+                ALU_BINV => "binv",
+                _ => "bset",
+            },
+            None => match funct7 {
+                0b000_0001 => ["mul", "mulh", "mulhsu", "mulhu", "div", "divu", "rem", "remu"][funct3 as usize],
+                0b010_0000 if funct3 == 0b000 => "sub",
+                0b010_0000 => "sra",
+                _ => ["add", "sll", "slt", "sltu", "xor", "srl", "or", "and"][funct3 as usize],
+            },
+        };
+        format!("{} {}, {}, {}", name, self.x(rd), self.x(rs1), self.x(rs2))
+    }
+
+    fn amo(&self, funct7: u8, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> String {
+        let ordering = ["", ".rl", ".aq", ".aqrl"][(funct7 & 0b11) as usize];
+        let name = match amo_op_e::from_funct5(funct7 >> 2) {
+            Some(amo_op_e::AMO_LR) => return format!("lr.w{} {}, ({})", ordering, self.x(rd), self.x(rs1)),
+            Some(amo_op_e::AMO_SC) => "sc.w",
+            Some(amo_op_e::AMO_SWAP) => "amoswap.w",
+            Some(amo_op_e::AMO_ADD) => "amoadd.w",
+            Some(amo_op_e::AMO_XOR) => "amoxor.w",
+            Some(amo_op_e::AMO_AND) => "amoand.w",
+            Some(amo_op_e::AMO_OR) => "amoor.w",
+            Some(amo_op_e::AMO_MIN) => "amomin.w",
+            Some(amo_op_e::AMO_MAX) => "amomax.w",
+            Some(amo_op_e::AMO_MINU) => "amominu.w",
+            Some(amo_op_e::AMO_MAXU) | None => "amomaxu.w",
+        };
+        format!("{}{} {}, {}, ({})", name, ordering, self.x(rd), self.x(rs2), self.x(rs1))
+    }
+
+    fn fp(&self, instr: instr_t, opcode: opcode_t) -> String {
+        let funct3 = ((instr >> 12) & 0x7) as u8;
+        let (rd, rs1, rs2, rs3) = (rd_field(instr), rs1_field(instr), rs2_field(instr), (instr >> 27) as reg_idx_t);
+        let Some(op) = fp_op_e::decode(opcode, funct3, (instr >> 25) as u8, rs2) else {
+            return format!(".word 0x{:08x}", instr);
+        };
+
+        let name = fp_op_name(op);
+        let rd = if op.writes_x() { self.x(rd) } else { self.f(rd) };
+        let mut text = match op {
+            _ if op.is_fused() => format!("{} {}, {}, {}, {}", name, rd, self.f(rs1), self.f(rs2), self.f(rs3)),
+            _ if op.reads_x() => format!("{} {}, {}", name, rd, self.x(rs1)),
+            fp_op_e::FP_SQRT | fp_op_e::FP_CLASS | fp_op_e::FP_CVT_W_S | fp_op_e::FP_CVT_WU_S | fp_op_e::FP_MV_X_W => {
+                format!("{} {}, {}", name, rd, self.f(rs1))
+            }
+            _ => format!("{} {}, {}, {}", name, rd, self.f(rs1), self.f(rs2)),
+        };
+        if op.uses_rm() && funct3 != FRM_DYN {
+            text.push_str(", ");
+            text.push_str(RM_NAMES[funct3 as usize]);
+        }
+        text
+    }
+
+    fn system(&self, instr: instr_t, funct3: u8, rd: reg_idx_t, rs1: reg_idx_t) -> String {
+        let csr = csr_name((instr >> 20) as csr_addr_t);
+        match funct3 {
+            0b000 => match instr {
+                INSTR_ECALL => "ecall".to_string(),
+                INSTR_EBREAK => "ebreak".to_string(),
+                INSTR_MRET => "mret".to_string(),
+                _ => format!("sfence.vma {}, {}", self.x(rs1), self.x(rs2_field(instr))),
+            },
+            0b001 => format!("csrrw {}, {}, {}", self.x(rd), csr, self.x(rs1)),
+            0b010 => format!("csrrs {}, {}, {}", self.x(rd), csr, self.x(rs1)),
+            0b011 => format!("csrrc {}, {}, {}", self.x(rd), csr, self.x(rs1)),
+            0b101 => format!("csrrwi {}, {}, {}", self.x(rd), csr, rs1),
+            0b110 => format!("csrrsi {}, {}, {}", self.x(rd), csr, rs1),
+            _ => format!("csrrci {}, {}, {}", self.x(rd), csr, rs1),
+        }
+    }
+}
+
+/// Assembly text of `instr` at `pc` for every standard extension, with
+/// numeric register names.
+pub fn disasm(instr: instr_t, pc: addr_t) -> String {
+    Disassembler::default().disasm(instr, pc)
+}
+
+/// Name of a CSR, or its address in hex when it has none.
+pub fn csr_name(addr: csr_addr_t) -> String {
+    let name = match addr {
+        CSR_FFLAGS => "fflags",
+        CSR_FRM => "frm",
+        CSR_FCSR => "fcsr",
+        CSR_SATP => "satp",
+        CSR_MSTATUS => "mstatus",
+        CSR_MISA => "misa",
+        CSR_MIE => "mie",
+        CSR_MTVEC => "mtvec",
+        CSR_MCOUNTEREN => "mcounteren",
+        CSR_MSCRATCH => "mscratch",
+        CSR_MEPC => "mepc",
+        CSR_MCAUSE => "mcause",
+        CSR_MTVAL => "mtval",
+        CSR_MIP => "mip",
+        CSR_MCYCLE => "mcycle",
+        CSR_MINSTRET => "minstret",
+        CSR_MCYCLEH => "mcycleh",
+        CSR_MINSTRETH => "minstreth",
+        CSR_CYCLE => "cycle",
+        CSR_INSTRET => "instret",
+        CSR_CYCLEH => "cycleh",
+        CSR_INSTRETH => "instreth",
+        CSR_MVENDORID => "mvendorid",
+        CSR_MARCHID => "marchid",
+        CSR_MIMPID => "mimpid",
+        CSR_MHARTID => "mhartid",
+        _ if (CSR_PMPCFG0..CSR_PMPCFG0 + PMP_ENTRIES as csr_addr_t / 4).contains(&addr) => return format!("pmpcfg{}", addr - CSR_PMPCFG0),
+        _ if (CSR_PMPADDR0..CSR_PMPADDR0 + PMP_ENTRIES as csr_addr_t).contains(&addr) => return format!("pmpaddr{}", addr - CSR_PMPADDR0),
+        _ => return format!("0x{:03x}", addr),
+    };
+    name.to_string()
+}
+
+/// Mnemonic of a single-precision operation.
+pub fn fp_op_name(op: fp_op_e) -> &'static str {
+    match op {
+        fp_op_e::FP_ADD => "fadd.s",
+        fp_op_e::FP_SUB => "fsub.s",
+        fp_op_e::FP_MUL => "fmul.s",
+        fp_op_e::FP_DIV => "fdiv.s",
+        fp_op_e::FP_SQRT => "fsqrt.s",
+        fp_op_e::FP_MADD => "fmadd.s",
+        fp_op_e::FP_MSUB => "fmsub.s",
+        fp_op_e::FP_NMSUB => "fnmsub.s",
+        fp_op_e::FP_NMADD => "fnmadd.s",
+        fp_op_e::FP_SGNJ => "fsgnj.s",
+        fp_op_e::FP_SGNJN => "fsgnjn.s",
+        fp_op_e::FP_SGNJX => "fsgnjx.s",
+        fp_op_e::FP_MIN => "fmin.s",
+        fp_op_e::FP_MAX => "fmax.s",
+        fp_op_e::FP_EQ => "feq.s",
+        fp_op_e::FP_LT => "flt.s",
+        fp_op_e::FP_LE => "fle.s",
+        fp_op_e::FP_CLASS => "fclass.s",
+        fp_op_e::FP_CVT_W_S => "fcvt.w.s",
+        fp_op_e::FP_CVT_WU_S => "fcvt.wu.s",
+        fp_op_e::FP_CVT_S_W => "fcvt.s.w",
+        fp_op_e::FP_CVT_S_WU => "fcvt.s.wu",
+        fp_op_e::FP_MV_X_W => "fmv.x.w",
+        fp_op_e::FP_MV_W_X => "fmv.w.x",
+    }
+}
+
+/// FENCE predecessor/successor set (low 4 bits of `bits`) as "iorw" letters.
+fn fence_set(bits: u32) -> String {
+    let set: String = "iorw".chars().enumerate().filter(|(i, _)| bits & (0b1000 >> i) != 0).map(|(_, c)| c).collect();
+    if set.is_empty() { "0".to_string() } else { set }
+}
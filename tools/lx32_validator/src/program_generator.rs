@@ -8,9 +8,19 @@
 // - Control flow
 // - PC correctness
 //
+// Instructions are built as typed `Instr` values (`CInstr` for
+// RV32C parcels) and encoded by models::arch, never packed here.
+//
 // Author: LX32 Validation Team
 // License: MIT
 
+use crate::models::arch::lx32_arch_pkg::{REG_COUNT, reg_idx_t};
+use crate::models::arch::lx32_cinstr_pkg::CInstr;
+use crate::models::arch::lx32_instr_pkg::Instr;
+use crate::models::arch::lx32_rvc_pkg::instr_len;
+use crate::models::core::custom::{CustomSet, rd_field, rs1_field, rs2_field};
+use crate::models::core::isa_config::IsaConfig;
+use crate::models::disasm::Disassembler;
 use rand::RngExt;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
@@ -19,6 +29,7 @@ use rand::rngs::StdRng;
 #[derive(Debug, Clone)]
 pub struct Instruction {
     pub encoding: u32,
+    /// Disassembly at address 0, so branch and jump targets read as offsets
     pub mnemonic: String,
     pub rd: Option<u8>,
     pub rs1: Option<u8>,
@@ -26,6 +37,50 @@ pub struct Instruction {
     pub imm: Option<i32>,
 }
 
+impl Instruction {
+    /// Size in bytes: 2 for a compressed instruction (`encoding` holds the
+    /// 16-bit parcel), 4 otherwise.
+    pub fn instr_len(&self) -> u32 {
+        instr_len(self.encoding)
+    }
+}
+
+/// The disassembler's text for a generated word, the only source of
+/// mnemonics (compressed parcels show their expansion)
+fn mnemonic(encoding: u32, custom: CustomSet) -> String {
+    Disassembler::new(IsaConfig { custom, ..IsaConfig::full() }).disasm(encoding, 0)
+}
+
+impl From<Instr> for Instruction {
+    fn from(instr: Instr) -> Self {
+        Instruction {
+            encoding: instr.encode(),
+            mnemonic: mnemonic(instr.encode(), CustomSet::default()),
+            rd: instr.rd(),
+            rs1: instr.rs1(),
+            rs2: instr.rs2(),
+            imm: instr.imm(),
+        }
+    }
+}
+
+/// A compressed instruction: `encoding` holds the parcel, the registers
+/// and immediate are those of its expansion
+impl From<CInstr> for Instruction {
+    fn from(instr: CInstr) -> Self {
+        let encoding = instr.encode() as u32;
+        let expanded = instr.expand();
+        Instruction {
+            encoding,
+            mnemonic: mnemonic(encoding, CustomSet::default()),
+            rd: expanded.rd(),
+            rs1: expanded.rs1(),
+            rs2: expanded.rs2(),
+            imm: expanded.imm(),
+        }
+    }
+}
+
 /// Program sequence configuration
 #[derive(Debug, Clone)]
 pub struct ProgramConfig {
@@ -36,6 +91,16 @@ pub struct ProgramConfig {
     pub enable_alu: bool,
     pub enable_jumps: bool,
     pub enable_upper_imm: bool,
+    /// Also emit LB/LH/LBU/LHU/SB/SH (the RTL LSU only performs word accesses)
+    pub enable_sub_word_mem: bool,
+    /// Also emit M extension multiply/divide (the RTL has no multiplier yet)
+    pub enable_mul_div: bool,
+    /// Also emit 16-bit RV32C instructions (the RTL only fetches 32-bit words)
+    pub enable_compressed: bool,
+    /// Also emit these registered custom instructions (the RTL decodes neither custom opcode)
+    pub custom: CustomSet,
+    /// Registers the program may name: 32, or 16 (x0-x15) for RV32E
+    pub reg_count: usize,
 }
 
 impl Default for ProgramConfig {
@@ -48,6 +113,11 @@ impl Default for ProgramConfig {
             enable_alu: true,
             enable_jumps: true,
             enable_upper_imm: true,
+            enable_sub_word_mem: false,
+            enable_mul_div: false,
+            enable_compressed: false,
+            custom: CustomSet::default(),
+            reg_count: REG_COUNT,
         }
     }
 }
@@ -98,6 +168,16 @@ impl Program {
         if config.enable_upper_imm {
             categories.push("UIMM");
         }
+        if config.enable_mul_div {
+            categories.push("MULDIV");
+        }
+        if config.enable_compressed {
+            categories.push("COMPRESSED");
+        }
+
+        if !config.custom.is_empty() {
+            categories.push("CUSTOM");
+        }
 
         if categories.is_empty() {
             categories.push("ALU"); // fallback
@@ -106,182 +186,201 @@ impl Program {
         let category = categories[rng.random_range(0..categories.len())];
 
         match category {
-            "ALU" => Self::generate_alu_instruction(rng),
-            "LOAD" => Self::generate_load_instruction(rng),
-            "STORE" => Self::generate_store_instruction(rng),
-            "BRANCH" => Self::generate_branch_instruction(rng),
-            "JUMP" => Self::generate_jump_instruction(rng),
-            "UIMM" => Self::generate_upper_imm_instruction(rng),
-            _ => Self::generate_alu_instruction(rng),
+            "ALU" => Self::generate_alu_instruction(config, rng),
+            "LOAD" => Self::generate_load_instruction(config, rng),
+            "STORE" => Self::generate_store_instruction(config, rng),
+            "BRANCH" => Self::generate_branch_instruction(config, rng),
+            "JUMP" => Self::generate_jump_instruction(config, rng),
+            "UIMM" => Self::generate_upper_imm_instruction(config, rng),
+            "MULDIV" => Self::generate_mul_div_instruction(config, rng),
+            "COMPRESSED" => Self::generate_compressed_instruction(config, rng).0,
+            "CUSTOM" => Self::generate_custom_instruction(config, rng),
+            _ => Self::generate_alu_instruction(config, rng),
         }
     }
 
     /// Generate JAL/JALR instructions
-    fn generate_jump_instruction(rng: &mut impl RngExt) -> Instruction {
+    fn generate_jump_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
         let jal = rng.random();
-        let rd = rng.random_range(1..32) as u8;
+        let rd = rng.random_range(1..config.reg_count as i32) as u8;
         if jal {
-            let offset = (rng.random_range(-524288..524287) as i32) & !1;
-            let imm20 = ((offset >> 20) & 0x1) as u32;
-            let imm10_1 = ((offset >> 1) & 0x3FF) as u32;
-            let imm11 = ((offset >> 11) & 0x1) as u32;
-            let imm19_12 = ((offset >> 12) & 0xFF) as u32;
-            let encoding = (imm20 << 31)
-                | (imm19_12 << 12)
-                | (imm11 << 20)
-                | (imm10_1 << 21)
-                | ((rd as u32) << 7)
-                | 0x6F;
-            Instruction {
-                encoding,
-                mnemonic: format!("JAL x{}, {}", rd, offset),
-                rd: Some(rd),
-                rs1: None,
-                rs2: None,
-                imm: Some(offset),
-            }
+            let imm = rng.random_range(-524288..524287) & !1;
+            Instr::Jal { rd, imm }.into()
         } else {
-            let rs1 = rng.random_range(0..32) as u8;
-            let imm = rng.random_range(-2048..2047) as i32;
-            let encoding = ((imm as u32) << 20)
-                | ((rs1 as u32) << 15)
-                | (0x0 << 12)
-                | ((rd as u32) << 7)
-                | 0x67;
-            Instruction {
-                encoding,
-                mnemonic: format!("JALR x{}, {}(x{})", rd, imm, rs1),
-                rd: Some(rd),
-                rs1: Some(rs1),
-                rs2: None,
-                imm: Some(imm),
-            }
+            let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+            let imm = rng.random_range(-2048..2047);
+            Instr::Jalr { rd, rs1, imm }.into()
         }
     }
 
     /// Generate LUI/AUIPC instructions
-    fn generate_upper_imm_instruction(rng: &mut impl RngExt) -> Instruction {
-        let rd = rng.random_range(1..32) as u8;
-        let imm20 = rng.random_range(0..(1 << 20)) as u32;
-        let lui = rng.random();
-        let opcode = if lui { 0x37 } else { 0x17 };
-        let encoding = (imm20 << 12) | ((rd as u32) << 7) | opcode;
-        Instruction {
-            encoding,
-            mnemonic: if lui {
-                format!("LUI x{}, 0x{:x}", rd, imm20)
-            } else {
-                format!("AUIPC x{}, 0x{:x}", rd, imm20)
-            },
-            rd: Some(rd),
-            rs1: None,
-            rs2: None,
-            imm: Some((imm20 << 12) as i32),
-        }
+    fn generate_upper_imm_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rd = rng.random_range(1..config.reg_count as i32) as u8;
+        let imm = rng.random_range(0i32..(1 << 20)) << 12;
+        if rng.random() { Instr::Lui { rd, imm }.into() } else { Instr::Auipc { rd, imm }.into() }
     }
 
-    /// Generate ALU instruction (ADD, XOR, OR, AND, SLT, SLTU)
-    fn generate_alu_instruction(rng: &mut impl RngExt) -> Instruction {
-        let rd = rng.random_range(1..32) as u8;
-        let rs1 = rng.random_range(0..32) as u8;
-        let imm = rng.random_range(0..4096) as i32;
-        let funct3_set = [0x0, 0x2, 0x4, 0x6, 0x7]; // ADDI, SLTI, XORI, ORI, ANDI
-        let funct3 = funct3_set[rng.random_range(0..funct3_set.len())];
-
-        let encoding = ((imm as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | 0x13;
-
-        let mnemonic = match funct3 {
-            0x0 => format!("ADDI x{}, x{}, {}", rd, rs1, imm),
-            0x2 => format!("SLTI x{}, x{}, {}", rd, rs1, imm),
-            0x4 => format!("XORI x{}, x{}, {}", rd, rs1, imm),
-            0x6 => format!("ORI x{}, x{}, {}", rd, rs1, imm),
-            0x7 => format!("ANDI x{}, x{}, {}", rd, rs1, imm),
-            _ => format!("ALU x{}, x{}, {}", rd, rs1, imm),
+    /// Generate ALU instruction (ADDI, SLTI, XORI, ORI, ANDI)
+    fn generate_alu_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rd = rng.random_range(1..config.reg_count as i32) as u8;
+        let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+        let imm = rng.random_range(-2048..2048);
+        let instr = match rng.random_range(0..5) {
+            0 => Instr::Addi { rd, rs1, imm },
+            1 => Instr::Slti { rd, rs1, imm },
+            2 => Instr::Xori { rd, rs1, imm },
+            3 => Instr::Ori { rd, rs1, imm },
+            _ => Instr::Andi { rd, rs1, imm },
         };
-
-        Instruction {
-            encoding,
-            mnemonic,
-            rd: Some(rd),
-            rs1: Some(rs1),
-            rs2: None,
-            imm: Some(imm),
-        }
+        instr.into()
     }
 
-    /// Generate LOAD instruction
-    fn generate_load_instruction(rng: &mut impl RngExt) -> Instruction {
-        let rd = rng.random_range(1..32) as u8;
-        let rs1 = rng.random_range(0..32) as u8;
-        let imm = rng.random_range(-2048..2047) as i32;
-        let funct3 = 0x2; // LW
+    /// Generate M extension instruction (MUL, MULH, MULHSU, MULHU, DIV, DIVU, REM, REMU)
+    fn generate_mul_div_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rd = rng.random_range(1..config.reg_count as i32) as u8;
+        let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+        let rs2 = rng.random_range(0..config.reg_count as i32) as u8;
+        let instr = match rng.random_range(0..8) {
+            0 => Instr::Mul { rd, rs1, rs2 },
+            1 => Instr::Mulh { rd, rs1, rs2 },
+            2 => Instr::Mulhsu { rd, rs1, rs2 },
+            3 => Instr::Mulhu { rd, rs1, rs2 },
+            4 => Instr::Div { rd, rs1, rs2 },
+            5 => Instr::Divu { rd, rs1, rs2 },
+            6 => Instr::Rem { rd, rs1, rs2 },
+            _ => Instr::Remu { rd, rs1, rs2 },
+        };
+        instr.into()
+    }
 
-        let encoding = ((imm as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | ((rd as u32) << 7) | 0x03;
+    /// Generate LOAD instruction (LW only, unless sub-word accesses are enabled)
+    fn generate_load_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rd = rng.random_range(1..config.reg_count as i32) as u8;
+        let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+        let imm = rng.random_range(-2048..2047);
+        let instr = match if config.enable_sub_word_mem { rng.random_range(0..5) } else { 2 } {
+            0 => Instr::Lb { rd, rs1, imm },
+            1 => Instr::Lh { rd, rs1, imm },
+            2 => Instr::Lw { rd, rs1, imm },
+            3 => Instr::Lbu { rd, rs1, imm },
+            _ => Instr::Lhu { rd, rs1, imm },
+        };
+        instr.into()
+    }
 
-        Instruction {
-            encoding,
-            mnemonic: format!("LW x{}, {}(x{})", rd, imm, rs1),
-            rd: Some(rd),
-            rs1: Some(rs1),
-            rs2: None,
-            imm: Some(imm),
-        }
+    /// Generate STORE instruction (SW only, unless sub-word accesses are enabled)
+    fn generate_store_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+        let rs2 = rng.random_range(0..config.reg_count as i32) as u8;
+        let imm = rng.random_range(-2048..2047);
+        let instr = match if config.enable_sub_word_mem { rng.random_range(0..3) } else { 2 } {
+            0 => Instr::Sb { rs1, rs2, imm },
+            1 => Instr::Sh { rs1, rs2, imm },
+            _ => Instr::Sw { rs1, rs2, imm },
+        };
+        instr.into()
     }
 
-    /// Generate STORE instruction
-    fn generate_store_instruction(rng: &mut impl RngExt) -> Instruction {
-        let rs1 = rng.random_range(0..32) as u8;
-        let rs2 = rng.random_range(0..32) as u8;
-        let imm = rng.random_range(-2048..2047) as i32;
-        let funct3 = 0x2; // SW
+    /// Generate BRANCH instruction
+    fn generate_branch_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let rs1 = rng.random_range(0..config.reg_count as i32) as u8;
+        let rs2 = rng.random_range(0..config.reg_count as i32) as u8;
+        let imm = rng.random_range(-128..128) * 4;
+        let instr = match rng.random_range(0..6) {
+            0 => Instr::Beq { rs1, rs2, imm },
+            1 => Instr::Bne { rs1, rs2, imm },
+            2 => Instr::Blt { rs1, rs2, imm },
+            3 => Instr::Bge { rs1, rs2, imm },
+            4 => Instr::Bltu { rs1, rs2, imm },
+            _ => Instr::Bgeu { rs1, rs2, imm },
+        };
+        instr.into()
+    }
 
-        let imm_11_5 = ((imm >> 5) & 0x7F) as u32;
-        let imm_4_0 = (imm & 0x1F) as u32;
-        let encoding = (imm_11_5 << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | (imm_4_0 << 7) | 0x23;
+    /// Generate one of the registered custom instructions with its own strategy
+    fn generate_custom_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> Instruction {
+        let custom = config.custom.0[rng.random_range(0..config.custom.0.len())];
+        let encoding = custom.generate(rng, config.reg_count);
+        let [rd, rs1, rs2] = custom.register_fields(encoding);
 
         Instruction {
             encoding,
-            mnemonic: format!("SW x{}, {}(x{})", rs2, imm, rs1),
-            rd: None,
-            rs1: Some(rs1),
-            rs2: Some(rs2),
-            imm: Some(imm),
+            mnemonic: mnemonic(encoding, config.custom),
+            rd: rd.then(|| rd_field(encoding)),
+            rs1: rs1.then(|| rs1_field(encoding)),
+            rs2: rs2.then(|| rs2_field(encoding)),
+            imm: None,
         }
     }
 
-    /// Generate BRANCH instruction
-    fn generate_branch_instruction(rng: &mut impl RngExt) -> Instruction {
-        let rs1 = rng.random_range(0..32) as u8;
-        let rs2 = rng.random_range(0..32) as u8;
-        let offset = (rng.random_range(-128..128) as i32) * 4;
-        let funct3_set = [0x0, 0x1, 0x4, 0x5, 0x6, 0x7]; // BEQ, BNE, BLT, BGE, BLTU, BGEU
-        let funct3 = funct3_set[rng.random_range(0..funct3_set.len())];
-
-        let imm_12 = ((offset >> 12) & 0x1) as u32;
-        let imm_10_5 = ((offset >> 5) & 0x3F) as u32;
-        let imm_4_1 = ((offset >> 1) & 0xF) as u32;
-        let imm_11 = ((offset >> 11) & 0x1) as u32;
-
-        let encoding = (imm_12 << 31) | (imm_10_5 << 25) | ((rs2 as u32) << 20) | ((rs1 as u32) << 15) | (funct3 << 12) | (imm_4_1 << 8) | (imm_11 << 7) | 0x63;
-
-        let mnemonic = match funct3 {
-            0x0 => format!("BEQ x{}, x{}, {}", rs1, rs2, offset),
-            0x1 => format!("BNE x{}, x{}, {}", rs1, rs2, offset),
-            0x4 => format!("BLT x{}, x{}, {}", rs1, rs2, offset),
-            0x5 => format!("BGE x{}, x{}, {}", rs1, rs2, offset),
-            0x6 => format!("BLTU x{}, x{}, {}", rs1, rs2, offset),
-            0x7 => format!("BGEU x{}, x{}, {}", rs1, rs2, offset),
-            _ => format!("BRANCH x{}, x{}, {}", rs1, rs2, offset),
-        };
-
-        Instruction {
-            encoding,
-            mnemonic,
-            rd: None,
-            rs1: Some(rs1),
-            rs2: Some(rs2),
-            imm: Some(offset),
+    /// Generate an RV32C instruction from the enabled categories, together with
+    /// the 32-bit instruction it expands to (from `CInstr::expand`, independently
+    /// of the decoder in `models::arch::lx32_rvc_pkg`)
+    pub fn generate_compressed_instruction(config: &ProgramConfig, rng: &mut impl RngExt) -> (Instruction, u32) {
+        // Full 5-bit register fields, and the x8-x15 ones of the 3-bit formats
+        let rd = rng.random_range(1..config.reg_count as reg_idx_t);
+        let rs2 = rng.random_range(1..config.reg_count as reg_idx_t);
+        let rd_p = rng.random_range(8..16);
+        let rs2_p = rng.random_range(8..16);
+        let imm6 = rng.random_range(-32..32);
+        let nzimm6 = if imm6 == 0 { 1 } else { imm6 };
+        let shamt = rng.random_range(0..32);
+
+        let mut forms = Vec::new();
+        if config.enable_alu {
+            forms.extend([
+                CInstr::Addi { rd, imm: imm6 },
+                CInstr::Li { rd, imm: imm6 },
+                // rd = x2 selects C.ADDI16SP
+                CInstr::Lui { rd: if rd == 2 { 3 } else { rd }, imm: nzimm6 << 12 },
+                CInstr::Addi16sp { imm: nzimm6 * 16 },
+                CInstr::Addi4spn { rd: rd_p, imm: rng.random_range(1..256) * 4 },
+                CInstr::Srli { rd: rd_p, shamt },
+                CInstr::Srai { rd: rd_p, shamt },
+                CInstr::Andi { rd: rd_p, imm: imm6 },
+                CInstr::Sub { rd: rd_p, rs2: rs2_p },
+                CInstr::Xor { rd: rd_p, rs2: rs2_p },
+                CInstr::Or { rd: rd_p, rs2: rs2_p },
+                CInstr::And { rd: rd_p, rs2: rs2_p },
+                CInstr::Slli { rd, shamt },
+                CInstr::Mv { rd, rs2 },
+                CInstr::Add { rd, rs2 },
+            ]);
+        }
+        if config.enable_loads {
+            forms.extend([
+                CInstr::Lw { rd: rs2_p, rs1: rd_p, imm: rng.random_range(0..32) * 4 },
+                CInstr::Lwsp { rd, imm: rng.random_range(0..64) * 4 },
+            ]);
+        }
+        if config.enable_stores {
+            forms.extend([
+                CInstr::Sw { rs1: rd_p, rs2: rs2_p, imm: rng.random_range(0..32) * 4 },
+                CInstr::Swsp { rs2, imm: rng.random_range(0..64) * 4 },
+            ]);
+        }
+        if config.enable_branches {
+            let imm = rng.random_range(-128..128) * 2;
+            forms.extend([CInstr::Beqz { rs1: rd_p, imm }, CInstr::Bnez { rs1: rd_p, imm }]);
         }
+        if config.enable_jumps {
+            let imm = rng.random_range(-1024..1024) * 2;
+            forms.extend([CInstr::J { imm }, CInstr::Jal { imm }, CInstr::Jr { rs1: rd }, CInstr::Jalr { rs1: rd }]);
+        }
+        if forms.is_empty() {
+            forms.push(CInstr::Addi { rd, imm: imm6 }); // fallback
+        }
+        let form = forms[rng.random_range(0..forms.len())];
+        (form.into(), form.expand().encode())
+    }
+
+    /// Little-endian memory image of the program, each instruction taking
+    /// its own size (compressed ones 2 bytes)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.instructions
+            .iter()
+            .flat_map(|instr| instr.encoding.to_le_bytes().into_iter().take(instr.instr_len() as usize))
+            .collect()
     }
 
     /// Try to shrink the program to minimal failing case
@@ -315,14 +414,20 @@ impl Program {
         }
     }
 
-    /// Display program in readable format
+    /// Display program in readable format, disassembled at the address each
+    /// instruction is loaded to
     pub fn display(&self) -> String {
+        let disassembler = Disassembler::new(IsaConfig {
+            custom: self.config.custom,
+            rv32e: self.config.reg_count < REG_COUNT,
+            ..IsaConfig::full()
+        });
         let mut output = format!("Program ({} instructions):\n", self.instructions.len());
+        let mut pc = 0;
         for (idx, instr) in self.instructions.iter().enumerate() {
-            output.push_str(&format!("{:4}: {:08x}  {}\n", idx, instr.encoding, instr.mnemonic));
+            output.push_str(&format!("{:4}: {:08x}  {}\n", idx, instr.encoding, disassembler.disasm(instr.encoding, pc)));
+            pc += instr.instr_len();
         }
         output
     }
 }
-
-
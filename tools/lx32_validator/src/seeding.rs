@@ -0,0 +1,30 @@
+// src/seeding.rs
+//
+// LX32 Seed Derivation - Splits the global --seed into per-suite seeds
+//
+// Every fuzzer suite draws from its own deterministic stream, derived from the
+// global seed and the suite name. Adding, removing or reordering suites never
+// changes the sequence another suite sees, so a single failing suite can be
+// replayed on its own.
+//
+// Author: LX32 Validation Team
+// License: MIT
+
+/// Derive the seed for `suite` from the global run seed.
+pub fn suite_seed(global_seed: u64, suite: &str) -> u64 {
+    // FNV-1a over the suite name
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in suite.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    splitmix64(global_seed ^ hash)
+}
+
+/// SplitMix64 finalizer: spreads nearby global seeds across the whole range.
+fn splitmix64(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -6,14 +6,16 @@
 // 1. Remove unnecessary instructions
 // 2. Simplify immediate values
 // 3. Reduce register usage
-// 4. Find the minimal program that reproduces the bug
+// 4. Widen byte/halfword loads and stores to word accesses
+// 5. Find the minimal program that reproduces the bug
 //
 // This is inspired by property-based testing frameworks like QuickCheck and modern fuzzers
 //
 // Author: LX32 Validation Team
 // License: MIT
 
-use crate::program_generator::{Instruction, Program};
+use crate::models::arch::lx32_instr_pkg::Instr;
+use crate::program_generator::Program;
 
 /// Shrinking strategy configuration
 pub struct ShrinkConfig {
@@ -95,6 +97,17 @@ impl Shrinker {
                     continue;
                 }
             }
+
+            // Strategy 4: Widen sub-word memory accesses
+            if self.config.aggressive {
+                if let Some(widened) = self.try_widen_memory_accesses(&current, &test_fn) {
+                    println!("  [Shrink] Widened sub-word memory access");
+                    current = widened;
+                    improved = true;
+                    iterations += 1;
+                    continue;
+                }
+            }
         }
 
         let shrunk_size = current.instructions.len();
@@ -159,77 +172,60 @@ impl Shrinker {
         None
     }
 
-    /// Try to simplify immediate values (reduce to 0, 1, or powers of 2)
+    /// Try to simplify immediate values (reduce to 0, 1, or powers of 2),
+    /// skipping values the instruction's format cannot encode
     fn try_simplify_immediates<F>(&self, program: &Program, test_fn: F) -> Option<Program>
     where
         F: Fn(&Program) -> bool,
     {
         for i in 0..program.instructions.len() {
-            if let Some(imm) = program.instructions[i].imm {
-                if imm == 0 || imm == 1 {
-                    continue; // Already simplified
-                }
-
-                // Try simplifying to 0
-                let mut candidate = program.clone();
-                candidate.instructions[i] = self.simplify_instruction_imm(&candidate.instructions[i], 0);
-                if test_fn(&candidate) {
-                    return Some(candidate);
-                }
+            // Compressed and custom instructions are left alone
+            let Some(instr) = Instr::decode(program.instructions[i].encoding) else {
+                continue;
+            };
+            let Some(imm) = instr.imm() else {
+                continue;
+            };
+            if imm == 0 || imm == 1 {
+                continue; // Already simplified
+            }
 
-                // Try simplifying to 1
+            let power_of_2 = (imm > 1).then(|| 1 << imm.ilog2());
+            for simplified in [Some(0), Some(1), power_of_2].into_iter().flatten() {
+                let Some(simpler) = instr.with_imm(simplified).filter(|simpler| *simpler != instr) else {
+                    continue;
+                };
                 let mut candidate = program.clone();
-                candidate.instructions[i] = self.simplify_instruction_imm(&candidate.instructions[i], 1);
+                candidate.instructions[i] = simpler.into();
                 if test_fn(&candidate) {
                     return Some(candidate);
                 }
-
-                // Try power of 2
-                if imm > 1 {
-                    let simplified = 1 << (imm.abs().ilog2());
-                    let mut candidate = program.clone();
-                    candidate.instructions[i] = self.simplify_instruction_imm(&candidate.instructions[i], simplified as i32);
-                    if test_fn(&candidate) {
-                        return Some(candidate);
-                    }
-                }
             }
         }
         None
     }
 
-    /// Create a new instruction with simplified immediate
-    fn simplify_instruction_imm(&self, instr: &Instruction, new_imm: i32) -> Instruction {
-        let mut simplified = instr.clone();
-        simplified.imm = Some(new_imm);
-
-        // Reconstruct encoding based on instruction type
-        // This is a simplified version - you'd need to handle all instruction formats
-        let opcode = instr.encoding & 0x7F;
+    /// Try to turn LB/LH/LBU/LHU/SB/SH into LW/SW, one at a time
+    fn try_widen_memory_accesses<F>(&self, program: &Program, test_fn: F) -> Option<Program>
+    where
+        F: Fn(&Program) -> bool,
+    {
+        for i in 0..program.instructions.len() {
+            let widened = match Instr::decode(program.instructions[i].encoding) {
+                Some(Instr::Lb { rd, rs1, imm } | Instr::Lh { rd, rs1, imm } | Instr::Lbu { rd, rs1, imm } | Instr::Lhu { rd, rs1, imm }) => {
+                    Instr::Lw { rd, rs1, imm }
+                }
+                Some(Instr::Sb { rs1, rs2, imm } | Instr::Sh { rs1, rs2, imm }) => Instr::Sw { rs1, rs2, imm },
+                _ => continue,
+            };
 
-        simplified.encoding = match opcode {
-            0x13 | 0x03 => {
-                // I-type (ALU-I, LOAD)
-                ((new_imm as u32) << 20) | (instr.encoding & 0x000FFFFF)
-            }
-            0x23 => {
-                // S-type (STORE)
-                let imm_11_5 = ((new_imm >> 5) & 0x7F) as u32;
-                let imm_4_0 = (new_imm & 0x1F) as u32;
-                (imm_11_5 << 25) | (instr.encoding & 0x01FFFFFF) | (imm_4_0 << 7)
-            }
-            0x63 => {
-                // B-type (BRANCH)
-                let imm_12 = ((new_imm >> 12) & 0x1) as u32;
-                let imm_10_5 = ((new_imm >> 5) & 0x3F) as u32;
-                let imm_4_1 = ((new_imm >> 1) & 0xF) as u32;
-                let imm_11 = ((new_imm >> 11) & 0x1) as u32;
-                (imm_12 << 31) | (imm_10_5 << 25) | (instr.encoding & 0x01FFF07F) | (imm_4_1 << 8) | (imm_11 << 7)
+            let mut candidate = program.clone();
+            candidate.instructions[i] = widened.into();
+            if test_fn(&candidate) {
+                return Some(candidate);
             }
-            _ => instr.encoding,
-        };
-
-        simplified
+        }
+        None
     }
 }
 
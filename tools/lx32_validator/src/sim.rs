@@ -0,0 +1,228 @@
+// ============================================================
+// LX32 Instruction-Set Simulator
+// ============================================================
+// Runs complete programs (flat binaries or linked ELF files)
+// on the golden model alone, with the memory map the RTL test
+// harness uses: RAM from address 0 and the two MMIO exit
+// ports of the bare-metal runtime.
+//
+// Used by the lx32-sim binary, which needs no Verilator build,
+// and by run_program to keep a golden model next to the RTL.
+//
+// Design Principles:
+//   - The model drives its own fetches, loads and stores
+//     through `SimBus`.
+//   - A store to an exit port ends the run with the stored
+//     value as the program's exit code; it never reaches RAM.
+//   - Loading fails loudly: ELF files that are not linked
+//     RV32 executables, and segments outside RAM, are errors.
+// ============================================================
+
+use crate::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use crate::models::arch::lx32_mem_pkg::mem_size_e;
+use crate::models::core::bus::Bus;
+use crate::models::core::isa_config::IsaConfig;
+use crate::models::core::lx32_system::{Lx32System, StepResult};
+
+/// MMIO exit ports: 0x8000_0000 is the legacy port, 0xFFFF_F004 the one crt0.S uses.
+pub const EXIT_PORTS: [addr_t; 2] = [0x8000_0000, 0xFFFF_F004];
+
+/// RAM size of the simulated system, as in run_program.
+pub const SIM_MEMORY_SIZE: usize = 64 * 1024;
+
+// ELF32 constants (little-endian RV32 executables only)
+const ELF_MAGIC: &[u8; 4] = b"\x7fELF";
+const ELFCLASS32: u8 = 1;
+const ELFDATA2LSB: u8 = 1;
+const ET_REL: u16 = 1;
+const ET_EXEC: u16 = 2;
+const PT_LOAD: u32 = 1;
+
+/// Bytes placed at one address; zero-filled parts (.bss) are included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub addr: addr_t,
+    pub data: Vec<u8>,
+}
+
+/// Program to run: what goes where, and where execution starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub entry: addr_t,
+    pub segments: Vec<Segment>,
+}
+
+impl Image {
+    /// ELF executable when `bytes` starts with the ELF magic, otherwise a
+    /// flat binary loaded and entered at address 0.
+    pub fn parse(bytes: &[u8]) -> Result<Image, String> {
+        if bytes.starts_with(ELF_MAGIC) { Self::from_elf(bytes) } else { Ok(Self::flat(bytes, 0)) }
+    }
+
+    /// Raw image placed and entered at `base`.
+    pub fn flat(bytes: &[u8], base: addr_t) -> Image {
+        Image {
+            entry: base,
+            segments: vec![Segment {
+                addr: base,
+                data: bytes.to_vec(),
+            }],
+        }
+    }
+
+    /// PT_LOAD segments of a linked ELF32 executable, at their physical
+    /// addresses, and its entry point.
+    pub fn from_elf(bytes: &[u8]) -> Result<Image, String> {
+        let header = bytes.get(..52).ok_or("truncated ELF header")?;
+        if header[4] != ELFCLASS32 || header[5] != ELFDATA2LSB {
+            return Err("not a little-endian ELF32 file".to_string());
+        }
+        match u16_at(header, 16) {
+            ET_EXEC => {}
+            ET_REL => return Err("relocatable object: link it (ld.lld -T link.ld) before running it".to_string()),
+            other => return Err(format!("unsupported ELF type {}", other)),
+        }
+
+        let entry = u32_at(header, 24);
+        let (phoff, phentsize, phnum) = (u32_at(header, 28) as usize, u16_at(header, 42) as usize, u16_at(header, 44) as usize);
+        let mut segments = Vec::new();
+        for index in 0..phnum {
+            let start = phoff + index * phentsize;
+            let ph = bytes.get(start..start + 32).ok_or("truncated program header table")?;
+            if u32_at(ph, 0) != PT_LOAD {
+                continue;
+            }
+            let (offset, paddr, filesz, memsz) = (u32_at(ph, 4) as usize, u32_at(ph, 12), u32_at(ph, 16) as usize, u32_at(ph, 20) as usize);
+            if memsz < filesz {
+                return Err(format!("segment {} is smaller in memory than in the file", index));
+            }
+            let mut data = bytes.get(offset..offset + filesz).ok_or_else(|| format!("segment {} extends past the end of the file", index))?.to_vec();
+            data.resize(memsz, 0);
+            segments.push(Segment { addr: paddr, data });
+        }
+        if segments.is_empty() {
+            return Err("no loadable segments".to_string());
+        }
+        Ok(Image { entry, segments })
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+/// RAM from address 0 plus the MMIO exit ports.
+pub struct SimBus {
+    pub ram: Vec<u8>,
+    /// Value stored to an exit port, once the program has exited
+    pub exit_code: Option<data_t>,
+}
+
+impl SimBus {
+    /// `size` bytes of zeroed RAM.
+    pub fn new(size: usize) -> Self {
+        Self {
+            ram: vec![0; size],
+            exit_code: None,
+        }
+    }
+
+    /// Copies every segment of `image` into RAM.
+    pub fn load_image(&mut self, image: &Image) -> Result<(), String> {
+        let size = self.ram.len();
+        for segment in &image.segments {
+            let (start, end) = (segment.addr as usize, segment.addr as usize + segment.data.len());
+            let dest = self.ram.get_mut(start..end).ok_or_else(|| format!("segment 0x{:08x}..0x{:08x} does not fit in {} bytes of RAM", start, end, size))?;
+            dest.copy_from_slice(&segment.data);
+        }
+        Ok(())
+    }
+
+    /// True when a store to `addr` reaches RAM or an exit port.
+    pub fn maps(&self, addr: addr_t) -> bool {
+        EXIT_PORTS.contains(&addr) || (addr as usize) < self.ram.len()
+    }
+}
+
+impl Bus for SimBus {
+    fn fetch(&mut self, addr: addr_t) -> instr_t {
+        self.ram.as_mut_slice().fetch(addr)
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        self.ram.as_mut_slice().load(addr, size)
+    }
+
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e) {
+        if EXIT_PORTS.contains(&addr) {
+            self.exit_code.get_or_insert(data & size.mask());
+        } else {
+            self.ram.as_mut_slice().store(addr, data, size);
+        }
+    }
+}
+
+/// Why a run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The program stored this exit code to an exit port
+    Exit(data_t),
+    /// The cycle limit was reached first
+    MaxCycles,
+    /// The PC left RAM
+    PcOutOfBounds(addr_t),
+}
+
+/// Golden model running a program from its own RAM.
+pub struct Simulator {
+    pub core: Lx32System,
+    pub bus: SimBus,
+    pub cycles: u64,
+}
+
+impl Simulator {
+    /// Model implementing `config` with `image` loaded into `memory_size`
+    /// bytes of RAM and the PC at the image's entry point.
+    pub fn new(config: IsaConfig, image: &Image, memory_size: usize) -> Result<Self, String> {
+        let mut bus = SimBus::new(memory_size);
+        bus.load_image(image)?;
+        let mut core = Lx32System::with_config(config);
+        core.pc = image.entry;
+        Ok(Self { core, bus, cycles: 0 })
+    }
+
+    /// Runs one cycle, without checking the PC or the exit ports.
+    pub fn step(&mut self) -> StepResult {
+        let res = self.core.step_with_bus(&mut self.bus);
+        self.cycles += 1;
+        res
+    }
+
+    /// Runs until the program exits, the PC leaves RAM or `max_cycles`
+    /// cycles have run in total, calling `on_step` after every cycle.
+    pub fn run(&mut self, max_cycles: u64, mut on_step: impl FnMut(u64, &StepResult)) -> Stop {
+        loop {
+            if self.core.pc as usize >= self.bus.ram.len() {
+                return Stop::PcOutOfBounds(self.core.pc);
+            }
+            let res = self.step();
+            on_step(self.cycles - 1, &res);
+            if let Some(code) = self.bus.exit_code {
+                return Stop::Exit(code);
+            }
+            if self.cycles >= max_cycles {
+                return Stop::MaxCycles;
+            }
+        }
+    }
+}
+
+/// Process exit status for a program exit code: 0-255 as is, anything
+/// larger (crt0_trap.S reports a trap as 0x100 + mcause) as 255.
+pub fn exit_status(code: data_t) -> u8 {
+    u8::try_from(code).unwrap_or(u8::MAX)
+}
@@ -1,10 +1,29 @@
 // tests/common/mod.rs
-pub use lx32_validator::models::core::lx32_system::Lx32System;
-pub use lx32_validator::{create_core, get_pc, get_reg, tick_core};
+pub use lx32_validator::models::core::lx32_system::{Lx32System, StepResult};
+pub use lx32_validator::models::core::memory_sim::MemorySim;
+pub use lx32_validator::models::disasm::disasm;
+pub use lx32_validator::{
+    create_core, eval_core, get_mem_addr, get_mem_wdata, get_mem_we, get_pc, get_reg, tick_core,
+};
+
+use std::fmt;
+
+/// Fixed seed for the `#[test]` entry points so `cargo test` is reproducible.
+pub const DEFAULT_TEST_SEED: u64 = 0x4C58_3332;
+
+/// Prints what is needed to replay a failing fuzzer iteration.
+pub fn print_replay(suite: &str, seed: u64, iteration: u32) {
+    println!(
+        "Replay: suite '{}' with seed {} fails at iteration {} (run_*_fuzzer(params, {}))",
+        suite, seed, iteration, seed
+    );
+}
 
 pub struct TestBench {
     pub rtl: *mut std::ffi::c_void,
     pub gold: Lx32System,
+    /// Golden `StepResult` of the last lockstep cycle
+    pub last_step: StepResult,
     current_instr: u32,
     current_mem_rdata: u32,
 }
@@ -20,11 +39,97 @@ impl TestBench {
         Self {
             rtl,
             gold,
+            last_step: StepResult::default(),
             current_instr: 0,
             current_mem_rdata: 0,
         }
     }
 
+    /// Runs one cycle on both models and captures their full architectural state.
+    ///
+    /// The RTL memory port is sampled after a combinational eval and before the
+    /// clock edge, while `instr` still drives the datapath. Sampling after the
+    /// edge would see the updated register file instead.
+    pub fn step_lockstep(&mut self, instr: u32, mem_rdata: u32) -> (CoreState, CoreState) {
+        let rtl_state = self.step_rtl(instr, mem_rdata);
+
+        let step = self.gold.step(instr, mem_rdata, false);
+        let gold_state = CoreState::from_gold(&self.gold, &step);
+        self.last_step = step;
+
+        (rtl_state, gold_state)
+    }
+
+    /// Runs one cycle on the RTL only and captures its state, with the memory
+    /// port sampled before the clock edge as in `step_lockstep`.
+    pub fn step_rtl(&mut self, instr: u32, mem_rdata: u32) -> CoreState {
+        self.current_instr = instr;
+        self.current_mem_rdata = mem_rdata;
+
+        let (mem_addr, mem_wdata, mem_we) = unsafe {
+            eval_core(self.rtl, 0, instr, mem_rdata);
+            (
+                get_mem_addr(self.rtl),
+                get_mem_wdata(self.rtl),
+                get_mem_we(self.rtl) != 0,
+            )
+        };
+        unsafe { tick_core(self.rtl, 0, instr, mem_rdata) };
+
+        CoreState {
+            pc: unsafe { get_pc(self.rtl) },
+            regs: std::array::from_fn(|i| unsafe { get_reg(self.rtl, i as u8) }),
+            mem_addr,
+            mem_wdata,
+            mem_we,
+            mem_be: rtl_mem_be(mem_we),
+        }
+    }
+
+    /// Runs one cycle with the instruction fetched at each model's own PC from `mem`.
+    ///
+    /// The RTL side is fed by hand: its load address is taken from a
+    /// combinational eval and the load data read from `mem`. The golden model
+    /// runs on `mem` itself through `step_with_bus` and commits its own store,
+    /// after the RTL has read the pre-cycle image. A store the RTL disagrees on
+    /// shows up in the diff, so the image stays shared until the first
+    /// divergence. Returns the word the RTL fetched.
+    pub fn step_from_memory(&mut self, mem: &mut MemorySim) -> (u32, CoreState, CoreState) {
+        // --- RTL side ---
+        let rtl_instr = mem.read_instr(unsafe { get_pc(self.rtl) });
+        let rtl_rdata = mem.read_data(unsafe {
+            eval_core(self.rtl, 0, rtl_instr, 0);
+            get_mem_addr(self.rtl)
+        });
+        let (mem_addr, mem_wdata, mem_we) = unsafe {
+            eval_core(self.rtl, 0, rtl_instr, rtl_rdata);
+            (
+                get_mem_addr(self.rtl),
+                get_mem_wdata(self.rtl),
+                get_mem_we(self.rtl) != 0,
+            )
+        };
+        unsafe { tick_core(self.rtl, 0, rtl_instr, rtl_rdata) };
+
+        // --- Golden side ---
+        let step = self.gold.step_with_bus(mem);
+
+        let rtl_state = CoreState {
+            pc: unsafe { get_pc(self.rtl) },
+            regs: std::array::from_fn(|i| unsafe { get_reg(self.rtl, i as u8) }),
+            mem_addr,
+            mem_wdata,
+            mem_we,
+            mem_be: rtl_mem_be(mem_we),
+        };
+        let gold_state = CoreState::from_gold(&self.gold, &step);
+        self.last_step = step;
+
+        self.current_instr = rtl_instr;
+        self.current_mem_rdata = rtl_rdata;
+        (rtl_instr, rtl_state, gold_state)
+    }
+
     // Helper to print a clean debug line for any module
     pub fn log_step(&self, iter: u32, instr: u32, rd: u32, rtl_val: u32, gold_val: u32) {
         let rtl_pc = unsafe { get_pc(self.rtl) };
@@ -37,12 +142,142 @@ impl TestBench {
         };
 
         println!(
-            "[{:>5}] Instr: 0x{:08x} | PC: [R:0x{:04x} G:0x{:04x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | {}",
-            iter, instr, rtl_pc, gold_pc, rd, rtl_val, gold_val, status
+            "[{:>5}] Instr: 0x{:08x} {:<24} | PC: [R:0x{:04x} G:0x{:04x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | {}",
+            iter, instr, disasm(instr, self.last_step.pc), rtl_pc, gold_pc, rd, rtl_val, gold_val, status
         );
     }
 }
 
+/// Prints what the golden model decoded and did in `step`, for mismatch reports.
+pub fn print_gold_step(step: &StepResult) {
+    println!(
+        "  Golden step: pc=0x{:08x} instr=0x{:08x} ({}) next_pc=0x{:08x} status={:?}",
+        step.pc,
+        step.instr,
+        disasm(step.instr, step.pc),
+        step.next_pc,
+        step.status
+    );
+    if let Some(parcel) = step.compressed {
+        println!("    compressed: 0x{:04x}", parcel);
+    }
+    println!("    ctrl: {:?}", step.ctrl);
+    println!(
+        "    branch_taken={} rd_write={:?} mem_access={:?} csr_write={:?}",
+        step.branch_taken, step.rd_write, step.mem_access, step.csr_write
+    );
+    if step.frd_write.is_some() || step.fflags != 0 {
+        println!("    frd_write={:?} fflags=0b{:05b}", step.frd_write, step.fflags);
+    }
+}
+
+// ------------------------------------------------------------
+// Lockstep state comparison
+// ------------------------------------------------------------
+
+/// Byte-enables implied by the RTL data port, which only performs word writes.
+pub fn rtl_mem_be(mem_we: bool) -> u8 {
+    if mem_we { 0xF } else { 0x0 }
+}
+
+/// Architectural state observed after one cycle: PC, the full register
+/// file and the data memory port driven during that cycle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreState {
+    pub pc: u32,
+    pub regs: [u32; 32],
+    pub mem_addr: u32,
+    pub mem_wdata: u32,
+    pub mem_we: bool,
+    pub mem_be: u8,
+}
+
+impl CoreState {
+    /// Builds the golden-side state from the model and the `StepResult` of its last step.
+    pub fn from_gold(gold: &Lx32System, step: &StepResult) -> Self {
+        Self {
+            pc: gold.pc,
+            regs: std::array::from_fn(|i| gold.reg_file.get_reg(i)),
+            mem_addr: step.lsu.mem_addr,
+            mem_wdata: step.lsu.mem_wdata,
+            mem_we: step.lsu.mem_we,
+            mem_be: step.lsu.mem_be,
+        }
+    }
+}
+
+/// One state element that can diverge between RTL and golden model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateField {
+    Pc,
+    Reg(u8),
+    MemAddr,
+    MemWdata,
+    MemWe,
+    MemBe,
+}
+
+impl fmt::Display for StateField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateField::Pc => write!(f, "pc"),
+            StateField::Reg(idx) => write!(f, "x{}", idx),
+            StateField::MemAddr => write!(f, "mem_addr"),
+            StateField::MemWdata => write!(f, "mem_wdata"),
+            StateField::MemWe => write!(f, "mem_we"),
+            StateField::MemBe => write!(f, "mem_be"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub field: StateField,
+    pub rtl: u32,
+    pub gold: u32,
+}
+
+/// Every field that differs between an RTL and a golden `CoreState`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    pub fields: Vec<FieldDiff>,
+}
+
+impl StateDiff {
+    pub fn between(rtl: &CoreState, gold: &CoreState) -> Self {
+        let mut fields = Vec::new();
+        let mut check = |field: StateField, rtl: u32, gold: u32| {
+            if rtl != gold {
+                fields.push(FieldDiff { field, rtl, gold });
+            }
+        };
+
+        check(StateField::Pc, rtl.pc, gold.pc);
+        for i in 0..32 {
+            check(StateField::Reg(i as u8), rtl.regs[i], gold.regs[i]);
+        }
+        check(StateField::MemAddr, rtl.mem_addr, gold.mem_addr);
+        check(StateField::MemWdata, rtl.mem_wdata, gold.mem_wdata);
+        check(StateField::MemWe, rtl.mem_we as u32, gold.mem_we as u32);
+        check(StateField::MemBe, rtl.mem_be as u32, gold.mem_be as u32);
+
+        Self { fields }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for d in &self.fields {
+            writeln!(f, "  {:<9} RTL=0x{:08x} GOLD=0x{:08x}", d.field.to_string(), d.rtl, d.gold)?;
+        }
+        Ok(())
+    }
+}
+
 // Helper functions for long program tests
 pub unsafe fn reset(core: *mut std::ffi::c_void) {
     // Hold reset for a few cycles
@@ -61,4 +296,3 @@ pub unsafe fn posedge_clk(core: *mut std::ffi::c_void) {
     // Clock cycle with current instruction
     tick_core(core, 0, 0, 0);
 }
-
@@ -0,0 +1,333 @@
+// tests/test_asm.rs
+//
+// Assembler validation.
+//
+// Random words over every major opcode are disassembled and assembled back:
+// the word must come out unchanged (legal words through their mnemonic,
+// illegal ones through `.word`). FENCE drops its unused fields when printed,
+// so it only has to reproduce the same text. Directed tests cover crt0.S,
+// pseudo-instructions, %hi/%lo, directives and the ELF writer.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::asm::assemble;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::disasm::Disassembler;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "asm";
+
+pub struct AsmTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for AsmTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 20000,
+            enable_logging: true,
+        }
+    }
+}
+
+/// Every 32-bit major opcode the golden model decodes.
+const OPCODES: [u32; 19] = [
+    0x37, 0x17, 0x6F, 0x67, 0x63, 0x03, 0x23, 0x13, 0x33, 0x0F, 0x2F, 0x07, 0x27, 0x53, 0x43, 0x47, 0x4B, 0x4F, 0x73,
+];
+
+const MISC_MEM: u32 = 0x0F;
+
+fn random_word(rng: &mut StdRng) -> u32 {
+    let word: u32 = rng.random();
+    match rng.random_range(0..3) {
+        0 => word,
+        // Known opcode with mostly-zero funct7, so OP/OP-IMM/OP-FP forms show up
+        1 => (word & !0x7F & !(0x7C << 25)) | OPCODES[rng.random_range(0..OPCODES.len())],
+        _ => (word & !0x7F) | OPCODES[rng.random_range(0..OPCODES.len())],
+    }
+}
+
+/// The single word `text` assembles to at address 0.
+fn assemble_word(text: &str) -> Result<u32, String> {
+    let image = assemble(text).and_then(|obj| obj.to_flat(0)).map_err(|err| err.to_string())?;
+    match image[..] {
+        [b0, b1, b2, b3] => Ok(u32::from_le_bytes([b0, b1, b2, b3])),
+        _ => Err(format!("{} bytes instead of one word", image.len())),
+    }
+}
+
+pub fn run_asm_fuzzer(params: AsmTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING ASSEMBLER FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    // The assembler never emits compressed parcels: print every word whole
+    let numeric = Disassembler::new(IsaConfig { c_ext: false, ..IsaConfig::full() });
+    let abi = numeric.with_abi_names();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut instr_count = 0;
+
+    for i in 0..params.iterations {
+        let instr = random_word(&mut rng);
+        // Targets print as absolute addresses; at pc 0 they equal the offset
+        let text = if rng.random_bool(0.5) { numeric.disasm(instr, 0) } else { abi.disasm(instr, 0) };
+        let assembled = assemble_word(&text);
+        instr_count += !text.starts_with(".word") as u32;
+
+        if params.enable_logging {
+            println!("[{:>5}] 0x{:08x} | {:<36} | {:x?}", i, instr, text, assembled);
+        }
+
+        let matches = match &assembled {
+            Ok(word) if instr & 0x7F == MISC_MEM => numeric.disasm(*word, 0) == numeric.disasm(instr, 0),
+            Ok(word) => *word == instr,
+            Err(_) => false,
+        };
+
+        if !matches {
+            println!("\n{:=^100}", " ASSEMBLER MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x}", instr);
+            println!("Text:        {}", text);
+            println!("Assembled:   {:x?}", assembled);
+            print_replay(SUITE, seed, i);
+            panic!("Assembler test failed at iteration {}", i);
+        }
+    }
+
+    println!("Assembler fuzzer passed ({} instructions, {} data words)", instr_count, params.iterations - instr_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::asm::{Object, SymbolDef, SymbolKind};
+
+    const CRT0: &str = include_str!("../../lx32_backend/tests/baremetal/crt0.S");
+    const CRT0_TRAP: &str = include_str!("../../lx32_backend/tests/baremetal/crt0_trap.S");
+
+    fn words(image: &[u8]) -> Vec<u32> {
+        image.chunks(4).map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())).collect()
+    }
+
+    fn flat(source: &str) -> Vec<u32> {
+        words(&assemble(source).unwrap().to_flat(0).unwrap())
+    }
+
+    fn error(source: &str) -> String {
+        match assemble(source).and_then(|obj| obj.to_flat(0)) {
+            Ok(image) => panic!("`{}` assembled to {:x?}", source, words(&image)),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_asm_default() {
+        run_asm_fuzzer(
+            AsmTestParams {
+                enable_logging: false,
+                ..AsmTestParams::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
+    }
+
+    /// crt0.S as checked in: 5 words, main reached at 0x14.
+    #[test]
+    fn test_crt0() {
+        let obj = assemble(CRT0).unwrap();
+        assert_eq!(words(&obj.to_flat(0).unwrap()), [0x0000_1137, 0x0100_00EF, 0xFFFF_F2B7, 0x00A2_A223, 0x0000_006F]);
+
+        let start = obj.symbol("_start").unwrap();
+        assert!(start.global);
+        assert_eq!((start.kind, start.size), (SymbolKind::Func, 0x14));
+        assert_eq!(obj.sections[0].name, ".text.startup");
+        assert!(obj.to_elf().is_ok());
+    }
+
+    /// crt0_trap.S as checked in: 17 words, main reached at 0x44.
+    #[test]
+    fn test_crt0_trap() {
+        let obj = assemble(CRT0_TRAP).unwrap();
+        let expected = [
+            0x0000_1137, 0x0000_0297, 0x0242_8293, 0x3052_9073, 0x0340_00EF, 0x05D0_0893, 0x0000_0073, 0xFFFF_F2B7, 0x00A2_A223, 0x0000_006F, 0x3420_2373,
+            0x00B0_0393, 0x0073_0463, 0x1003_0513, 0xFFFF_F2B7, 0x00A2_A223, 0xFE5F_F06F,
+        ];
+        assert_eq!(words(&obj.to_flat(0).unwrap()), expected);
+
+        let start = obj.symbol("_start").unwrap();
+        assert!(start.global);
+        assert_eq!((start.kind, start.size), (SymbolKind::Func, 0x44));
+        assert_eq!(obj.symbol("_trap").unwrap().def, SymbolDef::Label { section: 0, offset: 0x28 });
+        assert_eq!(obj.sections[0].name, ".text.startup");
+        assert!(obj.to_elf().is_ok());
+    }
+
+    #[test]
+    fn test_pseudo_instructions() {
+        let cases: [(&str, &[u32]); 18] = [
+            ("nop", &[0x0000_0013]),
+            ("li a0, 5", &[0x0050_0513]),
+            ("li a0, -2048", &[0x8000_0513]),
+            ("li a0, 0x12345000", &[0x1234_5537]),
+            ("li a0, 0x12345FFF", &[0x1234_6537, 0xFFF5_0513]),
+            ("li a0, 0xFFFFFFFF", &[0xFFF0_0513]),
+            ("mv a0, a1", &[0x0005_8513]),
+            ("not a0, a1", &[0xFFF5_C513]),
+            ("neg a0, a1", &[0x40B0_0533]),
+            ("seqz a0, a1", &[0x0015_B513]),
+            ("j .", &[0x0000_006F]),
+            ("jal 8", &[0x0080_00EF]),
+            ("ret", &[0x0000_8067]),
+            ("jr t0", &[0x0002_8067]),
+            ("beqz a0, . + 8", &[0x0005_0463]),
+            ("bgt a0, a1, 12", &[0x00A5_C663]),
+            ("csrr t0, mcause", &[0x3420_22F3]),
+            ("csrw mtvec, t0", &[0x3052_9073]),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(flat(source), expected, "{}", source);
+        }
+    }
+
+    /// la/call are AUIPC pairs; %hi/%lo round so lo is sign-extended.
+    #[test]
+    fn test_relocations() {
+        let source = "
+            .text
+            la a0, data
+            call func
+            lui a1, %hi(data)
+            lw a1, %lo(data)(a1)
+            sw a2, %lo(data)(a1)
+            addi a1, a1, %lo(data)
+        func:
+            ret
+            .data
+            .align 2
+            .word 0
+        data:
+            .word func, data + 4
+        ";
+        let obj = assemble(source).unwrap();
+        assert_eq!(obj.layout(0x1000), [0x1000, 0x1024]);
+        assert_eq!(obj.symbol_address("data", 0x1000), Some(0x1028));
+        assert_eq!(
+            words(&obj.to_flat(0x1000).unwrap()),
+            [
+                0x0000_0517, 0x0285_0513, // la a0, 0x1028 from 0x1000
+                0x0000_0097, 0x0180_80E7, // call 0x1020 from 0x1008
+                0x0000_15B7, 0x0285_A583, 0x02C5_A423, 0x0285_8593, // %hi/%lo(0x1028)
+                0x0000_8067, 0, 0x0000_1020, 0x0000_102C,
+            ]
+        );
+
+        // %hi rounds up when %lo is negative
+        assert_eq!(flat("lui a0, %hi(0x12345FFF)\naddi a0, a0, %lo(0x12345FFF)"), [0x1234_6537, 0xFFF5_0513]);
+    }
+
+    #[test]
+    fn test_directives() {
+        let source = "
+            .equ COUNT, 3
+            .set LIMIT, COUNT * 4 + 1
+            .data
+            .byte 1, 2, 'a'
+            .half 0x1234
+            .ascii \"hi\"
+            .asciz \"x\\n\"
+            .balign 4
+            .word LIMIT, -1
+            .zero 2
+            .space 2, 0xAA
+        ";
+        let obj = assemble(source).unwrap();
+        let data = &obj.section(".data").unwrap().data;
+        assert_eq!(
+            data[..],
+            [1, 2, b'a', 0x34, 0x12, b'h', b'i', b'x', b'\n', 0, 0, 0, 13, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0xAA, 0xAA]
+        );
+        assert_eq!(obj.symbol("LIMIT").unwrap().def, SymbolDef::Absolute(13));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(error("addi x1, x0, 2048").contains("out of range"));
+        assert!(error("lw x1, 4(x32)").contains("offset(register)"));
+        assert!(error("beq x1, x2, 3").contains("multiple of 2"));
+        assert!(error("frob x1").contains("unknown instruction"));
+        assert!(error("call missing").contains("undefined symbol `missing`"));
+        assert!(error("a:\na:").starts_with("line 2:"));
+        assert!(error("addi x1, x0, label\nlabel:").contains("%hi/%lo"));
+        assert!(error(".bss\n.word 1").contains("only hold zeros"));
+    }
+
+    /// Minimal ELF reader: section names with their headers, and relocations
+    /// as (offset, type, symbol name, addend).
+    fn elf_relocations(elf: &[u8]) -> Vec<(u32, u32, String, i32)> {
+        let u16_at = |at: usize| u16::from_le_bytes([elf[at], elf[at + 1]]) as usize;
+        let u32_at = |at: usize| u32::from_le_bytes(elf[at..at + 4].try_into().unwrap()) as usize;
+        let (shoff, shnum) = (u32_at(32), u16_at(48));
+        let header = |index: usize, field: usize| u32_at(shoff + index * 40 + field * 4);
+        let string = |table: usize, offset: usize| {
+            let start = header(table, 4) + offset;
+            let end = start + elf[start..].iter().position(|b| *b == 0).unwrap();
+            String::from_utf8(elf[start..end].to_vec()).unwrap()
+        };
+
+        assert_eq!(&elf[..4], b"\x7FELF");
+        assert_eq!(u16_at(18), 243, "EM_RISCV");
+        let mut relocs = Vec::new();
+        for index in 0..shnum {
+            if header(index, 1) != 4 {
+                continue;
+            }
+            let symtab = header(index, 6);
+            let strtab = header(symtab, 6);
+            for entry in 0..header(index, 5) / 12 {
+                let at = header(index, 4) + entry * 12;
+                let info = u32_at(at + 4);
+                let symbol = header(symtab, 4) + (info >> 8) * 16;
+                relocs.push((u32_at(at) as u32, (info & 0xFF) as u32, string(strtab, u32_at(symbol)), u32_at(at + 8) as i32));
+            }
+        }
+        relocs
+    }
+
+    #[test]
+    fn test_elf_object() {
+        let source = "
+            .section .text.startup, \"ax\", @progbits
+            .globl _start
+        _start:
+            lui sp, %hi(_stack_top)
+            addi sp, sp, %lo(_stack_top)
+            la a0, table
+            call main
+            j _start
+            .data
+        table:
+            .word main + 8
+        ";
+        let obj: Object = assemble(source).unwrap();
+        let relocs = elf_relocations(&obj.to_elf().unwrap());
+        assert_eq!(
+            relocs,
+            [
+                (0x00, 26, "_stack_top".to_string(), 0), // R_RISCV_HI20
+                (0x04, 27, "_stack_top".to_string(), 0), // R_RISCV_LO12_I
+                (0x08, 23, String::new(), 0),             // R_RISCV_PCREL_HI20 against .data
+                (0x0C, 24, ".Lpcrel_hi0".to_string(), 0), // R_RISCV_PCREL_LO12_I
+                (0x10, 18, "main".to_string(), 0),        // R_RISCV_CALL
+                (0x00, 1, "main".to_string(), 8),         // R_RISCV_32
+            ]
+        );
+        // The branch back to _start stays inside the section
+        assert_eq!(words(&obj.sections[0].data)[6], 0xFE9F_F06F);
+        assert!(error(".globl main\ncall main").contains("undefined symbol"));
+    }
+}
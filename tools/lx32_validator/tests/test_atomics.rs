@@ -0,0 +1,403 @@
+// tests/test_atomics.rs
+//
+// Atomic memory operation (A extension) validation for the golden model.
+//
+// The RTL has no AMO opcode yet, so this suite is golden-only. Two golden
+// harts share one memory through `SharedMemory`, which forwards every store
+// and AMO write a hart commits to the other harts' reservation sets. Random
+// LR.W/SC.W/AMO*.W/SW instructions on a handful of contended words run on a
+// random hart and are checked against an independent reference: the rd
+// result (SC: 0 on success, 1 on failure), the memory word and both
+// reservations.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{MemAccess, MemOp, RegWrite};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "atomics";
+
+/// Contended data words used by the fuzzer
+pub const DATA_BASE: addr_t = 0x0000_0800;
+pub const DATA_WORDS: usize = 4;
+
+pub struct AtomicTestParams {
+    pub iterations: u32,
+    pub harts: usize,
+    pub enable_logging: bool,
+}
+
+impl Default for AtomicTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 3000,
+            harts: 2,
+            enable_logging: false,
+        }
+    }
+}
+
+/// Golden harts executing against one shared memory.
+///
+/// Each hart fetches, loads and stores through the same `MemorySim`; a
+/// store or AMO committed by one hart is snooped by every other hart, so a
+/// write to a reserved word makes the other hart's SC.W fail.
+pub struct SharedMemory {
+    pub mem: MemorySim,
+    pub harts: Vec<Lx32System>,
+}
+
+impl SharedMemory {
+    pub fn new(harts: usize, config: IsaConfig) -> Self {
+        Self {
+            mem: MemorySim::new(),
+            harts: (0..harts).map(|_| Lx32System::with_config(config)).collect(),
+        }
+    }
+
+    /// Steps `hart` one instruction and broadcasts its write, if any.
+    pub fn step(&mut self, hart: usize) -> StepResult {
+        let res = self.harts[hart].step_with_bus(&mut self.mem);
+        if let Some(MemAccess {
+            op: MemOp::Store | MemOp::Amo,
+            addr,
+            size,
+            ..
+        }) = res.mem_access
+        {
+            for (_, other) in self.harts.iter_mut().enumerate().filter(|(i, _)| *i != hart) {
+                other.snoop_store(addr, size);
+            }
+        }
+        res
+    }
+}
+
+/// funct5 values of the A extension, plus `SW` (a plain store) for the fuzzer.
+const LR: u32 = 0b00010;
+const SC: u32 = 0b00011;
+const SW: u32 = u32::MAX;
+const OPS: [(u32, &str); 12] = [
+    (LR, "lr.w"),
+    (SC, "sc.w"),
+    (0b00001, "amoswap.w"),
+    (0b00000, "amoadd.w"),
+    (0b00100, "amoxor.w"),
+    (0b01100, "amoand.w"),
+    (0b01000, "amoor.w"),
+    (0b10000, "amomin.w"),
+    (0b10100, "amomax.w"),
+    (0b11000, "amominu.w"),
+    (0b11100, "amomaxu.w"),
+    (SW, "sw"),
+];
+
+/// AMO-format word: funct5 | aq | rl | rs2 | rs1 | 010 | rd | 0101111.
+pub fn amo_instr(funct5: u32, rs2: u32, rs1: u32, rd: u32) -> instr_t {
+    funct5 << 27 | rs2 << 20 | rs1 << 15 | 0b010 << 12 | rd << 7 | 0b0101111
+}
+
+/// Reference value an AMO writes back (spec table, independent of the model).
+fn amo_reference(funct5: u32, old: data_t, src: data_t) -> data_t {
+    match funct5 {
+        0b00001 => src,
+        0b00000 => old.wrapping_add(src),
+        0b00100 => old ^ src,
+        0b01100 => old & src,
+        0b01000 => old | src,
+        0b10000 => if (old as i32) < (src as i32) { old } else { src },
+        0b10100 => if (old as i32) > (src as i32) { old } else { src },
+        0b11000 => if old < src { old } else { src },
+        0b11100 => if old > src { old } else { src },
+        _ => unreachable!("not a read-modify-write funct5"),
+    }
+}
+
+pub fn run_atomic_fuzzer(params: AtomicTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING ATOMIC MEMORY OPERATION FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Harts: {}", params.harts);
+    println!("Seed: {}", seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut shared = SharedMemory::new(params.harts, IsaConfig::rv32imac());
+
+    // Reference state: contended words and one reservation per hart
+    let mut mem_ref = [0u32; DATA_WORDS];
+    let mut res_ref: Vec<Option<addr_t>> = vec![None; params.harts];
+    let mut sc_success = 0;
+
+    for i in 0..params.iterations {
+        let hart = rng.random_range(0..params.harts);
+        let (funct5, mnemonic) = OPS[rng.random_range(0..OPS.len())];
+        let word = rng.random_range(0..DATA_WORDS);
+        let addr = DATA_BASE + 4 * word as addr_t;
+
+        // rs1 = x5 holds the address, rs2 = x6 the operand; rd may alias either
+        let src: data_t = rng.random();
+        let rd = rng.random_range(0..32u32);
+        let rs2 = if funct5 == LR { 0 } else { 6 };
+        let instr = if funct5 == SW {
+            // sw x6, 0(x5)
+            6 << 20 | 5 << 15 | 0b010 << 12 | 0b0100011
+        } else {
+            amo_instr(funct5, rs2, 5, rd)
+        };
+
+        // Each hart executes from its own code word
+        let pc = 4 * hart as addr_t;
+        shared.mem.write_data(pc, instr, true);
+        let gold = &mut shared.harts[hart];
+        gold.pc = pc;
+        gold.reg_file.tick(false, 5, addr, true);
+        gold.reg_file.tick(false, 6, src, true);
+
+        // Expected effects
+        let old = mem_ref[word];
+        let (rd_data, new_word) = match funct5 {
+            LR => (Some(old), old),
+            SC if res_ref[hart] == Some(addr) => (Some(0), src),
+            SC => (Some(1), old),
+            SW => (None, src),
+            _ => (Some(old), amo_reference(funct5, old, src)),
+        };
+        let stored = match funct5 {
+            LR => false,
+            SC => res_ref[hart] == Some(addr),
+            _ => true,
+        };
+        if funct5 == LR {
+            res_ref[hart] = Some(addr);
+        }
+        if funct5 == SC {
+            res_ref[hart] = None;
+            sc_success += stored as u32;
+        }
+        if stored {
+            res_ref.iter_mut().filter(|r| **r == Some(addr)).for_each(|r| *r = None);
+        }
+        mem_ref[word] = new_word;
+        let expected_rd = rd_data.filter(|_| rd != 0).map(|data| RegWrite { rd: rd as u8, data });
+
+        let res = shared.step(hart);
+        let reservations: Vec<Option<addr_t>> = shared.harts.iter().map(|h| h.reservation).collect();
+        let matches = res.rd_write == expected_rd
+            && shared.mem.read_data(addr) == new_word
+            && reservations == res_ref;
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] hart {} {:<10} x{:<2} 0x{:08x} src:0x{:08x} -> rd:{:?} mem:0x{:08x} | {}",
+                i,
+                hart,
+                mnemonic,
+                rd,
+                addr,
+                src,
+                res.rd_write.map(|w| w.data),
+                shared.mem.read_data(addr),
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " ATOMIC MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Hart {}: {} x{}, x6, (x5) with x5=0x{:08x} x6=0x{:08x} (0x{:08x})", hart, mnemonic, rd, addr, src, instr);
+            println!("Expected: rd_write={:?} mem=0x{:08x} reservations={:?}", expected_rd, new_word, res_ref);
+            println!("Actual:   rd_write={:?} mem=0x{:08x} reservations={:?}", res.rd_write, shared.mem.read_data(addr), reservations);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 ATOMIC TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("Successful SC.W: {}", sc_success);
+    println!("{:=^100}", " ATOMIC MEMORY OPERATION FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_csr_pkg::CSR_MISA;
+    use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+    use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+    use lx32_validator::models::core::decoder::{IllegalReason, classify_instr};
+    use lx32_validator::models::core::lx32_system::{StepStatus, Trap};
+
+    const COUNTER: addr_t = 0x100;
+
+    #[test]
+    fn test_atomic_default() {
+        run_atomic_fuzzer(AtomicTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// Two harts increment one counter with an LR/SC retry loop under a
+    /// random interleaving; no increment may be lost.
+    #[test]
+    fn test_lr_sc_counter() {
+        let program = [
+            0x1000_0093, // 0x00: addi x1, x0, 0x100   (counter)
+            0x00a0_0113, // 0x04: addi x2, x0, 10      (increments)
+            0x1000_a1af, // 0x08: lr.w x3, (x1)
+            0x0011_8193, // 0x0c: addi x3, x3, 1
+            0x1830_a22f, // 0x10: sc.w x4, x3, (x1)
+            0xfe02_1ae3, // 0x14: bne  x4, x0, 0x08    (retry)
+            0xfff1_0113, // 0x18: addi x2, x2, -1
+            0xfe01_16e3, // 0x1c: bne  x2, x0, 0x08
+            0x0000_006f, // 0x20: jal  x0, 0           (halt)
+        ];
+        const HALT: addr_t = 0x20;
+
+        let mut shared = SharedMemory::new(2, IsaConfig::rv32imac());
+        shared.mem.load_program(&program);
+        let mut rng = StdRng::seed_from_u64(DEFAULT_TEST_SEED);
+        let mut failed_sc = 0;
+
+        for _ in 0..10_000 {
+            if shared.harts.iter().all(|h| h.pc == HALT) {
+                break;
+            }
+            let res = shared.step(rng.random_range(0..2));
+            if res.ctrl.amo.is_some() && res.rd_write == Some(RegWrite { rd: 4, data: 1 }) {
+                failed_sc += 1;
+            }
+        }
+
+        assert!(shared.harts.iter().all(|h| h.pc == HALT));
+        assert_eq!(shared.mem.read_data(COUNTER), 20);
+        // The interleaving must actually have contended
+        assert!(failed_sc > 0);
+    }
+
+    /// SC.W succeeds only right after an LR.W of the same word with no
+    /// intervening store to it; rd reports the outcome either way.
+    #[test]
+    fn test_sc_outcomes() {
+        let mut mem = MemorySim::new();
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imac());
+        gold.reg_file.tick(false, 1, COUNTER, true);
+        gold.reg_file.tick(false, 2, 0xAAAA_5555, true);
+        mem.write_data(COUNTER, 7, true);
+
+        let lr = amo_instr(LR, 0, 1, 3);
+        let sc = amo_instr(SC, 2, 1, 3);
+        let sw = 0x0020_a023; // sw x2, 0(x1)
+        let exec = |gold: &mut Lx32System, mem: &mut MemorySim, instr: instr_t| {
+            mem.write_data(0, instr, true);
+            gold.pc = 0;
+            gold.step_with_bus(mem)
+        };
+
+        // No reservation: fails without writing
+        let res = exec(&mut gold, &mut mem, sc);
+        assert_eq!((res.rd_write, res.mem_access), (Some(RegWrite { rd: 3, data: 1 }), None));
+        assert_eq!(mem.read_data(COUNTER), 7);
+
+        // LR then SC: succeeds once
+        let res = exec(&mut gold, &mut mem, lr);
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 3, data: 7 }));
+        assert_eq!(gold.reservation, Some(COUNTER));
+        let res = exec(&mut gold, &mut mem, sc);
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 3, data: 0 }));
+        assert_eq!(mem.read_data(COUNTER), 0xAAAA_5555);
+        let res = exec(&mut gold, &mut mem, sc);
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 3, data: 1 }));
+
+        // The hart's own store to the reserved word invalidates it
+        exec(&mut gold, &mut mem, lr);
+        exec(&mut gold, &mut mem, sw);
+        assert_eq!(gold.reservation, None);
+
+        // A byte store by another agent into the reserved word does too;
+        // one to a neighbouring word does not
+        exec(&mut gold, &mut mem, lr);
+        gold.snoop_store(COUNTER + 4, mem_size_e::MEM_BYTE);
+        assert_eq!(gold.reservation, Some(COUNTER));
+        gold.snoop_store(COUNTER + 3, mem_size_e::MEM_BYTE);
+        assert_eq!(gold.reservation, None);
+
+        // Reset drops the reservation
+        exec(&mut gold, &mut mem, lr);
+        gold.step(0, 0, true);
+        assert_eq!(gold.reservation, None);
+    }
+
+    /// AMO*.W writes back op(old, rs2) and returns the old value.
+    #[test]
+    fn test_amo_read_modify_write() {
+        let mut mem = MemorySim::new();
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imac());
+        gold.reg_file.tick(false, 1, COUNTER, true);
+        gold.reg_file.tick(false, 2, 0xFFFF_FFFE, true); // -2
+        mem.write_data(COUNTER, 5, true);
+
+        for (funct5, old, new) in [
+            (0b00000, 5, 3),                     // amoadd  5 + -2
+            (0b10000, 3, 0xFFFF_FFFE),           // amomin  signed
+            (0b11000, 0xFFFF_FFFE, 0xFFFF_FFFE), // amominu
+            (0b00001, 0xFFFF_FFFE, 0xFFFF_FFFE), // amoswap
+            (0b01100, 0xFFFF_FFFE, 0xFFFF_FFFE), // amoand
+        ] {
+            mem.write_data(0, amo_instr(funct5, 2, 1, 3), true);
+            gold.pc = 0;
+            let res = gold.step_with_bus(&mut mem);
+            assert_eq!(res.rd_write, Some(RegWrite { rd: 3, data: old }), "funct5 {:05b}", funct5);
+            assert_eq!(
+                res.mem_access,
+                Some(MemAccess {
+                    op: MemOp::Amo,
+                    addr: COUNTER,
+                    data: new,
+                    size: mem_size_e::MEM_WORD
+                })
+            );
+            assert_eq!(mem.read_data(COUNTER), new);
+        }
+    }
+
+    /// Misaligned LR.W raises a load fault; SC.W and AMOs a store/AMO fault.
+    #[test]
+    fn test_misaligned_atomics() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imac());
+        gold.reg_file.tick(false, 1, COUNTER + 2, true);
+
+        for (instr, cause) in [
+            (amo_instr(LR, 0, 1, 3), trap_cause_e::LOAD_ADDR_MISALIGNED),
+            (amo_instr(SC, 2, 1, 3), trap_cause_e::STORE_ADDR_MISALIGNED),
+            (amo_instr(0b00000, 2, 1, 3), trap_cause_e::STORE_ADDR_MISALIGNED),
+        ] {
+            gold.pc = 0;
+            let res = gold.step(instr, 0, false);
+            assert_eq!(res.status, StepStatus::Trap(Trap { cause, pc: 0, tval: COUNTER + 2 }));
+            assert_eq!(res.rd_write, None);
+        }
+        assert_eq!(gold.reservation, None);
+    }
+
+    /// Strict decode and misa; without the extension the opcode stays a NOP.
+    #[test]
+    fn test_atomic_decode() {
+        let cfg = IsaConfig::rv32imac();
+        assert_ne!(Lx32System::with_config(cfg).csr.read(CSR_MISA) & 1, 0);
+
+        assert!(classify_instr(amo_instr(LR, 0, 1, 3), &cfg).is_ok());
+        // lr.w with rs2 != x0, reserved funct5, .D width
+        assert_eq!(classify_instr(amo_instr(LR, 2, 1, 3), &cfg), Err(IllegalReason::Funct7));
+        assert_eq!(classify_instr(amo_instr(0b00101, 2, 1, 3), &cfg), Err(IllegalReason::Funct7));
+        assert_eq!(classify_instr(amo_instr(0, 2, 1, 3) | 1 << 12, &cfg), Err(IllegalReason::Funct3));
+        // aq/rl are accepted
+        assert!(classify_instr(amo_instr(0, 2, 1, 3) | 0b11 << 25, &cfg).is_ok());
+
+        let base = IsaConfig::base();
+        assert_eq!(classify_instr(amo_instr(0, 2, 1, 3), &base), Err(IllegalReason::Opcode));
+        let mut gold = Lx32System::new();
+        let res = gold.step(amo_instr(0, 2, 1, 3), 0xFFFF_FFFF, false);
+        assert_eq!((res.status, res.rd_write, res.mem_access), (StepStatus::Illegal, None, None));
+    }
+}
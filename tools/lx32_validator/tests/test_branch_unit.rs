@@ -3,6 +3,11 @@
 mod common;
 use common::*;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "branch_unit";
 
 pub struct BranchTestParams {
     pub iterations: u32,
@@ -46,9 +51,10 @@ fn log_branch_step(rtl: &BranchState, gold: &BranchState, matches: bool) {
     let status = if matches { "✓ MATCH" } else { "✗ MISMATCH" };
 
     println!(
-        "[{:>5}] Instr: 0x{:08x} | PC: 0x{:04x}->[R:0x{:04x} G:0x{:04x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | Taken: {:<5} | {}",
+        "[{:>5}] Instr: 0x{:08x} {:<24} | PC: 0x{:04x}->[R:0x{:04x} G:0x{:04x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | x{:>2}: [R:0x{:08x} G:0x{:08x}] | Taken: {:<5} | {}",
         rtl.iteration,
         rtl.instr,
+        disasm(rtl.instr, rtl.pre_pc),
         rtl.pre_pc,
         rtl.post_pc,
         gold.post_pc,
@@ -63,14 +69,15 @@ fn log_branch_step(rtl: &BranchState, gold: &BranchState, matches: bool) {
     );
 }
 
-pub fn run_branch_fuzzer(params: BranchTestParams) {
+pub fn run_branch_fuzzer(params: BranchTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING BRANCH UNIT FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Registers: {:?}", params.reg_range);
     println!("Offset words: {:?}", params.offset_word_range);
 
     let mut tb = TestBench::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         // 1. Randomize sources
@@ -106,7 +113,7 @@ pub fn run_branch_fuzzer(params: BranchTestParams) {
 
         // 6. Execute Step
         unsafe { tick_core(tb.rtl, 0, instr, 0) };
-        tb.gold.step(instr, 0, false);
+        let step = tb.gold.step(instr, 0, false);
 
         // 7. Capture state AFTER
         let post_rtl_pc = unsafe { get_pc(tb.rtl) };
@@ -143,10 +150,14 @@ pub fn run_branch_fuzzer(params: BranchTestParams) {
         }
 
         if !states_match {
+            print_gold_step(&step);
+            print_replay(SUITE, seed, i);
             panic!(
-                "🔥 BRANCH MISMATCH!\nIter: {}\nInstr: 0x{:08x}\nOffset: {}\nPC RTL: 0x{:08x} | GOLD: 0x{:08x}\nRS1 x{} RTL:0x{:08x} | GOLD:0x{:08x}\nRS2 x{} RTL:0x{:08x} | GOLD:0x{:08x}",
+                "🔥 BRANCH MISMATCH!\nSeed: {}\nIter: {}\nInstr: 0x{:08x} ({})\nOffset: {}\nPC RTL: 0x{:08x} | GOLD: 0x{:08x}\nRS1 x{} RTL:0x{:08x} | GOLD:0x{:08x}\nRS2 x{} RTL:0x{:08x} | GOLD:0x{:08x}",
+                seed,
                 i,
                 instr,
+                disasm(instr, step.pc),
                 rtl_state.offset,
                 rtl_state.post_pc,
                 gold_state.post_pc,
@@ -168,6 +179,6 @@ mod tests {
 
     #[test]
     fn test_branch_default() {
-        run_branch_fuzzer(BranchTestParams::default());
+        run_branch_fuzzer(BranchTestParams::default(), DEFAULT_TEST_SEED);
     }
 }
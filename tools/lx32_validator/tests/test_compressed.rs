@@ -0,0 +1,378 @@
+// tests/test_compressed.rs
+//
+// Compressed instruction (C extension) validation for the golden model.
+//
+// The RTL fetches 32-bit words only, so this suite is golden-only. Random
+// RV32C instructions from the program generator run on a golden model with
+// the C extension; the same register state then runs the 32-bit instruction
+// the generator built from the same fields on a base golden model. Both must
+// agree, except that the fall-through PC and the link value advance by 2.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{RegWrite, StepStatus};
+use lx32_validator::program_generator::{Program, ProgramConfig};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "compressed";
+
+pub struct CompressedTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for CompressedTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            enable_logging: true,
+        }
+    }
+}
+
+pub fn run_compressed_fuzzer(params: CompressedTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING COMPRESSED INSTRUCTION FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let mut gold = Lx32System::with_config(IsaConfig {
+        c_ext: true,
+        ..IsaConfig::base()
+    });
+    let mut reference = Lx32System::new();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let config = ProgramConfig::default();
+
+    for i in 0..params.iterations {
+        // Halfword-aligned PC and identical random register state
+        let pc = rng.random_range(0..0x800u32) << 1;
+        gold.pc = pc;
+        reference.pc = pc;
+        for reg in 1..32u8 {
+            let value = rng.random();
+            gold.reg_file.tick(false, reg, value, true);
+            reference.reg_file.tick(false, reg, value, true);
+        }
+
+        let (instr, expanded) = Program::generate_compressed_instruction(&config, &mut rng);
+        let mem_rdata = rng.random();
+
+        let res = gold.step(instr.encoding, mem_rdata, false);
+        let full = reference.step(expanded, mem_rdata, false);
+
+        // Only the instruction length differs from the 32-bit equivalent
+        let fall_through = !full.ctrl.jump && !full.branch_taken;
+        let next_pc = if fall_through { pc.wrapping_add(2) } else { full.next_pc };
+        let rd_write = match full.rd_write {
+            Some(w) if full.ctrl.jump => Some(RegWrite {
+                rd: w.rd,
+                data: pc.wrapping_add(2),
+            }),
+            other => other,
+        };
+
+        let matches = res.instr == expanded
+            && res.compressed == Some(instr.encoding as u16)
+            && res.instr_len() == 2
+            && res.status == StepStatus::Retired
+            && res.next_pc == next_pc
+            && res.rd_write == rd_write
+            && res.mem_access == full.mem_access;
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] pc:0x{:08x} {:<24} -> 0x{:08x} | next_pc:0x{:08x} rd:{:?} | {}",
+                i,
+                pc,
+                instr.mnemonic,
+                expanded,
+                res.next_pc,
+                res.rd_write,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " COMPRESSED MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:04x} ({}) at pc 0x{:08x}", instr.encoding, instr.mnemonic, pc);
+            println!("Expected expansion: 0x{:08x}", expanded);
+            println!("Expected: next_pc=0x{:08x} rd_write={:?} mem_access={:?}", next_pc, rd_write, full.mem_access);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 COMPRESSED TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("{:=^100}", " COMPRESSED INSTRUCTION FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_csr_pkg::{CSR_MEPC, CSR_MISA, CSR_MTVAL};
+    use lx32_validator::models::arch::lx32_cinstr_pkg::CInstr;
+    use lx32_validator::models::arch::lx32_rvc_pkg::expand_compressed;
+    use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+    use lx32_validator::models::core::lx32_system::Trap;
+
+    #[test]
+    fn test_compressed_default() {
+        run_compressed_fuzzer(CompressedTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// Encodings as emitted by a standard RV32C assembler.
+    #[test]
+    fn test_expansion_table() {
+        let table: [(u16, u32); 8] = [
+            (0x0001, 0x0000_0013), // c.nop            -> addi x0, x0, 0
+            (0x0040, 0x0041_0413), // c.addi4spn s0, 4 -> addi x8, x2, 4
+            (0x557d, 0xfff0_0513), // c.li a0, -1      -> addi x10, x0, -1
+            (0x852e, 0x00b0_0533), // c.mv a0, a1      -> add x10, x0, x11
+            (0x8082, 0x0000_8067), // c.jr ra          -> jalr x0, 0(x1)
+            (0x9002, 0x0010_0073), // c.ebreak         -> ebreak
+            (0x40b2, 0x00c1_2083), // c.lwsp ra, 12(sp) -> lw x1, 12(x2)
+            (0xc606, 0x0011_2623), // c.swsp ra, 12(sp) -> sw x1, 12(x2)
+        ];
+        for (parcel, word) in table {
+            assert_eq!(expand_compressed(parcel, false), Some(word), "0x{:04x}", parcel);
+        }
+
+        // all-zero, c.lwsp x0, c.jr x0, c.addi16sp 0, c.lui x1 0, c.slli shamt[5], c.fld
+        for parcel in [0x0000, 0x4002, 0x8002, 0x6101, 0x6081, 0x1086, 0x2000] {
+            assert_eq!(expand_compressed(parcel, false), None, "0x{:04x}", parcel);
+        }
+    }
+
+    /// RV32FC single-precision loads/stores, cross-checked with llvm-mc. They
+    /// only exist with the F extension; the double-precision forms never do.
+    /// Random operands over every field value: `encode` and `expand` must
+    /// agree with `expand_compressed`.
+    fn random_cinstr(rng: &mut StdRng) -> CInstr {
+        let (rd, rs2) = (rng.random_range(0..32), rng.random_range(1..32));
+        let (rd_p, rs2_p) = (rng.random_range(8..16), rng.random_range(8..16));
+        let (imm6, shamt) = (rng.random_range(-32..32), rng.random_range(0..32));
+        let nzimm6 = if imm6 == 0 { -32 } else { imm6 };
+        match rng.random_range(0..26) {
+            0 => CInstr::Addi4spn { rd: rd_p, imm: rng.random_range(1..256) * 4 },
+            1 => CInstr::Lw { rd: rd_p, rs1: rs2_p, imm: rng.random_range(0..32) * 4 },
+            2 => CInstr::Sw { rs1: rd_p, rs2: rs2_p, imm: rng.random_range(0..32) * 4 },
+            3 => CInstr::Addi { rd, imm: imm6 },
+            4 => CInstr::Jal { imm: rng.random_range(-1024..1024) * 2 },
+            5 => CInstr::Li { rd, imm: imm6 },
+            6 => CInstr::Addi16sp { imm: nzimm6 * 16 },
+            7 => CInstr::Lui { rd: if rd == 2 { 0 } else { rd }, imm: nzimm6 << 12 },
+            8 => CInstr::Srli { rd: rd_p, shamt },
+            9 => CInstr::Srai { rd: rd_p, shamt },
+            10 => CInstr::Andi { rd: rd_p, imm: imm6 },
+            11 => CInstr::Sub { rd: rd_p, rs2: rs2_p },
+            12 => CInstr::Xor { rd: rd_p, rs2: rs2_p },
+            13 => CInstr::Or { rd: rd_p, rs2: rs2_p },
+            14 => CInstr::And { rd: rd_p, rs2: rs2_p },
+            15 => CInstr::J { imm: rng.random_range(-1024..1024) * 2 },
+            16 => CInstr::Beqz { rs1: rd_p, imm: rng.random_range(-128..128) * 2 },
+            17 => CInstr::Bnez { rs1: rd_p, imm: rng.random_range(-128..128) * 2 },
+            18 => CInstr::Slli { rd, shamt },
+            19 => CInstr::Lwsp { rd: rs2, imm: rng.random_range(0..64) * 4 },
+            20 => CInstr::Swsp { rs2: rd, imm: rng.random_range(0..64) * 4 },
+            21 => CInstr::Jr { rs1: rs2 },
+            22 => CInstr::Mv { rd, rs2 },
+            23 => CInstr::Ebreak,
+            24 => CInstr::Jalr { rs1: rs2 },
+            _ => CInstr::Add { rd, rs2 },
+        }
+    }
+
+    /// The typed encoder round-trips through the decoder's expansion, and
+    /// matches the assembler on a few parcels.
+    #[test]
+    fn test_cinstr_round_trip() {
+        let mut rng = StdRng::seed_from_u64(DEFAULT_TEST_SEED);
+        for _ in 0..5000 {
+            let instr = random_cinstr(&mut rng);
+            assert!(instr.fits(), "{:?}", instr);
+            assert_eq!(expand_compressed(instr.encode(), false), Some(instr.expand().encode()), "{:?}", instr);
+        }
+
+        let known: [(CInstr, u16); 22] = [
+            (CInstr::Addi { rd: 0, imm: 0 }, 0x0001),
+            (CInstr::Addi4spn { rd: 8, imm: 4 }, 0x0040),
+            (CInstr::Addi4spn { rd: 15, imm: 1020 }, 0x1ffc),
+            (CInstr::Li { rd: 10, imm: -1 }, 0x557d),
+            (CInstr::Mv { rd: 10, rs2: 11 }, 0x852e),
+            (CInstr::Jr { rs1: 1 }, 0x8082),
+            (CInstr::Ebreak, 0x9002),
+            (CInstr::Lwsp { rd: 1, imm: 12 }, 0x40b2),
+            (CInstr::Swsp { rs2: 1, imm: 12 }, 0xc606),
+            (CInstr::Addi16sp { imm: -64 }, 0x7139),
+            (CInstr::Lui { rd: 15, imm: -4096 }, 0x77fd),
+            (CInstr::Lui { rd: 5, imm: 31 << 12 }, 0x62fd),
+            (CInstr::Beqz { rs1: 10, imm: -8 }, 0xdd65),
+            (CInstr::Bnez { rs1: 9, imm: 254 }, 0xecfd),
+            (CInstr::J { imm: 2046 }, 0xaffd),
+            (CInstr::Jal { imm: -2048 }, 0x3001),
+            (CInstr::Srai { rd: 9, shamt: 31 }, 0x84fd),
+            (CInstr::Andi { rd: 15, imm: -32 }, 0x9b81),
+            (CInstr::Sub { rd: 8, rs2: 15 }, 0x8c1d),
+            (CInstr::Lw { rd: 10, rs1: 15, imm: 124 }, 0x5fe8),
+            (CInstr::Sw { rs1: 9, rs2: 11, imm: 64 }, 0xc0ac),
+            (CInstr::Slli { rd: 31, shamt: 31 }, 0x0ffe),
+        ];
+        for (instr, parcel) in known {
+            assert_eq!(instr.encode(), parcel, "{:?}", instr);
+        }
+
+        // Out of range, misaligned, or another instruction's code point
+        let rejected = [
+            CInstr::Addi4spn { rd: 8, imm: 0 },
+            CInstr::Lw { rd: 7, rs1: 8, imm: 0 },
+            CInstr::Sw { rs1: 8, rs2: 9, imm: 2 },
+            CInstr::Addi { rd: 1, imm: 32 },
+            CInstr::Addi16sp { imm: 0 },
+            CInstr::Addi16sp { imm: 8 },
+            CInstr::Lui { rd: 2, imm: 4096 },
+            CInstr::Lui { rd: 1, imm: 0 },
+            CInstr::Srli { rd: 8, shamt: 32 },
+            CInstr::Beqz { rs1: 8, imm: 256 },
+            CInstr::J { imm: 3 },
+            CInstr::Lwsp { rd: 0, imm: 0 },
+            CInstr::Jr { rs1: 0 },
+            CInstr::Mv { rd: 1, rs2: 0 },
+        ];
+        for instr in rejected {
+            assert!(!instr.fits(), "{:?}", instr);
+        }
+    }
+
+    #[test]
+    fn test_fp_expansion_table() {
+        let table: [(u16, u32); 6] = [
+            (0x61c8, 0x0045_a507), // c.flw fa0, 4(a1)     -> flw f10, 4(x11)
+            (0xfc7c, 0x06f4_2e27), // c.fsw fa5, 124(s0)   -> fsw f15, 124(x8)
+            (0x6032, 0x00c1_2007), // c.flwsp ft0, 12(sp)  -> flw f0, 12(x2)
+            (0x7dfe, 0x0fc1_2d87), // c.flwsp fs11, 252(sp) -> flw f27, 252(x2)
+            (0xe62a, 0x00a1_2627), // c.fswsp fa0, 12(sp)  -> fsw f10, 12(x2)
+            (0xfffe, 0x0ff1_2e27), // c.fswsp ft11, 252(sp) -> fsw f31, 252(x2)
+        ];
+        for (parcel, word) in table {
+            assert_eq!(expand_compressed(parcel, true), Some(word), "0x{:04x}", parcel);
+            assert_eq!(expand_compressed(parcel, false), None, "0x{:04x}", parcel);
+        }
+
+        // c.fld, c.fsd, c.fldsp, c.fsdsp
+        for parcel in [0x2588, 0xa588, 0x2522, 0xa42a] {
+            assert_eq!(expand_compressed(parcel, true), None, "0x{:04x}", parcel);
+        }
+    }
+
+    /// Under RV32IMAFC the FP parcels execute like FLW/FSW; without F they trap.
+    #[test]
+    fn test_fp_parcels_execute() {
+        let mut gold = Lx32System::with_config(IsaConfig::full());
+        gold.reg_file.tick(false, 2, 0x100, true);
+        let res = gold.step(0x6032, 0x3F80_0000, false); // c.flwsp ft0, 12(sp)
+        assert_eq!((res.status, res.compressed, res.next_pc), (StepStatus::Retired, Some(0x6032), 2));
+        assert_eq!(res.mem_access.map(|access| access.addr), Some(0x10C));
+        assert_eq!(gold.f_regs.read(0), 0x3F80_0000);
+
+        let res = gold.step(0xe002, 0, false); // c.fswsp ft0, 0(sp)
+        assert_eq!(res.status, StepStatus::Retired);
+        assert_eq!(res.mem_access.map(|access| (access.addr, access.data)), Some((0x100, 0x3F80_0000)));
+
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imc());
+        let res = gold.step(0x6032, 0, false);
+        assert!(matches!(res.status, StepStatus::Trap(Trap { tval: 0x6032, .. })));
+    }
+
+    /// Fetch at 2-byte alignment, including a 32-bit instruction that straddles
+    /// two memory words; links are PC + instruction length.
+    #[test]
+    fn test_mixed_fetch_and_link() {
+        let mut image = Vec::new();
+        image.extend(0x2019u16.to_le_bytes()); // 0: c.jal 6
+        image.extend(0x0001u16.to_le_bytes()); // 2: c.nop
+        image.extend(0x0001u16.to_le_bytes()); // 4: c.nop
+        image.extend(0x0000_82e7u32.to_le_bytes()); // 6: jalr x5, 0(x1)
+        image.resize(12, 0);
+        let words: Vec<u32> = image.chunks(4).map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]])).collect();
+
+        let mut mem = MemorySim::new();
+        mem.load_program(&words);
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imc());
+
+        let res = gold.step_with_bus(&mut mem);
+        assert_eq!((res.compressed, res.next_pc), (Some(0x2019), 6));
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 1, data: 2 }));
+
+        let res = gold.step_with_bus(&mut mem);
+        assert_eq!((res.instr, res.compressed, res.next_pc), (0x0000_82e7, None, 2));
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 5, data: 10 }));
+
+        let res = gold.step_with_bus(&mut mem);
+        assert_eq!((res.compressed, res.next_pc), (Some(0x0001), 4));
+    }
+
+    /// Reserved parcels trap with the parcel in mtval; mepc keeps bit 1.
+    #[test]
+    fn test_illegal_parcel_traps() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32imc());
+        assert_ne!(gold.csr.read(CSR_MISA) & (1 << 2), 0);
+
+        gold.pc = 2;
+        let res = gold.step(0x4002, 0, false);
+        assert_eq!(
+            res.status,
+            StepStatus::Trap(Trap {
+                cause: trap_cause_e::ILLEGAL_INSTRUCTION,
+                pc: 2,
+                tval: 0x4002
+            })
+        );
+        assert_eq!(gold.csr.read(CSR_MEPC), 2);
+        assert_eq!(gold.csr.read(CSR_MTVAL), 0x4002);
+
+        // Without the C extension a compressed parcel is not an instruction at all
+        let mut gold = Lx32System::with_config(IsaConfig::rv32im());
+        let res = gold.step(0x0001, 0, false);
+        assert!(matches!(res.status, StepStatus::Trap(Trap { tval: 0x0001, .. })));
+    }
+
+    /// Generated programs mix both sizes and lay out contiguously.
+    #[test]
+    fn test_mixed_program_layout() {
+        let config = ProgramConfig {
+            length: 200,
+            enable_branches: false,
+            enable_jumps: false,
+            enable_stores: false,
+            enable_compressed: true,
+            ..ProgramConfig::default()
+        };
+        let program = Program::generate(config, DEFAULT_TEST_SEED);
+        let compressed = program.instructions.iter().filter(|i| i.instr_len() == 2).count();
+        assert!(compressed > 0 && compressed < program.instructions.len());
+
+        // No alignment traps: random loads must not redirect the PC
+        let mut gold = Lx32System::with_config(IsaConfig {
+            c_ext: true,
+            ..IsaConfig::base()
+        });
+        gold.load_image(0, &program.to_bytes());
+
+        let mut pc = 0;
+        for instr in &program.instructions {
+            let res = gold.run(1, |_| false).last.expect("one step");
+            assert_eq!(res.pc, pc, "{}", instr.mnemonic);
+            assert_eq!(res.instr_len(), instr.instr_len(), "{}", instr.mnemonic);
+            match res.compressed {
+                Some(parcel) => assert_eq!(parcel as u32, instr.encoding),
+                None => assert_eq!(res.instr, instr.encoding),
+            }
+            pc += instr.instr_len();
+        }
+    }
+}
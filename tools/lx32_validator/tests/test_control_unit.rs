@@ -3,6 +3,11 @@
 mod common;
 use common::*;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "control_unit";
 
 /// Control Unit Test Parameters - Allows parameterization of test behavior
 pub struct ControlUnitTestParams {
@@ -101,13 +106,14 @@ fn validate_states(rtl_state: &ExecutionState, gold_state: &ExecutionState) -> b
 }
 
 /// Log execution for debugging - Shows instruction decode and resulting state
-fn log_execution(rtl_state: &ExecutionState, gold_state: &ExecutionState, matches: bool) {
+fn log_execution(rtl_state: &ExecutionState, gold_state: &ExecutionState, pc: u32, matches: bool) {
     let status = if matches { "✓ MATCH" } else { "✗ MISMATCH" };
 
     println!(
-        "[{:>5}] Instr: 0x{:08x} | PC: [R:0x{:04x} G:0x{:04x}] | x{:>2}(RD): [R:0x{:08x} G:0x{:08x}] | {}",
+        "[{:>5}] Instr: 0x{:08x} {:<24} | PC: [R:0x{:04x} G:0x{:04x}] | x{:>2}(RD): [R:0x{:08x} G:0x{:08x}] | {}",
         rtl_state.iteration,
         rtl_state.instr,
+        disasm(rtl_state.instr, pc),
         rtl_state.pc,
         gold_state.pc,
         rtl_state.rd,
@@ -135,14 +141,15 @@ fn log_execution(rtl_state: &ExecutionState, gold_state: &ExecutionState, matche
 
 /// Execute a parametrizable Control Unit fuzzer
 /// Tests various instruction types and validates instruction decoding
-pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
+pub fn run_control_unit_fuzzer(params: ControlUnitTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING CONTROL UNIT FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Registers: {:?}", params.reg_range);
     println!("Immediates: {:?}", params.imm_range);
 
     let mut tb = TestBench::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         // Randomly select instruction type (R, I, S, B, JAL, JALR, U)
@@ -174,7 +181,7 @@ pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
                 // S-Type: SW
                 let rs1 = rng.random_range(0..32);
                 let rs2 = rng.random_range(0..32);
-                let offset = rng.random_range(0..4096) as i32;
+                let offset = rng.random_range(0..4096);
                 let imm11_5 = ((offset >> 5) & 0x7F) as u32;
                 let imm4_0 = (offset & 0x1F) as u32;
                 let opcode = 0x23; // OP_STORE
@@ -222,7 +229,7 @@ pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
                 // I-Type: JALR
                 let rd = rng.random_range(1..32);
                 let rs1 = rng.random_range(0..32);
-                let imm = rng.random_range(-2048..2048) as i32;
+                let imm = rng.random_range(-2048..2048);
                 let imm12 = (imm as u32) & 0xFFF;
                 (imm12 << 20) | (rs1 << 15) | (0 << 12) | (rd << 7) | 0x67
             }
@@ -239,7 +246,7 @@ pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
 
         // Execute instruction on both RTL and Golden Model
         unsafe { tick_core(tb.rtl, 0, instr, 0) };
-        tb.gold.step(instr, 0, false);
+        let step = tb.gold.step(instr, 0, false);
 
         // Capture state AFTER execution
         let (post_rtl_state, post_gold_state) = capture_state(&tb, instr, i, rd, rs1, rs2);
@@ -248,14 +255,14 @@ pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
         let states_match = validate_states(&post_rtl_state, &post_gold_state);
 
         if params.enable_logging {
-            log_execution(&post_rtl_state, &post_gold_state, states_match);
+            log_execution(&post_rtl_state, &post_gold_state, step.pc, states_match);
         }
 
         // Panic if mismatch detected
         if !states_match {
             println!("\n{:=^100}", " CONTROL UNIT MISMATCH DETECTED ");
             println!("Iteration: {}", i);
-            println!("Instruction: 0x{:08x}", instr);
+            println!("Instruction: 0x{:08x} ({})", instr, disasm(instr, step.pc));
             println!("\nRTL State:");
             println!("  PC: 0x{:04x}", post_rtl_state.pc);
             println!("  x{:>2} (RD): 0x{:08x}", rd, post_rtl_state.rd_value);
@@ -266,7 +273,9 @@ pub fn run_control_unit_fuzzer(params: ControlUnitTestParams) {
             println!("  x{:>2} (RD): 0x{:08x}", rd, post_gold_state.rd_value);
             println!("  x{:>2} (RS1): 0x{:08x}", rs1, post_gold_state.rs1_value);
             println!("  x{:>2} (RS2): 0x{:08x}", rs2, post_gold_state.rs2_value);
-            panic!("🔥 CONTROL UNIT TEST FAILED AT ITERATION {}", i);
+            print_gold_step(&step);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 CONTROL UNIT TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
     println!("{:=^100}\n", " CONTROL UNIT FUZZER PASSED ");
@@ -279,7 +288,8 @@ mod tests {
     fn run_directed_jump_case(instr: u32, rd: u8) {
         let mut tb = TestBench::new();
         unsafe { tick_core(tb.rtl, 0, instr, 0) };
-        tb.gold.step(instr, 0, false);
+        let step = tb.gold.step(instr, 0, false);
+        assert!(step.ctrl.jump, "directed jump case did not decode as a jump");
 
         let rtl_pc = unsafe { get_pc(tb.rtl) };
         let gold_pc = tb.gold.pc;
@@ -293,35 +303,44 @@ mod tests {
     /// Default configuration test - 100 iterations, standard ranges
     #[test]
     fn test_control_unit_default() {
-        run_control_unit_fuzzer(ControlUnitTestParams::default());
+        run_control_unit_fuzzer(ControlUnitTestParams::default(), DEFAULT_TEST_SEED);
     }
 
     /// Extended test with more iterations
     #[test]
     fn test_control_unit_extended() {
-        run_control_unit_fuzzer(ControlUnitTestParams {
-            iterations: 500,
-            ..Default::default()
-        });
+        run_control_unit_fuzzer(
+            ControlUnitTestParams {
+                iterations: 500,
+                ..Default::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
     }
 
     /// Test with extended register range
     #[test]
     fn test_control_unit_full_register_range() {
-        run_control_unit_fuzzer(ControlUnitTestParams {
-            iterations: 200,
-            reg_range: (0, 32),
-            ..Default::default()
-        });
+        run_control_unit_fuzzer(
+            ControlUnitTestParams {
+                iterations: 200,
+                reg_range: (0, 32),
+                ..Default::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
     }
 
     /// Stress test - many iterations, all ranges enabled
     #[test]
     fn test_control_unit_stress() {
-        run_control_unit_fuzzer(ControlUnitTestParams {
-            iterations: 1000,
-            ..Default::default()
-        });
+        run_control_unit_fuzzer(
+            ControlUnitTestParams {
+                iterations: 1000,
+                ..Default::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
     }
 
     #[test]
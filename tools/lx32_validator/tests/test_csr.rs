@@ -0,0 +1,319 @@
+// tests/test_csr.rs
+//
+// Zicsr validation for the golden CSR file.
+//
+// The RTL has no CSRs yet, so this suite is golden-only: random CSR
+// instructions run on an RV32I golden model and are checked against a small
+// reference model of the CSR semantics kept in this file (WARL masks,
+// read-only space, counters and the rd/old-value rule).
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_csr_pkg::*;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::StepStatus;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::HashMap;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "csr";
+
+pub struct CsrTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for CsrTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            enable_logging: true,
+        }
+    }
+}
+
+const CSRS: [u16; 21] = [
+    CSR_MVENDORID,
+    CSR_MARCHID,
+    CSR_MIMPID,
+    CSR_MHARTID,
+    CSR_MSTATUS,
+    CSR_MISA,
+    CSR_MIE,
+    CSR_MTVEC,
+    CSR_MSCRATCH,
+    CSR_MEPC,
+    CSR_MCAUSE,
+    CSR_MTVAL,
+    CSR_MIP,
+    CSR_MCYCLE,
+    CSR_MINSTRET,
+    CSR_MCYCLEH,
+    CSR_MINSTRETH,
+    CSR_CYCLE,
+    CSR_INSTRET,
+    CSR_CYCLEH,
+    CSR_INSTRETH,
+];
+
+/// funct3 of CSRRW, CSRRS, CSRRC, CSRRWI, CSRRSI, CSRRCI
+const FUNCT3S: [u32; 6] = [0b001, 0b010, 0b011, 0b101, 0b110, 0b111];
+
+/// Encodes a Zicsr instruction; `src` is rs1 or the 5-bit zimm.
+pub fn csr_instr(funct3: u32, rd: u32, src: u32, csr: u16) -> u32 {
+    ((csr as u32) << 20) | (src << 15) | (funct3 << 12) | (rd << 7) | 0b1110011
+}
+
+/// Independent model of the expected CSR behaviour.
+struct CsrReference {
+    plain: HashMap<u16, u32>,
+    cycle: u64,
+    instret: u64,
+}
+
+impl CsrReference {
+    fn new() -> Self {
+        let plain = [
+            (CSR_MSTATUS, MSTATUS_MPP),
+            // MXL = 32, I
+            (CSR_MISA, 0x4000_0100),
+            (CSR_MIE, 0),
+            (CSR_MTVEC, 0),
+            (CSR_MSCRATCH, 0),
+            (CSR_MEPC, 0),
+            (CSR_MCAUSE, 0),
+            (CSR_MTVAL, 0),
+        ];
+        Self {
+            plain: plain.into_iter().collect(),
+            cycle: 0,
+            instret: 0,
+        }
+    }
+
+    fn read(&self, csr: u16) -> u32 {
+        match csr {
+            CSR_MCYCLE | CSR_CYCLE => self.cycle as u32,
+            CSR_MCYCLEH | CSR_CYCLEH => (self.cycle >> 32) as u32,
+            CSR_MINSTRET | CSR_INSTRET => self.instret as u32,
+            CSR_MINSTRETH | CSR_INSTRETH => (self.instret >> 32) as u32,
+            _ => self.plain.get(&csr).copied().unwrap_or(0),
+        }
+    }
+
+    fn write(&mut self, csr: u16, value: u32) {
+        let lo = |old: u64| (old & !0xFFFF_FFFF) | value as u64;
+        let hi = |old: u64| (old & 0xFFFF_FFFF) | (value as u64) << 32;
+        match csr {
+            CSR_MCYCLE => self.cycle = lo(self.cycle),
+            CSR_MCYCLEH => self.cycle = hi(self.cycle),
+            CSR_MINSTRET => self.instret = lo(self.instret),
+            CSR_MINSTRETH => self.instret = hi(self.instret),
+            CSR_MSTATUS => {
+                self.plain.insert(csr, MSTATUS_MPP | (value & (MSTATUS_MIE | MSTATUS_MPIE)));
+            }
+            CSR_MTVEC => {
+                let mode = if value & 0b11 == 1 { 1 } else { 0 };
+                self.plain.insert(csr, (value & !0b11) | mode);
+            }
+            CSR_MEPC => {
+                self.plain.insert(csr, value & !0b11);
+            }
+            CSR_MIE => {
+                self.plain.insert(csr, value & (MIP_MSIP | MIP_MTIP | MIP_MEIP));
+            }
+            // misa is WARL with every field fixed; mip bits follow the interrupt lines
+            CSR_MISA | CSR_MIP => {}
+            _ => {
+                self.plain.insert(csr, value);
+            }
+        }
+    }
+
+    fn trap(&mut self, pc: u32, instr: u32) {
+        let mstatus = self.plain[&CSR_MSTATUS];
+        let mpie = if mstatus & MSTATUS_MIE != 0 { MSTATUS_MPIE } else { 0 };
+        self.plain.insert(CSR_MSTATUS, MSTATUS_MPP | mpie);
+        self.plain.insert(CSR_MEPC, pc);
+        self.plain.insert(CSR_MCAUSE, 2);
+        self.plain.insert(CSR_MTVAL, instr);
+        self.cycle += 1;
+    }
+}
+
+pub fn run_csr_fuzzer(params: CsrTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING CSR FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+    let mut reference = CsrReference::new();
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for i in 0..params.iterations {
+        let csr = CSRS[rng.random_range(0..CSRS.len())];
+        let funct3 = FUNCT3S[rng.random_range(0..FUNCT3S.len())];
+        let rd = rng.random_range(0..32);
+        // Bias towards 0 so read-only accesses are exercised
+        let src = if rng.random_range(0..4) == 0 { 0 } else { rng.random_range(0..32) };
+        let rs1_value: u32 = rng.random();
+        if funct3 & 0b100 == 0 {
+            gold.reg_file.tick(false, src as u8, rs1_value, true);
+        }
+        let operand = if funct3 & 0b100 != 0 { src } else { gold.reg_file.read_rs1(src as u8) };
+
+        let instr = csr_instr(funct3, rd, src, csr);
+        let pc = gold.pc;
+        let res = gold.step(instr, 0, false);
+
+        // Expected result
+        let writes = funct3 & 0b011 == 0b001 || src != 0;
+        let trap = writes && csr >> 10 == 0b11;
+        let old = reference.read(csr);
+        let mut expected_rd = None;
+        if trap {
+            reference.trap(pc, instr);
+        } else {
+            reference.cycle += 1;
+            reference.instret += 1;
+            if writes {
+                let new = match funct3 & 0b011 {
+                    0b001 => operand,
+                    0b010 => old | operand,
+                    _ => old & !operand,
+                };
+                reference.write(csr, new);
+            }
+            expected_rd = (rd != 0).then_some((rd as u8, old));
+        }
+
+        let got_rd = res.rd_write.map(|w| (w.rd, w.data));
+        let trapped = matches!(res.status, StepStatus::Trap(_));
+        let mismatched: Vec<u16> = CSRS.iter().copied().filter(|&c| gold.csr.read(c) != reference.read(c)).collect();
+        let matches = got_rd == expected_rd && trapped == trap && mismatched.is_empty();
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] 0x{:08x} csr:0x{:03x} funct3:{:03b} src:{:>2} | rd:{:?} | {}",
+                i,
+                instr,
+                csr,
+                funct3,
+                src,
+                got_rd,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " CSR MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} ({})", instr, disasm(instr, pc));
+            println!("Trap: expected {} got {}", trap, trapped);
+            println!("rd write: expected {:?} got {:?}", expected_rd, got_rd);
+            for c in mismatched {
+                println!("CSR 0x{:03x}: expected 0x{:08x} got 0x{:08x}", c, reference.read(c), gold.csr.read(c));
+            }
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 CSR TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("{:=^100}", " CSR FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+    use lx32_validator::models::core::decoder::{IllegalReason, classify_instr};
+    use lx32_validator::models::core::lx32_system::{CsrWrite, Trap};
+
+    #[test]
+    fn test_csr_default() {
+        run_csr_fuzzer(CsrTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// rdcycle/rdinstret see every earlier cycle; high halves carry.
+    #[test]
+    fn test_counters() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+        for _ in 0..3 {
+            gold.step(0x0000_0013, 0, false); // nop
+        }
+
+        let res = gold.step(csr_instr(0b010, 5, 0, CSR_CYCLE), 0, false); // rdcycle x5
+        assert_eq!(res.rd_write.map(|w| w.data), Some(3));
+        assert_eq!((gold.csr.cycles(), gold.csr.instret()), (4, 4));
+
+        // A write to minstret wins over the writing instruction's own increment
+        gold.step(0xFFF0_0093, 0, false); // addi x1, x0, -1
+        let res = gold.step(csr_instr(0b001, 0, 1, CSR_MINSTRET), 0, false);
+        assert_eq!(res.csr_write, Some(CsrWrite { addr: CSR_MINSTRET, data: 0xFFFF_FFFF }));
+        gold.step(0x0000_0013, 0, false);
+        assert_eq!(gold.csr.read(CSR_MINSTRETH), 1);
+        assert_eq!(gold.csr.read(CSR_INSTRET), 0);
+        assert_eq!(gold.csr.read(CSR_MCYCLE), 7);
+    }
+
+    /// WARL fields return legal values only.
+    #[test]
+    fn test_warl_fields() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+        gold.step(0xFFF0_0093, 0, false); // addi x1, x0, -1
+
+        for csr in [CSR_MSTATUS, CSR_MTVEC, CSR_MEPC, CSR_MISA] {
+            gold.step(csr_instr(0b001, 0, 1, csr), 0, false);
+        }
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE | MSTATUS_MIE);
+        assert_eq!(gold.csr.read(CSR_MTVEC), 0xFFFF_FFFC);
+        assert_eq!(gold.csr.read(CSR_MEPC), 0xFFFF_FFFC);
+        assert_eq!(gold.csr.read(CSR_MISA), 0x4000_0100);
+
+        // Vectored mode is kept, csrrci clears MIE only
+        gold.step(csr_instr(0b101, 0, 0b01, CSR_MTVEC), 0, false); // csrrwi mtvec, 1
+        gold.step(csr_instr(0b111, 0, 0b01000, CSR_MSTATUS), 0, false); // csrrci mstatus, 8
+        assert_eq!(gold.csr.read(CSR_MTVEC), 1);
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE);
+    }
+
+    /// Writes to read-only or unknown CSRs trap; reads of read-only CSRs do not.
+    #[test]
+    fn test_illegal_csr_access() {
+        let cfg = IsaConfig::rv32i();
+        assert!(classify_instr(csr_instr(0b010, 1, 0, CSR_MHARTID), &cfg).is_ok());
+        assert_eq!(classify_instr(csr_instr(0b011, 1, 1, CSR_CYCLE), &cfg), Err(IllegalReason::Csr));
+        assert_eq!(classify_instr(csr_instr(0b001, 1, 0, 0x7C0), &cfg), Err(IllegalReason::Csr));
+        assert_eq!(classify_instr(csr_instr(0b100, 1, 0, CSR_MSCRATCH), &cfg), Err(IllegalReason::Funct3));
+
+        let mut gold = Lx32System::with_config(cfg);
+        gold.step(csr_instr(0b101, 0, 0b10000, CSR_MTVEC), 0, false); // csrrwi mtvec, 16
+        gold.step(csr_instr(0b110, 0, 0b01000, CSR_MSTATUS), 0, false); // csrrsi mstatus, 8
+        let instr = csr_instr(0b101, 2, 3, CSR_MHARTID);
+        let res = gold.step(instr, 0, false);
+
+        let expected = Trap {
+            cause: trap_cause_e::ILLEGAL_INSTRUCTION,
+            pc: 8,
+            tval: instr,
+        };
+        assert_eq!(res.status, StepStatus::Trap(expected));
+        assert_eq!(res.rd_write, None);
+        assert_eq!(gold.pc, 16);
+        assert_eq!((gold.csr.read(CSR_MEPC), gold.csr.read(CSR_MCAUSE), gold.csr.read(CSR_MTVAL)), (8, 2, instr));
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE);
+        assert_eq!((gold.csr.cycles(), gold.csr.instret()), (3, 2));
+    }
+
+    /// The base profile keeps SYSTEM as an RTL NOP.
+    #[test]
+    fn test_base_profile_has_no_csrs() {
+        let mut gold = Lx32System::new();
+        let res = gold.step(csr_instr(0b010, 5, 0, CSR_MISA), 0, false);
+        assert_eq!(res.status, StepStatus::Illegal);
+        assert_eq!((res.rd_write, res.csr_write), (None, None));
+    }
+}
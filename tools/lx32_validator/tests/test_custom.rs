@@ -0,0 +1,347 @@
+// tests/test_custom.rs
+//
+// Custom instruction extension point validation for the golden model.
+//
+// The RTL decodes neither custom opcode, so this suite is golden-only. Three
+// example accelerator instructions are registered through
+// `IsaConfig::with_custom`: the program generator emits them with their own
+// strategies, and the golden model must retire each one with the result of
+// an independent reference, decode it as legal and re-encode it from its
+// register fields.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{data_t, instr_t, reg_idx_t};
+use lx32_validator::models::arch::lx32_isa_pkg::opcode_t;
+use lx32_validator::models::core::custom::{CustomInstruction, encode_custom, rd_field, rs1_field, rs2_field};
+use lx32_validator::models::core::decoder::is_legal_instr;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{RegWrite, StepStatus};
+use lx32_validator::program_generator::{Program, ProgramConfig};
+use rand::{Rng, RngExt, SeedableRng};
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "custom";
+
+/// hdist rd, rs1, rs2 (custom-0, funct3 = 000): Hamming distance of rs1 and rs2.
+pub struct HammingDistance;
+
+impl CustomInstruction for HammingDistance {
+    fn opcode(&self) -> opcode_t {
+        opcode_t::OP_CUSTOM_0
+    }
+
+    fn name(&self) -> &'static str {
+        "hdist"
+    }
+
+    fn decode(&self, instr: instr_t) -> bool {
+        instr >> 25 == 0 && (instr >> 12) & 0x7 == 0b000
+    }
+
+    fn execute(&self, _instr: instr_t, rs1: data_t, rs2: data_t) -> data_t {
+        (rs1 ^ rs2).count_ones()
+    }
+
+    fn encode(&self, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> instr_t {
+        encode_custom(opcode_t::OP_CUSTOM_0, 0, 0b000, rd, rs1, rs2)
+    }
+}
+
+/// sadd rd, rs1, rs2 (custom-1, funct3 = 001): signed saturating add.
+/// The generator favours x1-x3 so results feed later instructions.
+pub struct SaturatingAdd;
+
+impl CustomInstruction for SaturatingAdd {
+    fn opcode(&self) -> opcode_t {
+        opcode_t::OP_CUSTOM_1
+    }
+
+    fn name(&self) -> &'static str {
+        "sadd"
+    }
+
+    fn decode(&self, instr: instr_t) -> bool {
+        instr >> 25 == 0 && (instr >> 12) & 0x7 == 0b001
+    }
+
+    fn execute(&self, _instr: instr_t, rs1: data_t, rs2: data_t) -> data_t {
+        (rs1 as i32).saturating_add(rs2 as i32) as data_t
+    }
+
+    fn encode(&self, rd: reg_idx_t, rs1: reg_idx_t, rs2: reg_idx_t) -> instr_t {
+        encode_custom(opcode_t::OP_CUSTOM_1, 0, 0b001, rd, rs1, rs2)
+    }
+
+    fn mnemonic(&self, instr: instr_t) -> String {
+        format!("sadd.sat x{}, x{}, x{}", rd_field(instr), rs1_field(instr), rs2_field(instr))
+    }
+
+    fn generate(&self, rng: &mut dyn Rng, _reg_count: usize) -> instr_t {
+        self.encode(rng.random_range(1..4), rng.random_range(0..4), rng.random_range(0..4))
+    }
+}
+
+/// rori rd, rs1, shamt (custom-0, funct3 = 101): rotate right by an
+/// immediate held in the rs2 field.
+pub struct RotateImm;
+
+impl CustomInstruction for RotateImm {
+    fn opcode(&self) -> opcode_t {
+        opcode_t::OP_CUSTOM_0
+    }
+
+    fn name(&self) -> &'static str {
+        "rori"
+    }
+
+    fn decode(&self, instr: instr_t) -> bool {
+        instr >> 25 == 0 && (instr >> 12) & 0x7 == 0b101
+    }
+
+    fn execute(&self, instr: instr_t, rs1: data_t, _rs2: data_t) -> data_t {
+        rs1.rotate_right(rs2_field(instr) as u32)
+    }
+
+    fn encode(&self, rd: reg_idx_t, rs1: reg_idx_t, shamt: reg_idx_t) -> instr_t {
+        encode_custom(opcode_t::OP_CUSTOM_0, 0, 0b101, rd, rs1, shamt)
+    }
+
+    fn mnemonic(&self, instr: instr_t) -> String {
+        format!("rori x{}, x{}, {}", rd_field(instr), rs1_field(instr), rs2_field(instr))
+    }
+
+    fn register_fields(&self, _instr: instr_t) -> [bool; 3] {
+        [true, true, false]
+    }
+
+    fn generate(&self, rng: &mut dyn Rng, reg_count: usize) -> instr_t {
+        let count = reg_count as reg_idx_t;
+        self.encode(rng.random_range(1..count), rng.random_range(0..count), rng.random_range(0..32))
+    }
+}
+
+pub static EXTENSIONS: [&dyn CustomInstruction; 3] = [&HammingDistance, &SaturatingAdd, &RotateImm];
+
+/// Reference results, written independently of the implementations above.
+/// For `rori`, `b` is the shift amount.
+fn reference(encoding: instr_t, a: data_t, b: data_t) -> Option<data_t> {
+    match (encoding & 0x7F, (encoding >> 12) & 0x7) {
+        (0b0001011, 0b000) => Some((0..32).filter(|i| (a >> i) & 1 != (b >> i) & 1).count() as data_t),
+        (0b0101011, 0b001) => {
+            let sum = a as i32 as i64 + b as i32 as i64;
+            Some(sum.clamp(i32::MIN as i64, i32::MAX as i64) as i32 as data_t)
+        }
+        (0b0001011, 0b101) => Some((a >> b) | a.checked_shl(32 - b).unwrap_or(0)),
+        _ => None,
+    }
+}
+
+pub struct CustomTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for CustomTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            enable_logging: false,
+        }
+    }
+}
+
+pub fn run_custom_fuzzer(params: CustomTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING CUSTOM INSTRUCTION FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let cfg = IsaConfig::rv32i().with_custom(&EXTENSIONS);
+    let mut gold = Lx32System::with_config(cfg);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    // Custom instructions only: every other category is off
+    let config = ProgramConfig {
+        length: params.iterations as usize,
+        enable_branches: false,
+        enable_loads: false,
+        enable_stores: false,
+        enable_alu: false,
+        enable_jumps: false,
+        enable_upper_imm: false,
+        custom: cfg.custom,
+        ..ProgramConfig::default()
+    };
+    let program = Program::generate(config, seed);
+
+    for (i, instr) in program.instructions.iter().enumerate() {
+        // Small values around the saturation points as well as random words
+        for reg in 1..32u8 {
+            let value = match rng.random_range(0..4) {
+                0 => i32::MAX as u32 - rng.random_range(0..4),
+                1 => i32::MIN as u32 + rng.random_range(0..4),
+                _ => rng.random(),
+            };
+            gold.reg_file.tick(false, reg, value, true);
+        }
+        // rori holds its shift amount in the rs2 field
+        let (rd, rs1) = (instr.rd.unwrap(), instr.rs1.unwrap());
+        let rs2 = instr.rs2.unwrap_or(rs2_field(instr.encoding));
+        let a = gold.reg_file.read_rs1(rs1);
+        let b = if instr.rs2.is_some() { gold.reg_file.read_rs2(rs2) } else { rs2 as data_t };
+        let expected = reference(instr.encoding, a, b).map(|data| RegWrite { rd, data }).filter(|_| rd != 0);
+
+        let registered = cfg.custom.find(instr.encoding);
+        let reencoded = registered.map(|c| c.encode(rd, rs1, rs2));
+        let res = gold.step(instr.encoding, 0, false);
+
+        let matches = res.status == StepStatus::Retired
+            && res.ctrl.custom
+            && res.rd_write == expected
+            && reencoded == Some(instr.encoding)
+            && is_legal_instr(instr.encoding, &cfg);
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] {:<24} a:0x{:08x} b:0x{:08x} -> {:?} | {}",
+                i,
+                instr.mnemonic,
+                a,
+                b,
+                res.rd_write.map(|w| w.data),
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " CUSTOM INSTRUCTION MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} ({}) a=0x{:08x} b=0x{:08x}", instr.encoding, instr.mnemonic, a, b);
+            println!("Expected: rd_write={:?} re-encoding=0x{:08x}", expected, instr.encoding);
+            println!("Actual:   rd_write={:?} re-encoding={:x?}", res.rd_write, reencoded);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i as u32);
+            panic!("🔥 CUSTOM INSTRUCTION TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("{:=^100}", " CUSTOM INSTRUCTION FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+    use lx32_validator::models::core::decoder::{IllegalReason, classify_instr};
+    use lx32_validator::models::core::lx32_system::Trap;
+
+    #[test]
+    fn test_custom_default() {
+        run_custom_fuzzer(CustomTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// A word under a claimed opcode that no instruction decodes is illegal;
+    /// without registrations both opcodes are unknown, like the RTL.
+    #[test]
+    fn test_unclaimed_encodings() {
+        let cfg = IsaConfig::rv32i().with_custom(&EXTENSIONS);
+        let hdist_f7 = encode_custom(opcode_t::OP_CUSTOM_0, 1, 0b000, 3, 1, 2);
+        assert_eq!(classify_instr(hdist_f7, &cfg), Err(IllegalReason::Custom));
+
+        let mut gold = Lx32System::with_config(cfg);
+        let res = gold.step(hdist_f7, 0, false);
+        assert!(matches!(res.status, StepStatus::Trap(Trap { cause: trap_cause_e::ILLEGAL_INSTRUCTION, .. })));
+
+        let hdist = HammingDistance.encode(3, 1, 2);
+        assert_eq!(classify_instr(hdist, &IsaConfig::rv32i()), Err(IllegalReason::Opcode));
+        let mut base = Lx32System::new();
+        base.reg_file.tick(false, 1, 0xFF, true);
+        let res = base.step(hdist, 0, false);
+        assert_eq!((res.status, res.rd_write, res.ctrl.custom), (StepStatus::Illegal, None, false));
+
+        // Registered but rejected, traps off: a NOP
+        let mut gold = Lx32System::with_config(IsaConfig::base().with_custom(&EXTENSIONS));
+        let res = gold.step(hdist_f7, 0, false);
+        assert_eq!((res.status, res.rd_write), (StepStatus::Illegal, None));
+    }
+
+    /// Custom instructions mix with the base categories in generated programs
+    /// and run from memory like any other instruction.
+    #[test]
+    fn test_mixed_program() {
+        let cfg = IsaConfig::base().with_custom(&EXTENSIONS);
+        let config = ProgramConfig {
+            length: 300,
+            enable_branches: false,
+            enable_jumps: false,
+            enable_loads: false,
+            enable_stores: false,
+            custom: cfg.custom,
+            ..ProgramConfig::default()
+        };
+        let program = Program::generate(config, DEFAULT_TEST_SEED);
+        let custom = program.instructions.iter().filter(|i| cfg.custom.find(i.encoding).is_some()).count();
+        assert!(custom > 0 && custom < program.instructions.len());
+        assert!(program.display().contains("sadd.sat x"));
+        assert!(program.display().contains("hdist x"));
+        assert!(program.display().contains("rori x"));
+
+        let mut gold = Lx32System::with_config(cfg);
+        gold.load_image(0, &program.to_bytes());
+        let summary = gold.run(program.instructions.len() as u64, |res| res.status != StepStatus::Retired);
+        assert!(!summary.halted);
+        assert_eq!(gold.pc, 4 * program.instructions.len() as u32);
+    }
+
+    /// Sets compare by registration and print their instruction names.
+    #[test]
+    fn test_custom_set() {
+        let cfg = IsaConfig::base().with_custom(&EXTENSIONS);
+        assert_eq!(cfg, IsaConfig::base().with_custom(&EXTENSIONS));
+        assert_ne!(cfg, IsaConfig::base());
+        assert!(cfg.custom.claims(opcode_t::OP_CUSTOM_1));
+        assert!(!IsaConfig::base().with_custom(&EXTENSIONS[..1]).custom.claims(opcode_t::OP_CUSTOM_1));
+        assert_eq!(format!("{:?}", cfg.custom), r#"["hdist", "sadd", "rori"]"#);
+    }
+
+    /// Under RV32E only the fields an instruction reports as registers must
+    /// name x0-x15, and the generator keeps those within the 16 registers
+    /// without touching immediates.
+    #[test]
+    fn test_rv32e_register_fields() {
+        let cfg = IsaConfig::rv32i().with_rv32e().with_custom(&EXTENSIONS);
+        assert!(is_legal_instr(RotateImm.encode(1, 2, 20), &cfg));
+        assert_eq!(classify_instr(RotateImm.encode(16, 2, 20), &cfg), Err(IllegalReason::Register));
+        assert_eq!(classify_instr(HammingDistance.encode(1, 2, 20), &cfg), Err(IllegalReason::Register));
+
+        let mut gold = Lx32System::with_config(cfg);
+        gold.reg_file.tick(false, 2, 0x0000_0003, true);
+        let res = gold.step(RotateImm.encode(1, 2, 20), 0, false);
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 1, data: 0x0000_3000 }));
+
+        let config = ProgramConfig {
+            length: 1000,
+            enable_branches: false,
+            enable_loads: false,
+            enable_stores: false,
+            enable_alu: false,
+            enable_jumps: false,
+            enable_upper_imm: false,
+            custom: cfg.custom,
+            reg_count: cfg.reg_count(),
+            ..ProgramConfig::default()
+        };
+        let program = Program::generate(config, DEFAULT_TEST_SEED);
+        let mut wide_shamt = false;
+        for instr in &program.instructions {
+            assert!(is_legal_instr(instr.encoding, &cfg), "{}", instr.mnemonic);
+            assert_ne!(instr.rd, Some(0), "{}", instr.mnemonic);
+            if RotateImm.decode(instr.encoding) && instr.encoding & 0x7F == opcode_t::OP_CUSTOM_0 as u32 {
+                assert_eq!(instr.rs2, None);
+                wide_shamt |= rs2_field(instr.encoding) >= 16;
+            }
+        }
+        assert!(wide_shamt);
+    }
+}
@@ -0,0 +1,205 @@
+// tests/test_disasm.rs
+//
+// Disassembler validation.
+//
+// Random words over every major opcode are disassembled for the full golden
+// profile. A word must print as an instruction exactly when the strict
+// decoder accepts it, a compressed parcel must print like its expansion, and
+// the ABI-named text must name the same registers as the numeric one.
+// Directed tests pin the exact text of every instruction class.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_rvc_pkg::{expand_compressed, is_compressed};
+use lx32_validator::models::core::decoder::is_legal_instr;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::disasm::{Disassembler, F_ABI_NAMES, X_ABI_NAMES};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "disasm";
+
+pub struct DisasmTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for DisasmTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 20000,
+            enable_logging: true,
+        }
+    }
+}
+
+/// Every 32-bit major opcode the golden model decodes.
+const OPCODES: [u32; 19] = [
+    0x37, 0x17, 0x6F, 0x67, 0x63, 0x03, 0x23, 0x13, 0x33, 0x0F, 0x2F, 0x07, 0x27, 0x53, 0x43, 0x47, 0x4B, 0x4F, 0x73,
+];
+
+fn random_word(rng: &mut StdRng) -> u32 {
+    let word: u32 = rng.random();
+    match rng.random_range(0..4) {
+        // Compressed parcel (upper half ignored)
+        0 => word & 0xFFFF,
+        1 => word,
+        // Known opcode with mostly-zero funct7, so OP/OP-IMM/OP-FP forms show up
+        2 => (word & !0x7F & !(0x7C << 25)) | OPCODES[rng.random_range(0..OPCODES.len())],
+        _ => (word & !0x7F) | OPCODES[rng.random_range(0..OPCODES.len())],
+    }
+}
+
+/// `text` with every ABI register name replaced by its numeric name.
+fn numeric_names(text: &str) -> String {
+    let mut out = String::new();
+    let mut token = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() || c == '.' {
+            token.push(c);
+            continue;
+        }
+        match (X_ABI_NAMES.iter().position(|n| *n == token), F_ABI_NAMES.iter().position(|n| *n == token)) {
+            (Some(idx), _) if !out.is_empty() => out.push_str(&format!("x{}", idx)),
+            (_, Some(idx)) if !out.is_empty() => out.push_str(&format!("f{}", idx)),
+            _ => out.push_str(&token),
+        }
+        token.clear();
+        out.push(c);
+    }
+    out.pop();
+    out
+}
+
+pub fn run_disasm_fuzzer(params: DisasmTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING DISASSEMBLER FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let cfg = IsaConfig::full();
+    let numeric = Disassembler::new(cfg);
+    let abi = numeric.with_abi_names();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut legal_count = 0;
+
+    for i in 0..params.iterations {
+        let instr = random_word(&mut rng);
+        let pc = rng.random::<u32>() & !1;
+
+        let legal = is_legal_instr(instr, &cfg);
+        let text = numeric.disasm(instr, pc);
+        let abi_text = abi.disasm(instr, pc);
+        let expanded_text = if is_compressed(instr) { expand_compressed(instr as u16, cfg.f_ext).map(|e| numeric.disasm(e, pc)) } else { None };
+        legal_count += legal as u32;
+
+        if params.enable_logging {
+            println!("[{:>5}] 0x{:08x} @ 0x{:08x} | {:<32} | {}", i, instr, pc, text, abi_text);
+        }
+
+        let matches = legal != text.starts_with(".word")
+            && numeric_names(&abi_text) == text
+            && expanded_text.as_ref().is_none_or(|expanded| *expanded == text);
+
+        if !matches {
+            println!("\n{:=^100}", " DISASSEMBLER MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} at pc 0x{:08x} (legal: {})", instr, pc, legal);
+            println!("Numeric:   {}", text);
+            println!("ABI:       {}", abi_text);
+            println!("Expansion: {:?}", expanded_text);
+            print_replay(SUITE, seed, i);
+            panic!("Disassembler test failed at iteration {}", i);
+        }
+    }
+
+    println!("Disassembler fuzzer passed ({} legal words)", legal_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::disasm::csr_name;
+
+    #[test]
+    fn test_disasm_default() {
+        run_disasm_fuzzer(
+            DisasmTestParams {
+                enable_logging: false,
+                ..DisasmTestParams::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
+    }
+
+    /// Exact text of every instruction class.
+    #[test]
+    fn test_disasm_text() {
+        let cases = [
+            (0x0050_0093, "addi x1, x0, 5"),
+            (0x4020_81B3, "sub x3, x1, x2"),
+            (0x4030_D093, "srai x1, x1, 3"),
+            (0x1234_52B7, "lui x5, 0x12345"),
+            (0x0000_8067, "jalr x0, 0(x1)"),
+            (0x0080_00EF, "jal x1, 0x108"),
+            (0xFE20_8EE3, "beq x1, x2, 0xfc"),
+            (0xFF81_2303, "lw x6, -8(x2)"),
+            (0x0061_2623, "sw x6, 12(x2)"),
+            (0x0220_81B3, "mul x3, x1, x2"),
+            (0x2020_A1B3, "sh1add x3, x1, x2"),
+            (0x6001_1093, "clz x1, x2"),
+            (0x6071_5093, "rori x1, x2, 7"),
+            (0x0FF0_000F, "fence iorw, iorw"),
+            (0x1005_22AF, "lr.w x5, (x10)"),
+            (0x0665_22AF, "amoadd.w.aqrl x5, x6, (x10)"),
+            (0x0041_2087, "flw f1, 4(x2)"),
+            (0x0011_2427, "fsw f1, 8(x2)"),
+            (0x0031_10D3, "fadd.s f1, f2, f3, rtz"),
+            (0x0031_70D3, "fadd.s f1, f2, f3"),
+            (0x2031_00C3, "fmadd.s f1, f2, f3, f4, rne"),
+            (0xA020_A553, "feq.s x10, f1, f2"),
+            (0xD005_70D3, "fcvt.s.w f1, x10"),
+            (0x3000_22F3, "csrrs x5, mstatus, x0"),
+            (0x3050_D073, "csrrwi x0, mtvec, 1"),
+            (0x0000_0073, "ecall"),
+            (0x3020_0073, "mret"),
+            (0x1200_0073, "sfence.vma x0, x0"),
+            (0x0000_0085, "addi x1, x1, 1"), // c.addi x1, 1
+            (0xFFFF_FFFF, ".word 0xffffffff"),
+            (0x0000_0000, ".word 0x00000000"),
+            (0x0000_100F, ".word 0x0000100f"), // fence.i is not implemented
+        ];
+        for (instr, expected) in cases {
+            assert_eq!(disasm(instr, 0x100), expected, "0x{:08x}", instr);
+        }
+    }
+
+    /// ABI names, and a profile without the extensions a word needs.
+    #[test]
+    fn test_disasm_options() {
+        let abi = Disassembler::default().with_abi_names();
+        assert_eq!(abi.disasm(0x0050_0093, 0), "addi ra, zero, 5");
+        assert_eq!(abi.disasm(0x0000_8067, 0), "jalr zero, 0(ra)");
+        assert_eq!(abi.disasm(0x0031_10D3, 0), "fadd.s ft1, ft2, ft3, rtz");
+        assert_eq!(abi.disasm(0xA020_A553, 0), "feq.s a0, ft1, ft2");
+
+        let base = Disassembler::new(IsaConfig::base());
+        assert_eq!(base.disasm(0x0220_81B3, 0), ".word 0x022081b3");
+        assert_eq!(base.disasm(0x0000_0085, 0), ".word 0x00000085");
+        assert_eq!(base.disasm(0x4020_81B3, 0), "sub x3, x1, x2");
+
+        let rv32e = Disassembler::new(IsaConfig::rv32i().with_rv32e());
+        assert_eq!(rv32e.disasm(0x0050_0893, 0), ".word 0x00500893"); // addi x17, x0, 5
+    }
+
+    #[test]
+    fn test_csr_names() {
+        assert_eq!(csr_name(0x300), "mstatus");
+        assert_eq!(csr_name(0x003), "fcsr");
+        assert_eq!(csr_name(0x3A1), "pmpcfg1");
+        assert_eq!(csr_name(0x3B5), "pmpaddr5");
+        assert_eq!(csr_name(0x7C0), "0x7c0");
+        assert_eq!(disasm(0x7C00_22F3, 0), ".word 0x7c0022f3"); // unimplemented CSR
+    }
+}
@@ -0,0 +1,732 @@
+// tests/test_fpu.rs
+//
+// Single-precision floating-point (F extension) validation for the golden model.
+//
+// The RTL has no FPU yet, so this suite is golden-only. Random RV32F
+// instructions run through `Lx32System` with operands mixing random bit
+// patterns, special values and near-cancelling pairs, under a random static
+// or dynamic rounding mode. Each result and its exception flags are checked
+// against a reference built on host f64 arithmetic: the exact result (or an
+// f64 approximation plus the sign of its error) is rounded to binary32
+// independently of the model's soft-float code.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{data_t, instr_t};
+use lx32_validator::models::arch::lx32_csr_pkg::{CSR_FFLAGS, CSR_FRM};
+use lx32_validator::models::arch::lx32_fpu_pkg::*;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "fpu";
+
+pub struct FpuTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for FpuTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 20000,
+            enable_logging: false,
+        }
+    }
+}
+
+const SIGN: u32 = 0x8000_0000;
+const QNAN: u32 = 0x7FC0_0000;
+const SNAN: u32 = 0x7F80_0001;
+const INF: u32 = 0x7F80_0000;
+const ONE: u32 = 0x3F80_0000;
+const TWO: u32 = 0x4000_0000;
+const MAX: u32 = 0x7F7F_FFFF;
+const MIN_NORMAL: u32 = 0x0080_0000;
+const MIN_SUBNORMAL: u32 = 0x0000_0001;
+
+/// Operands the fuzzer draws besides random bit patterns
+const SPECIALS: [u32; 15] = [
+    0,
+    SIGN,
+    INF,
+    INF | SIGN,
+    QNAN,
+    SNAN,
+    ONE,
+    ONE | SIGN,
+    TWO,
+    MAX,
+    MIN_NORMAL,
+    MIN_SUBNORMAL,
+    0x007F_FFFF, // Largest subnormal
+    0x4F00_0000, // 2^31
+    0x4F80_0000, // 2^32
+];
+
+/// Registers the fuzzer uses: sources f1/x1, f2, f3; destination f10/x10
+const RS1: u32 = 1;
+const RS2: u32 = 2;
+const RS3: u32 = 3;
+const RD: u32 = 10;
+
+const OPS: [(fp_op_e, &str); 24] = [
+    (fp_op_e::FP_ADD, "fadd.s"),
+    (fp_op_e::FP_SUB, "fsub.s"),
+    (fp_op_e::FP_MUL, "fmul.s"),
+    (fp_op_e::FP_DIV, "fdiv.s"),
+    (fp_op_e::FP_SQRT, "fsqrt.s"),
+    (fp_op_e::FP_MADD, "fmadd.s"),
+    (fp_op_e::FP_MSUB, "fmsub.s"),
+    (fp_op_e::FP_NMSUB, "fnmsub.s"),
+    (fp_op_e::FP_NMADD, "fnmadd.s"),
+    (fp_op_e::FP_SGNJ, "fsgnj.s"),
+    (fp_op_e::FP_SGNJN, "fsgnjn.s"),
+    (fp_op_e::FP_SGNJX, "fsgnjx.s"),
+    (fp_op_e::FP_MIN, "fmin.s"),
+    (fp_op_e::FP_MAX, "fmax.s"),
+    (fp_op_e::FP_EQ, "feq.s"),
+    (fp_op_e::FP_LT, "flt.s"),
+    (fp_op_e::FP_LE, "fle.s"),
+    (fp_op_e::FP_CLASS, "fclass.s"),
+    (fp_op_e::FP_CVT_W_S, "fcvt.w.s"),
+    (fp_op_e::FP_CVT_WU_S, "fcvt.wu.s"),
+    (fp_op_e::FP_CVT_S_W, "fcvt.s.w"),
+    (fp_op_e::FP_CVT_S_WU, "fcvt.s.wu"),
+    (fp_op_e::FP_MV_X_W, "fmv.x.w"),
+    (fp_op_e::FP_MV_W_X, "fmv.w.x"),
+];
+
+/// Encodes `op` (spec opcode map) with rd = x10/f10, rs1 = x1/f1, rs2 = f2,
+/// rs3 = f3 and `rm` in funct3 when the operation takes a rounding mode.
+pub fn fp_instr(op: fp_op_e, rm: u32) -> instr_t {
+    let r4 = |opcode: u32| RS3 << 27 | RS2 << 20 | RS1 << 15 | rm << 12 | RD << 7 | opcode;
+    let op_fp = |funct5: u32, rs2: u32, funct3: u32| funct5 << 27 | rs2 << 20 | RS1 << 15 | funct3 << 12 | RD << 7 | 0b1010011;
+    match op {
+        fp_op_e::FP_MADD => r4(0b1000011),
+        fp_op_e::FP_MSUB => r4(0b1000111),
+        fp_op_e::FP_NMSUB => r4(0b1001011),
+        fp_op_e::FP_NMADD => r4(0b1001111),
+        fp_op_e::FP_ADD => op_fp(0b00000, RS2, rm),
+        fp_op_e::FP_SUB => op_fp(0b00001, RS2, rm),
+        fp_op_e::FP_MUL => op_fp(0b00010, RS2, rm),
+        fp_op_e::FP_DIV => op_fp(0b00011, RS2, rm),
+        fp_op_e::FP_SQRT => op_fp(0b01011, 0, rm),
+        fp_op_e::FP_SGNJ => op_fp(0b00100, RS2, 0b000),
+        fp_op_e::FP_SGNJN => op_fp(0b00100, RS2, 0b001),
+        fp_op_e::FP_SGNJX => op_fp(0b00100, RS2, 0b010),
+        fp_op_e::FP_MIN => op_fp(0b00101, RS2, 0b000),
+        fp_op_e::FP_MAX => op_fp(0b00101, RS2, 0b001),
+        fp_op_e::FP_EQ => op_fp(0b10100, RS2, 0b010),
+        fp_op_e::FP_LT => op_fp(0b10100, RS2, 0b001),
+        fp_op_e::FP_LE => op_fp(0b10100, RS2, 0b000),
+        fp_op_e::FP_CLASS => op_fp(0b11100, 0, 0b001),
+        fp_op_e::FP_CVT_W_S => op_fp(0b11000, 0, rm),
+        fp_op_e::FP_CVT_WU_S => op_fp(0b11000, 1, rm),
+        fp_op_e::FP_CVT_S_W => op_fp(0b11010, 0, rm),
+        fp_op_e::FP_CVT_S_WU => op_fp(0b11010, 1, rm),
+        fp_op_e::FP_MV_X_W => op_fp(0b11100, 0, 0b000),
+        fp_op_e::FP_MV_W_X => op_fp(0b11110, 0, 0b000),
+    }
+}
+
+// ------------------------------------------------------------
+// Reference rounding on host f64
+// ------------------------------------------------------------
+
+/// Largest binary32 magnitude not above |hi + lo|, where hi >= 0 is the f64
+/// nearest the exact value and lo only contributes its sign.
+fn floor_mag(hi: f64, lo: f64) -> f32 {
+    let r = hi as f32;
+    if (r as f64) > hi || (r as f64 == hi && lo < 0.0) { r.next_down() } else { r }
+}
+
+/// hi + lo (|lo| at most half an f64 ulp of hi, hi >= 0) rounded to a binary32
+/// magnitude; `up`/`down` are the directed modes as seen on the magnitude.
+fn round_mag(hi: f64, lo: f64, rm: round_mode_e, negative: bool) -> f32 {
+    let lower = floor_mag(hi, lo);
+    if lower as f64 == hi && lo == 0.0 {
+        return lower;
+    }
+    let upper = lower.next_up();
+    // The midpoint above MAX is 2^128 - 2^103
+    let ulp = if upper.is_infinite() { 2f64.powi(104) } else { upper as f64 - lower as f64 };
+    let mid = lower as f64 + ulp / 2.0;
+    let above_mid = if hi != mid { hi > mid } else { lo > 0.0 };
+    let tie = hi == mid && lo == 0.0;
+    match rm {
+        round_mode_e::RM_RTZ => lower,
+        round_mode_e::RM_RDN => if negative { upper } else { lower },
+        round_mode_e::RM_RUP => if negative { lower } else { upper },
+        round_mode_e::RM_RNE if tie => if lower.to_bits() & 1 == 0 { lower } else { upper },
+        round_mode_e::RM_RMM if tie => upper,
+        _ => if above_mid { upper } else { lower },
+    }
+}
+
+/// Rounds the non-zero value hi + lo to binary32 under `rm` and derives
+/// NX/UF/OF: tininess is checked after rounding, on the value rounded to
+/// 24 bits with an unbounded exponent (computed 2^64 times larger).
+fn round_ref(hi: f64, lo: f64, rm: round_mode_e) -> (u32, u8) {
+    let negative = hi < 0.0 || (hi == 0.0 && lo < 0.0);
+    let (hi, lo) = if negative { (-hi, -lo) } else { (hi, lo) };
+    let mag = round_mag(hi, lo, rm, negative);
+    let sign = if negative { SIGN } else { 0 };
+
+    let inexact = !(mag as f64 == hi && lo == 0.0);
+    let mut flags = if inexact { FFLAG_NX } else { 0 };
+    if mag.is_infinite() || hi >= 2f64.powi(128) {
+        flags |= FFLAG_OF;
+    }
+    let scale = 2f64.powi(64);
+    let tiny = round_mag(hi * scale, lo * scale, rm, negative) < 2f32.powi(-126 + 64);
+    if tiny && inexact {
+        flags |= FFLAG_UF;
+    }
+    (sign | mag.to_bits(), flags)
+}
+
+/// Exact a + b as an unevaluated f64 pair (TwoSum).
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+fn is_nan(x: u32) -> bool {
+    f32::from_bits(x).is_nan()
+}
+
+fn is_snan(x: u32) -> bool {
+    is_nan(x) && x & 0x0040_0000 == 0
+}
+
+/// Canonical NaN, with invalid raised for signaling NaN operands or `invalid`.
+fn nan_ref(operands: &[u32], invalid: bool) -> (u32, u8) {
+    let nv = invalid || operands.iter().any(|&x| is_snan(x));
+    (QNAN, if nv { FFLAG_NV } else { 0 })
+}
+
+/// a + b with its rounding and the sign rules of exact zero sums.
+fn add_ref(a: f64, b: f64, rm: round_mode_e) -> (u32, u8) {
+    if a.is_infinite() || b.is_infinite() {
+        let s = a + b;
+        return if s.is_nan() { (QNAN, FFLAG_NV) } else { ((s as f32).to_bits(), 0) };
+    }
+    let (hi, lo) = two_sum(a, b);
+    if hi == 0.0 && lo == 0.0 {
+        let negative = if a.is_sign_negative() == b.is_sign_negative() { a.is_sign_negative() } else { rm == round_mode_e::RM_RDN };
+        return (if negative { SIGN } else { 0 }, 0);
+    }
+    round_ref(hi, lo, rm)
+}
+
+/// Reference result of `op` and the flags it raises.
+fn fp_reference(op: fp_op_e, a: u32, b: u32, c: u32, x: data_t, rm: round_mode_e) -> (u32, u8) {
+    let (fa, fb, fc) = (f32::from_bits(a) as f64, f32::from_bits(b) as f64, f32::from_bits(c) as f64);
+    let any_nan = |ops: &[u32]| ops.iter().any(|&v| is_nan(v));
+    let bool_ref = |v: bool| (v as u32, 0);
+
+    match op {
+        fp_op_e::FP_ADD | fp_op_e::FP_SUB if any_nan(&[a, b]) => nan_ref(&[a, b], false),
+        fp_op_e::FP_ADD => add_ref(fa, fb, rm),
+        fp_op_e::FP_SUB => add_ref(fa, -fb, rm),
+
+        fp_op_e::FP_MUL if any_nan(&[a, b]) => nan_ref(&[a, b], false),
+        fp_op_e::FP_MUL => {
+            // The product of two binary32 values is exact in f64
+            let p = fa * fb;
+            match p {
+                _ if p.is_nan() => (QNAN, FFLAG_NV),
+                _ if p == 0.0 || p.is_infinite() => ((p as f32).to_bits(), 0),
+                _ => round_ref(p, 0.0, rm),
+            }
+        }
+
+        fp_op_e::FP_DIV if any_nan(&[a, b]) => nan_ref(&[a, b], false),
+        fp_op_e::FP_DIV => {
+            let q = fa / fb;
+            match q {
+                _ if q.is_nan() => (QNAN, FFLAG_NV),
+                _ if fb == 0.0 && fa.is_finite() => ((q as f32).to_bits(), FFLAG_DZ),
+                _ if q == 0.0 || q.is_infinite() => ((q as f32).to_bits(), 0),
+                // The remainder of the f64 division is exact and gives the error's sign
+                _ => round_ref(q, (-q).mul_add(fb, fa) / fb, rm),
+            }
+        }
+
+        fp_op_e::FP_SQRT if is_nan(a) => nan_ref(&[a], false),
+        fp_op_e::FP_SQRT => match fa {
+            _ if fa == 0.0 => (a, 0),
+            _ if fa < 0.0 => (QNAN, FFLAG_NV),
+            _ if fa.is_infinite() => (a, 0),
+            _ => {
+                let r = fa.sqrt();
+                round_ref(r, (-r).mul_add(r, fa) / (2.0 * r), rm)
+            }
+        },
+
+        fp_op_e::FP_MADD | fp_op_e::FP_MSUB | fp_op_e::FP_NMSUB | fp_op_e::FP_NMADD => {
+            let inf_times_zero = (fa.is_infinite() && fb == 0.0) || (fa == 0.0 && fb.is_infinite());
+            if any_nan(&[a, b, c]) || inf_times_zero {
+                return nan_ref(&[a, b, c], inf_times_zero);
+            }
+            let negate_product = matches!(op, fp_op_e::FP_NMSUB | fp_op_e::FP_NMADD);
+            let negate_addend = matches!(op, fp_op_e::FP_MSUB | fp_op_e::FP_NMADD);
+            let p = if negate_product { -(fa * fb) } else { fa * fb };
+            let c = if negate_addend { -fc } else { fc };
+            add_ref(p, c, rm)
+        }
+
+        fp_op_e::FP_SGNJ => ((a & !SIGN) | (b & SIGN), 0),
+        fp_op_e::FP_SGNJN => ((a & !SIGN) | (!b & SIGN), 0),
+        fp_op_e::FP_SGNJX => (a ^ (b & SIGN), 0),
+
+        fp_op_e::FP_MIN | fp_op_e::FP_MAX => {
+            let nv = if is_snan(a) || is_snan(b) { FFLAG_NV } else { 0 };
+            let max = op == fp_op_e::FP_MAX;
+            let value = match (is_nan(a), is_nan(b)) {
+                (true, true) => QNAN,
+                (true, false) => b,
+                (false, true) => a,
+                _ if fa < fb => if max { b } else { a },
+                _ if fb < fa => if max { a } else { b },
+                // Equal: only ±0 differ, and -0 is the smaller
+                _ if max => a & b,
+                _ => a | b,
+            };
+            (value, nv)
+        }
+
+        fp_op_e::FP_EQ if any_nan(&[a, b]) => (0, if is_snan(a) || is_snan(b) { FFLAG_NV } else { 0 }),
+        fp_op_e::FP_LT | fp_op_e::FP_LE if any_nan(&[a, b]) => (0, FFLAG_NV),
+        fp_op_e::FP_EQ => bool_ref(fa == fb),
+        fp_op_e::FP_LT => bool_ref(fa < fb),
+        fp_op_e::FP_LE => bool_ref(fa <= fb),
+
+        fp_op_e::FP_CLASS => {
+            use std::num::FpCategory::*;
+            let v = f32::from_bits(a);
+            let negative = v.is_sign_negative();
+            let bit = match v.classify() {
+                Nan => return (if is_snan(a) { 1 << 8 } else { 1 << 9 }, 0),
+                Infinite => 0,
+                Normal => 1,
+                Subnormal => 2,
+                Zero => 3,
+            };
+            (if negative { 1 << bit } else { 1 << (7 - bit) }, 0)
+        }
+
+        fp_op_e::FP_CVT_W_S | fp_op_e::FP_CVT_WU_S => {
+            let signed = op == fp_op_e::FP_CVT_W_S;
+            let (min, max) = if signed { (i32::MIN as f64, i32::MAX as f64) } else { (0.0, u32::MAX as f64) };
+            let rounded = match rm {
+                round_mode_e::RM_RNE => fa.round_ties_even(),
+                round_mode_e::RM_RTZ => fa.trunc(),
+                round_mode_e::RM_RDN => fa.floor(),
+                round_mode_e::RM_RUP => fa.ceil(),
+                round_mode_e::RM_RMM => fa.round(),
+            };
+            let nx = if rounded != fa { FFLAG_NX } else { 0 };
+            match rounded {
+                _ if fa.is_nan() || rounded > max => (max as i64 as u32, FFLAG_NV),
+                _ if rounded < min => (min as i64 as u32, FFLAG_NV),
+                _ => (rounded as i64 as u32, nx),
+            }
+        }
+        fp_op_e::FP_CVT_S_W | fp_op_e::FP_CVT_S_WU => {
+            let v = if op == fp_op_e::FP_CVT_S_W { x as i32 as f64 } else { x as f64 };
+            if v == 0.0 { (0, 0) } else { round_ref(v, 0.0, rm) }
+        }
+
+        fp_op_e::FP_MV_X_W => (a, 0),
+        fp_op_e::FP_MV_W_X => (x, 0),
+    }
+}
+
+/// An operand: a random pattern, a special value, a small integer or a value
+/// close to `near` (to provoke cancellation and ties).
+fn random_operand(rng: &mut StdRng, near: u32) -> u32 {
+    match rng.random_range(0..10) {
+        0..=3 => rng.random(),
+        4 | 5 => SPECIALS[rng.random_range(0..SPECIALS.len())],
+        6 => (rng.random_range(-64..64i32) as f32).to_bits(),
+        7 => near ^ SIGN ^ rng.random_range(0..4u32),
+        8 => near.wrapping_add(rng.random_range(0..(1 << 26))) ^ (rng.random::<u32>() & SIGN),
+        _ => near.wrapping_sub(rng.random_range(0..(1 << 24))),
+    }
+}
+
+/// Model execution of one instruction on fresh operand registers, with
+/// fflags cleared and frm set beforehand. Returns the written register value
+/// and the flags the instruction accrued.
+fn execute(gold: &mut Lx32System, instr: instr_t, fs: [u32; 3], x: data_t, frm: u8) -> (StepResult, u32, u8) {
+    gold.f_regs.write(RS1 as u8, fs[0]);
+    gold.f_regs.write(RS2 as u8, fs[1]);
+    gold.f_regs.write(RS3 as u8, fs[2]);
+    gold.reg_file.tick(false, RS1 as u8, x, true);
+    gold.csr.write(CSR_FFLAGS, 0);
+    gold.csr.write(CSR_FRM, frm as u32);
+    gold.pc = 0;
+
+    let res = gold.step(instr, 0, false);
+    let value = res.rd_write.or(res.frd_write).map_or(0, |w| w.data);
+    (res, value, gold.csr.read(CSR_FFLAGS) as u8)
+}
+
+pub fn run_fpu_fuzzer(params: FpuTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING FLOATING-POINT (F) FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_f());
+    let mut inexact = 0;
+
+    for i in 0..params.iterations {
+        let (op, mnemonic) = OPS[rng.random_range(0..OPS.len())];
+        let near = rng.random();
+        let a = random_operand(&mut rng, near);
+        let b = random_operand(&mut rng, a);
+        let c = random_operand(&mut rng, b);
+        let x: data_t = match rng.random_range(0..3) {
+            0 => rng.random_range(0..4096),
+            1 => rng.random::<i16>() as i32 as u32,
+            _ => rng.random(),
+        };
+
+        // Half the instructions use the dynamic mode in frm
+        let rm = round_mode_e::from_bits(rng.random_range(0..5)).unwrap();
+        let (rm_field, frm) = if rng.random_bool(0.5) { (FRM_DYN, rm as u8) } else { (rm as u8, rng.random_range(0..5)) };
+        let instr = fp_instr(op, rm_field as u32);
+
+        let expected = fp_reference(op, a, b, c, x, rm);
+        let (res, value, flags) = execute(&mut gold, instr, [a, b, c], x, frm);
+        let actual = (value, flags);
+        let matches = actual == expected && res.fflags == flags;
+        inexact += (flags & FFLAG_NX != 0) as u32;
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] {:<10} {:?} a:0x{:08x} b:0x{:08x} c:0x{:08x} x:0x{:08x} -> 0x{:08x} flags:{:05b} | {}",
+                i,
+                mnemonic,
+                rm,
+                a,
+                b,
+                c,
+                x,
+                value,
+                flags,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " FLOATING-POINT MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("{} (0x{:08x}) rm={:?} (field {:03b}, frm {:03b})", mnemonic, instr, rm, rm_field, frm);
+            println!("Operands: f1=0x{:08x} f2=0x{:08x} f3=0x{:08x} x1=0x{:08x}", a, b, c, x);
+            println!("Expected: 0x{:08x} flags={:05b}", expected.0, expected.1);
+            println!("Actual:   0x{:08x} flags={:05b} (step reported {:05b})", value, flags, res.fflags);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 FPU TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("Inexact results: {}", inexact);
+    println!("{:=^100}", " FLOATING-POINT (F) FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_csr_pkg::{CSR_FCSR, CSR_MISA, CSR_MSTATUS, FS_CLEAN, FS_DIRTY, FS_INITIAL, FS_OFF, MSTATUS_FS, MSTATUS_FS_SHIFT, MSTATUS_SD};
+    use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+    use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+    use lx32_validator::models::core::decoder::{IllegalReason, classify_instr};
+    use lx32_validator::models::core::lx32_system::{MemAccess, MemOp, RegWrite, StepStatus};
+    use lx32_validator::models::core::softfloat::*;
+
+    const RNE: round_mode_e = round_mode_e::RM_RNE;
+    const RTZ: round_mode_e = round_mode_e::RM_RTZ;
+    const RDN: round_mode_e = round_mode_e::RM_RDN;
+    const RUP: round_mode_e = round_mode_e::RM_RUP;
+    const RMM: round_mode_e = round_mode_e::RM_RMM;
+
+    fn f(v: f32) -> u32 {
+        v.to_bits()
+    }
+
+    #[test]
+    fn test_fpu_default() {
+        run_fpu_fuzzer(FpuTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// Hand-checked vectors covering every rounding mode and exception flag.
+    #[test]
+    fn test_softfloat_vectors() {
+        // 1 + 2^-24 is a tie: even under RNE, away under RMM, up under RUP
+        assert_eq!(f32_add(ONE, 0x3380_0000, RNE), (ONE, FFLAG_NX));
+        assert_eq!(f32_add(ONE, 0x3380_0000, RMM), (0x3F80_0001, FFLAG_NX));
+        assert_eq!(f32_add(ONE, 0x3380_0000, RUP), (0x3F80_0001, FFLAG_NX));
+        assert_eq!(f32_add(ONE | SIGN, 0x3380_0000 | SIGN, RDN), (0xBF80_0001, FFLAG_NX));
+        // A far smaller addend only steers directed rounding
+        assert_eq!(f32_add(ONE, MIN_SUBNORMAL, RUP), (0x3F80_0001, FFLAG_NX));
+        assert_eq!(f32_sub(ONE, MIN_SUBNORMAL, RTZ), (0x3F7F_FFFF, FFLAG_NX));
+        assert_eq!(f32_sub(ONE, MIN_SUBNORMAL, RNE), (ONE, FFLAG_NX));
+        // Exact cancellation: +0, or -0 when rounding down
+        assert_eq!(f32_sub(ONE, ONE, RNE), (0, 0));
+        assert_eq!(f32_sub(ONE, ONE, RDN), (SIGN, 0));
+        assert_eq!(f32_add(SIGN, SIGN, RNE), (SIGN, 0));
+        assert_eq!(f32_add(INF, INF | SIGN, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+
+        // Overflow goes to infinity or the largest finite value by mode
+        assert_eq!(f32_mul(MAX, TWO, RNE), (INF, FFLAG_OF | FFLAG_NX));
+        assert_eq!(f32_mul(MAX, TWO, RTZ), (MAX, FFLAG_OF | FFLAG_NX));
+        assert_eq!(f32_mul(MAX | SIGN, TWO, RUP), (MAX | SIGN, FFLAG_OF | FFLAG_NX));
+        assert_eq!(f32_mul(MAX | SIGN, TWO, RDN), (INF | SIGN, FFLAG_OF | FFLAG_NX));
+        assert_eq!(f32_mul(INF, 0, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+
+        // Underflow: half the smallest subnormal ties to 0, exact subnormals raise nothing
+        assert_eq!(f32_mul(MIN_SUBNORMAL, f(0.5), RNE), (0, FFLAG_UF | FFLAG_NX));
+        assert_eq!(f32_mul(MIN_SUBNORMAL, f(0.5), RUP), (MIN_SUBNORMAL, FFLAG_UF | FFLAG_NX));
+        assert_eq!(f32_mul(MIN_SUBNORMAL | SIGN, f(0.5), RNE), (SIGN, FFLAG_UF | FFLAG_NX));
+        assert_eq!(f32_mul(MIN_NORMAL, f(0.5), RNE), (0x0040_0000, 0));
+        // Rounds up to the smallest normal yet is tiny: 24 bits below 2^-126 are exact
+        assert_eq!(f32_mul(MIN_NORMAL, 0x3F7F_FFFF, RNE), (MIN_NORMAL, FFLAG_UF | FFLAG_NX));
+
+        assert_eq!(f32_div(ONE, f(3.0), RNE), (0x3EAA_AAAB, FFLAG_NX));
+        assert_eq!(f32_div(ONE, f(3.0), RTZ), (0x3EAA_AAAA, FFLAG_NX));
+        assert_eq!(f32_div(ONE, 0, RNE), (INF, FFLAG_DZ));
+        assert_eq!(f32_div(ONE | SIGN, 0, RNE), (INF | SIGN, FFLAG_DZ));
+        assert_eq!(f32_div(0, 0, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+        assert_eq!(f32_div(INF, 0, RNE), (INF, 0));
+
+        assert_eq!(f32_sqrt(f(4.0), RNE), (f(2.0), 0));
+        assert_eq!(f32_sqrt(TWO, RNE), (0x3FB5_04F3, FFLAG_NX));
+        assert_eq!(f32_sqrt(TWO, RUP), (0x3FB5_04F4, FFLAG_NX));
+        assert_eq!(f32_sqrt(SIGN, RNE), (SIGN, 0));
+        assert_eq!(f32_sqrt(ONE | SIGN, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+        assert_eq!(f32_sqrt(MIN_SUBNORMAL, RNE), (0x1A35_04F3, FFLAG_NX));
+
+        // One rounding: (1 + 2^-12)^2 - 1 keeps the 2^-24 term a separate multiply loses
+        let x = 0x3F80_0800;
+        assert_eq!(f32_mul_add(x, x, ONE | SIGN, false, false, RNE), (0x3A00_0400, 0));
+        assert_eq!(f32_sub(f32_mul(x, x, RNE).0, ONE, RNE), (0x3A00_0000, 0));
+        assert_eq!(f32_mul_add(INF, 0, QNAN, false, false, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+        assert_eq!(f32_mul_add(INF, ONE, INF, false, true, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+
+        // NaN payloads are never propagated; only signaling NaNs raise invalid
+        assert_eq!(f32_add(0x7FC1_2345, ONE, RNE), (F32_CANONICAL_NAN, 0));
+        assert_eq!(f32_add(SNAN, ONE, RNE), (F32_CANONICAL_NAN, FFLAG_NV));
+    }
+
+    /// Conversions saturate with invalid, and compares follow IEEE quiet/signaling rules.
+    #[test]
+    fn test_softfloat_convert_compare() {
+        assert_eq!(f32_to_i32(f(2.5), RNE), (2, FFLAG_NX));
+        assert_eq!(f32_to_i32(f(2.5), RMM), (3, FFLAG_NX));
+        assert_eq!(f32_to_i32(f(-2.5), RDN), (-3i32 as u32, FFLAG_NX));
+        assert_eq!(f32_to_i32(f(-2.5), RTZ), (-2i32 as u32, FFLAG_NX));
+        assert_eq!(f32_to_i32(f(-2147483648.0), RNE), (i32::MIN as u32, 0));
+        assert_eq!(f32_to_i32(0x4F00_0000, RNE), (i32::MAX as u32, FFLAG_NV));
+        assert_eq!(f32_to_i32(INF | SIGN, RNE), (i32::MIN as u32, FFLAG_NV));
+        assert_eq!(f32_to_i32(QNAN, RNE), (i32::MAX as u32, FFLAG_NV));
+        assert_eq!(f32_to_u32(f(-0.25), RNE), (0, FFLAG_NX));
+        assert_eq!(f32_to_u32(f(-1.0), RNE), (0, FFLAG_NV));
+        assert_eq!(f32_to_u32(0x4F80_0000, RNE), (u32::MAX, FFLAG_NV));
+        assert_eq!(f32_to_u32(f(4294967040.0), RNE), (4294967040, 0));
+        assert_eq!(i32_to_f32(0x0100_0001, RNE), (0x4B80_0000, FFLAG_NX));
+        assert_eq!(i32_to_f32(0x0100_0001, RUP), (0x4B80_0001, FFLAG_NX));
+        assert_eq!(i32_to_f32(i32::MIN as u32, RNE), (0xCF00_0000, 0));
+        assert_eq!(u32_to_f32(u32::MAX, RTZ), (0x4F7F_FFFF, FFLAG_NX));
+
+        assert_eq!(f32_eq(0, SIGN), (true, 0));
+        assert_eq!(f32_eq(QNAN, QNAN), (false, 0));
+        assert_eq!(f32_eq(SNAN, ONE), (false, FFLAG_NV));
+        assert_eq!(f32_lt(QNAN, ONE), (false, FFLAG_NV));
+        assert_eq!(f32_le(SIGN, 0), (true, 0));
+        assert_eq!(f32_lt(SIGN, 0), (false, 0));
+
+        assert_eq!(f32_min(0, SIGN), (SIGN, 0));
+        assert_eq!(f32_max(SIGN, 0), (0, 0));
+        assert_eq!(f32_min(QNAN, ONE), (ONE, 0));
+        assert_eq!(f32_max(SNAN, ONE), (ONE, FFLAG_NV));
+        assert_eq!(f32_min(SNAN, QNAN), (F32_CANONICAL_NAN, FFLAG_NV));
+
+        assert_eq!(f32_classify(INF | SIGN), FCLASS_NEG_INF);
+        assert_eq!(f32_classify(0x807F_FFFF), FCLASS_NEG_SUBNORMAL);
+        assert_eq!(f32_classify(0), FCLASS_POS_ZERO);
+        assert_eq!(f32_classify(SNAN), FCLASS_SNAN);
+        assert_eq!(f32_classify(QNAN), FCLASS_QNAN);
+    }
+
+    /// FLW/FSW move raw bits between memory and the f registers, and
+    /// FMV/FCVT cross between the register files.
+    #[test]
+    fn test_fp_load_store_move() {
+        let mut mem = MemorySim::new();
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_f());
+        let program = [
+            0x1000_0093, // addi   x1, x0, 0x100
+            0x0000_a007, // flw    f0, 0(x1)
+            0x0000_0053, // fadd.s f0, f0, f0, rne
+            0x0000_a227, // fsw    f0, 4(x1)
+            0xe000_0153, // fmv.x.w x2, f0
+            0xc000_71d3, // fcvt.w.s x3, f0 (dyn)
+            0xd001_f0d3, // fcvt.s.w f1, x3 (dyn)
+        ];
+        mem.load_program(&program);
+        mem.write_data(0x100, f(-1.75), true);
+
+        let res = [(); 7].map(|_| gold.step_with_bus(&mut mem));
+        assert_eq!(res[1].mem_access.map(|m| (m.op, m.addr)), Some((MemOp::Load, 0x100)));
+        assert_eq!(res[1].frd_write, Some(RegWrite { rd: 0, data: f(-1.75) }));
+        assert_eq!(res[1].rd_write, None);
+        assert_eq!(res[2].frd_write, Some(RegWrite { rd: 0, data: f(-3.5) }));
+        assert_eq!(
+            res[3].mem_access,
+            Some(MemAccess {
+                op: MemOp::Store,
+                addr: 0x104,
+                data: f(-3.5),
+                size: mem_size_e::MEM_WORD,
+            })
+        );
+        assert_eq!(mem.read_data(0x104), f(-3.5));
+        assert_eq!(res[4].rd_write, Some(RegWrite { rd: 2, data: f(-3.5) }));
+        // Dynamic mode RNE: -3.5 ties to the even -4, which is inexact
+        assert_eq!(res[5].rd_write, Some(RegWrite { rd: 3, data: -4i32 as u32 }));
+        assert_eq!(res[5].fflags, FFLAG_NX);
+        assert_eq!(res[6].frd_write, Some(RegWrite { rd: 1, data: f(-4.0) }));
+        assert_eq!(gold.csr.read(CSR_FFLAGS), FFLAG_NX as u32);
+        assert!(res.iter().all(|r| r.status == StepStatus::Retired));
+    }
+
+    /// fcsr is the concatenation of frm and fflags; flags accrue until cleared.
+    #[test]
+    fn test_fcsr() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_f());
+        assert_eq!(gold.csr.read(CSR_MISA) & (1 << 5), 1 << 5);
+
+        gold.csr.write(CSR_FCSR, 0xFFFF_FFFF);
+        assert_eq!(gold.csr.read(CSR_FCSR), 0xFF);
+        assert_eq!(gold.csr.read(CSR_FRM), 0b111);
+        assert_eq!(gold.csr.read(CSR_FFLAGS), 0x1F);
+        gold.csr.write(CSR_FRM, 0b001);
+        gold.csr.write(CSR_FFLAGS, FFLAG_DZ as u32);
+        assert_eq!(gold.csr.read(CSR_FCSR), 0b001 << 5 | FFLAG_DZ as u32);
+
+        // Flags accrue across instructions; csrrw x5, fflags, x0 reads and clears them
+        gold.f_regs.write(RS1 as u8, ONE);
+        gold.f_regs.write(RS2 as u8, f(3.0));
+        gold.pc = 0;
+        gold.step(fp_instr(fp_op_e::FP_DIV, 0b000), 0, false);
+        assert_eq!(gold.csr.read(CSR_FFLAGS), (FFLAG_DZ | FFLAG_NX) as u32);
+        let res = gold.step(0x0010_12f3, 0, false);
+        assert_eq!(res.rd_write, Some(RegWrite { rd: 5, data: (FFLAG_DZ | FFLAG_NX) as u32 }));
+        assert_eq!(gold.csr.read(CSR_FFLAGS), 0);
+
+        // Dynamic rounding follows frm (RTZ here)
+        let (_, value, _) = execute(&mut gold, fp_instr(fp_op_e::FP_DIV, FRM_DYN as u32), [ONE, f(3.0), 0], 0, 0b001);
+        assert_eq!(value, 0x3EAA_AAAA);
+    }
+
+    /// Reserved rounding modes: statically in the rm field (decode), and
+    /// dynamically through frm (illegal at execution, depending on CSR state).
+    #[test]
+    fn test_fp_illegal() {
+        let cfg = IsaConfig::rv32i().with_f();
+        let fadd = |rm: u32| fp_instr(fp_op_e::FP_ADD, rm);
+        assert_eq!(classify_instr(fadd(0b101), &cfg), Err(IllegalReason::Funct3));
+        assert_eq!(classify_instr(fadd(0b110), &cfg), Err(IllegalReason::Funct3));
+        assert!(classify_instr(fadd(FRM_DYN as u32), &cfg).is_ok());
+        // fmt = D and an unknown funct5
+        assert_eq!(classify_instr(fadd(0) | 1 << 25, &cfg), Err(IllegalReason::Funct7));
+        assert_eq!(classify_instr(0x7800_0053, &cfg), Err(IllegalReason::Funct7));
+        // FSQRT needs rs2 = 0; FLD is not RV32F
+        assert_eq!(classify_instr(fp_instr(fp_op_e::FP_SQRT, 0) | 1 << 20, &cfg), Err(IllegalReason::Funct7));
+        assert_eq!(classify_instr(0x0000_b007, &cfg), Err(IllegalReason::Funct3));
+        // The rm field of sign injection is a sub-opcode
+        assert_eq!(classify_instr(fp_instr(fp_op_e::FP_SGNJ, 0) | 0b011 << 12, &cfg), Err(IllegalReason::Funct7));
+        // Without F the opcodes are unknown
+        assert_eq!(classify_instr(fadd(0), &IsaConfig::rv32i()), Err(IllegalReason::Opcode));
+        // F brings the Zicsr that fcsr needs, even on the base profile
+        assert!(classify_instr(0x0030_2573, &IsaConfig::base().with_f()).is_ok()); // frcsr a0
+        // RV32E limits the x registers only
+        let rv32e = cfg.with_rv32e();
+        assert!(classify_instr(fp_instr(fp_op_e::FP_MADD, 0) | 0x1F << 27, &rv32e).is_ok());
+        assert_eq!(classify_instr(fp_instr(fp_op_e::FP_MV_X_W, 0) | 0x10 << 7, &rv32e), Err(IllegalReason::Register));
+
+        let mut gold = Lx32System::with_config(cfg);
+        for frm in 5..8 {
+            gold.csr.write(CSR_FRM, frm);
+            gold.pc = 0x40;
+            gold.f_regs.write(RD as u8, 0x1234_5678);
+            let res = gold.step(fadd(FRM_DYN as u32), 0, false);
+            assert!(matches!(res.status, StepStatus::Trap(t) if t.cause == trap_cause_e::ILLEGAL_INSTRUCTION));
+            assert_eq!((res.frd_write, res.fflags), (None, 0));
+            assert_eq!(gold.f_regs.read(RD as u8), 0x1234_5678);
+        }
+
+        // Operations without a rounding mode ignore frm
+        let res = gold.step(fp_instr(fp_op_e::FP_SGNJ, 0), 0, false);
+        assert_eq!(res.status, StepStatus::Retired);
+    }
+
+    /// mstatus.FS tells a trap handler whether FP state needs saving: Initial
+    /// out of reset, Dirty (with SD) after any f-register or fcsr write, kept
+    /// across trap entry and MRET. With FS = Off, FP instructions and fcsr
+    /// accesses are illegal.
+    #[test]
+    fn test_mstatus_fs() {
+        let set_fs = |gold: &mut Lx32System, fs: u32| {
+            let mstatus = gold.csr.read(CSR_MSTATUS) & !(MSTATUS_FS | MSTATUS_SD);
+            gold.csr.write(CSR_MSTATUS, mstatus | fs << MSTATUS_FS_SHIFT);
+        };
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_f());
+        assert_eq!(gold.csr.fs(), FS_INITIAL);
+        assert_eq!(gold.csr.read(CSR_MSTATUS) & MSTATUS_SD, 0);
+
+        // FSW and a quiet compare only read FP state
+        gold.step(0x0000_a227, 0, false); // fsw f0, 4(x1)
+        gold.step(fp_instr(fp_op_e::FP_EQ, 0), 0, false);
+        assert_eq!(gold.csr.fs(), FS_INITIAL);
+
+        // After saving, software marks the state Clean; the next FP write makes it Dirty
+        set_fs(&mut gold, FS_CLEAN);
+        assert_eq!(gold.csr.fs(), FS_CLEAN);
+        gold.step(fp_instr(fp_op_e::FP_SGNJ, 0), 0, false);
+        assert_eq!(gold.csr.fs(), FS_DIRTY);
+        assert_ne!(gold.csr.read(CSR_MSTATUS) & MSTATUS_SD, 0);
+
+        set_fs(&mut gold, FS_CLEAN);
+        gold.step(0x0030_1073, 0, false); // csrw fcsr, x0
+        assert_eq!(gold.csr.fs(), FS_DIRTY);
+
+        gold.csr.enter_trap(0x40, trap_cause_e::ECALL_M as u32, 0);
+        assert_eq!(gold.csr.fs(), FS_DIRTY);
+        gold.csr.mret();
+        assert_eq!(gold.csr.fs(), FS_DIRTY);
+
+        // Off: every FP instruction and fcsr access traps, and nothing changes
+        set_fs(&mut gold, FS_OFF);
+        for instr in [fp_instr(fp_op_e::FP_ADD, 0), 0x0000_a007, 0x0000_a227, 0x0030_1073, 0x0030_22f3] {
+            gold.pc = 0;
+            let res = gold.step(instr, 0, false);
+            assert!(matches!(res.status, StepStatus::Trap(t) if t.cause == trap_cause_e::ILLEGAL_INSTRUCTION), "0x{:08x}", instr);
+            assert_eq!(gold.csr.fs(), FS_OFF);
+        }
+        assert_eq!(gold.csr.read(CSR_MSTATUS) & MSTATUS_SD, 0);
+
+        // Without F, FS is hardwired to Off
+        let mut base = Lx32System::with_config(IsaConfig::rv32i());
+        base.csr.write(CSR_MSTATUS, MSTATUS_FS);
+        assert_eq!(base.csr.read(CSR_MSTATUS) & (MSTATUS_FS | MSTATUS_SD), 0);
+    }
+}
@@ -4,6 +4,11 @@ mod common;
 use common::*;
 use lx32_validator::models::core::imm_gen::imm_gen_golden;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "imm_gen";
 
 pub struct ImmGenTestParams {
     pub iterations: u32,
@@ -95,10 +100,11 @@ fn log_imm_step(rtl: &ImmGenState, gold: &ImmGenState, matches: bool) {
     let status = if matches { "✓ MATCH" } else { "✗ MISMATCH" };
 
     println!(
-        "[{:>5}] {:?} Instr: 0x{:08x} | imm_ext:0x{:08x} | PC: 0x{:04x}->[R:0x{:04x} G:0x{:04x}] | x{:>2}(RD): [R:0x{:08x} G:0x{:08x}] | {}",
+        "[{:>5}] {:?} Instr: 0x{:08x} {:<24} | imm_ext:0x{:08x} | PC: 0x{:04x}->[R:0x{:04x} G:0x{:04x}] | x{:>2}(RD): [R:0x{:08x} G:0x{:08x}] | {}",
         rtl.iteration,
         rtl.kind,
         rtl.instr,
+        disasm(rtl.instr, rtl.pre_pc),
         rtl.imm_ext,
         rtl.pre_pc,
         rtl.post_pc,
@@ -110,16 +116,17 @@ fn log_imm_step(rtl: &ImmGenState, gold: &ImmGenState, matches: bool) {
     );
 }
 
-pub fn run_imm_gen_fuzzer(params: ImmGenTestParams) {
+pub fn run_imm_gen_fuzzer(params: ImmGenTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING IMM_GEN FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("RD Range: {:?}", params.rd_range);
     println!("I-Imm Range: {:?}", params.i_imm_range);
     println!("S-Imm Range: {:?}", params.s_imm_range);
     println!("B-Offset Range: {:?}", params.branch_offset_range);
 
     let mut tb = TestBench::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         let choice = rng.random_range(0..3);
@@ -150,10 +157,9 @@ pub fn run_imm_gen_fuzzer(params: ImmGenTestParams) {
 
         let imm_ext = imm_gen_golden(instr);
         let pre_pc_rtl = unsafe { get_pc(tb.rtl) };
-        let pre_pc_gold = tb.gold.pc;
 
         unsafe { tick_core(tb.rtl, 0, instr, 0) };
-        tb.gold.step(instr, 0, false);
+        let step = tb.gold.step(instr, 0, false);
 
         let rtl_state = ImmGenState {
             pre_pc: pre_pc_rtl,
@@ -169,8 +175,8 @@ pub fn run_imm_gen_fuzzer(params: ImmGenTestParams) {
         };
 
         let gold_state = ImmGenState {
-            pre_pc: pre_pc_gold,
-            post_pc: tb.gold.pc,
+            pre_pc: step.pc,
+            post_pc: step.next_pc,
             rd,
             rd_value: tb.gold.reg_file.read_rs1(rd),
             rs2,
@@ -191,7 +197,7 @@ pub fn run_imm_gen_fuzzer(params: ImmGenTestParams) {
             println!("\n{:=^100}", " IMM_GEN MISMATCH DETECTED ");
             println!("Iteration: {}", i);
             println!("Kind: {:?}", kind);
-            println!("Instr: 0x{:08x}", instr);
+            println!("Instr: 0x{:08x} ({})", instr, disasm(instr, step.pc));
             println!("imm_ext (golden): 0x{:08x}", imm_ext);
             println!(
                 "RTL  -> prePC:0x{:04x} postPC:0x{:04x} x{}:0x{:08x}",
@@ -201,7 +207,9 @@ pub fn run_imm_gen_fuzzer(params: ImmGenTestParams) {
                 "GOLD -> prePC:0x{:04x} postPC:0x{:04x} x{}:0x{:08x}",
                 gold_state.pre_pc, gold_state.post_pc, rd, gold_state.rd_value
             );
-            panic!("🔥 IMM_GEN TEST FAILED AT ITERATION {}", i);
+            print_gold_step(&step);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 IMM_GEN TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
 
@@ -214,6 +222,6 @@ mod tests {
 
     #[test]
     fn test_imm_gen_default() {
-        run_imm_gen_fuzzer(ImmGenTestParams::default());
+        run_imm_gen_fuzzer(ImmGenTestParams::default(), DEFAULT_TEST_SEED);
     }
 }
@@ -0,0 +1,344 @@
+// tests/test_instr.rs
+//
+// Typed instruction (Instr) validation.
+//
+// Random words over the RV32IM opcodes are decoded into `Instr`: a word must
+// decode exactly when the strict RV32IM decoder accepts it (FENCE and SYSTEM
+// excepted, whose reserved fields the decoder ignores), re-encode to itself
+// and carry the mnemonic the disassembler prints. Directed tests sweep every
+// immediate and register value of every variant and every opcode/funct3/
+// funct7 slot, and check the shrinker built on Instr.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_instr_pkg::Instr;
+use lx32_validator::models::core::decoder::is_legal_instr;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::disasm::Disassembler;
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "instr";
+
+pub struct InstrTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for InstrTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 20000,
+            enable_logging: true,
+        }
+    }
+}
+
+/// RV32IM major opcodes: LUI, AUIPC, JAL, JALR, BRANCH, LOAD, STORE, OP-IMM, OP, MISC-MEM, SYSTEM.
+const OPCODES: [u32; 11] = [0x37, 0x17, 0x6F, 0x67, 0x63, 0x03, 0x23, 0x13, 0x33, 0x0F, 0x73];
+
+fn random_word(rng: &mut StdRng) -> u32 {
+    let word: u32 = rng.random();
+    match rng.random_range(0..3) {
+        0 => word,
+        // Mostly-zero funct7, so shifts, OP and M forms show up
+        1 => (word & !0x7F & !(0x5E << 25)) | OPCODES[rng.random_range(0..OPCODES.len())],
+        _ => (word & !0x7F) | OPCODES[rng.random_range(0..OPCODES.len())],
+    }
+}
+
+pub fn run_instr_fuzzer(params: InstrTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING INSTR FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let cfg = IsaConfig::rv32im();
+    let disassembler = Disassembler::new(cfg);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut decoded_count = 0;
+
+    for i in 0..params.iterations {
+        let instr = random_word(&mut rng);
+        let decoded = Instr::decode(instr);
+        let legal = is_legal_instr(instr, &cfg);
+        let text = disassembler.disasm(instr, 0);
+        decoded_count += decoded.is_some() as u32;
+
+        if params.enable_logging {
+            println!("[{:>5}] 0x{:08x} | {:<28} | {:?}", i, instr, text, decoded);
+        }
+
+        // Reserved FENCE fields and the CSR instructions are legal but not RV32IM Instr
+        let lenient = matches!(instr & 0x7F, 0x0F | 0x73);
+        let matches = match decoded {
+            Some(d) => {
+                legal && d.fits() && d.encode() == instr && text.split_whitespace().next() == Some(d.mnemonic().to_lowercase().as_str())
+            }
+            None => !legal || lenient,
+        };
+
+        if !matches {
+            println!("\n{:=^100}", " INSTR MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} ({}, legal: {})", instr, text, legal);
+            println!("Decoded:     {:?}", decoded);
+            println!("Re-encoded:  {:x?}", decoded.filter(|d| d.fits()).map(|d| d.encode()));
+            print_replay(SUITE, seed, i);
+            panic!("Instr test failed at iteration {}", i);
+        }
+    }
+
+    println!("Instr fuzzer passed ({} RV32IM words)", decoded_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::program_generator::{Instruction, Program, ProgramConfig};
+    use lx32_validator::shrinking::{ShrinkConfig, Shrinker};
+    use std::collections::BTreeSet;
+
+    /// One instance of every variant, with non-zero operands.
+    fn every_variant() -> Vec<Instr> {
+        let (rd, rs1, rs2, imm, shamt) = (1, 2, 3, -4, 5);
+        vec![
+            Instr::Lui { rd, imm: 0x1000 },
+            Instr::Auipc { rd, imm: 0x1000 },
+            Instr::Jal { rd, imm },
+            Instr::Jalr { rd, rs1, imm },
+            Instr::Beq { rs1, rs2, imm },
+            Instr::Bne { rs1, rs2, imm },
+            Instr::Blt { rs1, rs2, imm },
+            Instr::Bge { rs1, rs2, imm },
+            Instr::Bltu { rs1, rs2, imm },
+            Instr::Bgeu { rs1, rs2, imm },
+            Instr::Lb { rd, rs1, imm },
+            Instr::Lh { rd, rs1, imm },
+            Instr::Lw { rd, rs1, imm },
+            Instr::Lbu { rd, rs1, imm },
+            Instr::Lhu { rd, rs1, imm },
+            Instr::Sb { rs1, rs2, imm },
+            Instr::Sh { rs1, rs2, imm },
+            Instr::Sw { rs1, rs2, imm },
+            Instr::Addi { rd, rs1, imm },
+            Instr::Slti { rd, rs1, imm },
+            Instr::Sltiu { rd, rs1, imm },
+            Instr::Xori { rd, rs1, imm },
+            Instr::Ori { rd, rs1, imm },
+            Instr::Andi { rd, rs1, imm },
+            Instr::Slli { rd, rs1, shamt },
+            Instr::Srli { rd, rs1, shamt },
+            Instr::Srai { rd, rs1, shamt },
+            Instr::Add { rd, rs1, rs2 },
+            Instr::Sub { rd, rs1, rs2 },
+            Instr::Sll { rd, rs1, rs2 },
+            Instr::Slt { rd, rs1, rs2 },
+            Instr::Sltu { rd, rs1, rs2 },
+            Instr::Xor { rd, rs1, rs2 },
+            Instr::Srl { rd, rs1, rs2 },
+            Instr::Sra { rd, rs1, rs2 },
+            Instr::Or { rd, rs1, rs2 },
+            Instr::And { rd, rs1, rs2 },
+            Instr::Mul { rd, rs1, rs2 },
+            Instr::Mulh { rd, rs1, rs2 },
+            Instr::Mulhsu { rd, rs1, rs2 },
+            Instr::Mulhu { rd, rs1, rs2 },
+            Instr::Div { rd, rs1, rs2 },
+            Instr::Divu { rd, rs1, rs2 },
+            Instr::Rem { rd, rs1, rs2 },
+            Instr::Remu { rd, rs1, rs2 },
+            Instr::Fence { pred: 0b1111, succ: 0b0011 },
+            Instr::Ecall,
+            Instr::Ebreak,
+        ]
+    }
+
+    /// Values the immediate of `instr` can hold as (lowest, highest, step).
+    fn imm_range(instr: Instr) -> Option<(i32, i32, i32)> {
+        match instr {
+            Instr::Lui { .. } | Instr::Auipc { .. } => Some((i32::MIN, i32::MAX - 0xFFF, 0x1000)),
+            Instr::Jal { .. } => Some((-(1 << 20), (1 << 20) - 2, 2)),
+            Instr::Slli { .. } | Instr::Srli { .. } | Instr::Srai { .. } => Some((0, 31, 1)),
+            Instr::Beq { .. } | Instr::Bne { .. } | Instr::Blt { .. } | Instr::Bge { .. } | Instr::Bltu { .. } | Instr::Bgeu { .. } => {
+                Some((-4096, 4094, 2))
+            }
+            _ if instr.imm().is_some() => Some((-2048, 2047, 1)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_instr_default() {
+        run_instr_fuzzer(
+            InstrTestParams {
+                enable_logging: false,
+                ..InstrTestParams::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
+    }
+
+    /// Every immediate of every variant encodes and decodes back to itself;
+    /// values just outside the range or off the alignment are refused.
+    #[test]
+    fn test_every_immediate_round_trips() {
+        for instr in every_variant() {
+            let Some((lo, hi, step)) = imm_range(instr) else {
+                assert_eq!(instr.with_imm(0), None, "{:?}", instr);
+                continue;
+            };
+            let mut imm = lo;
+            loop {
+                let with = instr.with_imm(imm).unwrap_or_else(|| panic!("{:?} with imm {}", instr, imm));
+                assert_eq!(with.imm(), Some(imm));
+                assert_eq!(Instr::decode(with.encode()), Some(with), "{:?} -> 0x{:08x}", with, with.encode());
+                if imm > hi - step {
+                    break;
+                }
+                imm += step;
+            }
+            for outside in [lo.checked_sub(step), hi.checked_add(step), (step > 1).then_some(lo + 1)].into_iter().flatten() {
+                assert_eq!(instr.with_imm(outside), None, "{:?} with imm {}", instr, outside);
+            }
+        }
+    }
+
+    /// Every register value in every register field round-trips.
+    #[test]
+    fn test_every_register_round_trips() {
+        for instr in every_variant() {
+            let word = instr.encode();
+            let fields = [(instr.rd(), 7), (instr.rs1(), 15), (instr.rs2(), 20)];
+            for (index, (field, shift)) in fields.into_iter().enumerate() {
+                if field.is_none() {
+                    continue;
+                }
+                for reg in 0..32u32 {
+                    let with = (word & !(0x1F << shift)) | (reg << shift);
+                    let decoded = Instr::decode(with).unwrap_or_else(|| panic!("0x{:08x}", with));
+                    let got = [decoded.rd(), decoded.rs1(), decoded.rs2()][index];
+                    assert_eq!(got, Some(reg as u8), "{:?} field at bit {}", decoded, shift);
+                    assert_eq!(decoded.mnemonic(), instr.mnemonic());
+                    assert_eq!(decoded.encode(), with);
+                }
+            }
+        }
+    }
+
+    /// Every opcode/funct3/funct7 slot (and rs2 field, for the shift amounts,
+    /// FENCE sets and EBREAK): decoded words round-trip and every variant is
+    /// reached.
+    #[test]
+    fn test_every_opcode_slot() {
+        let mut reached = BTreeSet::new();
+        for opcode in 0..128u32 {
+            for funct3 in 0..8u32 {
+                for funct7 in 0..128u32 {
+                    for rs2 in 0..32u32 {
+                        let word = (funct7 << 25) | (rs2 << 20) | (funct3 << 12) | opcode;
+                        if let Some(instr) = Instr::decode(word) {
+                            assert_eq!(instr.encode(), word, "{:?}", instr);
+                            reached.insert(instr.mnemonic());
+                        }
+                    }
+                }
+            }
+        }
+        let every: BTreeSet<_> = every_variant().into_iter().map(|instr| instr.mnemonic()).collect();
+        assert_eq!(every.len(), every_variant().len());
+        assert_eq!(reached, every);
+    }
+
+    /// Non-canonical words (reserved FENCE fields, other SYSTEM words) do not decode.
+    #[test]
+    fn test_non_canonical_words() {
+        assert_eq!(Instr::decode(0x0FF0_000F), Some(Instr::Fence { pred: 0b1111, succ: 0b1111 }));
+        // FENCE.TSO (fm = 1000), FENCE with rd = x1, FENCE.I
+        for word in [0x8330_000F, 0x0FF0_008F, 0x0000_100F] {
+            assert_eq!(Instr::decode(word), None, "0x{:08x}", word);
+        }
+        // MRET, CSRRW, compressed parcel
+        for word in [0x3020_0073, 0x3400_9073, 0x0000_4501] {
+            assert_eq!(Instr::decode(word), None, "0x{:08x}", word);
+        }
+    }
+
+    /// Operands outside their field are refused rather than truncated.
+    #[test]
+    fn test_fits() {
+        assert!(!Instr::Addi { rd: 32, rs1: 0, imm: 0 }.fits());
+        assert!(!Instr::Add { rd: 1, rs1: 2, rs2: 40 }.fits());
+        assert!(!Instr::Lui { rd: 1, imm: 0x1234 }.fits());
+        assert!(!Instr::Fence { pred: 16, succ: 0 }.fits());
+        assert_eq!(Instr::Slli { rd: 1, rs1: 1, shamt: 1 }.with_imm(-1), None);
+        assert_eq!(Instr::Beq { rs1: 1, rs2: 2, imm: 8 }.with_imm(4), Some(Instr::Beq { rs1: 1, rs2: 2, imm: 4 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_encode_refuses_out_of_range() {
+        Instr::Addi { rd: 1, rs1: 0, imm: 4095 }.encode();
+    }
+
+    /// The shrinker, built on Instr, simplifies the immediates of every
+    /// format (J, U and JALR included) and keeps unencodable values out.
+    #[test]
+    fn test_shrinker_simplifies_every_format() {
+        let original = [
+            Instr::Jal { rd: 1, imm: 0x1F2C },
+            Instr::Lui { rd: 2, imm: 0x7654_3000 },
+            Instr::Jalr { rd: 3, rs1: 4, imm: -100 },
+            Instr::Beq { rs1: 5, rs2: 6, imm: 96 },
+            Instr::Sh { rs1: 7, rs2: 8, imm: 12 },
+            Instr::Srai { rd: 9, rs1: 10, shamt: 17 },
+        ];
+        let program = Program {
+            instructions: original.iter().map(|instr| Instruction::from(*instr)).collect(),
+            config: ProgramConfig::default(),
+        };
+        // The "bug" needs all six instructions, whatever their immediates
+        let reproduces = |candidate: &Program| {
+            candidate.instructions.len() == original.len()
+                && candidate.instructions.iter().zip(&original).all(|(instr, orig)| {
+                    let decoded = Instr::decode(instr.encoding).expect("shrinker produced an undecodable word");
+                    decoded.mnemonic() == orig.mnemonic() && instr.mnemonic == disasm(instr.encoding, 0) && instr.imm == decoded.imm()
+                })
+        };
+        let shrinker = Shrinker::new(ShrinkConfig {
+            max_iterations: 100,
+            aggressive: true,
+        });
+        let shrunk = shrinker.shrink(&program, reproduces).program;
+        let imms: Vec<_> = shrunk.instructions.iter().map(|instr| instr.imm).collect();
+        assert_eq!(imms, vec![Some(0); original.len()]);
+    }
+
+    /// A few words cross-checked against the assembler, with their
+    /// disassembly at address 0.
+    #[test]
+    fn test_known_encodings() {
+        let cases = [
+            (Instr::Addi { rd: 1, rs1: 0, imm: 5 }, 0x0050_0093, "addi x1, x0, 5"),
+            (Instr::Lui { rd: 5, imm: 0x1234_5000 }, 0x1234_52B7, "lui x5, 0x12345"),
+            (Instr::Auipc { rd: 1, imm: -4096 }, 0xFFFF_F097, "auipc x1, 0xfffff"),
+            (Instr::Jal { rd: 1, imm: -24 }, 0xFE9F_F0EF, "jal x1, 0xffffffe8"),
+            (Instr::Jalr { rd: 0, rs1: 1, imm: 0 }, 0x0000_8067, "jalr x0, 0(x1)"),
+            (Instr::Beq { rs1: 1, rs2: 2, imm: -4 }, 0xFE20_8EE3, "beq x1, x2, 0xfffffffc"),
+            (Instr::Lw { rd: 5, rs1: 2, imm: 8 }, 0x0081_2283, "lw x5, 8(x2)"),
+            (Instr::Sw { rs1: 2, rs2: 5, imm: -8 }, 0xFE51_2C23, "sw x5, -8(x2)"),
+            (Instr::Srai { rd: 1, rs1: 2, shamt: 3 }, 0x4031_5093, "srai x1, x2, 3"),
+            (Instr::Sub { rd: 3, rs1: 4, rs2: 5 }, 0x4052_01B3, "sub x3, x4, x5"),
+            (Instr::Mulhsu { rd: 1, rs1: 2, rs2: 3 }, 0x0231_20B3, "mulhsu x1, x2, x3"),
+            (Instr::Fence { pred: 0b1111, succ: 0b1111 }, 0x0FF0_000F, "fence iorw, iorw"),
+            (Instr::Ecall, 0x0000_0073, "ecall"),
+            (Instr::Ebreak, 0x0010_0073, "ebreak"),
+        ];
+        for (instr, word, text) in cases {
+            assert_eq!(instr.encode(), word, "{}", text);
+            assert_eq!(disasm(word, 0), text);
+            assert_eq!(Instr::decode(word), Some(instr));
+        }
+    }
+}
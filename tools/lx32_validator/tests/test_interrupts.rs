@@ -0,0 +1,288 @@
+// tests/test_interrupts.rs
+//
+// Interrupt validation for the golden model.
+//
+// The RTL has no interrupt support yet, so this suite is golden-only. Random
+// programs run twice from the same memory image: once with interrupts
+// injected at random cycles and once without. The handler is a single MRET
+// served by the bus at the mtvec address, so an interrupted run must retire
+// exactly the same instruction stream as the reference run, with every
+// interrupt taken precisely at the next instruction boundary and returning
+// to the instruction it replaced.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use lx32_validator::models::arch::lx32_csr_pkg::*;
+use lx32_validator::models::arch::lx32_isa_pkg::INSTR_MRET;
+use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+use lx32_validator::models::arch::lx32_trap_pkg::irq_cause_e;
+use lx32_validator::models::core::bus::Bus;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{Interrupt, StepStatus};
+use lx32_validator::program_generator::{Program, ProgramConfig};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "interrupts";
+
+/// mtvec of the fuzzer; the bus serves an MRET there
+pub const HANDLER: addr_t = 0x0000_2000;
+
+pub struct InterruptTestParams {
+    pub num_programs: usize,
+    pub program_length: usize,
+    /// One injection every `inject_period` cycles on average
+    pub inject_period: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for InterruptTestParams {
+    fn default() -> Self {
+        Self {
+            num_programs: 20,
+            program_length: 300,
+            inject_period: 16,
+            enable_logging: false,
+        }
+    }
+}
+
+const SOURCES: [irq_cause_e; 3] = [irq_cause_e::M_SOFTWARE, irq_cause_e::M_TIMER, irq_cause_e::M_EXTERNAL];
+
+/// Program memory whose fetches at `HANDLER` return MRET.
+struct HandlerBus {
+    mem: MemorySim,
+}
+
+impl Bus for HandlerBus {
+    fn fetch(&mut self, addr: addr_t) -> instr_t {
+        if addr == HANDLER { INSTR_MRET } else { self.mem.fetch(addr) }
+    }
+
+    fn load(&mut self, addr: addr_t, size: mem_size_e) -> data_t {
+        self.mem.load(addr, size)
+    }
+
+    fn store(&mut self, addr: addr_t, data: data_t, size: mem_size_e) {
+        self.mem.store(addr, data, size)
+    }
+}
+
+/// Golden model with interrupts and the fuzzer's mtvec; `enabled` sets mstatus.MIE.
+pub fn interrupt_system(enabled: bool) -> Lx32System {
+    let mut gold = Lx32System::with_config(IsaConfig {
+        zicsr: true,
+        interrupts: true,
+        ..IsaConfig::base()
+    });
+    gold.csr.write(CSR_MTVEC, HANDLER);
+    gold.csr.write(CSR_MIE, MIP_MSIP | MIP_MTIP | MIP_MEIP);
+    if enabled {
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MIE);
+    }
+    gold
+}
+
+/// Drives the interrupt line of `cause` on `gold`; the timer fires on the next cycle.
+fn raise(gold: &mut Lx32System, cause: irq_cause_e, level: bool) {
+    match cause {
+        irq_cause_e::M_SOFTWARE => gold.clint.msip = level,
+        irq_cause_e::M_TIMER => gold.clint.mtimecmp = if level { gold.clint.mtime + 1 } else { u64::MAX },
+        irq_cause_e::M_EXTERNAL => gold.ext_irq = level,
+    }
+}
+
+fn fail(prog_idx: usize, cycle: usize, seed: u64, what: String) -> ! {
+    println!("\n{:=^100}", " INTERRUPT MISMATCH DETECTED ");
+    println!("Program: {} Cycle: {}", prog_idx, cycle);
+    println!("{}", what);
+    print_replay(SUITE, seed, prog_idx as u32);
+    panic!("🔥 INTERRUPT TEST FAILED IN PROGRAM {} AT CYCLE {} (seed {})", prog_idx, cycle, seed);
+}
+
+pub fn run_interrupt_fuzzer(params: InterruptTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING INTERRUPT FUZZER ");
+    println!("Number of programs: {}", params.num_programs);
+    println!("Program length: {} instructions", params.program_length);
+    println!("Injection period: {} cycles", params.inject_period);
+    println!("Seed: {}", seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut taken = 0;
+
+    for prog_idx in 0..params.num_programs {
+        let config = ProgramConfig {
+            length: params.program_length,
+            ..ProgramConfig::default()
+        };
+        let program = Program::generate(config, seed.wrapping_add(prog_idx as u64));
+        let image: Vec<u32> = program.instructions.iter().map(|i| i.encoding).collect();
+
+        let mut bus_irq = HandlerBus { mem: MemorySim::new() };
+        let mut bus_ref = HandlerBus { mem: MemorySim::new() };
+        bus_irq.mem.load_program(&image);
+        bus_ref.mem.load_program(&image);
+        let mut gold = interrupt_system(true);
+        let mut reference = interrupt_system(false);
+
+        for cycle in 0..program.instructions.len() {
+            // Without alignment exceptions the program may reach a misaligned
+            // PC, which mepc cannot hold; interrupts are only injected on aligned ones
+            let injected = rng.random_range(0..params.inject_period) == 0 && gold.pc & 0b11 == 0;
+            let cause = SOURCES[rng.random_range(0..SOURCES.len())];
+            if injected {
+                raise(&mut gold, cause, true);
+            }
+
+            let res = gold.step_with_bus(&mut bus_irq);
+
+            if !injected {
+                let expected = reference.step_with_bus(&mut bus_ref);
+                if res != expected {
+                    fail(prog_idx, cycle, seed, format!("Retired step differs\n  irq: {:?}\n  ref: {:?}", res, expected));
+                }
+                continue;
+            }
+
+            // Entry: taken before the instruction the reference executes next
+            let entry = StepStatus::Interrupt(Interrupt { cause, pc: reference.pc });
+            let mstatus = gold.csr.read(CSR_MSTATUS);
+            let entered = res.status == entry
+                && gold.pc == HANDLER
+                && gold.csr.read(CSR_MEPC) == reference.pc
+                && gold.csr.read(CSR_MCAUSE) == cause.code()
+                && mstatus == MSTATUS_MPP | MSTATUS_MPIE;
+            if !entered {
+                fail(
+                    prog_idx,
+                    cycle,
+                    seed,
+                    format!("Bad entry: expected {:?}, got {:?} (pc=0x{:08x}, mstatus=0x{:08x})", entry, res.status, gold.pc, mstatus),
+                );
+            }
+
+            // The handler acknowledges the source, then returns
+            raise(&mut gold, cause, false);
+            let ret = gold.step_with_bus(&mut bus_irq);
+            let returned = ret.instr == INSTR_MRET
+                && ret.status == StepStatus::Retired
+                && gold.pc == reference.pc
+                && gold.csr.read(CSR_MSTATUS) == MSTATUS_MPP | MSTATUS_MPIE | MSTATUS_MIE;
+            if !returned {
+                fail(prog_idx, cycle, seed, format!("Bad return: {:?} (pc=0x{:08x})", ret, gold.pc));
+            }
+
+            taken += 1;
+            if params.enable_logging {
+                println!("[Program {}][Cycle {}] {:?} at pc 0x{:08x} ✓", prog_idx, cycle, cause, reference.pc);
+            }
+        }
+
+        if !params.enable_logging {
+            print!(".");
+        }
+    }
+
+    if !params.enable_logging {
+        println!();
+    }
+    println!("Interrupts taken: {}", taken);
+    println!("{:=^100}", " INTERRUPT FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::core::clint::{CLINT_MTIME, CLINT_MTIMECMP};
+
+    const NOP: u32 = 0x0000_0013;
+
+    #[test]
+    fn test_interrupt_default() {
+        run_interrupt_fuzzer(InterruptTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// mtimecmp fires once mtime reaches it; mip mirrors the line.
+    #[test]
+    fn test_timer_interrupt() {
+        let mut gold = interrupt_system(true);
+        gold.clint.mtimecmp = 3;
+
+        gold.step(NOP, 0, false);
+        gold.step(NOP, 0, false);
+        assert_eq!(gold.csr.read(CSR_MIP), 0);
+
+        let res = gold.step(NOP, 0, false);
+        assert_eq!(
+            res.status,
+            StepStatus::Interrupt(Interrupt {
+                cause: irq_cause_e::M_TIMER,
+                pc: 8
+            })
+        );
+        assert_eq!(gold.csr.read(CSR_MIP), MIP_MTIP);
+        assert_eq!(gold.csr.read(CSR_MCAUSE), 0x8000_0007);
+        assert_eq!((gold.csr.cycles(), gold.csr.instret()), (3, 2));
+    }
+
+    /// External beats software beats timer; masked sources wait.
+    #[test]
+    fn test_priority_and_masking() {
+        let mut gold = interrupt_system(true);
+        gold.ext_irq = true;
+        gold.clint.msip = true;
+        gold.clint.mtimecmp = 0;
+
+        gold.csr.write(CSR_MIE, MIP_MSIP | MIP_MTIP);
+        let res = gold.step(NOP, 0, false);
+        assert!(matches!(res.status, StepStatus::Interrupt(Interrupt { cause: irq_cause_e::M_SOFTWARE, .. })));
+
+        // Inside the handler MIE is clear: nothing is taken
+        let res = gold.step(NOP, 0, false);
+        assert_eq!(res.status, StepStatus::Retired);
+
+        gold.csr.write(CSR_MIE, MIP_MSIP | MIP_MTIP | MIP_MEIP);
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MIE);
+        let res = gold.step(NOP, 0, false);
+        assert!(matches!(res.status, StepStatus::Interrupt(Interrupt { cause: irq_cause_e::M_EXTERNAL, .. })));
+    }
+
+    /// Vectored mtvec jumps to BASE + 4 * cause.
+    #[test]
+    fn test_vectored_mode() {
+        let mut gold = interrupt_system(true);
+        gold.csr.write(CSR_MTVEC, 0x100 | 1);
+        gold.ext_irq = true;
+        gold.step(NOP, 0, false);
+        assert_eq!(gold.pc, 0x100 + 4 * 11);
+    }
+
+    /// Programs reach mtime/mtimecmp through loads and stores, which never reach the bus.
+    #[test]
+    fn test_clint_mmio() {
+        let mut mem = MemorySim::new();
+        mem.load_program(&[
+            0x0200_40b7, // lui  x1, 0x2004    (x1 = mtimecmp)
+            0x0640_0113, // addi x2, x0, 100
+            0x0020_a023, // sw   x2, 0(x1)
+            0x0000_a223, // sw   x0, 4(x1)
+            0x0200_c1b7, // lui  x3, 0x200c    (x3 = 0x0200_c000)
+            0xff81_a203, // lw   x4, -8(x3)    (mtime low)
+        ]);
+
+        let mut gold = interrupt_system(false);
+        for _ in 0..6 {
+            gold.step_with_bus(&mut mem);
+        }
+        assert_eq!(CLINT_MTIMECMP, 0x0200_4000);
+        assert_eq!(CLINT_MTIME, 0x0200_bff8);
+        assert_eq!(gold.clint.mtimecmp, 100);
+        assert_eq!(gold.reg_file.read_rs1(4), 6);
+        // The aliased RAM words were not overwritten
+        assert_eq!(mem.read_data(0), 0x0200_40b7);
+        assert_eq!(mem.read_data(4), 0x0640_0113);
+    }
+}
@@ -9,6 +9,9 @@
 // - PC tracking issues
 // - Pipeline hazards (if pipelined)
 //
+// Every cycle compares PC, all 32 registers and the data memory port
+// (mem_addr/mem_wdata/mem_we) between RTL and golden model in lockstep.
+//
 // When a test fails, the shrinker automatically reduces it to minimal reproducible case
 //
 // Author: LX32 Validation Team
@@ -20,13 +23,33 @@ use common::*;
 
 use lx32_validator::program_generator::{Program, ProgramConfig};
 use lx32_validator::shrinking::{Shrinker, ShrinkConfig};
+use lx32_validator::models::core::isa_config::IsaConfig;
 use lx32_validator::models::core::lx32_system::Lx32System;
+use lx32_validator::models::disasm::Disassembler;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "long_programs";
+
+/// How the long-program fuzzer feeds instructions to the core.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExecutionMode {
+    /// Send `program.instructions` in list order, regardless of where the PC points.
+    #[default]
+    Streamed,
+    /// Place the program in a shared `MemorySim` image and fetch at the current PC.
+    /// Loads are served from and stores written to that image on both sides.
+    PcDriven,
+}
 
 pub struct LongProgramTestParams {
     pub num_programs: usize,
     pub program_length: usize,
     pub enable_shrinking: bool,
     pub enable_logging: bool,
+    pub execution_mode: ExecutionMode,
+    /// Generate programs over x0-x15 only and run the golden model as RV32E,
+    /// for an RTL built with REG_COUNT = 16
+    pub rv32e: bool,
 }
 
 impl Default for LongProgramTestParams {
@@ -36,6 +59,8 @@ impl Default for LongProgramTestParams {
             program_length: 500,
             enable_shrinking: true,
             enable_logging: false,
+            execution_mode: ExecutionMode::Streamed,
+            rv32e: false,
         }
     }
 }
@@ -43,18 +68,99 @@ impl Default for LongProgramTestParams {
 #[derive(Debug)]
 struct ProgramFailure {
     program: Program,
-    failed_at_instruction: usize,
-    rtl_pc: u32,
-    gold_pc: u32,
+    failed_at_cycle: usize,
+    failed_at_pc: u32,
+    /// Program list index of the failing instruction, when its PC maps to one
+    failed_at_instruction: Option<usize>,
+    diff: StateDiff,
+}
+
+impl ProgramFailure {
+    /// Where the program failed: the instruction when known, otherwise the cycle and PC.
+    fn location(&self) -> String {
+        match self.failed_at_instruction {
+            Some(index) => format!("instruction {}/{}", index, self.program.instructions.len()),
+            None => format!("cycle {} (PC=0x{:08x} holds no program instruction)", self.failed_at_cycle, self.failed_at_pc),
+        }
+    }
+}
+
+/// One executed cycle: where it ran, what was executed and both resulting states.
+struct CycleRecord {
+    cycle: usize,
+    pc: u32,
+    instr: u32,
+    index: Option<usize>,
+    rtl: CoreState,
+    gold: CoreState,
+}
+
+/// Program list index of the instruction at `pc`, if the image still holds it there.
+fn program_index_at(program: &Program, mem: &MemorySim, pc: u32) -> Option<usize> {
+    let index = ((pc & 0xFFF) >> 2) as usize;
+    program
+        .instructions
+        .get(index)
+        .filter(|instr| instr.encoding == mem.read_instr(pc))
+        .map(|_| index)
+}
+
+/// Runs `program` from reset on both models in the given mode and returns the
+/// first cycle whose full architectural state diverges, with its per-field diff.
+/// `on_cycle` sees every executed cycle, diverging or not.
+fn run_program_lockstep(
+    tb: &mut TestBench,
+    program: &Program,
+    mode: ExecutionMode,
+    mut on_cycle: impl FnMut(&CycleRecord, &StateDiff),
+) -> Option<(CycleRecord, StateDiff)> {
+    let mut mem = MemorySim::new();
+    if mode == ExecutionMode::PcDriven {
+        let image: Vec<u32> = program.instructions.iter().map(|i| i.encoding).collect();
+        mem.load_program(&image);
+    }
+
+    // Both modes run one cycle per generated instruction
+    for cycle in 0..program.instructions.len() {
+        let pc = unsafe { get_pc(tb.rtl) };
+        let (record, diff) = match mode {
+            ExecutionMode::Streamed => {
+                let instr = program.instructions[cycle].encoding;
+                let (rtl, gold) = tb.step_lockstep(instr, 0);
+                let diff = StateDiff::between(&rtl, &gold);
+                (CycleRecord { cycle, pc, instr, index: Some(cycle), rtl, gold }, diff)
+            }
+            ExecutionMode::PcDriven => {
+                let index = program_index_at(program, &mem, pc);
+                let (instr, rtl, gold) = tb.step_from_memory(&mut mem);
+                let diff = StateDiff::between(&rtl, &gold);
+                (CycleRecord { cycle, pc, instr, index, rtl, gold }, diff)
+            }
+        };
+
+        on_cycle(&record, &diff);
+        if !diff.is_empty() {
+            return Some((record, diff));
+        }
+    }
+    None
 }
 
 pub fn run_long_program_fuzzer(params: LongProgramTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING LONG PROGRAM FUZZER ");
     println!("Number of programs: {}", params.num_programs);
     println!("Program length: {} instructions", params.program_length);
+    println!("Execution mode: {:?}", params.execution_mode);
     println!("Shrinking enabled: {}", params.enable_shrinking);
+    println!("RV32E: {}", params.rv32e);
     println!("Seed: {}", seed);
 
+    if params.execution_mode == ExecutionMode::PcDriven && params.program_length > 1024 {
+        println!("Warning: programs longer than 1024 instructions do not fit the 4KB image and are truncated");
+    }
+
+    let isa = if params.rv32e { IsaConfig::base().with_rv32e() } else { IsaConfig::base() };
+    let disassembler = Disassembler::new(isa);
     let mut tb = TestBench::new();
     let mut failures = Vec::new();
 
@@ -69,6 +175,12 @@ pub fn run_long_program_fuzzer(params: LongProgramTestParams, seed: u64) {
             enable_alu: true,
             enable_jumps: true,
             enable_upper_imm: true,
+            // The RTL LSU is word-only; sub-word accesses would diverge by design
+            enable_sub_word_mem: false,
+            enable_mul_div: false,
+            enable_compressed: false,
+            custom: Default::default(),
+            reg_count: isa.reg_count(),
         };
 
         let program = Program::generate(config, program_seed);
@@ -78,50 +190,49 @@ pub fn run_long_program_fuzzer(params: LongProgramTestParams, seed: u64) {
         }
 
         // Reset CPU state
-        tb.gold = Lx32System::new();
+        tb.gold = Lx32System::with_config(isa);
         unsafe {
             common::reset(tb.rtl);
         }
 
-        // Execute program
-        let mut failed = false;
-        let mut fail_idx = 0;
-
-        for (idx, instr) in program.instructions.iter().enumerate() {
-            // Clock cycle with instruction
-            unsafe {
-                common::tick_core(tb.rtl, 0, instr.encoding, 0);
-            }
-            tb.gold.step(instr.encoding, 0, false);
-
-            // Check state
-            let rtl_pc = unsafe { common::get_pc(tb.rtl) };
-            let gold_pc = tb.gold.pc;
-
+        // Execute program, comparing PC, registers and memory port every cycle
+        let mismatch = run_program_lockstep(&mut tb, &program, params.execution_mode, |record, diff| {
             if params.enable_logging {
                 println!(
-                    "[Program {}][Instr {}] PC: RTL=0x{:04x}, GOLD=0x{:04x}, Instr=0x{:08x} ({})",
-                    prog_idx, idx, rtl_pc, gold_pc, instr.encoding, instr.mnemonic
+                    "[Program {}][Cycle {}] PC: RTL=0x{:04x}, GOLD=0x{:04x}, Instr=0x{:08x} ({}) | {}",
+                    prog_idx,
+                    record.cycle,
+                    record.rtl.pc,
+                    record.gold.pc,
+                    record.instr,
+                    disassembler.disasm(record.instr, record.pc),
+                    if diff.is_empty() { "MATCH" } else { "MISMATCH" }
                 );
             }
-
-            if rtl_pc != gold_pc {
-                failed = true;
-                fail_idx = idx;
-
-                failures.push(ProgramFailure {
-                    program: program.clone(),
-                    failed_at_instruction: idx,
-                    rtl_pc,
-                    gold_pc,
-                });
-
-                println!("✗ [Program {}] FAILED at instruction {}/{}", prog_idx, idx, program.instructions.len());
-                println!("  PC mismatch: RTL=0x{:04x}, GOLD=0x{:04x}", rtl_pc, gold_pc);
-                println!("  Failing instruction: 0x{:08x} ({})", instr.encoding, instr.mnemonic);
-
-                break;
-            }
+        });
+        let failed = mismatch.is_some();
+
+        if let Some((record, diff)) = mismatch {
+            println!(
+                "✗ [Program {}] FAILED at cycle {}/{} (PC=0x{:08x})",
+                prog_idx,
+                record.cycle,
+                program.instructions.len(),
+                record.pc
+            );
+            println!("  Program seed: {} (Program::generate with suite seed {} + {})", program_seed, seed, prog_idx);
+            println!("  Failing instruction: 0x{:08x} ({})", record.instr, disassembler.disasm(record.instr, record.pc));
+            println!("  Divergent state ({} field(s)):", diff.fields.len());
+            print!("{}", diff);
+            print_gold_step(&tb.last_step);
+
+            failures.push(ProgramFailure {
+                program: program.clone(),
+                failed_at_cycle: record.cycle,
+                failed_at_pc: record.pc,
+                failed_at_instruction: record.index,
+                diff,
+            });
         }
 
         if !failed {
@@ -147,29 +258,15 @@ pub fn run_long_program_fuzzer(params: LongProgramTestParams, seed: u64) {
 
             for (idx, failure) in failures.iter().enumerate() {
                 println!("\n--- Failure {} ---", idx + 1);
-                println!("Original failure at instruction {}/{}",
-                    failure.failed_at_instruction,
-                    failure.program.instructions.len()
-                );
+                println!("Original failure at {}", failure.location());
+                print!("{}", failure.diff);
 
                 // Create test function that reproduces the bug
                 let test_reproduces_bug = |prog: &Program| -> bool {
                     let mut test_tb = TestBench::new();
-
-                    for instr in &prog.instructions {
-                        unsafe {
-                            common::tick_core(test_tb.rtl, 0, instr.encoding, 0);
-                        }
-                        test_tb.gold.step(instr.encoding, 0, false);
-
-                        let rtl_pc = unsafe { common::get_pc(test_tb.rtl) };
-                        let gold_pc = test_tb.gold.pc;
-
-                        if rtl_pc != gold_pc {
-                            return true; // Bug still present
-                        }
-                    }
-                    false // Bug not reproduced
+                    test_tb.gold = Lx32System::with_config(isa);
+                    // Bug still present
+                    run_program_lockstep(&mut test_tb, prog, params.execution_mode, |_, _| {}).is_some()
                 };
 
                 // Shrink the failing program
@@ -186,17 +283,16 @@ pub fn run_long_program_fuzzer(params: LongProgramTestParams, seed: u64) {
             // Just show the failing programs without shrinking
             for (idx, failure) in failures.iter().enumerate() {
                 println!("\n--- Failure {} ---", idx + 1);
-                println!("Failed at instruction {}/{}",
-                    failure.failed_at_instruction,
-                    failure.program.instructions.len()
-                );
+                println!("Failed at {}", failure.location());
+                print!("{}", failure.diff);
+                let Some(index) = failure.failed_at_instruction else { continue };
                 println!("Context (last 5 instructions before failure):");
 
-                let start = failure.failed_at_instruction.saturating_sub(5);
-                let end = (failure.failed_at_instruction + 1).min(failure.program.instructions.len());
+                let start = index.saturating_sub(5);
+                let end = (index + 1).min(failure.program.instructions.len());
 
                 for i in start..end {
-                    let marker = if i == failure.failed_at_instruction { ">>>" } else { "   " };
+                    let marker = if i == index { ">>>" } else { "   " };
                     println!("{} {:4}: {:08x}  {}",
                         marker,
                         i,
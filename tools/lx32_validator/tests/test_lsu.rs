@@ -2,8 +2,12 @@
 #[path = "common/mod.rs"]
 mod common;
 use common::*;
-use lx32_validator::models::core::lsu::lsu_golden;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "lsu";
 
 pub struct LsuTestParams {
     pub iterations: u32,
@@ -125,14 +129,15 @@ fn log_lsu_step(rtl: &LsuState, gold: &LsuState, matches: bool) {
     );
 }
 
-pub fn run_lsu_fuzzer(params: LsuTestParams) {
+pub fn run_lsu_fuzzer(params: LsuTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING LSU FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Registers: {:?}", params.reg_range);
     println!("Immediates: {:?}", params.imm_range);
 
     let mut tb = TestBench::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         let is_load = rng.random_range(0..2) == 0;
@@ -149,9 +154,9 @@ pub fn run_lsu_fuzzer(params: LsuTestParams) {
         };
 
         unsafe { tick_core(tb.rtl, 0, instr, mem_rdata) };
-        let (alu_res, rs2_data, mem_write) = tb.gold.step(instr, mem_rdata, false);
+        let step = tb.gold.step(instr, mem_rdata, false);
 
-        let lsu_if = lsu_golden(alu_res, rs2_data, mem_write);
+        let lsu_if = step.lsu;
         let (rtl_state, gold_state) =
             capture_lsu_state(&tb, instr, i, rd, rs1, rs2, mem_rdata, is_load);
         let matches = lsu_states_match(&rtl_state, &gold_state);
@@ -163,7 +168,7 @@ pub fn run_lsu_fuzzer(params: LsuTestParams) {
         if !matches {
             println!("\n{:=^100}", " LSU MISMATCH DETECTED ");
             println!("Iteration: {}", i);
-            println!("Instruction: 0x{:08x}", instr);
+            println!("Instruction: 0x{:08x} ({})", instr, disasm(instr, step.pc));
             println!("Operation: {}", if is_load { "LW" } else { "SW" });
             println!(
                 "Expected LSU IF -> addr:0x{:08x} wdata:0x{:08x} we:{}",
@@ -181,7 +186,9 @@ pub fn run_lsu_fuzzer(params: LsuTestParams) {
                 gold_state.rs2,
                 gold_state.rs2_value
             );
-            panic!("🔥 LSU TEST FAILED AT ITERATION {}", i);
+            print_gold_step(&step);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 LSU TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
 
@@ -194,6 +201,56 @@ mod tests {
 
     #[test]
     fn test_lsu_default() {
-        run_lsu_fuzzer(LsuTestParams::default());
+        run_lsu_fuzzer(LsuTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// Byte and halfword accesses on a golden model with sub-word decode enabled.
+    #[test]
+    fn test_sub_word_load_store() {
+        use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+        use lx32_validator::models::core::isa_config::IsaConfig;
+
+        let mut mem = MemorySim::new();
+        mem.load_program(&[
+            0x1000_0093,        // addi x1, x0, 256
+            0xf800_0113,        // addi x2, x0, -128
+            encode_sw(1, 0, 0), // sw   x0, 0(x1)
+            0x0020_81a3,        // sb   x2, 3(x1)
+            0x0020_9123,        // sh   x2, 2(x1)
+            0x0030_8183,        // lb   x3, 3(x1)
+            0x0030_c203,        // lbu  x4, 3(x1)
+            0x0020_9283,        // lh   x5, 2(x1)
+            0x0020_d303,        // lhu  x6, 2(x1)
+        ]);
+
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+        for _ in 0..3 {
+            gold.step_with_bus(&mut mem);
+        }
+
+        let sb = gold.step_with_bus(&mut mem);
+        assert_eq!((sb.lsu.mem_be, sb.lsu.mem_wdata), (0b1000, 0x8000_0000));
+        assert_eq!(sb.mem_access.map(|m| m.size), Some(mem_size_e::MEM_BYTE));
+        assert_eq!(mem.read_data(256), 0x8000_0000);
+
+        let sh = gold.step_with_bus(&mut mem);
+        assert_eq!((sh.lsu.mem_be, sh.lsu.mem_wdata), (0b1100, 0xff80_0000));
+        assert_eq!(mem.read_data(256), 0xff80_0000);
+
+        for (rd, expected) in [(3, 0xffff_ffff), (4, 0x0000_00ff), (5, 0xffff_ff80), (6, 0x0000_ff80)] {
+            let load = gold.step_with_bus(&mut mem);
+            assert_eq!(load.lsu.mem_be, 0, "loads drive no write strobes");
+            assert_eq!(load.rd_write.map(|w| (w.rd, w.data)), Some((rd, expected)));
+        }
+    }
+
+    /// The base profile keeps treating every width as a word, like the RTL.
+    #[test]
+    fn test_base_profile_ignores_width() {
+        let mut gold = Lx32System::new();
+        let sb = gold.step(0x0000_0023, 0, false); // sb x0, 0(x0)
+        assert_eq!(sb.lsu.mem_be, 0xF);
+        let lb = gold.step(0x0000_0083, 0x8000_0080, false); // lb x1, 0(x0)
+        assert_eq!(lb.rd_write.map(|w| w.data), Some(0x8000_0080));
     }
 }
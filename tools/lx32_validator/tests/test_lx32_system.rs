@@ -2,8 +2,12 @@
 #[path = "common/mod.rs"]
 mod common;
 use common::*;
-use lx32_validator::models::core::lx32_system::Lx32System;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "lx32_system";
 
 pub struct LX32SystemTestParams {
     pub iterations: u32,
@@ -23,106 +27,50 @@ impl Default for LX32SystemTestParams {
     }
 }
 
-#[derive(Debug, Clone)]
-struct SystemState {
-    pc: u32,
-    registers: Vec<u32>,
-    iteration: u32,
-}
-
-fn copy_registers_from_model(system: &Lx32System) -> Vec<u32> {
-    let mut regs = Vec::new();
-    for i in 0..32 {
-        regs.push(system.reg_file.read_rs1(i as u8));
-    }
-    regs
-}
-
-fn capture_system_state(system: &Lx32System, pc: u32, iteration: u32) -> SystemState {
-    SystemState {
-        pc,
-        registers: copy_registers_from_model(system),
-        iteration,
-    }
-}
-
-fn system_states_match(rtl: &SystemState, gold: &SystemState) -> bool {
-    // PC should match
-    if rtl.pc != gold.pc {
-        return false;
-    }
-
-    // All registers should match
-    for i in 0..32 {
-        if rtl.registers[i] != gold.registers[i] {
-            return false;
-        }
-    }
-
-    true
-}
-
-fn log_system_step(rtl: &SystemState, gold: &SystemState, matches: bool, instr: u32) {
-    let status = if matches { "✓ MATCH" } else { "✗ MISMATCH" };
+fn log_system_step(iteration: u32, rtl: &CoreState, gold: &CoreState, diff: &StateDiff, step: &StepResult, mem_rdata: u32) {
+    let status = if diff.is_empty() { "✓ MATCH" } else { "✗ MISMATCH" };
 
     println!(
-        "[{:>5}] PC: 0x{:08x} | INSTR: 0x{:08x} | {}",
-        rtl.iteration, rtl.pc, instr, status
+        "[{:>5}] INSTR: 0x{:08x} {:<24} | RDATA: 0x{:08x} | PC: [R:0x{:08x} G:0x{:08x}] | {:?} | {}",
+        iteration,
+        step.instr,
+        disasm(step.instr, step.pc),
+        mem_rdata, rtl.pc, gold.pc, step.status, status
     );
-
-    if !matches {
-        for i in 0..32 {
-            if rtl.registers[i] != gold.registers[i] {
-                println!(
-                    "       x{:<2}: 0x{:08x} vs 0x{:08x} ✗",
-                    i, rtl.registers[i], gold.registers[i]
-                );
-            }
-        }
-    }
 }
 
-
-pub fn run_lx32_system_fuzzer(params: LX32SystemTestParams) {
+pub fn run_lx32_system_fuzzer(params: LX32SystemTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING LX32_SYSTEM FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Register Range: {:?}", params.reg_range);
     println!("Immediate Range: {:?}", params.imm_range);
 
-    let mut gold_sys = Lx32System::new();
-    let mut rng = rand::rng();
+    let mut tb = TestBench::new();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
-        let is_reset = i == 0;
-
-        if is_reset {
-            // Reset cycle
-            let pre_state = capture_system_state(&gold_sys, 0, i);
-            gold_sys.step(0, 0, true);
-            let post_state = capture_system_state(&gold_sys, gold_sys.pc, i);
-
-            let matches = system_states_match(&post_state, &post_state); // Trivially true for golden model only
-            if params.enable_logging {
-                log_system_step(&post_state, &post_state, true, 0);
-            }
-        } else {
-            // Generate random instruction word
-            let instr = rng.random::<u32>();
-            let mem_rdata = rng.random::<u32>();
-
-            let pre_pc = gold_sys.pc;
-            let pre_state = capture_system_state(&gold_sys, pre_pc, i);
-
-            // Execute one cycle on golden model
-            let (alu_res, rs2_data, mem_write) = gold_sys.step(instr, mem_rdata, false);
-
-            let post_pc = gold_sys.pc;
-            let post_state = capture_system_state(&gold_sys, post_pc, i);
-
-            let matches = system_states_match(&post_state, &post_state); // Trivially true for golden model only
-            if params.enable_logging {
-                log_system_step(&post_state, &post_state, true, instr);
-            }
+        // Same random instruction word and load data on both sides
+        let instr = rng.random::<u32>();
+        let mem_rdata = rng.random::<u32>();
+
+        let (rtl_state, gold_state) = tb.step_lockstep(instr, mem_rdata);
+        let diff = StateDiff::between(&rtl_state, &gold_state);
+
+        if params.enable_logging {
+            log_system_step(i, &rtl_state, &gold_state, &diff, &tb.last_step, mem_rdata);
+        }
+
+        if !diff.is_empty() {
+            println!("\n{:=^100}", " LX32_SYSTEM MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} ({})", instr, disasm(instr, tb.last_step.pc));
+            println!("mem_rdata: 0x{:08x}", mem_rdata);
+            println!("Divergent state ({} field(s)):", diff.fields.len());
+            print!("{}", diff);
+            print_gold_step(&tb.last_step);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 LX32_SYSTEM TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
 
@@ -135,6 +83,32 @@ mod tests {
 
     #[test]
     fn test_lx32_system_default() {
-        run_lx32_system_fuzzer(LX32SystemTestParams::default());
+        run_lx32_system_fuzzer(LX32SystemTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// The golden `StepResult` records decode, write-back, branch and status.
+    #[test]
+    fn test_step_result_record() {
+        use lx32_validator::models::core::lx32_system::{RegWrite, StepStatus};
+
+        let mut gold = Lx32System::new();
+
+        let addi = gold.step(0x0050_0093, 0, false); // addi x1, x0, 5
+        assert_eq!(addi.status, StepStatus::Retired);
+        assert!(addi.ctrl.reg_write && addi.ctrl.alu_src);
+        assert_eq!(addi.rd_write, Some(RegWrite { rd: 1, data: 5 }));
+        assert_eq!((addi.pc, addi.next_pc), (0, 4));
+
+        let bne = gold.step(0x0000_9463, 0, false); // bne x1, x0, 8
+        assert!(bne.ctrl.branch && bne.branch_taken);
+        assert_eq!((bne.next_pc, bne.rd_write), (12, None));
+
+        let illegal = gold.step(0xFFFF_FFFF, 0, false);
+        assert_eq!(illegal.status, StepStatus::Illegal);
+        assert_eq!((illegal.next_pc, illegal.rd_write, illegal.mem_access), (16, None, None));
+
+        let reset = gold.step(0, 0, true);
+        assert_eq!(reset.status, StepStatus::Reset);
+        assert_eq!(gold.pc, 0);
     }
 }
@@ -4,6 +4,11 @@ mod common;
 use common::*;
 use lx32_validator::models::core::memory_sim::MemorySim;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "memory_sim";
 
 pub struct MemorySimTestParams {
     pub iterations: u32,
@@ -63,14 +68,15 @@ fn log_memory_step(gold: &MemoryState, matches: bool) {
     );
 }
 
-pub fn run_memory_sim_fuzzer(params: MemorySimTestParams) {
+pub fn run_memory_sim_fuzzer(params: MemorySimTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING MEMORY_SIM FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Address Range: {:?}", params.addr_range);
     println!("Data Range: {:?}", params.data_range);
 
     let mut gold_mem = MemorySim::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         let we = rng.random_range(0..2) == 0;
@@ -101,7 +107,8 @@ pub fn run_memory_sim_fuzzer(params: MemorySimTestParams) {
                 println!("Write Data: 0x{:08x}", data);
                 println!("Read Back: 0x{:08x}", read_back);
                 println!("Expected Read Back: 0x{:08x}", data);
-                panic!("🔥 MEMORY_SIM TEST FAILED AT ITERATION {}", i);
+                print_replay(SUITE, seed, i);
+                panic!("🔥 MEMORY_SIM TEST FAILED AT ITERATION {} (seed {})", i, seed);
             }
         } else {
             // READ operation: just verify we can read from the address
@@ -126,6 +133,6 @@ mod tests {
 
     #[test]
     fn test_memory_sim_default() {
-        run_memory_sim_fuzzer(MemorySimTestParams::default());
+        run_memory_sim_fuzzer(MemorySimTestParams::default(), DEFAULT_TEST_SEED);
     }
 }
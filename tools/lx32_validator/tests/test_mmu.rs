@@ -0,0 +1,452 @@
+// tests/test_mmu.rs
+//
+// Sv32 virtual memory validation for the golden model.
+//
+// The RTL has no MMU, so this suite is golden-only. Page tables are built in a
+// 64 KB memory: a root table with a pointer to one second-level table and a
+// megapage, random leaf PTEs behind the pointer and an unmapped region. Random
+// U-mode loads and stores run through `step_with_bus`, and the outcome (page
+// fault, loaded value, stored bytes and the A/D bits written back to the PTE)
+// must match an independent reference walk of the same tables.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{addr_t, data_t, instr_t};
+use lx32_validator::models::arch::lx32_csr_pkg::*;
+use lx32_validator::models::arch::lx32_isa_pkg::INSTR_MRET;
+use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+use lx32_validator::models::arch::lx32_vm_pkg::*;
+use lx32_validator::models::core::bus::Bus;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{StepStatus, Trap};
+use rand::{RngExt, SeedableRng};
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "mmu";
+
+/// Physical layout: root table, second-level table, user code page, data pages
+pub const MEM_SIZE: usize = 0x1_0000;
+pub const ROOT: addr_t = 0x1000;
+pub const L0: addr_t = 0x2000;
+pub const CODE: addr_t = 0x3000;
+pub const DATA: addr_t = 0x8000;
+
+/// mtvec of the fuzzer and of the directed tests
+pub const HANDLER: addr_t = 0x0000_0040;
+
+/// Virtual bases: pages behind the second-level table (VA 0 is the code
+/// page), the megapage (maps PA 0-4 MB) and an unmapped 4 MB region
+const MEGAPAGE_VA: addr_t = 0x0040_0000;
+const UNMAPPED_VA: addr_t = 0x0080_0000;
+
+pub struct MmuTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for MmuTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 3000,
+            enable_logging: false,
+        }
+    }
+}
+
+/// (encoding with rd/rs2 = x2, rs1 = x1, offset 0; size; is store; mnemonic)
+const ACCESSES: [(instr_t, mem_size_e, bool, &str); 6] = [
+    (0x0000_c103, mem_size_e::MEM_BYTE, false, "lbu"),
+    (0x0000_d103, mem_size_e::MEM_HALF, false, "lhu"),
+    (0x0000_a103, mem_size_e::MEM_WORD, false, "lw"),
+    (0x0020_8023, mem_size_e::MEM_BYTE, true, "sb"),
+    (0x0020_9023, mem_size_e::MEM_HALF, true, "sh"),
+    (0x0020_a023, mem_size_e::MEM_WORD, true, "sw"),
+];
+
+pub fn pte(ppn: u32, flags: u32) -> u32 {
+    ppn << PTE_PPN_SHIFT | flags
+}
+
+pub fn read_word(mem: &mut [u8], addr: addr_t) -> u32 {
+    mem.load(addr, mem_size_e::MEM_WORD)
+}
+
+pub fn write_word(mem: &mut [u8], addr: addr_t, value: u32) {
+    mem.store(addr, value, mem_size_e::MEM_WORD)
+}
+
+/// satp selecting Sv32 with the root table at `ROOT`
+pub fn satp(asid: u32) -> u32 {
+    SATP_MODE_SV32 | asid << SATP_ASID_SHIFT | ROOT >> PAGE_SHIFT
+}
+
+/// Root table: VPN[1] = 0 points at `L0`, VPN[1] = 1 is a megapage over
+/// PA 0-4 MB with `mega_flags`; `L0[0]` maps VA 0 to the code page.
+pub fn build_tables(mem: &mut [u8], mega_flags: u32) {
+    write_word(mem, ROOT, pte(L0 >> PAGE_SHIFT, PTE_V));
+    write_word(mem, ROOT + 4, pte(0, mega_flags));
+    write_word(mem, L0, pte(CODE >> PAGE_SHIFT, PTE_V | PTE_R | PTE_X | PTE_U | PTE_A));
+}
+
+/// Golden model with Sv32 and the test handler; translation enabled.
+pub fn mmu_system() -> Lx32System {
+    let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_sv32());
+    gold.csr.write(CSR_MTVEC, HANDLER);
+    gold.csr.write(CSR_SATP, satp(0));
+    gold
+}
+
+/// Drops `gold` to U-mode at `pc` through MRET (or just moves the PC when already there).
+pub fn enter_user(gold: &mut Lx32System, pc: addr_t) {
+    if gold.csr.privilege() == priv_mode_e::PRIV_U {
+        gold.pc = pc;
+        return;
+    }
+    gold.csr.write(CSR_MSTATUS, 0);
+    gold.csr.write(CSR_MEPC, pc);
+    gold.step(INSTR_MRET, 0, false);
+}
+
+/// Reference Sv32 walk, written from the privileged spec independently of
+/// the model. Returns the physical address, the leaf PTE address and the
+/// leaf with A (and D for a store) set.
+fn reference_walk(mem: &mut [u8], va: addr_t, store: bool) -> Result<(addr_t, addr_t, u32), trap_cause_e> {
+    let fault = if store { trap_cause_e::STORE_PAGE_FAULT } else { trap_cause_e::LOAD_PAGE_FAULT };
+    let valid = |pte: u32| pte & 1 != 0 && pte & 0b110 != 0b100;
+    let leaf = |pte: u32| pte & 0b1010 != 0;
+
+    let pte1_addr = ROOT + 4 * (va >> 22);
+    let pte1 = read_word(mem, pte1_addr);
+    if !valid(pte1) {
+        return Err(fault);
+    }
+    let (pte_addr, pte, pa) = if leaf(pte1) {
+        if (pte1 >> 10) & 0x3FF != 0 {
+            return Err(fault);
+        }
+        (pte1_addr, pte1, (pte1 >> 20) << 22 | (va & 0x3F_FFFF))
+    } else {
+        let pte0_addr = ((pte1 >> 10) << 12) + 4 * ((va >> 12) & 0x3FF);
+        let pte0 = read_word(mem, pte0_addr);
+        if !valid(pte0) || !leaf(pte0) {
+            return Err(fault);
+        }
+        (pte0_addr, pte0, (pte0 >> 10) << 12 | (va & 0xFFF))
+    };
+
+    let needed = if store { 0b100 } else { 0b010 };
+    if pte & 0x10 == 0 || pte & needed == 0 {
+        return Err(fault);
+    }
+    Ok((pa, pte_addr, pte | 0x40 | if store { 0x80 } else { 0 }))
+}
+
+/// Leaf PTE over one of the data pages: mostly a readable user page with
+/// random W/X/G/A/D, sometimes entirely random flags.
+fn random_leaf(rng: &mut StdRng) -> u32 {
+    let ppn = (DATA >> PAGE_SHIFT) + rng.random_range(0..8);
+    let random = rng.random_range(0..0x100);
+    let flags = if rng.random_bool(0.75) { PTE_V | PTE_R | PTE_U | random } else { random };
+    pte(ppn, flags)
+}
+
+pub fn run_mmu_fuzzer(params: MmuTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING SV32 MMU FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut mem = vec![0u8; MEM_SIZE];
+    let mut gold = mmu_system();
+    let mut faults = 0;
+
+    for i in 0..params.iterations {
+        // The harness plays the kernel: any table edit is followed by a full fence
+        if i % 64 == 0 {
+            let mega = PTE_V | PTE_U | rng.random_range(0..0x100) & (PTE_R | PTE_W | PTE_X | PTE_A | PTE_D);
+            build_tables(&mut mem, mega);
+            for vpn0 in 1..16 {
+                write_word(&mut mem, L0 + 4 * vpn0, random_leaf(&mut rng));
+            }
+            gold.mmu.flush(None, None);
+        }
+        if rng.random_bool(0.25) {
+            let vpn0 = rng.random_range(1..16);
+            write_word(&mut mem, L0 + 4 * vpn0, random_leaf(&mut rng));
+            gold.mmu.flush(None, None);
+        }
+
+        let (encoding, size, is_store, mnemonic) = ACCESSES[rng.random_range(0..ACCESSES.len())];
+        let offset = rng.random_range(0..PAGE_SIZE) & !(size.bytes() - 1);
+        let va = match rng.random_range(0..8) {
+            0 => MEGAPAGE_VA + DATA + rng.random_range(0..8) * PAGE_SIZE + offset,
+            1 => UNMAPPED_VA + offset,
+            _ => rng.random_range(1..16) << PAGE_SHIFT | offset,
+        };
+        let data: data_t = rng.random();
+
+        write_word(&mut mem, CODE, encoding);
+        enter_user(&mut gold, 0);
+        gold.reg_file.tick(false, 1, va, true);
+        gold.reg_file.tick(false, 2, data, true);
+
+        let expected = reference_walk(&mut mem, va, is_store);
+        let before = expected.map(|(pa, pte_addr, _)| (mem.load(pa, size), read_word(&mut mem, pte_addr)));
+        let res = gold.step_with_bus(mem.as_mut_slice());
+
+        let matches = match expected {
+            Err(cause) => {
+                faults += 1;
+                res.status == StepStatus::Trap(Trap { cause, pc: 0, tval: va })
+            }
+            Ok((pa, pte_addr, leaf)) => {
+                let value = mem.load(pa, size);
+                let written = if is_store { value == data & size.mask() } else { value == before.unwrap().0 };
+                let loaded = is_store || gold.reg_file.read_rs1(2) == before.unwrap().0;
+                res.status == StepStatus::Retired && written && loaded && read_word(&mut mem, pte_addr) == leaf
+            }
+        };
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] {:<3} va:0x{:08x} -> {:?} | {:?} | {}",
+                i,
+                mnemonic,
+                va,
+                expected.map(|(pa, _, _)| pa),
+                res.status,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " SV32 MMU MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Access: {} va=0x{:08x} data=0x{:08x}", mnemonic, va, data);
+            println!("Root: [0]=0x{:08x} [1]=0x{:08x}", read_word(&mut mem, ROOT), read_word(&mut mem, ROOT + 4));
+            println!("L0[{}]=0x{:08x}", (va >> 12) & 0x3FF, read_word(&mut mem, L0 + 4 * ((va >> 12) & 0x3FF)));
+            println!("Expected: {:x?} (value, PTE before: {:x?})", expected, before);
+            println!("Actual:   {:?}", res.status);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 SV32 MMU TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("TLB hits: {} misses: {} page faults: {}", gold.mmu.hits, gold.mmu.misses, faults);
+    println!("{:=^100}", " SV32 MMU FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_isa_pkg::INSTR_SFENCE_VMA;
+    use lx32_validator::models::core::mmu::TLB_ENTRIES;
+
+    const LW: instr_t = 0x0000_a103; // lw x2, 0(x1)
+    const SW: instr_t = 0x0020_a023; // sw x2, 0(x1)
+
+    fn sfence_vma(rs1: u32, rs2: u32) -> instr_t {
+        INSTR_SFENCE_VMA | rs2 << 20 | rs1 << 15
+    }
+
+    fn trap_of(res: &StepResult) -> Option<(trap_cause_e, u32)> {
+        match res.status {
+            StepStatus::Trap(Trap { cause, tval, .. }) => Some((cause, tval)),
+            _ => None,
+        }
+    }
+
+    /// Runs `instr` from the code page in U-mode with x1 = `va`.
+    fn user_access(gold: &mut Lx32System, mem: &mut [u8], instr: instr_t, va: addr_t) -> StepResult {
+        write_word(mem, CODE, instr);
+        enter_user(gold, 0);
+        gold.reg_file.tick(false, 1, va, true);
+        gold.step_with_bus(mem)
+    }
+
+    #[test]
+    fn test_mmu_default() {
+        run_mmu_fuzzer(MmuTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// A kernel in M-mode enables Sv32 and drops to a user program at VA 0.
+    /// Its store lands in the physical data page, reads back through both the
+    /// 4 KB mapping and the megapage alias, and sets A/D in the PTEs it used.
+    #[test]
+    fn test_kernel_and_user_program() {
+        let mut mem = vec![0u8; MEM_SIZE];
+        build_tables(&mut mem, PTE_V | PTE_R | PTE_U);
+        write_word(&mut mem, L0 + 4, pte(DATA >> PAGE_SHIFT, PTE_V | PTE_R | PTE_W | PTE_U));
+
+        let kernel = [
+            0x8000_00b7, // lui   x1, 0x80000        (satp.MODE = Sv32)
+            0x0010_8093, // addi  x1, x1, 1          (root PPN)
+            0x1800_9073, // csrw  satp, x1
+            0x3410_1073, // csrw  mepc, x0           (user entry VA 0)
+            0x3000_1073, // csrw  mstatus, x0        (MPP = U)
+            0x0400_0113, // addi  x2, x0, 0x40
+            0x3051_1073, // csrw  mtvec, x2
+            INSTR_MRET,
+        ];
+        let user = [
+            0x07b0_0293, // addi  x5, x0, 0x7B
+            0x0000_1337, // lui   x6, 1              (VA 0x1000 -> PA 0x8000)
+            0x0053_2423, // sw    x5, 8(x6)
+            0x0083_2383, // lw    x7, 8(x6)
+            0x0040_8437, // lui   x8, 0x408          (megapage alias of PA 0x8000)
+            0x0084_2483, // lw    x9, 8(x8)
+            0x0000_0073, // ecall
+        ];
+        for (i, word) in kernel.iter().enumerate() {
+            write_word(&mut mem, 4 * i as u32, *word);
+        }
+        for (i, word) in user.iter().enumerate() {
+            write_word(&mut mem, CODE + 4 * i as u32, *word);
+        }
+
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_sv32());
+        let summary = gold.run_with_bus(mem.as_mut_slice(), 32, |res| res.status != StepStatus::Retired);
+        let last = summary.last.unwrap();
+        assert_eq!(trap_of(&last), Some((trap_cause_e::ECALL_U, 0)));
+        assert_eq!(gold.csr.read(CSR_MEPC), 0x18);
+
+        assert_eq!(read_word(&mut mem, DATA + 8), 0x7B);
+        assert_eq!((gold.reg_file.read_rs1(7), gold.reg_file.read_rs1(9)), (0x7B, 0x7B));
+        assert_eq!(read_word(&mut mem, L0) & (PTE_A | PTE_D), PTE_A);
+        assert_eq!(read_word(&mut mem, L0 + 4) & (PTE_A | PTE_D), PTE_A | PTE_D);
+        assert_eq!(read_word(&mut mem, ROOT + 4) & (PTE_A | PTE_D), PTE_A);
+        assert_eq!(read_word(&mut mem, ROOT) & (PTE_A | PTE_D), 0);
+    }
+
+    /// Faulting walks raise the page fault of the access with the virtual
+    /// address in mtval and leave the PTE untouched.
+    #[test]
+    fn test_page_faults() {
+        let load = trap_cause_e::LOAD_PAGE_FAULT;
+        let store = trap_cause_e::STORE_PAGE_FAULT;
+        let cases = [
+            (0, LW, load),                                          // invalid
+            (pte(8, PTE_V | PTE_W | PTE_U), LW, load),              // W without R
+            (pte(8, PTE_V | PTE_R), LW, load),                      // supervisor page
+            (pte(8, PTE_V | PTE_R | PTE_U), SW, store),             // read-only
+            (pte(8, PTE_V | PTE_X | PTE_U), LW, load),              // execute-only
+            (pte(L0 >> PAGE_SHIFT, PTE_V), LW, load),               // pointer at level 0
+        ];
+        for (leaf, instr, cause) in cases {
+            let mut mem = vec![0u8; MEM_SIZE];
+            let mut gold = mmu_system();
+            build_tables(&mut mem, PTE_V | PTE_R | PTE_U);
+            write_word(&mut mem, L0 + 4, leaf);
+
+            let res = user_access(&mut gold, &mut mem, instr, 0x1234);
+            assert_eq!(trap_of(&res), Some((cause, 0x1234)), "leaf 0x{:08x}", leaf);
+            assert_eq!(read_word(&mut mem, L0 + 4), leaf);
+            assert_eq!(gold.csr.read(CSR_MCAUSE), cause.code());
+        }
+
+        // Misaligned megapage (PPN[0] != 0), and a fetch from a non-executable page
+        let mut mem = vec![0u8; MEM_SIZE];
+        let mut gold = mmu_system();
+        build_tables(&mut mem, pte(1, PTE_V | PTE_R | PTE_U));
+        let res = user_access(&mut gold, &mut mem, LW, MEGAPAGE_VA + DATA);
+        assert_eq!(trap_of(&res), Some((load, MEGAPAGE_VA + DATA)));
+
+        write_word(&mut mem, L0, pte(CODE >> PAGE_SHIFT, PTE_V | PTE_R | PTE_U));
+        gold.mmu.flush(None, None);
+        let res = user_access(&mut gold, &mut mem, LW, 0x1000);
+        assert_eq!(trap_of(&res), Some((trap_cause_e::INSTR_PAGE_FAULT, 0)));
+
+        // satp and SFENCE.VMA belong to the kernel
+        enter_user(&mut gold, 0);
+        let csrr_satp = 0x1800_22f3; // csrr x5, satp
+        for instr in [csrr_satp, sfence_vma(0, 0)] {
+            let res = gold.step(instr, 0, false);
+            assert_eq!(trap_of(&res), Some((trap_cause_e::ILLEGAL_INSTRUCTION, instr)));
+            enter_user(&mut gold, 0);
+        }
+    }
+
+    /// The TLB keeps serving a remapped page until SFENCE.VMA; an ASID
+    /// fence spares global pages.
+    #[test]
+    fn test_tlb_and_sfence() {
+        let mut mem = vec![0u8; MEM_SIZE];
+        let mut gold = mmu_system();
+        build_tables(&mut mem, PTE_V | PTE_R | PTE_U);
+        write_word(&mut mem, L0 + 4, pte(DATA >> PAGE_SHIFT, PTE_V | PTE_R | PTE_U | PTE_A));
+        write_word(&mut mem, DATA, 0x1111);
+        write_word(&mut mem, DATA + PAGE_SIZE, 0x2222);
+
+        user_access(&mut gold, &mut mem, LW, 0x1000);
+        assert_eq!(gold.reg_file.read_rs1(2), 0x1111);
+        let misses = gold.mmu.misses;
+
+        // Remapped without a fence: still the old page, from the TLB
+        write_word(&mut mem, L0 + 4, pte((DATA >> PAGE_SHIFT) + 1, PTE_V | PTE_R | PTE_U | PTE_A));
+        user_access(&mut gold, &mut mem, LW, 0x1000);
+        assert_eq!((gold.reg_file.read_rs1(2), gold.mmu.misses), (0x1111, misses));
+
+        // The kernel fences that page
+        gold.step(0x0000_0073, 0, false); // ecall back to M-mode
+        gold.reg_file.tick(false, 3, 0x1000, true);
+        let res = gold.step(sfence_vma(3, 0), 0, false);
+        assert_eq!(res.status, StepStatus::Retired);
+        user_access(&mut gold, &mut mem, LW, 0x1000);
+        assert_eq!(gold.reg_file.read_rs1(2), 0x2222);
+
+        // ASID 1: a global and a private page; fencing ASID 1 keeps the global one
+        gold.csr.write(CSR_SATP, satp(1));
+        write_word(&mut mem, L0 + 8, pte(DATA >> PAGE_SHIFT, PTE_V | PTE_R | PTE_U | PTE_G));
+        gold.mmu.flush(None, None);
+        user_access(&mut gold, &mut mem, LW, 0x1000);
+        user_access(&mut gold, &mut mem, LW, 0x2000);
+        assert_eq!(gold.mmu.tlb_len(), 3); // code, private, global
+
+        gold.mmu.flush(None, Some(1));
+        assert_eq!(gold.mmu.tlb_len(), 1);
+        gold.mmu.flush(Some(0x2abc), None);
+        assert_eq!(gold.mmu.tlb_len(), 0);
+
+        // Round-robin replacement bounds the TLB
+        for vpn0 in 1..16 {
+            write_word(&mut mem, L0 + 4 * vpn0, pte(DATA >> PAGE_SHIFT, PTE_V | PTE_R | PTE_U));
+            user_access(&mut gold, &mut mem, LW, vpn0 << PAGE_SHIFT);
+        }
+        assert_eq!(gold.mmu.tlb_len(), TLB_ENTRIES);
+    }
+
+    /// MPRV with MPP = U translates M-mode loads and stores but not fetches;
+    /// without Sv32 in satp every access is physical.
+    #[test]
+    fn test_mprv_and_bare() {
+        let mut mem = vec![0u8; MEM_SIZE];
+        let mut gold = mmu_system();
+        build_tables(&mut mem, PTE_V | PTE_R | PTE_U);
+        write_word(&mut mem, L0 + 4, pte(DATA >> PAGE_SHIFT, PTE_V | PTE_R | PTE_U));
+        write_word(&mut mem, DATA, 0xCAFE);
+        write_word(&mut mem, CODE + 0x100, LW);
+
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MPRV);
+        gold.pc = CODE + 0x100;
+        gold.reg_file.tick(false, 1, 0x1000, true);
+        let res = gold.step_with_bus(mem.as_mut_slice());
+        assert_eq!((res.status, gold.reg_file.read_rs1(2)), (StepStatus::Retired, 0xCAFE));
+        assert_eq!(res.mem_access.unwrap().addr, DATA);
+
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MPP);
+        gold.pc = CODE + 0x100;
+        gold.step_with_bus(mem.as_mut_slice());
+        assert_eq!(gold.reg_file.read_rs1(2), read_word(&mut mem, 0x1000));
+
+        // Bare satp: U-mode is physical too
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_sv32());
+        enter_user(&mut gold, CODE + 0x100);
+        gold.reg_file.tick(false, 1, DATA, true);
+        gold.step_with_bus(mem.as_mut_slice());
+        assert_eq!(gold.reg_file.read_rs1(2), 0xCAFE);
+        assert_eq!(gold.mmu.misses, 0);
+    }
+}
@@ -0,0 +1,462 @@
+// tests/test_privilege.rs
+//
+// U-mode and physical memory protection validation for the golden model.
+//
+// The RTL always runs in M-mode and has no PMP, so this suite is golden-only.
+// Each iteration programs random PMP entries through the CSR file, places the
+// core in M-mode, U-mode or M-mode with MPRV, and executes one load or store
+// from a random PC. The outcome (retired, instruction access fault or
+// load/store access fault) must match an independent reference of the
+// privileged spec's matching rules, evaluated on the legalised entries read
+// back through pmpcfg/pmpaddr.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_arch_pkg::{addr_t, instr_t};
+use lx32_validator::models::arch::lx32_csr_pkg::*;
+use lx32_validator::models::arch::lx32_isa_pkg::INSTR_MRET;
+use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{StepStatus, Trap};
+use lx32_validator::models::core::pmp::PMP_ENTRIES;
+use rand::{RngExt, SeedableRng};
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "privilege";
+
+/// mtvec of the fuzzer and of the monitor test
+pub const HANDLER: addr_t = 0x0000_0040;
+
+/// Accesses and regions stay in the low 8 KB so they overlap often
+const SPACE: u32 = 0x2000;
+
+pub struct PrivilegeTestParams {
+    pub iterations: u32,
+    /// Up to this many random entries are programmed per iteration
+    pub max_entries: usize,
+    pub enable_logging: bool,
+}
+
+impl Default for PrivilegeTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 3000,
+            max_entries: 4,
+            enable_logging: false,
+        }
+    }
+}
+
+/// (encoding with rd/rs2 = x2, rs1 = x1, offset 0; size in bytes; is store; mnemonic)
+const ACCESSES: [(instr_t, u32, bool, &str); 8] = [
+    (0x0000_8103, 1, false, "lb"),
+    (0x0000_9103, 2, false, "lh"),
+    (0x0000_a103, 4, false, "lw"),
+    (0x0000_c103, 1, false, "lbu"),
+    (0x0000_d103, 2, false, "lhu"),
+    (0x0020_8023, 1, true, "sb"),
+    (0x0020_9023, 2, true, "sh"),
+    (0x0020_a023, 4, true, "sw"),
+];
+
+/// Reference PMP check, written from the privileged spec independently of
+/// the model: the lowest-numbered entry matching any byte decides, a partial
+/// match fails, and without a match only M-mode succeeds.
+fn reference_allows(cfg: &[u8; PMP_ENTRIES], pmpaddr: &[u32; PMP_ENTRIES], addr: u32, len: u32, perms: u8, user: bool) -> bool {
+    let bytes = addr as u64..addr as u64 + len as u64;
+    for i in 0..PMP_ENTRIES {
+        let word = pmpaddr[i] as u64;
+        let (lo, hi) = match (cfg[i] >> 3) & 0b11 {
+            0b01 => (if i == 0 { 0 } else { 4 * pmpaddr[i - 1] as u64 }, 4 * word),
+            0b10 => (4 * word, 4 * word + 4),
+            0b11 => {
+                let size = 8u64 << pmpaddr[i].trailing_ones();
+                let base = (4 * word) & !(size - 1);
+                (base, base + size)
+            }
+            _ => continue,
+        };
+        let hits = bytes.clone().filter(|b| (lo..hi).contains(b)).count() as u32;
+        if hits == 0 {
+            continue;
+        }
+        if hits != len {
+            return false;
+        }
+        let locked = cfg[i] & 0x80 != 0;
+        return (!user && !locked) || cfg[i] & perms == perms;
+    }
+    !user
+}
+
+/// PMP entries as the CSR file reports them.
+fn read_pmp(gold: &Lx32System) -> ([u8; PMP_ENTRIES], [u32; PMP_ENTRIES]) {
+    let cfg = std::array::from_fn(|i| (gold.csr.read(CSR_PMPCFG0 + (i / 4) as csr_addr_t) >> (8 * (i % 4))) as u8);
+    let addr = std::array::from_fn(|i| gold.csr.read(CSR_PMPADDR0 + i as csr_addr_t));
+    (cfg, addr)
+}
+
+/// Writes entry `index`: pmpaddr first, then its byte of pmpcfg.
+fn write_entry(gold: &mut Lx32System, index: usize, cfg: u8, addr: u32) {
+    gold.csr.write(CSR_PMPADDR0 + index as csr_addr_t, addr);
+    let csr = CSR_PMPCFG0 + (index / 4) as csr_addr_t;
+    let shift = 8 * (index % 4);
+    let old = gold.csr.read(csr);
+    gold.csr.write(csr, (old & !(0xFF << shift)) | (cfg as u32) << shift);
+}
+
+/// Drops `gold` to U-mode at `pc` through MRET.
+fn enter_user(gold: &mut Lx32System, pc: addr_t) {
+    gold.csr.write(CSR_MSTATUS, 0);
+    gold.csr.write(CSR_MEPC, pc);
+    let res = gold.step(INSTR_MRET, 0, false);
+    assert_eq!(res.status, StepStatus::Retired);
+    assert_eq!(gold.csr.privilege(), priv_mode_e::PRIV_U);
+}
+
+pub fn run_privilege_fuzzer(params: PrivilegeTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING PRIVILEGE / PMP FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Entries per iteration: up to {}", params.max_entries);
+    println!("Seed: {}", seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut faults = 0;
+
+    for i in 0..params.iterations {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_user_mode());
+        gold.csr.write(CSR_MTVEC, HANDLER);
+
+        // Privilege first: once PMP is programmed the MRET itself could fault
+        let pc = rng.random_range(0..SPACE) & !0b11;
+        let (mode, mprv) = match rng.random_range(0..3) {
+            0 => (priv_mode_e::PRIV_U, false),
+            1 => (priv_mode_e::PRIV_M, true),
+            _ => (priv_mode_e::PRIV_M, false),
+        };
+        if mode == priv_mode_e::PRIV_U {
+            enter_user(&mut gold, pc);
+        } else {
+            gold.pc = pc;
+            // MPP = U: with MPRV set, loads and stores are checked as U-mode
+            gold.csr.write(CSR_MSTATUS, if mprv { MSTATUS_MPRV } else { MSTATUS_MPP });
+        }
+
+        // NAPOT regions get up to 1 KB; locks are rare so M-mode keeps some freedom
+        for _ in 0..rng.random_range(0..=params.max_entries) {
+            let word = rng.random_range(0..SPACE / 4);
+            let addr = if rng.random_bool(0.5) { word | ((1 << rng.random_range(0..8)) - 1) } else { word };
+            let cfg = rng.random::<u8>() & if rng.random_bool(0.2) { 0xFF } else { !PMP_L };
+            write_entry(&mut gold, rng.random_range(0..PMP_ENTRIES), cfg, addr);
+        }
+        let (cfg, pmpaddr) = read_pmp(&gold);
+
+        let (encoding, len, is_store, mnemonic) = ACCESSES[rng.random_range(0..ACCESSES.len())];
+        let addr = rng.random_range(0..SPACE) & !(len - 1);
+        gold.reg_file.tick(false, 1, addr, true);
+
+        let user = mode == priv_mode_e::PRIV_U;
+        let perms = if is_store { PMP_W } else { PMP_R };
+        let expected = if !reference_allows(&cfg, &pmpaddr, pc, 4, PMP_X, user) {
+            StepStatus::Trap(Trap { cause: trap_cause_e::INSTR_ACCESS_FAULT, pc, tval: pc })
+        } else if !reference_allows(&cfg, &pmpaddr, addr, len, perms, user || mprv) {
+            let cause = if is_store { trap_cause_e::STORE_ACCESS_FAULT } else { trap_cause_e::LOAD_ACCESS_FAULT };
+            StepStatus::Trap(Trap { cause, pc, tval: addr })
+        } else {
+            StepStatus::Retired
+        };
+
+        let res = gold.step(encoding, 0, false);
+
+        // A trap always lands in M-mode with the interrupted mode in MPP
+        let trapped = matches!(res.status, StepStatus::Trap(_));
+        let mpp = priv_mode_e::from_mpp(gold.csr.read(CSR_MSTATUS));
+        let matches = res.status == expected
+            && (!trapped || (gold.pc == HANDLER && gold.csr.privilege() == priv_mode_e::PRIV_M && mpp == mode));
+        faults += trapped as u32;
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] {:?}{} pc:0x{:04x} {:<3} 0x{:04x} -> {:?} | {}",
+                i,
+                mode,
+                if mprv { "+MPRV" } else { "" },
+                pc,
+                mnemonic,
+                addr,
+                res.status,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " PRIVILEGE / PMP MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Mode: {:?} MPRV: {} Access: {} 0x{:08x} at pc 0x{:08x}", mode, mprv, mnemonic, addr, pc);
+            for (entry, (c, a)) in cfg.iter().zip(pmpaddr.iter()).enumerate().filter(|(_, (c, _))| **c != 0) {
+                println!("  pmp{:<2}: cfg=0x{:02x} addr=0x{:08x}", entry, c, a);
+            }
+            println!("Expected: {:?}", expected);
+            println!("Actual:   {:?} (privilege {:?}, MPP {:?})", res.status, gold.csr.privilege(), mpp);
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 PRIVILEGE TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("Access faults: {}", faults);
+    println!("{:=^100}", " PRIVILEGE / PMP FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_isa_pkg::INSTR_ECALL;
+    use lx32_validator::models::core::csr::csr_exists;
+
+    const NOP: u32 = 0x0000_0013;
+
+    /// addi rd, rs1, imm
+    fn addi(rd: u32, rs1: u32, imm: i32) -> instr_t {
+        ((imm as u32 & 0xFFF) << 20) | rs1 << 15 | rd << 7 | 0x13
+    }
+
+    /// csrrw x0, csr, rs1
+    fn csrw(csr: csr_addr_t, rs1: u32) -> instr_t {
+        (csr as u32) << 20 | rs1 << 15 | 0b001 << 12 | 0x73
+    }
+
+    /// csrrs rd, csr, x0
+    fn csrr(rd: u32, csr: csr_addr_t) -> instr_t {
+        (csr as u32) << 20 | 0b010 << 12 | rd << 7 | 0x73
+    }
+
+    /// Lets U-mode access the whole address space (it has no access by default).
+    fn grant_all(gold: &mut Lx32System) {
+        let napot = (pmp_match_e::PMP_NAPOT as u8) << PMP_A_SHIFT;
+        write_entry(gold, PMP_ENTRIES - 1, napot | PMP_X | PMP_W | PMP_R, 0x1FFF_FFFF);
+    }
+
+    fn trap_of(res: &StepResult) -> Option<(trap_cause_e, u32)> {
+        match res.status {
+            StepStatus::Trap(Trap { cause, tval, .. }) => Some((cause, tval)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_privilege_default() {
+        run_privilege_fuzzer(PrivilegeTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// A monitor at 0x000-0x7FF drops an untrusted program at 0x800 to U-mode
+    /// behind one NAPOT region. The program cannot store to, load from or jump
+    /// into the monitor; each attempt traps back to it, as does its ECALL.
+    #[test]
+    fn test_monitor_isolation() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_user_mode());
+        let monitor = [
+            addi(1, 0, 0x2FF), // NAPOT 0x800-0xFFF
+            csrw(CSR_PMPADDR0, 1),
+            addi(1, 0, 0x1F), // NAPOT | X | W | R
+            csrw(CSR_PMPCFG0, 1),
+            addi(1, 0, HANDLER as i32),
+            csrw(CSR_MTVEC, 1),
+            0x0000_10b7,      // lui  x1, 1
+            addi(1, 1, -0x800), // x1 = 0x800
+            csrw(CSR_MEPC, 1),
+            csrw(CSR_MSTATUS, 0), // MPP = U
+            INSTR_MRET,
+        ];
+        let user = [
+            addi(2, 0, 0x55),
+            0x1020_2023, // sw   x2, 0x100(x0)   monitor data
+            0x0000_2183, // lw   x3, 0(x0)       monitor code
+            0x7e20_ae23, // sw   x2, 0x7FC(x1)   own region (0xFFC)
+            0x0000_0067, // jalr x0, 0(x0)       into the monitor
+            INSTR_ECALL,
+        ];
+        for (i, word) in monitor.iter().enumerate() {
+            gold.load_image(4 * i as u32, &word.to_le_bytes());
+        }
+        gold.load_image(HANDLER, &INSTR_MRET.to_le_bytes());
+        for (i, word) in user.iter().enumerate() {
+            gold.load_image(0x800 + 4 * i as u32, &word.to_le_bytes());
+        }
+
+        let boot = gold.run(monitor.len() as u64, |res| res.status != StepStatus::Retired);
+        assert!(!boot.halted);
+        assert_eq!((gold.pc, gold.csr.privilege()), (0x800, priv_mode_e::PRIV_U));
+
+        // (cause, mepc, mtval, where the monitor resumes the program)
+        let expected = [
+            (trap_cause_e::STORE_ACCESS_FAULT, 0x804, 0x100, 0x808),
+            (trap_cause_e::LOAD_ACCESS_FAULT, 0x808, 0x000, 0x80C),
+            (trap_cause_e::INSTR_ACCESS_FAULT, 0x000, 0x000, 0x814),
+            (trap_cause_e::ECALL_U, 0x814, 0, 0x818),
+        ];
+        for (cause, mepc, mtval, resume) in expected {
+            let summary = gold.run(8, |res| res.status != StepStatus::Retired);
+            assert!(summary.halted);
+            assert_eq!(trap_of(&summary.last.unwrap()), Some((cause, mtval)));
+            assert_eq!(gold.csr.read(CSR_MCAUSE), cause.code());
+            assert_eq!(gold.csr.read(CSR_MEPC), mepc);
+            assert_eq!(gold.csr.privilege(), priv_mode_e::PRIV_M);
+            assert_eq!(priv_mode_e::from_mpp(gold.csr.read(CSR_MSTATUS)), priv_mode_e::PRIV_U);
+
+            // The monitor's handler (an MRET) resumes after the faulting instruction
+            gold.csr.write(CSR_MEPC, resume);
+            gold.run(1, |_| false);
+            assert_eq!((gold.pc, gold.csr.privilege()), (resume, priv_mode_e::PRIV_U));
+        }
+
+        assert_eq!(gold.read_mem(0x100), 0);
+        assert_eq!(gold.read_mem(0xFFC), 0x55);
+        assert_eq!(gold.read_mem(0), monitor[0]);
+    }
+
+    /// WARL pmpcfg fields, and locked entries: frozen until reset, binding
+    /// in M-mode, and freezing the pmpaddr below a locked TOR entry.
+    #[test]
+    fn test_pmp_locking() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_user_mode());
+
+        // R = 0, W = 1 is reserved: W is dropped; bits 6:5 are read-only zero
+        gold.csr.write(CSR_PMPCFG0, 0x0000_0062 | (0x0F << 8));
+        assert_eq!(gold.csr.read(CSR_PMPCFG0), 0x0F << 8);
+
+        // Entry 3: locked, read-only TOR over [0x100, 0x200)
+        write_entry(&mut gold, 2, 0, 0x100 >> 2);
+        write_entry(&mut gold, 3, PMP_L | (pmp_match_e::PMP_TOR as u8) << PMP_A_SHIFT | PMP_R, 0x200 >> 2);
+        write_entry(&mut gold, 3, 0, 0);
+        gold.csr.write(CSR_PMPADDR0 + 2, 0);
+        let (cfg, addr) = read_pmp(&gold);
+        assert_eq!((cfg[3] & PMP_L, addr[2], addr[3]), (PMP_L, 0x40, 0x80));
+
+        // M-mode obeys the locked entry but nothing else
+        gold.reg_file.tick(false, 1, 0x104, true);
+        gold.pc = 0x400;
+        let res = gold.step(0x0020_a023, 0, false); // sw x2, 0(x1)
+        assert_eq!(trap_of(&res), Some((trap_cause_e::STORE_ACCESS_FAULT, 0x104)));
+        gold.pc = 0x400;
+        let res = gold.step(0x0000_a103, 0, false); // lw x2, 0(x1)
+        assert_eq!(res.status, StepStatus::Retired);
+        gold.pc = 0x100;
+        let res = gold.step(NOP, 0, false);
+        assert_eq!(trap_of(&res), Some((trap_cause_e::INSTR_ACCESS_FAULT, 0x100)));
+
+        // Only reset unlocks
+        gold.step(NOP, 0, true);
+        assert_eq!(read_pmp(&gold), ([0; PMP_ENTRIES], [0; PMP_ENTRIES]));
+    }
+
+    /// NA4 covers one word; an access straddling a region boundary fails
+    /// even when both regions grant it.
+    #[test]
+    fn test_partial_match() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_user_mode());
+        let na4 = (pmp_match_e::PMP_NA4 as u8) << PMP_A_SHIFT;
+        let napot = (pmp_match_e::PMP_NAPOT as u8) << PMP_A_SHIFT;
+        write_entry(&mut gold, 0, na4 | PMP_R, 0x300 >> 2);
+        write_entry(&mut gold, 1, napot | PMP_R | PMP_W | PMP_X, 0x1FF); // 0x000-0xFFF
+        enter_user(&mut gold, 0x400);
+
+        // Entry 0 wins over entry 1 for the word it covers
+        let cases = [(0x300, 0x0020_9023, Some(trap_cause_e::STORE_ACCESS_FAULT)), (0x304, 0x0020_9023, None)];
+        for (addr, instr, fault) in cases {
+            gold.reg_file.tick(false, 1, addr, true);
+            let res = gold.step(instr, 0, false);
+            assert_eq!(trap_of(&res).map(|t| t.0), fault);
+            if fault.is_some() {
+                enter_user(&mut gold, 0x400);
+            }
+        }
+        assert!(!gold.csr.pmp_allows(0x2FE, 4, PMP_R, priv_mode_e::PRIV_U));
+        assert!(!gold.csr.pmp_allows(0x302, 4, PMP_R, priv_mode_e::PRIV_U));
+        assert!(gold.csr.pmp_allows(0x300, 4, PMP_R, priv_mode_e::PRIV_U));
+        // Partial matches fail in M-mode too; past the last region only M-mode succeeds
+        assert!(!gold.csr.pmp_allows(0xFFE, 4, PMP_R, priv_mode_e::PRIV_M));
+        assert!(gold.csr.pmp_allows(0x1000, 4, PMP_R, priv_mode_e::PRIV_M));
+        assert!(!gold.csr.pmp_allows(0x1000, 4, PMP_R, priv_mode_e::PRIV_U));
+    }
+
+    /// MPP holds M or U and survives a trap/MRET round trip; MRET leaving
+    /// M-mode clears MPRV. Without U-mode MPP stays M.
+    #[test]
+    fn test_mpp_round_trip() {
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i().with_user_mode());
+        gold.csr.write(CSR_MTVEC, HANDLER);
+        grant_all(&mut gold);
+        assert_eq!(gold.csr.read(CSR_MSTATUS) & MSTATUS_MPP, MSTATUS_MPP);
+
+        // 0b01 (S) is not implemented: MPP keeps its value
+        gold.csr.write(CSR_MSTATUS, 1 << MSTATUS_MPP_SHIFT);
+        assert_eq!(gold.csr.read(CSR_MSTATUS) & MSTATUS_MPP, MSTATUS_MPP);
+
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MPRV);
+        gold.csr.write(CSR_MEPC, 0x200);
+        gold.step(INSTR_MRET, 0, false);
+        assert_eq!((gold.pc, gold.csr.privilege()), (0x200, priv_mode_e::PRIV_U));
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPIE);
+
+        let res = gold.step(INSTR_ECALL, 0, false);
+        assert_eq!(trap_of(&res), Some((trap_cause_e::ECALL_U, 0)));
+        assert_eq!(gold.csr.read(CSR_MCAUSE), 8);
+        assert_eq!(priv_mode_e::from_mpp(gold.csr.read(CSR_MSTATUS)), priv_mode_e::PRIV_U);
+
+        // Returning with MPP = M stays in M-mode
+        gold.csr.write(CSR_MSTATUS, MSTATUS_MPP);
+        let res = gold.step(INSTR_MRET, 0, false);
+        assert_eq!((res.status, gold.csr.privilege()), (StepStatus::Retired, priv_mode_e::PRIV_M));
+        let res = gold.step(INSTR_ECALL, 0, false);
+        assert_eq!(trap_of(&res), Some((trap_cause_e::ECALL_M, 0)));
+
+        let mut base = Lx32System::with_config(IsaConfig::rv32i());
+        base.csr.write(CSR_MSTATUS, MSTATUS_MPRV);
+        base.step(INSTR_MRET, 0, false);
+        assert_eq!(base.csr.privilege(), priv_mode_e::PRIV_M);
+        assert_eq!(base.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE);
+    }
+
+    /// U-mode may not touch machine CSRs or execute MRET; the counters need
+    /// their mcounteren bit. Interrupts are taken in U-mode whatever MIE says.
+    #[test]
+    fn test_user_csr_access() {
+        let cfg = IsaConfig::rv32i().with_user_mode();
+        let mut gold = Lx32System::with_config(cfg);
+        gold.csr.write(CSR_MTVEC, HANDLER);
+        grant_all(&mut gold);
+        enter_user(&mut gold, 0x100);
+
+        let illegal = [csrr(5, CSR_MSTATUS), csrw(CSR_MSCRATCH, 0), csrr(5, CSR_CYCLE), INSTR_MRET];
+        for instr in illegal {
+            let res = gold.step(instr, 0, false);
+            assert_eq!(trap_of(&res), Some((trap_cause_e::ILLEGAL_INSTRUCTION, instr)));
+            enter_user(&mut gold, 0x100);
+        }
+
+        gold.csr.write(CSR_MCOUNTEREN, 0xFFFF_FFFF);
+        assert_eq!(gold.csr.read(CSR_MCOUNTEREN), MCOUNTEREN_CY | MCOUNTEREN_IR);
+        let res = gold.step(csrr(5, CSR_CYCLE), 0, false);
+        assert_eq!(res.status, StepStatus::Retired);
+
+        assert!(csr_exists(CSR_MCOUNTEREN, &cfg) && csr_exists(CSR_PMPADDR0 + 15, &cfg));
+        assert!(!csr_exists(CSR_MCOUNTEREN, &IsaConfig::rv32i()));
+        assert!(!csr_exists(CSR_PMPCFG0, &IsaConfig::rv32i()));
+        assert_eq!(gold.csr.read(CSR_MISA) & (1 << 20), 1 << 20);
+
+        let mut gold = Lx32System::with_config(IsaConfig {
+            interrupts: true,
+            ..cfg
+        });
+        gold.csr.write(CSR_MTVEC, HANDLER);
+        gold.csr.write(CSR_MIE, MIP_MSIP);
+        grant_all(&mut gold);
+        enter_user(&mut gold, 0x100);
+        gold.clint.msip = true;
+        let res = gold.step(NOP, 0, false);
+        assert!(matches!(res.status, StepStatus::Interrupt(_)));
+        assert_eq!(gold.csr.privilege(), priv_mode_e::PRIV_M);
+    }
+}
@@ -4,6 +4,11 @@ mod common;
 use common::*;
 use lx32_validator::models::core::reg_generic::RegGeneric;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "reg_generic";
 
 pub struct RegGenericTestParams {
     pub iterations: u32,
@@ -69,13 +74,14 @@ fn log_reg_step(pre: &RegState, post: &RegState, reset: bool, enable: bool, matc
     );
 }
 
-pub fn run_reg_generic_fuzzer(params: RegGenericTestParams) {
+pub fn run_reg_generic_fuzzer(params: RegGenericTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING REG_GENERIC FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Data Range: {:?}", params.data_range);
 
     let mut gold_reg = RegGeneric::new(32);
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         // 33% reset, 33% enable, 33% hold
@@ -110,7 +116,8 @@ pub fn run_reg_generic_fuzzer(params: RegGenericTestParams) {
             } else {
                 println!("Expected post output: 0x{:08x} (unchanged)", pre_state.data_out);
             }
-            panic!("🔥 REG_GENERIC TEST FAILED AT ITERATION {}", i);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 REG_GENERIC TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
 
@@ -123,6 +130,6 @@ mod tests {
 
     #[test]
     fn test_reg_generic_default() {
-        run_reg_generic_fuzzer(RegGenericTestParams::default());
+        run_reg_generic_fuzzer(RegGenericTestParams::default(), DEFAULT_TEST_SEED);
     }
 }
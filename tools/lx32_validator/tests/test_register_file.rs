@@ -4,6 +4,11 @@ mod common;
 use common::*;
 use lx32_validator::models::core::register_file::RegisterFile;
 use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "register_file";
 
 pub struct RegisterFileTestParams {
     pub iterations: u32,
@@ -95,14 +100,15 @@ fn log_rf_step(pre: &RegisterState, post: &RegisterState, matches: bool) {
     );
 }
 
-pub fn run_register_file_fuzzer(params: RegisterFileTestParams) {
+pub fn run_register_file_fuzzer(params: RegisterFileTestParams, seed: u64) {
     println!("\n{:=^100}", " STARTING REGISTER_FILE FUZZER ");
     println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
     println!("Register Range: {:?}", params.reg_range);
     println!("Data Range: {:?}", params.data_range);
 
     let mut gold_rf = RegisterFile::new();
-    let mut rng = rand::rng();
+    let mut rng = StdRng::seed_from_u64(seed);
 
     for i in 0..params.iterations {
         let reset = i == 0;
@@ -140,7 +146,8 @@ pub fn run_register_file_fuzzer(params: RegisterFileTestParams) {
             if addr_rs2 == 0 && post_state.data_rs2 != 0 {
                 println!("ERROR: x0 must always be 0!");
             }
-            panic!("🔥 REGISTER_FILE TEST FAILED AT ITERATION {}", i);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 REGISTER_FILE TEST FAILED AT ITERATION {} (seed {})", i, seed);
         }
     }
 
@@ -153,6 +160,23 @@ mod tests {
 
     #[test]
     fn test_register_file_default() {
-        run_register_file_fuzzer(RegisterFileTestParams::default());
+        run_register_file_fuzzer(RegisterFileTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// 16 registers: addresses keep their low 4 bits, x16 aliases x0.
+    #[test]
+    fn test_register_file_rv32e() {
+        let mut rf = RegisterFile::with_count(16);
+        assert_eq!(rf.count(), 16);
+
+        rf.tick(false, 17, 0xDEAD_BEEF, true);
+        assert_eq!((rf.read_rs1(1), rf.read_rs2(17)), (0xDEAD_BEEF, 0xDEAD_BEEF));
+        assert_eq!((rf.get_reg(1), rf.get_reg(17)), (0xDEAD_BEEF, 0));
+
+        rf.tick(false, 16, 1, true);
+        assert_eq!((rf.read_rs1(16), rf.read_rs1(0)), (0, 0));
+
+        rf.tick(true, 0, 0, false);
+        assert_eq!(rf.read_rs1(1), 0);
     }
 }
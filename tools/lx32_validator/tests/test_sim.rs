@@ -0,0 +1,322 @@
+// tests/test_sim.rs
+//
+// Instruction-set simulator (sim.rs / lx32-sim) validation.
+//
+// Random straight-line RV32IM programs (LUI, AUIPC, OP-IMM, OP) ending in the
+// crt0.S exit sequence are packaged either as a flat binary or as an ELF
+// executable at a random base, then run by the simulator. The run must end
+// through the exit port with x10 as the exit code, after one cycle per
+// instruction, with the same registers as a model fed the words directly.
+// Directed tests run crt0.S, the legacy exit port, trap exits, the cycle and
+// PC limits, and ELF loading errors.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_instr_pkg::Instr;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::sim::{Image, SIM_MEMORY_SIZE, Simulator, Stop};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "sim";
+
+pub struct SimTestParams {
+    pub iterations: u32,
+    pub max_program_len: usize,
+    pub enable_logging: bool,
+}
+
+impl Default for SimTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            max_program_len: 64,
+            enable_logging: true,
+        }
+    }
+}
+
+/// Opcodes without control flow or memory accesses: LUI, AUIPC, OP-IMM, OP.
+const OPCODES: [u32; 4] = [0x37, 0x17, 0x13, 0x33];
+
+/// `lui x5, 0xFFFFF; sw x10, 4(x5)`: exit through 0xFFFF_F004 with a0, as crt0.S does.
+const EXIT_SEQUENCE: [u32; 2] = [0xFFFF_F2B7, 0x00A2_A223];
+
+fn random_instr(rng: &mut StdRng) -> Instr {
+    loop {
+        let word: u32 = rng.random();
+        // funct7 of 0x00, 0x20 or 0x01 so shifts, SUB/SRA and M forms show up
+        let funct7 = [0x00, 0x20, 0x01][rng.random_range(0..3)];
+        let word = (word & !0x7F & !(0x7F << 25)) | (funct7 << 25) | OPCODES[rng.random_range(0..OPCODES.len())];
+        if let Some(instr) = Instr::decode(word) {
+            return instr;
+        }
+    }
+}
+
+fn to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+/// Linked little-endian RV32 ELF executable with one PT_LOAD per
+/// `(paddr, data, memsz)` entry.
+fn elf_exec(entry: u32, segments: &[(u32, &[u8], u32)]) -> Vec<u8> {
+    let mut elf = vec![0u8; 52];
+    elf[..7].copy_from_slice(b"\x7fELF\x01\x01\x01");
+    elf[16..18].copy_from_slice(&2u16.to_le_bytes()); // ET_EXEC
+    elf[18..20].copy_from_slice(&0xF3u16.to_le_bytes()); // EM_RISCV
+    elf[20..24].copy_from_slice(&1u32.to_le_bytes());
+    elf[24..28].copy_from_slice(&entry.to_le_bytes());
+    elf[28..32].copy_from_slice(&52u32.to_le_bytes());
+    elf[40..42].copy_from_slice(&52u16.to_le_bytes());
+    elf[42..44].copy_from_slice(&32u16.to_le_bytes());
+    elf[44..46].copy_from_slice(&(segments.len() as u16).to_le_bytes());
+
+    let mut offset = 52 + 32 * segments.len() as u32;
+    for (paddr, data, memsz) in segments {
+        for field in [1, offset, *paddr, *paddr, data.len() as u32, *memsz, 0x7, 4] {
+            elf.extend_from_slice(&field.to_le_bytes());
+        }
+        offset += data.len() as u32;
+    }
+    for (_, data, _) in segments {
+        elf.extend_from_slice(data);
+    }
+    elf
+}
+
+pub fn run_sim_fuzzer(params: SimTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING SIMULATOR FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let cfg = IsaConfig::rv32im();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut elf_count = 0;
+
+    for i in 0..params.iterations {
+        let len = rng.random_range(1..=params.max_program_len);
+        let mut words: Vec<u32> = (0..len).map(|_| random_instr(&mut rng).encode()).collect();
+        words.extend(EXIT_SEQUENCE);
+        let code = to_bytes(&words);
+
+        // Flat binaries always start at 0; ELF files anywhere in RAM, with a .bss after the code
+        let as_elf = rng.random_bool(0.5);
+        let (base, file) = if as_elf {
+            elf_count += 1;
+            let base = rng.random_range(0..(SIM_MEMORY_SIZE as u32 - 0x1000) / 4) * 4;
+            let bss = rng.random_range(0..256);
+            (base, elf_exec(base, &[(base, &code, code.len() as u32 + bss)]))
+        } else {
+            (0, code.clone())
+        };
+
+        let mut reference = Lx32System::with_config(cfg);
+        reference.pc = base;
+        for &word in &words {
+            reference.step(word, 0, false);
+        }
+
+        let image = Image::parse(&file).expect("generated image must load");
+        let mut sim = Simulator::new(cfg, &image, SIM_MEMORY_SIZE).expect("generated image must fit");
+        let stop = sim.run(words.len() as u64 + 1, |_, _| {});
+
+        let expected_exit = reference.reg_file.get_reg(10);
+        let regs_match = (0..reference.reg_file.count()).all(|r| sim.core.reg_file.get_reg(r) == reference.reg_file.get_reg(r));
+        if stop != Stop::Exit(expected_exit) || sim.cycles != words.len() as u64 || !regs_match || sim.core.pc != reference.pc {
+            println!("\n{:!^100}", " SIMULATOR MISMATCH ");
+            println!("Iteration: {} ({} at base 0x{:08X})", i, if as_elf { "ELF" } else { "flat binary" }, base);
+            println!("Stop:      {:?} (expected {:?})", stop, Stop::Exit(expected_exit));
+            println!("Cycles:    {} (expected {})", sim.cycles, words.len());
+            println!("PC:        0x{:08X} (expected 0x{:08X})", sim.core.pc, reference.pc);
+            for r in 0..reference.reg_file.count() {
+                let (got, expected) = (sim.core.reg_file.get_reg(r), reference.reg_file.get_reg(r));
+                if got != expected {
+                    println!("  x{:<2}: 0x{:08X} (expected 0x{:08X})", r, got, expected);
+                }
+            }
+            for (k, &word) in words.iter().enumerate() {
+                println!("  0x{:08X}: 0x{:08X}  {}", base + 4 * k as u32, word, disasm(word, base + 4 * k as u32));
+            }
+            print_replay(SUITE, seed, i);
+            panic!("Simulator mismatch");
+        }
+
+        if params.enable_logging && i % 100 == 0 {
+            println!("Iteration {}: {} instructions, exit code 0x{:08X}", i, words.len(), expected_exit);
+        }
+    }
+
+    println!("Simulator fuzzer passed ({} programs, {} as ELF)", params.iterations, elf_count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::asm::assemble;
+    use lx32_validator::models::arch::lx32_mem_pkg::mem_size_e;
+    use lx32_validator::models::core::bus::Bus;
+    use lx32_validator::sim::{SimBus, exit_status};
+
+    const CRT0: &str = include_str!("../../lx32_backend/tests/baremetal/crt0.S");
+    const CRT0_TRAP: &str = include_str!("../../lx32_backend/tests/baremetal/crt0_trap.S");
+
+    /// A startup file followed by `main`, placed right after it where it is called.
+    fn with_crt0(crt0: &str, main: &str) -> Vec<u8> {
+        assemble(&format!("{}\nmain:\n{}", crt0, main)).unwrap().to_flat(0).unwrap()
+    }
+
+    fn run(cfg: IsaConfig, file: &[u8], max_cycles: u64) -> (Stop, Simulator) {
+        let image = Image::parse(file).unwrap();
+        let mut sim = Simulator::new(cfg, &image, SIM_MEMORY_SIZE).unwrap();
+        let stop = sim.run(max_cycles, |_, _| {});
+        (stop, sim)
+    }
+
+    #[test]
+    fn test_sim_default() {
+        run_sim_fuzzer(
+            SimTestParams {
+                enable_logging: false,
+                ..SimTestParams::default()
+            },
+            DEFAULT_TEST_SEED,
+        );
+    }
+
+    /// `main` returning 42 exits with 42 through either startup: crt0_trap
+    /// leaves through ECALL on CSR-capable profiles and falls through to the
+    /// MMIO store on the base profile.
+    #[test]
+    fn test_crt0_exit() {
+        for crt0 in [CRT0, CRT0_TRAP] {
+            let program = with_crt0(crt0, "    li a0, 42\n    ret");
+            for cfg in [IsaConfig::base(), IsaConfig::rv32i(), IsaConfig::full()] {
+                let (stop, _) = run(cfg, &program, 1000);
+                assert_eq!(stop, Stop::Exit(42), "{:?}", cfg);
+            }
+        }
+    }
+
+    /// Under crt0_trap a trap other than ECALL exits with 0x100 + mcause
+    /// (illegal instruction: 2).
+    #[test]
+    fn test_trap_exit() {
+        let program = with_crt0(CRT0_TRAP, "    .word 0\n    ret");
+        let (stop, _) = run(IsaConfig::full(), &program, 1000);
+        assert_eq!(stop, Stop::Exit(0x102));
+        assert_eq!(exit_status(0x102), 255);
+    }
+
+    #[test]
+    fn test_legacy_exit_port() {
+        let program = to_bytes(&[0x8000_02B7, 0x0070_0513, 0x00A2_A023]); // lui x5, 0x80000; li a0, 7; sw a0, 0(x5)
+        let (stop, sim) = run(IsaConfig::base(), &program, 100);
+        assert_eq!(stop, Stop::Exit(7));
+        assert_eq!(sim.cycles, 3);
+    }
+
+    /// Exit ports see the stored bytes only, and the first exit wins.
+    #[test]
+    fn test_exit_port_store_sizes() {
+        let mut bus = SimBus::new(16);
+        bus.store(0xFFFF_F004, 0x1234_5678, mem_size_e::MEM_BYTE);
+        bus.store(0x8000_0000, 1, mem_size_e::MEM_WORD);
+        assert_eq!(bus.exit_code, Some(0x78));
+        assert!(bus.ram.iter().all(|&byte| byte == 0));
+        assert!(bus.maps(0x8000_0000) && bus.maps(15) && !bus.maps(16));
+    }
+
+    #[test]
+    fn test_max_cycles() {
+        let program = to_bytes(&[0x0000_006F]); // j .
+        let (stop, sim) = run(IsaConfig::full(), &program, 50);
+        assert_eq!(stop, Stop::MaxCycles);
+        assert_eq!(sim.cycles, 50);
+    }
+
+    #[test]
+    fn test_pc_out_of_bounds() {
+        let program = to_bytes(&[0x0001_02B7, 0x0002_8067]); // lui x5, 0x10; jr x5
+        let (stop, sim) = run(IsaConfig::base(), &program, 100);
+        assert_eq!(stop, Stop::PcOutOfBounds(0x1_0000));
+        assert_eq!(sim.cycles, 2);
+    }
+
+    /// `step` is one cycle of `run`, exit-port store included (as run_program --lockstep uses it).
+    #[test]
+    fn test_step() {
+        let program = to_bytes(&[0x0070_0513, 0xFFFF_F2B7, 0x00A2_A223]); // li a0, 7 + exit
+        let image = Image::parse(&program).unwrap();
+        let mut sim = Simulator::new(IsaConfig::base(), &image, SIM_MEMORY_SIZE).unwrap();
+        let steps: Vec<_> = (0..3).map(|_| sim.step()).collect();
+        assert_eq!(steps.iter().map(|step| step.pc).collect::<Vec<_>>(), [0, 4, 8]);
+        assert!(steps[2].lsu.mem_we);
+        assert_eq!((steps[2].lsu.mem_addr, steps[2].lsu.mem_wdata), (0xFFFF_F004, 7));
+        assert_eq!((sim.cycles, sim.bus.exit_code), (3, Some(7)));
+    }
+
+    #[test]
+    fn test_exit_status() {
+        assert_eq!(exit_status(0), 0);
+        assert_eq!(exit_status(42), 42);
+        assert_eq!(exit_status(255), 255);
+        assert_eq!(exit_status(256), 255);
+        assert_eq!(exit_status(0xFFFF_FFFF), 255);
+    }
+
+    /// Segments land at their physical address with .bss zero-filled, and
+    /// execution starts at the entry point.
+    #[test]
+    fn test_elf_image() {
+        let code = to_bytes(&[0x0050_0513, 0xFFFF_F2B7, 0x00A2_A223]); // li a0, 5 + exit
+        let data = [0xAA; 6];
+        let elf = elf_exec(0x200, &[(0x200, &code, 12), (0x400, &data, 16)]);
+        let image = Image::parse(&elf).unwrap();
+        assert_eq!(image.entry, 0x200);
+        assert_eq!(image.segments.len(), 2);
+        assert_eq!(image.segments[1].addr, 0x400);
+        assert_eq!(image.segments[1].data, [&data[..], &[0; 10]].concat());
+
+        let (stop, sim) = run(IsaConfig::base(), &elf, 100);
+        assert_eq!(stop, Stop::Exit(5));
+        assert_eq!(sim.bus.ram[0x400..0x406], data);
+    }
+
+    /// A file without the ELF magic is a flat binary, even if it is empty.
+    #[test]
+    fn test_flat_image() {
+        assert_eq!(Image::parse(&[1, 2, 3]).unwrap(), Image::flat(&[1, 2, 3], 0));
+        assert_eq!(Image::parse(&[]).unwrap().segments[0].data.len(), 0);
+    }
+
+    #[test]
+    fn test_elf_errors() {
+        let elf = elf_exec(0, &[(0, &[0; 4], 4)]);
+        let error = |bytes: &[u8]| Image::parse(bytes).unwrap_err();
+
+        let mut relocatable = elf.clone();
+        relocatable[16] = 1;
+        assert!(error(&relocatable).contains("relocatable object"));
+
+        let mut class64 = elf.clone();
+        class64[4] = 2;
+        assert!(error(&class64).contains("ELF32"));
+
+        assert!(error(&elf[..40]).contains("truncated"));
+        assert!(error(&elf[..elf.len() - 2]).contains("past the end"));
+        assert!(error(&elf_exec(0, &[])).contains("no loadable segments"));
+        assert!(error(&elf_exec(0, &[(0, &[0; 4], 2)])).contains("smaller in memory"));
+
+        // Assembler objects must be linked first
+        let object = assemble("nop").unwrap().to_elf().unwrap();
+        assert!(error(&object).contains("ld.lld"));
+
+        // Segments outside RAM are rejected when loading, not truncated
+        let outside = Image::parse(&elf_exec(0, &[(SIM_MEMORY_SIZE as u32 - 2, &[0; 4], 4)])).unwrap();
+        let err = Simulator::new(IsaConfig::base(), &outside, SIM_MEMORY_SIZE).err().unwrap();
+        assert!(err.contains("does not fit"));
+    }
+}
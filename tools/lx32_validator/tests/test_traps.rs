@@ -0,0 +1,257 @@
+// tests/test_traps.rs
+//
+// Machine-mode exception validation for the golden model.
+//
+// The RTL has no trap support yet, so this suite is golden-only. Random
+// loads, stores, jumps, ECALLs and EBREAKs run on an RV32I golden model with
+// random register values and a random mtvec; each step must either retire or
+// trap with the cause, mepc and mtval computed independently here.
+#[path = "common/mod.rs"]
+mod common;
+use common::*;
+use lx32_validator::models::arch::lx32_csr_pkg::*;
+use lx32_validator::models::arch::lx32_instr_pkg::Instr;
+use lx32_validator::models::arch::lx32_isa_pkg::{INSTR_EBREAK, INSTR_ECALL};
+use lx32_validator::models::arch::lx32_trap_pkg::trap_cause_e;
+use lx32_validator::models::core::isa_config::IsaConfig;
+use lx32_validator::models::core::lx32_system::{StepStatus, Trap};
+use rand::RngExt;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Suite name used to derive this fuzzer's seed from the global one.
+pub const SUITE: &str = "traps";
+
+pub struct TrapTestParams {
+    pub iterations: u32,
+    pub enable_logging: bool,
+}
+
+impl Default for TrapTestParams {
+    fn default() -> Self {
+        Self {
+            iterations: 2000,
+            enable_logging: true,
+        }
+    }
+}
+
+/// A memory instruction built from two register fields and an offset,
+/// paired with its access width in bytes
+type MemCase = (fn(u8, u8, i32) -> Instr, u32);
+
+/// LB, LH, LW, LBU, LHU (rd, rs1, imm)
+const LOADS: [MemCase; 5] = [
+    (|rd, rs1, imm| Instr::Lb { rd, rs1, imm }, 1),
+    (|rd, rs1, imm| Instr::Lh { rd, rs1, imm }, 2),
+    (|rd, rs1, imm| Instr::Lw { rd, rs1, imm }, 4),
+    (|rd, rs1, imm| Instr::Lbu { rd, rs1, imm }, 1),
+    (|rd, rs1, imm| Instr::Lhu { rd, rs1, imm }, 2),
+];
+/// SB, SH, SW (rs1, rs2, imm)
+const STORES: [MemCase; 3] = [
+    (|rs1, rs2, imm| Instr::Sb { rs1, rs2, imm }, 1),
+    (|rs1, rs2, imm| Instr::Sh { rs1, rs2, imm }, 2),
+    (|rs1, rs2, imm| Instr::Sw { rs1, rs2, imm }, 4),
+];
+
+/// JAL rd, imm (imm is a multiple of 2)
+pub fn jal(rd: u8, imm: i32) -> u32 {
+    let imm = imm as u32;
+    ((imm >> 20) & 1) << 31
+        | ((imm >> 1) & 0x3FF) << 21
+        | ((imm >> 11) & 1) << 20
+        | ((imm >> 12) & 0xFF) << 12
+        | (rd as u32) << 7
+        | 0x6F
+}
+
+/// JALR rd, imm(rs1)
+pub fn jalr(rd: u8, rs1: u8, imm: i32) -> u32 {
+    ((imm as u32 & 0xFFF) << 20) | ((rs1 as u32) << 15) | ((rd as u32) << 7) | 0x67
+}
+
+pub fn run_trap_fuzzer(params: TrapTestParams, seed: u64) {
+    println!("\n{:=^100}", " STARTING TRAP FUZZER ");
+    println!("Iterations: {}", params.iterations);
+    println!("Seed: {}", seed);
+
+    let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut trap_count = 0;
+
+    for i in 0..params.iterations {
+        let pc = rng.random_range(0..0x400u32) << 2;
+        let mtvec = rng.random_range(0..0x400u32) << 2;
+        gold.pc = pc;
+        gold.csr.write(CSR_MTVEC, mtvec);
+
+        let rs1 = rng.random_range(1..32u8);
+        let rs1_value = rng.random_range(0..0x1000u32);
+        gold.reg_file.tick(false, rs1, rs1_value, true);
+        let imm = rng.random_range(-64..64i32);
+        let addr = rs1_value.wrapping_add(imm as u32);
+
+        // (instruction, expected trap cause and tval, or None when it retires)
+        let (instr, expected) = match rng.random_range(0..6) {
+            0 => {
+                let (load, bytes) = LOADS[rng.random_range(0..LOADS.len())];
+                let misaligned = addr % bytes != 0;
+                let instr = load(5, rs1, imm).encode();
+                (instr, misaligned.then_some((trap_cause_e::LOAD_ADDR_MISALIGNED, addr)))
+            }
+            1 => {
+                let (store, bytes) = STORES[rng.random_range(0..STORES.len())];
+                let misaligned = addr % bytes != 0;
+                let instr = store(rs1, 6, imm).encode();
+                (instr, misaligned.then_some((trap_cause_e::STORE_ADDR_MISALIGNED, addr)))
+            }
+            2 => {
+                let offset = imm * 2;
+                let target = pc.wrapping_add(offset as u32);
+                let misaligned = target % 4 != 0;
+                (jal(1, offset), misaligned.then_some((trap_cause_e::INSTR_ADDR_MISALIGNED, target)))
+            }
+            3 => {
+                let target = addr & !1;
+                let misaligned = target % 4 != 0;
+                (jalr(1, rs1, imm), misaligned.then_some((trap_cause_e::INSTR_ADDR_MISALIGNED, target)))
+            }
+            4 => (INSTR_ECALL, Some((trap_cause_e::ECALL_M, 0))),
+            _ => (INSTR_EBREAK, Some((trap_cause_e::BREAKPOINT, pc))),
+        };
+
+        let res = gold.step(instr, 0, false);
+
+        let matches = match expected {
+            Some((cause, tval)) => {
+                trap_count += 1;
+                res.status == StepStatus::Trap(Trap { cause, pc, tval })
+                    && gold.pc == mtvec
+                    && gold.csr.read(CSR_MEPC) == pc
+                    && gold.csr.read(CSR_MCAUSE) == cause.code()
+                    && gold.csr.read(CSR_MTVAL) == tval
+                    && res.rd_write.is_none()
+                    && res.mem_access.is_none()
+            }
+            None => res.status == StepStatus::Retired,
+        };
+
+        if params.enable_logging {
+            println!(
+                "[{:>5}] pc:0x{:08x} instr:0x{:08x} | expected:{:?} | status:{:?} | {}",
+                i,
+                pc,
+                instr,
+                expected,
+                res.status,
+                if matches { "✓ MATCH" } else { "✗ MISMATCH" }
+            );
+        }
+
+        if !matches {
+            println!("\n{:=^100}", " TRAP MISMATCH DETECTED ");
+            println!("Iteration: {}", i);
+            println!("Instruction: 0x{:08x} ({}) at pc 0x{:08x}", instr, disasm(instr, pc), pc);
+            println!("Expected: {:?}", expected);
+            println!(
+                "Got: pc=0x{:08x} mepc=0x{:08x} mcause={} mtval=0x{:08x}",
+                gold.pc,
+                gold.csr.read(CSR_MEPC),
+                gold.csr.read(CSR_MCAUSE),
+                gold.csr.read(CSR_MTVAL)
+            );
+            print_gold_step(&res);
+            print_replay(SUITE, seed, i);
+            panic!("🔥 TRAP TEST FAILED AT ITERATION {} (seed {})", i, seed);
+        }
+    }
+
+    println!("Traps taken: {}/{}", trap_count, params.iterations);
+    println!("{:=^100}", " TRAP FUZZER PASSED ");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lx32_validator::models::arch::lx32_isa_pkg::INSTR_MRET;
+    use lx32_validator::models::core::decoder::{IllegalReason, classify_instr};
+
+    #[test]
+    fn test_trap_default() {
+        run_trap_fuzzer(TrapTestParams::default(), DEFAULT_TEST_SEED);
+    }
+
+    /// ECALL enters a handler that skips the ECALL and returns with MRET.
+    #[test]
+    fn test_ecall_mret_round_trip() {
+        let program: [u32; 10] = [
+            0x0200_0293, // addi  x5, x0, 32
+            0x3052_9073, // csrw  mtvec, x5
+            0x0080_0093, // addi  x1, x0, 8 (MIE)
+            0x3000_a073, // csrs  mstatus, x1
+            0x05d0_0893, // addi  x17, x0, 93
+            INSTR_ECALL, // ecall
+            0x0000_006f, // jal   x0, 0 (halt)
+            0x0000_0013, // nop
+            // handler at 32
+            0x3410_2373, // csrr  x6, mepc
+            0x0043_0313, // addi  x6, x6, 4
+        ];
+        let handler: [u32; 2] = [
+            0x3413_1073, // csrw  mepc, x6
+            INSTR_MRET,  // mret
+        ];
+        let image: Vec<u8> = program.iter().chain(&handler).flat_map(|w| w.to_le_bytes()).collect();
+
+        let mut gold = Lx32System::with_config(IsaConfig::rv32i());
+        gold.load_image(0, &image);
+
+        let summary = gold.run(6, |_| false);
+        let Some(last) = summary.last else { panic!("no step ran") };
+        assert_eq!(
+            last.status,
+            StepStatus::Trap(Trap {
+                cause: trap_cause_e::ECALL_M,
+                pc: 20,
+                tval: 0
+            })
+        );
+        assert_eq!(gold.pc, 32);
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE);
+
+        let summary = gold.run(100, |res| res.next_pc == res.pc);
+        assert!(summary.halted);
+        assert_eq!(gold.pc, 24);
+        assert_eq!(gold.csr.read(CSR_MSTATUS), MSTATUS_MPP | MSTATUS_MPIE | MSTATUS_MIE);
+        assert_eq!(gold.csr.read(CSR_MCAUSE), trap_cause_e::ECALL_M.code());
+        // The ECALL does not retire; everything else does
+        assert_eq!((gold.csr.cycles(), gold.csr.instret()), (11, 10));
+    }
+
+    /// Only the three defined funct3 = 000 SYSTEM words are legal.
+    #[test]
+    fn test_system_decode() {
+        let cfg = IsaConfig::rv32i();
+        for instr in [INSTR_ECALL, INSTR_EBREAK, INSTR_MRET] {
+            assert!(classify_instr(instr, &cfg).is_ok(), "0x{:08x}", instr);
+        }
+        // wfi, sret, ecall with rd != 0
+        for instr in [0x1050_0073, 0x1020_0073, 0x0000_00f3] {
+            assert_eq!(classify_instr(instr, &cfg), Err(IllegalReason::System), "0x{:08x}", instr);
+        }
+        assert_eq!(classify_instr(INSTR_ECALL, &IsaConfig::base()), Err(IllegalReason::Opcode));
+    }
+
+    /// The base profile keeps the RTL behaviour: no alignment checks.
+    #[test]
+    fn test_base_profile_has_no_alignment_traps() {
+        let mut gold = Lx32System::new();
+        let res = gold.step(jal(0, 6), 0, false);
+        assert_eq!((res.status, res.next_pc), (StepStatus::Retired, 6));
+
+        let res = gold.step(Instr::Lw { rd: 1, rs1: 0, imm: 3 }.encode(), 0x1234_5678, false);
+        assert_eq!(res.status, StepStatus::Retired);
+        assert_eq!(res.rd_write.map(|w| w.data), Some(0x1234_5678));
+    }
+}